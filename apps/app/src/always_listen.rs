@@ -3,9 +3,14 @@
 //! Uses Voice Activity Detection (VAD) to automatically detect speech,
 //! record utterances, and trigger transcription without hotkey presses.
 
+use crate::audio::{NoiseGate, NoiseGateConfig, PreRollBuffer};
+use crate::backend_loader::{
+    is_audio_too_short, ms_to_samples, DEFAULT_MIN_AUDIO_MS, DEFAULT_SAMPLE_RATE,
+};
 use anyhow::{Context, Result};
 use crossbeam_channel::{Receiver, Sender};
 use parking_lot::Mutex;
+use std::ops::RangeInclusive;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -40,6 +45,9 @@ impl AlwaysListenState {
     }
 }
 
+/// Length of a VAD frame
+pub const FRAME_MS: u64 = 30;
+
 /// Configuration for always-listen mode
 #[derive(Clone, Debug)]
 pub struct AlwaysListenConfig {
@@ -58,6 +66,16 @@ pub struct AlwaysListenConfig {
     pub cooldown_ms: u64,
     /// Frames to analyze per VAD check (must be power of 2, 10-30ms worth)
     pub frame_samples: usize,
+    /// Recordings shorter than this are discarded (keep in sync with the model's minimum)
+    pub min_audio_samples: usize,
+    /// Lowest zero-crossing rate (crossings per sample) counted as voiced speech
+    pub zcr_min: f32,
+    /// Highest zero-crossing rate counted as voiced speech (hiss and fans sit above this)
+    pub zcr_max: f32,
+    /// Rate of the incoming audio (the backend's required rate)
+    pub sample_rate: u32,
+    /// Gate applied to the buffered audio (the VAD still sees the raw input)
+    pub noise_gate: Option<NoiseGateConfig>,
 }
 
 impl Default for AlwaysListenConfig {
@@ -69,7 +87,12 @@ impl Default for AlwaysListenConfig {
             vad_threshold: 0.015,          // Energy threshold (tuned for typical mics)
             max_utterance_seconds: 30.0,   // Max 30s utterance
             cooldown_ms: 200,              // 200ms between utterances
-            frame_samples: 480,            // 30ms at 16kHz
+            frame_samples: ms_to_samples(FRAME_MS, DEFAULT_SAMPLE_RATE),
+            min_audio_samples: ms_to_samples(DEFAULT_MIN_AUDIO_MS, DEFAULT_SAMPLE_RATE),
+            zcr_min: DEFAULT_ZCR_MIN,      // ~80Hz pitch, below that is hum
+            zcr_max: DEFAULT_ZCR_MAX,      // white noise is ~0.5
+            sample_rate: DEFAULT_SAMPLE_RATE,
+            noise_gate: None,
         }
     }
 }
@@ -79,67 +102,70 @@ impl Default for AlwaysListenConfig {
 pub enum AlwaysListenCommand {
     Start,
     Stop,
-    #[allow(dead_code)]
     Pause,
-    #[allow(dead_code)]
     Resume,
 }
 
+/// State changes buffered for the UI before the oldest are dropped
+const STATE_CHANGE_CAPACITY: usize = 32;
+
+/// The controller's state, shared with the processing thread, which reports
+/// every change on a channel so the UI can follow it
+#[derive(Clone)]
+struct SharedState {
+    state: Arc<Mutex<AlwaysListenState>>,
+    changes: Sender<AlwaysListenState>,
+}
+
+impl SharedState {
+    fn new(initial: AlwaysListenState) -> (Self, Receiver<AlwaysListenState>) {
+        let (changes, changes_rx) = crossbeam_channel::bounded(STATE_CHANGE_CAPACITY);
+        let state = Arc::new(Mutex::new(initial));
+        (Self { state, changes }, changes_rx)
+    }
+
+    fn get(&self) -> AlwaysListenState {
+        *self.state.lock()
+    }
+
+    /// Set the state, reporting it if it changed. Changes nobody reads are dropped.
+    fn set(&self, new: AlwaysListenState) {
+        let mut state = self.state.lock();
+        if *state != new {
+            *state = new;
+            let _ = self.changes.try_send(new);
+        }
+    }
+}
+
 /// Audio buffer manager with circular pre-roll buffer
 pub struct AudioBufferManager {
-    /// Fixed-size circular buffer for pre-roll
-    pre_roll: Vec<f32>,
-    pre_roll_pos: usize,
-    pre_roll_full: bool,
+    /// Audio from before speech was detected
+    pre_roll: PreRollBuffer,
     /// Growing buffer for active recording
     recording: Vec<f32>,
     /// Sample rate
     sample_rate: u32,
-    /// Pre-roll capacity in samples
-    pre_roll_capacity: usize,
 }
 
 impl AudioBufferManager {
     pub fn new(sample_rate: u32, pre_roll_duration_ms: u64) -> Self {
         let pre_roll_capacity = (sample_rate as usize * pre_roll_duration_ms as usize) / 1000;
         Self {
-            pre_roll: vec![0.0; pre_roll_capacity],
-            pre_roll_pos: 0,
-            pre_roll_full: false,
+            pre_roll: PreRollBuffer::new(pre_roll_capacity),
             recording: Vec::new(),
             sample_rate,
-            pre_roll_capacity,
         }
     }
 
     /// Push samples to pre-roll buffer
     pub fn push_to_pre_roll(&mut self, samples: &[f32]) {
-        for &sample in samples {
-            self.pre_roll[self.pre_roll_pos] = sample;
-            self.pre_roll_pos += 1;
-            if self.pre_roll_pos >= self.pre_roll_capacity {
-                self.pre_roll_pos = 0;
-                self.pre_roll_full = true;
-            }
-        }
+        self.pre_roll.push(samples);
     }
 
-    /// Start recording - returns pre-roll buffer content
+    /// Start recording - returns pre-roll buffer content (oldest to newest)
     pub fn start_recording(&mut self) -> Vec<f32> {
-        let mut result = if self.pre_roll_full {
-            // Return buffer from current position (oldest to newest)
-            let mut ordered = Vec::with_capacity(self.pre_roll_capacity);
-            ordered.extend_from_slice(&self.pre_roll[self.pre_roll_pos..]);
-            ordered.extend_from_slice(&self.pre_roll[..self.pre_roll_pos]);
-            ordered
-        } else {
-            // Buffer not full yet, return what we have
-            self.pre_roll[..self.pre_roll_pos].to_vec()
-        };
-
-        // Move to recording buffer
-        self.recording.clear();
-        self.recording.append(&mut result);
+        self.recording = self.pre_roll.ordered();
         self.recording.clone()
     }
 
@@ -155,13 +181,8 @@ impl AudioBufferManager {
 
     /// Reset for next utterance
     pub fn reset(&mut self) {
-        self.pre_roll_pos = 0;
-        self.pre_roll_full = false;
+        self.pre_roll.clear();
         self.recording.clear();
-        // Clear pre-roll buffer
-        for sample in &mut self.pre_roll {
-            *sample = 0.0;
-        }
     }
 
     /// Get current recording duration in seconds
@@ -170,7 +191,133 @@ impl AudioBufferManager {
     }
 }
 
-/// Energy-based Voice Activity Detection
+/// VAD threshold = calibrated noise floor x this
+pub const NOISE_FLOOR_MULTIPLIER: f32 = 3.0;
+/// Lower bound for a calibrated threshold (a dead-silent room would trigger on any sound)
+pub const MIN_CALIBRATED_THRESHOLD: f32 = 0.005;
+/// Any calibration frame louder than this means the user was talking or there was a bang
+pub const MAX_CALIBRATION_RMS: f32 = 0.05;
+
+// Ranges the user-tunable settings are clamped to
+pub const VAD_THRESHOLD_RANGE: RangeInclusive<f32> = 0.001..=0.2;
+pub const PRE_ROLL_MS_RANGE: RangeInclusive<u64> = 0..=2000;
+pub const MIN_SPEECH_MS_RANGE: RangeInclusive<u64> = 30..=2000;
+pub const POST_SILENCE_MS_RANGE: RangeInclusive<u64> = 100..=5000;
+
+/// RMS energy of a frame
+pub fn frame_rms(frame: &[f32]) -> f32 {
+    if frame.is_empty() {
+        return 0.0;
+    }
+    (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt()
+}
+
+/// VAD threshold for a calibrated ambient noise level
+pub fn threshold_from_noise_floor(noise_floor: f32) -> f32 {
+    (noise_floor * NOISE_FLOOR_MULTIPLIER).max(MIN_CALIBRATED_THRESHOLD)
+}
+
+/// The threshold to use: an explicit one, else one from the calibrated noise floor,
+/// else the default (clamped to `VAD_THRESHOLD_RANGE`)
+pub fn effective_threshold(explicit: Option<f32>, noise_floor: Option<f32>) -> f32 {
+    let threshold = explicit
+        .or(noise_floor.map(threshold_from_noise_floor))
+        .unwrap_or(AlwaysListenConfig::default().vad_threshold);
+    threshold.clamp(*VAD_THRESHOLD_RANGE.start(), *VAD_THRESHOLD_RANGE.end())
+}
+
+/// Measure the ambient noise level (median frame RMS) of a silent recording.
+/// Fails if the recording is too short or contains speech-level energy.
+pub fn measure_noise_floor(samples: &[f32], frame_size: usize) -> Result<f32> {
+    let mut levels: Vec<f32> = samples.chunks_exact(frame_size).map(frame_rms).collect();
+    if levels.is_empty() {
+        anyhow::bail!("No audio captured - check the mic");
+    }
+
+    let loudest = levels.iter().copied().fold(0.0f32, f32::max);
+    if loudest > MAX_CALIBRATION_RMS {
+        anyhow::bail!("Too loud (peak {:.3}) - stay quiet", loudest);
+    }
+
+    levels.sort_by(|a, b| a.total_cmp(b));
+    Ok(levels[levels.len() / 2])
+}
+
+/// Default lower zero-crossing bound for voiced frames
+pub const DEFAULT_ZCR_MIN: f32 = 0.01;
+/// Default upper zero-crossing bound for voiced frames
+pub const DEFAULT_ZCR_MAX: f32 = 0.25;
+/// Spectral flatness at or above this is treated as pure noise
+const NOISE_FLATNESS: f32 = 0.5;
+/// Smoothed voicing score needed (on top of energy) to call a frame speech
+const MIN_VOICING: f32 = 0.5;
+/// Highest frequency included in the flatness estimate (Hz)
+const FLATNESS_MAX_HZ: usize = 4000;
+
+/// Zero crossings per sample, after removing the frame's DC offset
+pub fn zero_crossing_rate(frame: &[f32]) -> f32 {
+    if frame.len() < 2 {
+        return 0.0;
+    }
+    let mean = frame.iter().sum::<f32>() / frame.len() as f32;
+    let crossings = frame
+        .windows(2)
+        .filter(|w| ((w[0] - mean) >= 0.0) != ((w[1] - mean) >= 0.0))
+        .count();
+    crossings as f32 / (frame.len() - 1) as f32
+}
+
+/// Spectral flatness (geometric / arithmetic mean of the power spectrum) over 0-4kHz.
+/// Near 0 for harmonic sounds like vowels, around 0.5 for white noise, 1.0 for silence.
+pub fn spectral_flatness(frame: &[f32], sample_rate: u32) -> f32 {
+    let n = frame.len();
+    if n < 4 {
+        return 1.0;
+    }
+    let mean = frame.iter().sum::<f32>() / n as f32;
+    // Hann window limits leakage between harmonics
+    let windowed: Vec<f32> = frame
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let w = 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (n - 1) as f32).cos();
+            (s - mean) * w
+        })
+        .collect();
+
+    let max_bin = (FLATNESS_MAX_HZ * n / sample_rate as usize).clamp(2, n / 2);
+    let mut log_sum = 0.0f64;
+    let mut sum = 0.0f64;
+    for k in 1..=max_bin {
+        let step = 2.0 * std::f32::consts::PI * k as f32 / n as f32;
+        let (mut re, mut im) = (0.0f32, 0.0f32);
+        for (i, s) in windowed.iter().enumerate() {
+            let (sin, cos) = (step * i as f32).sin_cos();
+            re += s * cos;
+            im -= s * sin;
+        }
+        let power = (re * re + im * im) as f64 + 1e-12;
+        log_sum += power.ln();
+        sum += power;
+    }
+    let bins = max_bin as f64;
+    ((log_sum / bins).exp() / (sum / bins)) as f32
+}
+
+/// How speech-like a frame is (0.0 - 1.0) from its zero-crossing rate and flatness
+fn voicing_score(zcr: f32, flatness: f32, zcr_min: f32, zcr_max: f32) -> f32 {
+    let zcr_score = if zcr < zcr_min {
+        zcr / zcr_min
+    } else if zcr > zcr_max {
+        zcr_max / zcr
+    } else {
+        1.0
+    };
+    let tonality = ((NOISE_FLATNESS - flatness) / NOISE_FLATNESS).clamp(0.0, 1.0);
+    zcr_score * tonality
+}
+
+/// Voice Activity Detection from frame energy, zero-crossing rate and spectral flatness
 pub struct VadEngine {
     threshold: f32,
     frame_size: usize,
@@ -182,6 +329,11 @@ pub struct VadEngine {
     smoothed_energy: f32,
     /// Alpha for EMA (0.0 = no smoothing, 1.0 = max smoothing)
     smoothing_alpha: f32,
+    /// Smoothed voicing score (same EMA as energy)
+    smoothed_voicing: f32,
+    zcr_min: f32,
+    zcr_max: f32,
+    sample_rate: u32,
 }
 
 impl VadEngine {
@@ -193,9 +345,26 @@ impl VadEngine {
             silence_frames: 0,
             smoothed_energy: 0.0,
             smoothing_alpha: 0.3, // Moderate smoothing
+            smoothed_voicing: 0.0,
+            zcr_min: DEFAULT_ZCR_MIN,
+            zcr_max: DEFAULT_ZCR_MAX,
+            sample_rate: DEFAULT_SAMPLE_RATE,
         }
     }
 
+    /// Set the rate of the frames passed to `process`
+    pub fn with_sample_rate(mut self, sample_rate: u32) -> Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+
+    /// Set the zero-crossing range counted as voiced speech
+    pub fn with_zcr_range(mut self, zcr_min: f32, zcr_max: f32) -> Self {
+        self.zcr_min = zcr_min;
+        self.zcr_max = zcr_max;
+        self
+    }
+
     /// Process a frame and return voice activity
     /// Returns: (is_voice, voice_probability)
     pub fn process(&mut self, frame: &[f32]) -> (bool, f32) {
@@ -203,18 +372,29 @@ impl VadEngine {
             return (false, 0.0);
         }
 
-        // Calculate RMS energy
-        let energy: f32 = frame[..self.frame_size].iter().map(|s| s * s).sum::<f32>()
-            / self.frame_size as f32;
-        let rms = energy.sqrt();
+        let frame = &frame[..self.frame_size];
+        let rms = frame_rms(frame);
 
         // Update smoothed energy with EMA
         self.smoothed_energy = self.smoothing_alpha * rms
             + (1.0 - self.smoothing_alpha) * self.smoothed_energy;
 
-        // Normalize probability (0.0 to 1.0)
-        let probability = (self.smoothed_energy / self.threshold).min(1.0);
-        let is_voice = self.smoothed_energy > self.threshold;
+        // Spectral features are only worth computing once there's energy to judge
+        let voicing = if rms > self.threshold * 0.5 {
+            let zcr = zero_crossing_rate(frame);
+            let flatness = spectral_flatness(frame, self.sample_rate);
+            voicing_score(zcr, flatness, self.zcr_min, self.zcr_max)
+        } else {
+            0.0
+        };
+        self.smoothed_voicing = self.smoothing_alpha * voicing
+            + (1.0 - self.smoothing_alpha) * self.smoothed_voicing;
+
+        // Loud enough AND speech-like: transients and hiss have energy but no voicing
+        let energy = (self.smoothed_energy / self.threshold).min(1.0);
+        let probability = energy * self.smoothed_voicing;
+        let is_voice =
+            self.smoothed_energy > self.threshold && self.smoothed_voicing >= MIN_VOICING;
 
         if is_voice {
             self.voice_frames += 1;
@@ -257,12 +437,15 @@ impl VadEngine {
         self.voice_frames = 0;
         self.silence_frames = 0;
         self.smoothed_energy = 0.0;
+        self.smoothed_voicing = 0.0;
     }
 }
 
 /// Controller for always-listen mode
 pub struct AlwaysListenController {
-    state: Arc<Mutex<AlwaysListenState>>,
+    state: SharedState,
+    /// State changes made by the processing thread
+    state_rx: Receiver<AlwaysListenState>,
     #[allow(dead_code)]
     config: AlwaysListenConfig,
     /// Control command sender
@@ -285,11 +468,11 @@ impl AlwaysListenController {
         let (command_tx, command_rx) = crossbeam_channel::bounded(10);
         let (internal_result_tx, result_rx) = crossbeam_channel::bounded::<Vec<f32>>(10);
 
-        let state = Arc::new(Mutex::new(AlwaysListenState::Listening));
+        let (state, state_rx) = SharedState::new(AlwaysListenState::Listening);
         let running = Arc::new(AtomicBool::new(true));
 
         // Clone values for the controller struct
-        let state_for_controller = Arc::clone(&state);
+        let state_for_controller = state.clone();
         let running_for_controller = Arc::clone(&running);
         let config_for_controller = config.clone();
         let command_tx_for_controller = command_tx;
@@ -308,6 +491,7 @@ impl AlwaysListenController {
 
         Self {
             state: state_for_controller,
+            state_rx,
             config: config_for_controller,
             command_tx: command_tx_for_controller,
             result_rx,
@@ -334,8 +518,7 @@ impl AlwaysListenController {
         Ok(())
     }
 
-    /// Pause listening temporarily
-    #[allow(dead_code)]
+    /// Pause listening temporarily; audio keeps arriving but is dropped
     pub fn pause(&self) -> Result<()> {
         self.command_tx
             .send(AlwaysListenCommand::Pause)
@@ -345,7 +528,6 @@ impl AlwaysListenController {
     }
 
     /// Resume listening
-    #[allow(dead_code)]
     pub fn resume(&self) -> Result<()> {
         self.command_tx
             .send(AlwaysListenCommand::Resume)
@@ -356,7 +538,12 @@ impl AlwaysListenController {
 
     /// Get current state
     pub fn state(&self) -> AlwaysListenState {
-        *self.state.lock()
+        self.state.get()
+    }
+
+    /// Next state change made by the processing thread, if any (non-blocking)
+    pub fn try_recv_state(&self) -> Option<AlwaysListenState> {
+        self.state_rx.try_recv().ok()
     }
 
     /// Check if running
@@ -388,14 +575,14 @@ impl Drop for AlwaysListenController {
 
 /// Main processing loop running in dedicated thread
 fn processing_loop(
-    state: Arc<Mutex<AlwaysListenState>>,
+    state: SharedState,
     running: Arc<AtomicBool>,
     config: AlwaysListenConfig,
     audio_rx: Receiver<Vec<f32>>,
     command_rx: Receiver<AlwaysListenCommand>,
     result_tx: Sender<Vec<f32>>,
 ) {
-    let sample_rate = 16000u32;
+    let sample_rate = config.sample_rate;
     let frame_samples = config.frame_samples;
     let min_voice_frames =
         ((config.min_speech_duration_ms as f32 / 1000.0) * sample_rate as f32) as usize
@@ -405,14 +592,23 @@ fn processing_loop(
             / frame_samples;
 
     let mut buffer_manager = AudioBufferManager::new(sample_rate, config.pre_roll_duration_ms);
-    let mut vad = VadEngine::new(config.vad_threshold, frame_samples);
+    let mut vad = VadEngine::new(config.vad_threshold, frame_samples)
+        .with_zcr_range(config.zcr_min, config.zcr_max)
+        .with_sample_rate(sample_rate);
+    let mut gate = config.noise_gate.map(|gate| NoiseGate::new(gate, sample_rate));
+    if let Some(gate) = &config.noise_gate {
+        info!(
+            "Noise gate: threshold={}, attack={}ms, release={}ms",
+            gate.threshold, gate.attack_ms, gate.release_ms
+        );
+    }
 
     // Accumulate samples for frame processing
     let mut sample_buffer: Vec<f32> = Vec::with_capacity(frame_samples * 2);
 
     info!(
-        "VAD initialized: threshold={}, frame_samples={}, min_voice_frames={}",
-        config.vad_threshold, frame_samples, min_voice_frames
+        "VAD initialized: threshold={}, frame_samples={}, min_voice_frames={}, zcr={}..{}",
+        config.vad_threshold, frame_samples, min_voice_frames, config.zcr_min, config.zcr_max
     );
 
     while running.load(Ordering::SeqCst) {
@@ -420,26 +616,30 @@ fn processing_loop(
         if let Ok(cmd) = command_rx.try_recv() {
             match cmd {
                 AlwaysListenCommand::Stop => {
-                    *state.lock() = AlwaysListenState::Paused;
+                    state.set(AlwaysListenState::Paused);
                 }
                 AlwaysListenCommand::Pause => {
-                    *state.lock() = AlwaysListenState::Paused;
+                    state.set(AlwaysListenState::Paused);
                 }
                 AlwaysListenCommand::Resume | AlwaysListenCommand::Start => {
-                    let mut s = state.lock();
-                    if *s == AlwaysListenState::Paused {
-                        *s = AlwaysListenState::Listening;
+                    if state.get() == AlwaysListenState::Paused {
+                        state.set(AlwaysListenState::Listening);
+                        sample_buffer.clear();
                         buffer_manager.reset();
                         vad.reset();
+                        if let Some(gate) = gate.as_mut() {
+                            gate.reset();
+                        }
                     }
                 }
             }
         }
 
-        let current_state = *state.lock();
+        let current_state = state.get();
 
-        // Skip processing if paused
+        // Skip processing if paused, dropping audio so none of it is heard on resume
         if current_state == AlwaysListenState::Paused {
+            while audio_rx.try_recv().is_ok() {}
             std::thread::sleep(Duration::from_millis(10));
             continue;
         }
@@ -455,6 +655,8 @@ fn processing_loop(
 
                     let (is_voice, prob) = vad.process(&frame);
                     trace!("VAD: voice={}, prob={:.3}", is_voice, prob);
+                    let gated = gate.as_mut().map(|gate| gate.process(&frame));
+                    let frame = gated.unwrap_or(frame);
 
                     match current_state {
                         AlwaysListenState::Listening => {
@@ -462,9 +664,7 @@ fn processing_loop(
 
                             if vad.has_sustained_voice(min_voice_frames) {
                                 info!("Speech detected, starting recording");
-                                *state.lock() = AlwaysListenState::Recording {
-                                    since: Instant::now(),
-                                };
+                                state.set(AlwaysListenState::Recording { since: Instant::now() });
                                 buffer_manager.start_recording();
                                 // Add current frame
                                 buffer_manager.push_to_recording(&frame);
@@ -481,6 +681,7 @@ fn processing_loop(
                                     &mut vad,
                                     &state,
                                     &result_tx,
+                                    config.min_audio_samples,
                                 );
                                 continue;
                             }
@@ -496,6 +697,7 @@ fn processing_loop(
                                     &mut vad,
                                     &state,
                                     &result_tx,
+                                    config.min_audio_samples,
                                 );
                             }
                         }
@@ -520,25 +722,87 @@ fn processing_loop(
     info!("Always-listen processing loop ended");
 }
 
+/// Text to type for an always-listen result, given when the previous result ended.
+/// Within `paragraph_gap` of the previous result the text is joined with a space,
+/// after a longer pause it starts a new line. A zero gap disables joining.
+pub fn join_utterance(
+    previous_end: Option<Instant>,
+    now: Instant,
+    text: &str,
+    paragraph_gap: Duration,
+) -> String {
+    let previous_end = match previous_end {
+        Some(t) if !text.is_empty() && !paragraph_gap.is_zero() => t,
+        _ => return text.to_string(),
+    };
+
+    if now.saturating_duration_since(previous_end) >= paragraph_gap {
+        format!("\n{}", text)
+    } else {
+        format!(" {}", text)
+    }
+}
+
+/// Tracks the end of the last always-listen result for `join_utterance`
+pub struct ParagraphJoiner {
+    paragraph_gap: Duration,
+    last_end: Option<Instant>,
+}
+
+impl ParagraphJoiner {
+    pub fn new(paragraph_gap: Duration) -> Self {
+        Self {
+            paragraph_gap,
+            last_end: None,
+        }
+    }
+
+    /// Join a result onto the previous one and remember when it ended
+    pub fn join(&mut self, text: &str) -> String {
+        if text.is_empty() {
+            return String::new();
+        }
+        let now = Instant::now();
+        let joined = join_utterance(self.last_end, now, text, self.paragraph_gap);
+        self.last_end = Some(now);
+        joined
+    }
+
+    /// Move the end of the previous result to now, for a result typed as it was decoded
+    pub fn mark_end(&mut self) {
+        self.last_end = Some(Instant::now());
+    }
+
+    /// Forget the previous result (e.g. when always-listen is turned off)
+    pub fn reset(&mut self) {
+        self.last_end = None;
+    }
+}
+
 /// Finalize recording and send audio data for transcription
 fn finalize_recording(
     buffer_manager: &mut AudioBufferManager,
     vad: &mut VadEngine,
-    state: &Arc<Mutex<AlwaysListenState>>,
+    state: &SharedState,
     result_tx: &Sender<Vec<f32>>,
+    min_audio_samples: usize,
 ) {
+    let duration = buffer_manager.recording_duration();
     let audio = buffer_manager.finalize();
 
-    if audio.len() < 1600 {
-        // Less than 100ms, probably noise
+    // Same check the model applies, so anything sent here is transcribed
+    if is_audio_too_short(audio.len(), min_audio_samples) {
+        // Too short, probably noise
         debug!("Recording too short ({} samples), discarding", audio.len());
-        *state.lock() = AlwaysListenState::Listening;
+        state.set(AlwaysListenState::Listening);
         buffer_manager.reset();
         vad.reset();
         return;
     }
 
-    info!("Finalized recording: {} samples ({:.2}s)", audio.len(), audio.len() as f32 / 16000.0);
+    info!("Finalized recording: {} samples ({:.2}s)", audio.len(), duration);
+    // Reported so the UI shows the hand-off before the transcription starts
+    state.set(AlwaysListenState::Processing);
 
     // Send the actual audio data for transcription
     if result_tx.send(audio).is_err() {
@@ -547,7 +811,7 @@ fn finalize_recording(
 
     // Return to listening state immediately - transcription happens async
     // This allows detecting the next utterance while previous one is being transcribed
-    *state.lock() = AlwaysListenState::Listening;
+    state.set(AlwaysListenState::Listening);
 
     // Reset for next utterance
     buffer_manager.reset();
@@ -557,6 +821,7 @@ fn finalize_recording(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend_loader::DEFAULT_MIN_AUDIO_SAMPLES;
 
     #[test]
     fn test_audio_buffer_manager() {
@@ -600,14 +865,196 @@ mod tests {
         }
     }
 
+    /// Deterministic white noise in [-amp, amp]
+    fn white_noise(len: usize, amp: f32, seed: u32) -> Vec<f32> {
+        let mut x = seed;
+        (0..len)
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 17;
+                x ^= x << 5;
+                (x as f32 / u32::MAX as f32 * 2.0 - 1.0) * amp
+            })
+            .collect()
+    }
+
+    /// Vowel-like signal: 150Hz fundamental with decaying harmonics up to ~3kHz
+    fn vowel(len: usize, amp: f32) -> Vec<f32> {
+        (0..len)
+            .map(|i| {
+                let t = i as f32 / 16000.0;
+                let sum: f32 = (1..=20)
+                    .map(|h| (2.0 * std::f32::consts::PI * 150.0 * h as f32 * t).sin() / h as f32)
+                    .sum();
+                sum * amp / 2.0
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_frame_features() {
+        let noise = white_noise(480, 0.3, 1);
+        let voice = vowel(480, 0.3);
+
+        let noise_zcr = zero_crossing_rate(&noise);
+        let voice_zcr = zero_crossing_rate(&voice);
+        assert!(noise_zcr > DEFAULT_ZCR_MAX, "noise zcr {}", noise_zcr);
+        assert!(
+            (DEFAULT_ZCR_MIN..=DEFAULT_ZCR_MAX).contains(&voice_zcr),
+            "voice zcr {}",
+            voice_zcr
+        );
+
+        let noise_flat = spectral_flatness(&noise, 16000);
+        let voice_flat = spectral_flatness(&voice, 16000);
+        assert!(noise_flat > 0.3, "noise flatness {}", noise_flat);
+        assert!(voice_flat < 0.1, "voice flatness {}", voice_flat);
+
+        // DC offset is not a crossing
+        assert_eq!(zero_crossing_rate(&[0.5; 160]), 0.0);
+    }
+
+    #[test]
+    fn test_vad_rejects_noise_bursts() {
+        let mut noise_vad = VadEngine::new(0.015, 480);
+        let mut voice_vad = VadEngine::new(0.015, 480);
+
+        let (mut noise_prob, mut voice_prob) = (0.0, 0.0);
+        let (mut noise_hit, mut voice_hit) = (false, false);
+        for seed in 1..=10 {
+            let (v, p) = noise_vad.process(&white_noise(480, 0.3, seed));
+            noise_hit |= v;
+            noise_prob = p;
+            let (v, p) = voice_vad.process(&vowel(480, 0.3));
+            voice_hit |= v;
+            voice_prob = p;
+        }
+
+        assert!(!noise_hit);
+        assert!(voice_hit);
+        assert!(noise_prob < voice_prob, "noise {} vs voice {}", noise_prob, voice_prob);
+        assert!(noise_prob < 0.2);
+        assert!(voice_prob > 0.8);
+    }
+
+    #[test]
+    fn test_threshold_from_noise_floor() {
+        assert!((threshold_from_noise_floor(0.005) - 0.015).abs() < 1e-6);
+        assert!((threshold_from_noise_floor(0.01) - 0.03).abs() < 1e-6);
+        // Silent rooms are clamped to the minimum
+        assert_eq!(threshold_from_noise_floor(0.0), MIN_CALIBRATED_THRESHOLD);
+        assert_eq!(threshold_from_noise_floor(0.001), MIN_CALIBRATED_THRESHOLD);
+    }
+
+    #[test]
+    fn test_effective_threshold() {
+        assert_eq!(effective_threshold(None, None), AlwaysListenConfig::default().vad_threshold);
+        assert!((effective_threshold(None, Some(0.01)) - 0.03).abs() < 1e-6);
+        // An explicit threshold wins over calibration, within range
+        assert_eq!(effective_threshold(Some(0.05), Some(0.01)), 0.05);
+        assert_eq!(effective_threshold(Some(5.0), None), *VAD_THRESHOLD_RANGE.end());
+        assert_eq!(effective_threshold(Some(0.0), None), *VAD_THRESHOLD_RANGE.start());
+    }
+
+    #[test]
+    fn test_measure_noise_floor() {
+        // Quiet hiss with alternating sign: RMS equals the amplitude
+        let hiss: Vec<f32> = (0..32000).map(|i| if i % 2 == 0 { 0.004 } else { -0.004 }).collect();
+        let floor = measure_noise_floor(&hiss, 480).unwrap();
+        assert!((floor - 0.004).abs() < 1e-4);
+
+        // A loud burst (talking during calibration) is rejected
+        let mut speech = hiss.clone();
+        for s in &mut speech[16000..16960] {
+            *s = 0.3;
+        }
+        assert!(measure_noise_floor(&speech, 480).is_err());
+
+        // Less than one frame
+        assert!(measure_noise_floor(&hiss[..100], 480).is_err());
+    }
+
+    #[test]
+    fn test_finalize_recording_min_samples_boundary() {
+        let (tx, rx) = crossbeam_channel::unbounded::<Vec<f32>>();
+        let recording = AlwaysListenState::Recording { since: Instant::now() };
+        let (state, changes) = SharedState::new(recording);
+        let mut vad = VadEngine::new(0.1, 160);
+
+        // One sample short of the minimum: discarded
+        let mut manager = AudioBufferManager::new(16000, 0);
+        manager.start_recording();
+        manager.push_to_recording(&vec![0.1f32; DEFAULT_MIN_AUDIO_SAMPLES - 1]);
+        finalize_recording(&mut manager, &mut vad, &state, &tx, DEFAULT_MIN_AUDIO_SAMPLES);
+        assert!(rx.try_recv().is_err());
+        assert_eq!(state.get(), AlwaysListenState::Listening);
+        assert_eq!(changes.try_iter().collect::<Vec<_>>(), [AlwaysListenState::Listening]);
+
+        // Exactly the minimum: sent unchanged for transcription, reporting the hand-off
+        state.set(recording);
+        manager.start_recording();
+        manager.push_to_recording(&vec![0.1f32; DEFAULT_MIN_AUDIO_SAMPLES]);
+        finalize_recording(&mut manager, &mut vad, &state, &tx, DEFAULT_MIN_AUDIO_SAMPLES);
+        assert_eq!(rx.try_recv().unwrap().len(), DEFAULT_MIN_AUDIO_SAMPLES);
+        assert_eq!(
+            changes.try_iter().collect::<Vec<_>>(),
+            [recording, AlwaysListenState::Processing, AlwaysListenState::Listening]
+        );
+    }
+
+    #[test]
+    fn test_join_utterance() {
+        let gap = Duration::from_millis(5000);
+        let start = Instant::now();
+
+        // First result of a session is typed as-is
+        assert_eq!(join_utterance(None, start, "Hello.", gap), "Hello.");
+
+        // Within the gap: joined with a space
+        let now = start + Duration::from_millis(4999);
+        assert_eq!(join_utterance(Some(start), now, "World.", gap), " World.");
+
+        // At or past the gap: new line
+        let now = start + gap;
+        assert_eq!(join_utterance(Some(start), now, "Next.", gap), "\nNext.");
+
+        // Disabled or empty text: unchanged
+        assert_eq!(join_utterance(Some(start), now, "Text", Duration::ZERO), "Text");
+        assert_eq!(join_utterance(Some(start), now, "", gap), "");
+    }
+
+    #[test]
+    fn test_paragraph_joiner_reset() {
+        let mut joiner = ParagraphJoiner::new(Duration::from_secs(60));
+        assert_eq!(joiner.join("One."), "One.");
+        assert_eq!(joiner.join("Two."), " Two.");
+        // Empty results don't count as the previous utterance
+        assert_eq!(joiner.join(""), "");
+        joiner.reset();
+        assert_eq!(joiner.join("Three."), "Three.");
+    }
+
     #[test]
     fn test_state_transitions() {
-        let state = Arc::new(Mutex::new(AlwaysListenState::Listening));
-        
-        *state.lock() = AlwaysListenState::Recording { since: Instant::now() };
-        assert_eq!(state.lock().name(), "Recording");
+        let (state, changes) = SharedState::new(AlwaysListenState::Listening);
+
+        state.set(AlwaysListenState::Recording { since: Instant::now() });
+        assert_eq!(state.get().name(), "Recording");
 
-        *state.lock() = AlwaysListenState::Processing;
-        assert_eq!(state.lock().name(), "Processing");
+        state.set(AlwaysListenState::Processing);
+        assert_eq!(state.get().name(), "Processing");
+
+        // Only changes are reported
+        state.set(AlwaysListenState::Processing);
+        let reported: Vec<_> = changes.try_iter().map(|s| s.name()).collect();
+        assert_eq!(reported, ["Recording", "Processing"]);
+
+        // A full channel drops changes rather than blocking the processing thread
+        for _ in 0..STATE_CHANGE_CAPACITY {
+            state.set(AlwaysListenState::Listening);
+            state.set(AlwaysListenState::Paused);
+        }
+        assert_eq!(state.get(), AlwaysListenState::Paused);
+        assert_eq!(changes.len(), STATE_CHANGE_CAPACITY);
     }
 }
@@ -1,19 +1,166 @@
 use anyhow::{Context, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{Device, SampleFormat, Stream, StreamConfig};
+use cpal::{
+    BuildStreamError, Device, SampleFormat, Stream, StreamConfig, StreamError,
+    SupportedStreamConfigRange,
+};
 use parking_lot::Mutex;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 
-const TARGET_SAMPLE_RATE: u32 = 16000;
+// Preferred capture rate; audio is resampled to the backend's rate afterwards
+const PREFERRED_CAPTURE_RATE: u32 = 16000;
+
+/// Sample formats the input callbacks can convert, most preferred first
+const CAPTURE_FORMATS: [SampleFormat; 3] = [SampleFormat::F32, SampleFormat::I16, SampleFormat::U16];
+
+/// Called from the audio thread when a recording hits its length limit
+pub type RecordingLimitCallback = Arc<dyn Fn() + Send + Sync>;
 
 pub struct AudioCapture {
+    /// Input device requested in the config (None = system default)
+    device_name: Option<String>,
     device: Device,
     config: StreamConfig,
+    /// Format the input stream delivers samples in
+    sample_format: SampleFormat,
     recording: Arc<AtomicBool>,
     buffer: Arc<Mutex<Vec<f32>>>,
     stream: Option<Stream>,
+    /// Rate the loaded backend wants audio resampled to
+    target_sample_rate: u32,
+    /// Maximum push-to-talk buffer length in samples (0 = unlimited)
+    max_samples: usize,
+    on_limit: Option<RecordingLimitCallback>,
+    /// RMS of the latest input chunk (f32 bits), for the overlay's level meter
+    level: Arc<AtomicU32>,
+    /// Set by a stream error (e.g. the mic was unplugged); cleared by `reconnect`
+    device_lost: Arc<AtomicBool>,
+    /// Clean-up applied to push-to-talk recordings in `stop_recording`
+    preprocess: PreprocessOptions,
+    /// Audio from just before `start_recording`, prepended to the recording
+    pre_roll: Arc<Mutex<PreRollBuffer>>,
+    /// Length of the pre-roll (0 = off; otherwise the stream stays open between recordings)
+    pre_roll_ms: u64,
+}
+
+/// Publish the RMS of an input chunk for the level meter
+fn store_level(level: &AtomicU32, samples: &[f32]) {
+    let rms = crate::always_listen::frame_rms(samples);
+    level.store(rms.to_bits(), Ordering::Relaxed);
+}
+
+/// Sample format to capture in: the device default if the callbacks can convert it,
+/// otherwise the most preferred of `CAPTURE_FORMATS` among the formats the device offers
+fn choose_sample_format(default: SampleFormat, offered: &[SampleFormat]) -> Option<SampleFormat> {
+    if CAPTURE_FORMATS.contains(&default) {
+        return Some(default);
+    }
+    CAPTURE_FORMATS.into_iter().find(|format| offered.contains(format))
+}
+
+/// Append samples to a recording buffer without exceeding `max_samples`
+/// (0 = unlimited). Returns true once the buffer is full.
+pub fn append_capped(buffer: &mut Vec<f32>, samples: &[f32], max_samples: usize) -> bool {
+    if max_samples == 0 {
+        buffer.extend_from_slice(samples);
+        return false;
+    }
+
+    let room = max_samples.saturating_sub(buffer.len());
+    buffer.extend_from_slice(&samples[..samples.len().min(room)]);
+    buffer.len() >= max_samples
+}
+
+/// Fixed-size circular buffer holding the most recent samples
+pub struct PreRollBuffer {
+    samples: Vec<f32>,
+    pos: usize,
+    full: bool,
+}
+
+impl PreRollBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self { samples: vec![0.0; capacity], pos: 0, full: false }
+    }
+
+    /// Add samples, overwriting the oldest once full (a no-op with no capacity)
+    pub fn push(&mut self, samples: &[f32]) {
+        if self.samples.is_empty() {
+            return;
+        }
+        for &sample in samples {
+            self.samples[self.pos] = sample;
+            self.pos += 1;
+            if self.pos == self.samples.len() {
+                self.pos = 0;
+                self.full = true;
+            }
+        }
+    }
+
+    /// The buffered samples, oldest first
+    pub fn ordered(&self) -> Vec<f32> {
+        if !self.full {
+            return self.samples[..self.pos].to_vec();
+        }
+        let mut ordered = Vec::with_capacity(self.samples.len());
+        ordered.extend_from_slice(&self.samples[self.pos..]);
+        ordered.extend_from_slice(&self.samples[..self.pos]);
+        ordered
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.fill(0.0);
+        self.pos = 0;
+        self.full = false;
+    }
+}
+
+/// Push-to-talk capture target shared with the audio callback
+struct RecordingSink {
+    buffer: Arc<Mutex<Vec<f32>>>,
+    recording: Arc<AtomicBool>,
+    /// Filled instead of `buffer` between recordings
+    pre_roll: Arc<Mutex<PreRollBuffer>>,
+    max_samples: usize,
+    on_limit: Option<RecordingLimitCallback>,
+}
+
+impl RecordingSink {
+    fn push(&self, samples: &[f32]) {
+        {
+            // Checked under the lock so `start_recording` can't miss samples
+            let mut pre_roll = self.pre_roll.lock();
+            if !self.recording.load(Ordering::SeqCst) {
+                pre_roll.push(samples);
+                return;
+            }
+        }
+
+        let full = append_capped(&mut self.buffer.lock(), samples, self.max_samples);
+        // Stop accumulating and notify once; the main thread finalizes the recording
+        if full && self.recording.swap(false, Ordering::SeqCst) {
+            warn!("Recording limit reached ({} samples), auto-stopping", self.max_samples);
+            if let Some(on_limit) = &self.on_limit {
+                on_limit();
+            }
+        }
+    }
+}
+
+/// Start a recording with the pre-roll's audio, which the stream stops filling
+fn start_from_pre_roll(
+    pre_roll: &Mutex<PreRollBuffer>,
+    buffer: &Mutex<Vec<f32>>,
+    recording: &AtomicBool,
+) {
+    let mut pre_roll = pre_roll.lock();
+    *buffer.lock() = pre_roll.ordered();
+    pre_roll.clear();
+    recording.store(true, Ordering::SeqCst);
 }
 
 impl AudioCapture {
@@ -56,131 +203,325 @@ impl AudioCapture {
 
         debug!("Default config: {:?}", supported_config);
 
-        // Try to use 16kHz mono, fall back to device default
-        let config = StreamConfig {
-            channels: 1,
-            sample_rate: cpal::SampleRate(TARGET_SAMPLE_RATE),
-            buffer_size: cpal::BufferSize::Default,
+        let ranges: Vec<SupportedStreamConfigRange> = match device.supported_input_configs() {
+            Ok(configs) => configs.collect(),
+            Err(e) => {
+                debug!("Failed to list supported input configs: {}", e);
+                Vec::new()
+            }
         };
 
-        // Check if the device supports our desired config
-        let config = match device.supported_input_configs() {
-            Ok(mut configs) => {
-                let supports_16k = configs.any(|c| {
-                    c.channels() >= 1
-                        && c.min_sample_rate().0 <= TARGET_SAMPLE_RATE
-                        && c.max_sample_rate().0 >= TARGET_SAMPLE_RATE
-                });
-
-                if supports_16k {
-                    debug!("Using 16kHz mono");
-                    config
-                } else {
-                    debug!(
-                        "Device doesn't support 16kHz, using default: {}Hz {}ch",
-                        supported_config.sample_rate().0,
-                        supported_config.channels()
-                    );
-                    StreamConfig {
-                        channels: supported_config.channels(),
-                        sample_rate: supported_config.sample_rate(),
-                        buffer_size: cpal::BufferSize::Default,
-                    }
-                }
+        // Fall back to another format the device offers if we can't convert the default one
+        let default_format = supported_config.sample_format();
+        let offered: Vec<SampleFormat> = ranges.iter().map(|c| c.sample_format()).collect();
+        let sample_format = choose_sample_format(default_format, &offered).with_context(|| {
+            format!("Unsupported sample format {:?} (need f32, i16 or u16)", default_format)
+        })?;
+        if sample_format != default_format {
+            warn!(
+                "Device default format {:?} isn't supported, capturing as {:?}",
+                default_format, sample_format
+            );
+        }
+        let format_ranges: Vec<&SupportedStreamConfigRange> =
+            ranges.iter().filter(|c| c.sample_format() == sample_format).collect();
+
+        // Try to use 16kHz mono, fall back to device default
+        let supports_16k = format_ranges.iter().any(|c| {
+            c.channels() >= 1
+                && c.min_sample_rate().0 <= PREFERRED_CAPTURE_RATE
+                && c.max_sample_rate().0 >= PREFERRED_CAPTURE_RATE
+        });
+        let config = if supports_16k {
+            debug!("Using 16kHz mono");
+            StreamConfig {
+                channels: 1,
+                sample_rate: cpal::SampleRate(PREFERRED_CAPTURE_RATE),
+                buffer_size: cpal::BufferSize::Default,
             }
-            Err(_) => {
-                debug!("Using default config");
-                StreamConfig {
-                    channels: supported_config.channels(),
-                    sample_rate: supported_config.sample_rate(),
-                    buffer_size: cpal::BufferSize::Default,
-                }
+        } else if sample_format == default_format || format_ranges.is_empty() {
+            debug!(
+                "Device doesn't support 16kHz, using default: {}Hz {}ch",
+                supported_config.sample_rate().0,
+                supported_config.channels()
+            );
+            StreamConfig {
+                channels: supported_config.channels(),
+                sample_rate: supported_config.sample_rate(),
+                buffer_size: cpal::BufferSize::Default,
+            }
+        } else {
+            // The default rate may not be offered in the fallback format
+            let range = format_ranges[0];
+            let rate = supported_config
+                .sample_rate()
+                .0
+                .clamp(range.min_sample_rate().0, range.max_sample_rate().0);
+            StreamConfig {
+                channels: range.channels(),
+                sample_rate: cpal::SampleRate(rate),
+                buffer_size: cpal::BufferSize::Default,
             }
         };
 
+        if config.sample_rate.0 < PREFERRED_CAPTURE_RATE {
+            warn!(
+                "Input device only captures {}Hz audio - transcription accuracy may suffer",
+                config.sample_rate.0
+            );
+        }
+
         Ok(Self {
+            device_name: device_name.map(str::to_string),
             device,
             config,
+            sample_format,
             recording: Arc::new(AtomicBool::new(false)),
             buffer: Arc::new(Mutex::new(Vec::new())),
             stream: None,
+            target_sample_rate: crate::backend_loader::DEFAULT_SAMPLE_RATE,
+            max_samples: 0,
+            on_limit: None,
+            level: Arc::new(AtomicU32::new(0)),
+            device_lost: Arc::new(AtomicBool::new(false)),
+            preprocess: PreprocessOptions::default(),
+            pre_roll: Arc::new(Mutex::new(PreRollBuffer::new(0))),
+            pre_roll_ms: 0,
         })
     }
 
+    /// Open the configured device again after it was lost, keeping the
+    /// recording settings. Falls back to the default device like `new_with_device`.
+    pub fn reconnect(&mut self) -> Result<()> {
+        let fresh = Self::new_with_device(self.device_name.as_deref())?;
+        self.recording.store(false, Ordering::SeqCst);
+        self.stream = None;
+        self.device = fresh.device;
+        self.config = fresh.config;
+        self.sample_format = fresh.sample_format;
+        self.device_lost.store(false, Ordering::SeqCst);
+        info!("Audio device reconnected: {}", self.device.name().unwrap_or_default());
+        self.restart_pre_roll()
+    }
+
+    /// Flag raised when a stream reports an error, shared with watcher threads
+    pub fn device_lost_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.device_lost)
+    }
+
+    pub fn is_device_lost(&self) -> bool {
+        self.device_lost.load(Ordering::SeqCst)
+    }
+
+    /// Stream error callback: log and mark the device as lost (cpal streams stop after an error)
+    fn stream_error_handler(
+        &self,
+        context: &'static str,
+    ) -> impl FnMut(StreamError) + Send + 'static {
+        let device_lost = Arc::clone(&self.device_lost);
+        move |err| {
+            error!("{} audio stream error: {}", context, err);
+            device_lost.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Mark the device as lost if a stream couldn't be built because it's gone
+    fn check_built(&self, stream: Result<Stream, BuildStreamError>) -> Result<Stream> {
+        if let Err(BuildStreamError::DeviceNotAvailable) = stream {
+            self.device_lost.store(true, Ordering::SeqCst);
+        }
+        Ok(stream?)
+    }
+
+    /// Cap push-to-talk recordings at `max_samples` (0 = unlimited) and call
+    /// `on_limit` when a recording is auto-stopped
+    pub fn set_recording_limit(&mut self, max_samples: usize, on_limit: Option<RecordingLimitCallback>) {
+        self.max_samples = max_samples;
+        self.on_limit = on_limit;
+        self.pre_roll_settings_changed();
+    }
+
+    /// Filter/normalize push-to-talk recordings before they are returned
+    pub fn set_preprocess(&mut self, options: PreprocessOptions) {
+        self.preprocess = options;
+    }
+
+    /// Live input level (RMS as f32 bits), updated while recording or listening
+    pub fn level_meter(&self) -> Arc<AtomicU32> {
+        Arc::clone(&self.level)
+    }
+
+    /// Resample captured audio to `sample_rate` (the loaded backend's required rate)
+    pub fn set_target_sample_rate(&mut self, sample_rate: u32) {
+        self.target_sample_rate = sample_rate;
+        self.pre_roll_settings_changed();
+    }
+
+    /// Keep the last `ms` of audio between push-to-talk recordings and prepend it
+    /// to the next one, so a word started just before the key press isn't cut off.
+    /// The input stream then stays open while idle. 0 turns it off.
+    pub fn set_pre_roll(&mut self, ms: u64) -> Result<()> {
+        self.pre_roll_ms = ms;
+        self.restart_pre_roll()
+    }
+
+    /// Rebuild the idle stream so it uses changed settings
+    fn pre_roll_settings_changed(&mut self) {
+        if self.pre_roll_ms > 0 {
+            if let Err(e) = self.restart_pre_roll() {
+                warn!("Failed to restart the pre-roll stream: {}", e);
+            }
+        }
+    }
+
+    /// (Re)start the stream that fills the pre-roll between recordings, or stop it
+    /// if pre-roll is off. A recording in progress is left alone.
+    fn restart_pre_roll(&mut self) -> Result<()> {
+        if self.is_recording() {
+            return Ok(());
+        }
+        self.stream = None;
+        let capacity = self.target_sample_rate as usize * self.pre_roll_ms as usize / 1000;
+        *self.pre_roll.lock() = PreRollBuffer::new(capacity);
+        if self.pre_roll_ms == 0 || self.is_device_lost() {
+            return Ok(());
+        }
+        let stream = self.build_stream()?;
+        stream.play()?;
+        self.stream = Some(stream);
+        Ok(())
+    }
+
+    /// Rate the device actually captures at (before resampling)
+    pub fn effective_sample_rate(&self) -> u32 {
+        self.config.sample_rate.0
+    }
+
+    /// Name of the device being captured from (the default one if the requested
+    /// device wasn't found)
+    pub fn current_device_name(&self) -> String {
+        self.device.name().unwrap_or_default()
+    }
+
+    /// True if the device captures below the backend's rate, so upsampling
+    /// can't restore the missing high frequencies
+    pub fn is_low_sample_rate(&self) -> bool {
+        self.effective_sample_rate() < self.target_sample_rate
+    }
+
+    /// Start push-to-talk capture, resampling to the target sample rate. With a
+    /// pre-roll the recording starts with the audio buffered just before.
     pub fn start_recording(&mut self) -> Result<()> {
         if self.recording.load(Ordering::SeqCst) {
             return Ok(());
         }
+        if self.is_device_lost() {
+            anyhow::bail!("Microphone disconnected");
+        }
+
+        // The pre-roll stream is already running: switch it over to the recording
+        if self.stream.is_some() {
+            start_from_pre_roll(&self.pre_roll, &self.buffer, &self.recording);
+            return Ok(());
+        }
 
         self.buffer.lock().clear();
         self.recording.store(true, Ordering::SeqCst);
+        let stream = self.build_stream().and_then(|stream| {
+            stream.play()?;
+            Ok(stream)
+        });
+        match stream {
+            Ok(stream) => self.stream = Some(stream),
+            Err(e) => {
+                self.recording.store(false, Ordering::SeqCst);
+                return Err(e);
+            }
+        }
 
-        let buffer = Arc::clone(&self.buffer);
-        let recording = Arc::clone(&self.recording);
+        Ok(())
+    }
+
+    /// Input stream feeding push-to-talk recordings (and the pre-roll between them)
+    fn build_stream(&self) -> Result<Stream> {
+        let sink = RecordingSink {
+            buffer: Arc::clone(&self.buffer),
+            recording: Arc::clone(&self.recording),
+            pre_roll: Arc::clone(&self.pre_roll),
+            max_samples: self.max_samples,
+            on_limit: self.on_limit.clone(),
+        };
         let source_sample_rate = self.config.sample_rate.0;
         let channels = self.config.channels as usize;
+        let target_sample_rate = self.target_sample_rate;
+        let level = Arc::clone(&self.level);
 
-        debug!("Starting audio stream: {}Hz, {} channels", source_sample_rate, channels);
+        debug!(
+            "Starting audio stream: {}Hz, {} channels (target {}Hz)",
+            source_sample_rate, channels, target_sample_rate
+        );
 
-        let err_fn = |err| error!("Audio stream error: {}", err);
+        let err_fn = self.stream_error_handler("Recording");
+        let mut resampler = StreamResampler::new(source_sample_rate, target_sample_rate);
+        let mut on_input = move |mono_data: Vec<f32>| {
+            // The level meter only moves while recording
+            if sink.recording.load(Ordering::SeqCst) {
+                store_level(&level, &mono_data);
+            }
+            sink.push(&resampler.process(&mono_data));
+        };
 
-        let stream = match self.device.default_input_config()?.sample_format() {
+        let stream = match self.sample_format {
             SampleFormat::F32 => self.device.build_input_stream(
                 &self.config,
-                move |data: &[f32], _| {
-                    if recording.load(Ordering::SeqCst) {
-                        let mono_data = convert_to_mono(data, channels);
-                        let resampled = resample(&mono_data, source_sample_rate, TARGET_SAMPLE_RATE);
-                        buffer.lock().extend(resampled);
-                    }
-                },
+                move |data: &[f32], _| on_input(convert_to_mono(data, channels)),
                 err_fn,
                 None,
-            )?,
+            ),
             SampleFormat::I16 => self.device.build_input_stream(
                 &self.config,
                 move |data: &[i16], _| {
-                    if recording.load(Ordering::SeqCst) {
-                        let float_data: Vec<f32> =
-                            data.iter().map(|&s| s as f32 / i16::MAX as f32).collect();
-                        let mono_data = convert_to_mono(&float_data, channels);
-                        let resampled = resample(&mono_data, source_sample_rate, TARGET_SAMPLE_RATE);
-                        buffer.lock().extend(resampled);
-                    }
+                    let float_data: Vec<f32> =
+                        data.iter().map(|&s| s as f32 / i16::MAX as f32).collect();
+                    on_input(convert_to_mono(&float_data, channels))
                 },
                 err_fn,
                 None,
-            )?,
+            ),
             SampleFormat::U16 => self.device.build_input_stream(
                 &self.config,
                 move |data: &[u16], _| {
-                    if recording.load(Ordering::SeqCst) {
-                        let float_data: Vec<f32> = data
-                            .iter()
-                            .map(|&s| (s as f32 / u16::MAX as f32) * 2.0 - 1.0)
-                            .collect();
-                        let mono_data = convert_to_mono(&float_data, channels);
-                        let resampled = resample(&mono_data, source_sample_rate, TARGET_SAMPLE_RATE);
-                        buffer.lock().extend(resampled);
-                    }
+                    let float_data: Vec<f32> = data
+                        .iter()
+                        .map(|&s| (s as f32 / u16::MAX as f32) * 2.0 - 1.0)
+                        .collect();
+                    on_input(convert_to_mono(&float_data, channels))
                 },
                 err_fn,
                 None,
-            )?,
+            ),
             _ => return Err(anyhow::anyhow!("Unsupported sample format")),
         };
-
-        stream.play()?;
-        self.stream = Some(stream);
-
-        Ok(())
+        self.check_built(stream)
     }
 
-    pub fn stop_recording(&mut self) -> Vec<f32> {
+    /// Stop push-to-talk capture and return the audio (empty if nothing was captured).
+    /// Fails if the device was lost, since the recording is cut short.
+    pub fn stop_recording(&mut self) -> Result<Vec<f32>> {
         self.recording.store(false, Ordering::SeqCst);
-        self.stream = None;
+        // With a pre-roll the stream keeps running to fill it for the next recording
+        if self.pre_roll_ms == 0 {
+            self.stream = None;
+        }
+        self.level.store(0, Ordering::Relaxed);
 
         let audio = std::mem::take(&mut *self.buffer.lock());
+        if self.is_device_lost() {
+            anyhow::bail!(
+                "Microphone disconnected during recording ({} samples discarded)",
+                audio.len()
+            );
+        }
 
         // Calculate audio stats
         if !audio.is_empty() {
@@ -189,7 +530,7 @@ impl AudioCapture {
             debug!(
                 "Audio captured: {} samples ({:.1}s), max={:.3}, rms={:.3}",
                 audio.len(),
-                audio.len() as f32 / 16000.0,
+                audio.len() as f32 / self.target_sample_rate as f32,
                 max_val,
                 rms
             );
@@ -201,35 +542,41 @@ impl AudioCapture {
             warn!("No audio captured!");
         }
 
-        audio
+        if self.preprocess.is_enabled() {
+            return Ok(preprocess(&audio, &self.preprocess));
+        }
+        Ok(audio)
     }
 
-    #[allow(dead_code)]
     pub fn is_recording(&self) -> bool {
         self.recording.load(Ordering::SeqCst)
     }
 
     /// Create a continuous audio stream for always-listen mode
-    /// Returns a stream that sends audio chunks to the provided channel
+    /// Returns a stream that sends audio chunks (at the target sample rate) to the provided channel
     pub fn create_always_listen_stream(
         &self,
         audio_tx: crossbeam_channel::Sender<Vec<f32>>,
         running: Arc<AtomicBool>,
     ) -> Result<Stream> {
         let source_sample_rate = self.config.sample_rate.0;
+        let target_sample_rate = self.target_sample_rate;
         let channels = self.config.channels as usize;
+        let level = Arc::clone(&self.level);
 
         info!("Creating always-listen audio stream: {}Hz, {} channels", source_sample_rate, channels);
 
-        let err_fn = |err| error!("Always-listen audio stream error: {}", err);
+        let err_fn = self.stream_error_handler("Always-listen");
+        let mut resampler = StreamResampler::new(source_sample_rate, target_sample_rate);
 
-        let stream = match self.device.default_input_config()?.sample_format() {
+        let stream = match self.sample_format {
             SampleFormat::F32 => self.device.build_input_stream(
                 &self.config,
                 move |data: &[f32], _| {
                     if running.load(Ordering::SeqCst) {
                         let mono_data = convert_to_mono(data, channels);
-                        let resampled = resample(&mono_data, source_sample_rate, TARGET_SAMPLE_RATE);
+                        store_level(&level, &mono_data);
+                        let resampled = resampler.process(&mono_data);
                         // Send audio chunk to always-listen controller
                         if audio_tx.send(resampled).is_err() {
                             // Channel closed, stop sending
@@ -238,7 +585,7 @@ impl AudioCapture {
                 },
                 err_fn,
                 None,
-            )?,
+            ),
             SampleFormat::I16 => self.device.build_input_stream(
                 &self.config,
                 move |data: &[i16], _| {
@@ -246,7 +593,8 @@ impl AudioCapture {
                         let float_data: Vec<f32> =
                             data.iter().map(|&s| s as f32 / i16::MAX as f32).collect();
                         let mono_data = convert_to_mono(&float_data, channels);
-                        let resampled = resample(&mono_data, source_sample_rate, TARGET_SAMPLE_RATE);
+                        store_level(&level, &mono_data);
+                        let resampled = resampler.process(&mono_data);
                         if audio_tx.send(resampled).is_err() {
                             // Channel closed, stop sending
                         }
@@ -254,7 +602,7 @@ impl AudioCapture {
                 },
                 err_fn,
                 None,
-            )?,
+            ),
             SampleFormat::U16 => self.device.build_input_stream(
                 &self.config,
                 move |data: &[u16], _| {
@@ -264,7 +612,8 @@ impl AudioCapture {
                             .map(|&s| (s as f32 / u16::MAX as f32) * 2.0 - 1.0)
                             .collect();
                         let mono_data = convert_to_mono(&float_data, channels);
-                        let resampled = resample(&mono_data, source_sample_rate, TARGET_SAMPLE_RATE);
+                        store_level(&level, &mono_data);
+                        let resampled = resampler.process(&mono_data);
                         if audio_tx.send(resampled).is_err() {
                             // Channel closed, stop sending
                         }
@@ -272,11 +621,11 @@ impl AudioCapture {
                 },
                 err_fn,
                 None,
-            )?,
+            ),
             _ => return Err(anyhow::anyhow!("Unsupported sample format")),
         };
 
-        Ok(stream)
+        self.check_built(stream)
     }
 }
 
@@ -290,11 +639,357 @@ fn convert_to_mono(data: &[f32], channels: usize) -> Vec<f32> {
         .collect()
 }
 
-fn resample(data: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+/// WAV format tag for integer PCM
+const WAVE_FORMAT_PCM: u16 = 1;
+/// WAV format tag for floating-point samples
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+/// WAV format tag whose real format is in the extension's sub-format GUID
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// Interleaved samples and format of a decoded audio file
+struct AudioData {
+    samples: Vec<f32>,
+    channels: u16,
+    sample_rate: u32,
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+/// Parse a RIFF/WAVE file holding 16/24-bit integer PCM or 32-bit float samples
+fn parse_wav(bytes: &[u8]) -> Result<AudioData> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        anyhow::bail!("Not a WAV file");
+    }
+
+    let mut format: Option<(u16, u16, u32, u16)> = None;
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let size = read_u32(bytes, pos + 4) as usize;
+        let body = pos + 8;
+        let end = body.saturating_add(size).min(bytes.len());
+
+        if id == b"fmt " {
+            if end - body < 16 {
+                anyhow::bail!("WAV fmt chunk is too short");
+            }
+            let mut tag = read_u16(bytes, body);
+            if tag == WAVE_FORMAT_EXTENSIBLE && end - body >= 26 {
+                // First two bytes of the sub-format GUID are the real format tag
+                tag = read_u16(bytes, body + 24);
+            }
+            format = Some((
+                tag,
+                read_u16(bytes, body + 2),
+                read_u32(bytes, body + 4),
+                read_u16(bytes, body + 14),
+            ));
+        } else if id == b"data" {
+            let (tag, channels, sample_rate, bits) =
+                format.context("WAV data chunk appears before the fmt chunk")?;
+            if channels == 0 || sample_rate == 0 {
+                anyhow::bail!("Invalid WAV header ({} channels, {}Hz)", channels, sample_rate);
+            }
+            let data = &bytes[body..end];
+            let samples = match (tag, bits) {
+                (WAVE_FORMAT_PCM, 16) => data
+                    .chunks_exact(2)
+                    .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+                    .collect(),
+                (WAVE_FORMAT_PCM, 24) => data
+                    .chunks_exact(3)
+                    // Place the 24 bits at the top of an i32 so the sign extends
+                    .map(|b| (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f32 / 8_388_608.0)
+                    .collect(),
+                (WAVE_FORMAT_IEEE_FLOAT, 32) => data
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect(),
+                (WAVE_FORMAT_PCM, _) | (WAVE_FORMAT_IEEE_FLOAT, _) => anyhow::bail!(
+                    "Unsupported WAV bit depth {} (use 16/24-bit PCM or 32-bit float)",
+                    bits
+                ),
+                // ADPCM (2, 0x11), mu-law/A-law, MP3 in WAV, ...
+                _ => anyhow::bail!(
+                    "Compressed WAV (format tag {:#06x}) is not supported; convert it to PCM first",
+                    tag
+                ),
+            };
+            return Ok(AudioData {
+                samples,
+                channels,
+                sample_rate,
+            });
+        }
+
+        // Chunks are padded to an even length
+        pos = body.saturating_add(size + (size & 1));
+    }
+
+    anyhow::bail!("WAV file has no data chunk")
+}
+
+/// Audio file formats `decode` understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AudioFormat {
+    Wav,
+    Flac,
+    OggVorbis,
+}
+
+impl AudioFormat {
+    /// Format from a file's magic bytes, or failing that its extension
+    fn detect(bytes: &[u8], extension: Option<&str>) -> Option<Self> {
+        if bytes.starts_with(b"RIFF") {
+            return Some(Self::Wav);
+        }
+        if bytes.starts_with(b"fLaC") {
+            return Some(Self::Flac);
+        }
+        if bytes.starts_with(b"OggS") {
+            return Some(Self::OggVorbis);
+        }
+        match extension?.to_ascii_lowercase().as_str() {
+            "wav" | "wave" => Some(Self::Wav),
+            "flac" => Some(Self::Flac),
+            "ogg" | "oga" => Some(Self::OggVorbis),
+            _ => None,
+        }
+    }
+}
+
+/// Decode FLAC or Ogg Vorbis. A file that is cut short or damaged part way
+/// through gives the audio before the damage.
+fn decode_compressed(bytes: &[u8], format: AudioFormat) -> Result<AudioData> {
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+    use symphonia::core::errors::Error;
+    use symphonia::core::formats::FormatOptions;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::MetadataOptions;
+    use symphonia::core::probe::Hint;
+
+    let source = std::io::Cursor::new(bytes.to_vec());
+    let source = MediaSourceStream::new(Box::new(source), Default::default());
+    let mut hint = Hint::new();
+    hint.with_extension(if format == AudioFormat::Flac { "flac" } else { "ogg" });
+    let mut reader = symphonia::default::get_probe()
+        .format(&hint, source, &FormatOptions::default(), &MetadataOptions::default())
+        .context("Unreadable FLAC/Ogg header")?
+        .format;
+    let track = reader
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .context("File has no audio track")?;
+    let track_id = track.id;
+    let mut channels = track.codec_params.channels.map(|c| c.count());
+    let mut sample_rate = track.codec_params.sample_rate;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .context("Unsupported codec (Ogg files must contain Vorbis audio)")?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match reader.next_packet() {
+            Ok(packet) => packet,
+            Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) if !samples.is_empty() => {
+                warn!("Audio file is damaged, using the part before it: {}", e);
+                break;
+            }
+            Err(e) => return Err(e).context("Failed to read audio"),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            Err(Error::DecodeError(e)) => {
+                warn!("Skipping a damaged audio packet: {}", e);
+                continue;
+            }
+            Err(e) => return Err(e).context("Failed to decode audio"),
+        };
+        let spec = *decoded.spec();
+        channels = Some(spec.channels.count());
+        sample_rate = Some(spec.rate);
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend_from_slice(buffer.samples());
+    }
+
+    let channels = channels.filter(|&c| c > 0).context("Audio has no channels")?;
+    let sample_rate = sample_rate.filter(|&r| r > 0).context("Audio has no sample rate")?;
+    Ok(AudioData {
+        samples,
+        channels: channels as u16,
+        sample_rate,
+    })
+}
+
+/// Decode a WAV, FLAC or Ogg Vorbis file held in memory to mono f32 samples at
+/// `target_sample_rate`. `extension` helps identify files with unusual headers.
+fn decode_as(bytes: &[u8], extension: Option<&str>, target_sample_rate: u32) -> Result<Vec<f32>> {
+    let audio = match AudioFormat::detect(bytes, extension) {
+        Some(AudioFormat::Wav) => parse_wav(bytes)?,
+        Some(format) => decode_compressed(bytes, format)?,
+        None => anyhow::bail!(
+            "Unsupported audio format{}; use WAV, FLAC or Ogg Vorbis",
+            extension.map(|e| format!(" (.{})", e)).unwrap_or_default()
+        ),
+    };
+    let mono = convert_to_mono(&audio.samples, audio.channels as usize);
+    Ok(resample(&mono, audio.sample_rate, target_sample_rate))
+}
+
+/// Read a WAV, FLAC or Ogg Vorbis file as mono f32 samples at `target_sample_rate`
+pub fn decode(path: &Path, target_sample_rate: u32) -> Result<Vec<f32>> {
+    let bytes = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let extension = path.extension().and_then(|e| e.to_str());
+    decode_as(&bytes, extension, target_sample_rate)
+        .with_context(|| format!("Failed to decode {}", path.display()))
+}
+
+/// Decode an in-memory WAV, FLAC or Ogg Vorbis file to mono f32 samples at
+/// `target_sample_rate`
+pub fn decode_bytes(bytes: &[u8], target_sample_rate: u32) -> Result<Vec<f32>> {
+    decode_as(bytes, None, target_sample_rate)
+}
+
+/// Sinc zero crossings on each side of the downsampling filter (at the output rate)
+const SINC_ZERO_CROSSINGS: f64 = 16.0;
+/// Filter cutoff as a fraction of the output Nyquist, leaving room for the transition band
+const SINC_CUTOFF: f64 = 0.92;
+
+/// Resample to `to_rate`: low-pass filtered (windowed sinc) when downsampling
+/// so content above the new Nyquist doesn't alias, linear when upsampling.
+/// Each call is independent, so the filter sees edge samples repeated at the
+/// chunk boundaries; live capture uses `StreamResampler` instead.
+pub fn resample(data: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
     if from_rate == to_rate {
         return data.to_vec();
     }
+    if to_rate > from_rate {
+        return resample_linear(data, from_rate, to_rate);
+    }
+
+    let ratio = to_rate as f64 / from_rate as f64;
+    let new_len = (data.len() as f64 * ratio) as usize;
+    let last = data.len() as isize - 1;
+    let sample = |j: isize| data[j.clamp(0, last) as usize];
+    (0..new_len).map(|i| sinc_sample(i as f64 / ratio, ratio, &sample)).collect()
+}
+
+/// Filter half-width in input samples when downsampling by `ratio`
+fn sinc_half_width(ratio: f64) -> f64 {
+    SINC_ZERO_CROSSINGS / ratio
+}
+
+/// One low-pass filtered output sample centred on input position `center`.
+/// `sample` reads input sample `j`, dealing with the edges.
+fn sinc_sample(center: f64, ratio: f64, sample: &impl Fn(isize) -> f32) -> f32 {
+    let cutoff = ratio * SINC_CUTOFF;
+    let half_width = sinc_half_width(ratio);
+    let first = (center - half_width).ceil() as isize;
+    let end = (center + half_width).floor() as isize;
+
+    let mut sum = 0.0f64;
+    let mut weight_sum = 0.0f64;
+    for j in first..=end {
+        let x = center - j as f64;
+        let weight = sinc(cutoff * x) * blackman(x / half_width);
+        sum += sample(j) as f64 * weight;
+        weight_sum += weight;
+    }
+    if weight_sum != 0.0 {
+        (sum / weight_sum) as f32
+    } else {
+        0.0
+    }
+}
+
+/// Resamples a live stream that arrives in small chunks (one per audio callback).
+/// Unlike `resample` it carries the input the filter still needs and the output
+/// position from one chunk to the next, so chunk boundaries leave no edge
+/// artifacts and no rounding drift. Output lags input by the filter's reach
+/// (about 1ms when downsampling).
+pub struct StreamResampler {
+    from_rate: u32,
+    to_rate: u32,
+    /// Input still needed; `pending[0]` is input sample number `first_input`
+    pending: Vec<f32>,
+    first_input: u64,
+    /// Number of the next output sample
+    next_output: u64,
+}
+
+impl StreamResampler {
+    pub fn new(from_rate: u32, to_rate: u32) -> Self {
+        Self {
+            from_rate,
+            to_rate,
+            pending: Vec::new(),
+            first_input: 0,
+            next_output: 0,
+        }
+    }
+
+    /// Resample the next chunk of the stream
+    pub fn process(&mut self, data: &[f32]) -> Vec<f32> {
+        if self.from_rate == self.to_rate {
+            return data.to_vec();
+        }
+        self.pending.extend_from_slice(data);
+
+        let ratio = self.to_rate as f64 / self.from_rate as f64;
+        let downsampling = ratio < 1.0;
+        // How far before and after its centre an output sample reads (linear
+        // interpolation reads the samples either side)
+        let (behind, ahead) = if downsampling {
+            (sinc_half_width(ratio), sinc_half_width(ratio))
+        } else {
+            (1.0, 1.0)
+        };
+        let available = self.first_input + self.pending.len() as u64;
+        let first_input = self.first_input;
+        let pending = &self.pending;
+        // Before the first sample, repeat it like `resample` does
+        let sample = |j: isize| pending[(j.max(0) as u64 - first_input) as usize];
+
+        let mut result = Vec::new();
+        loop {
+            let center = self.next_output as f64 / ratio;
+            if (center + ahead).floor() as u64 >= available {
+                break;
+            }
+            result.push(if downsampling {
+                sinc_sample(center, ratio, &sample)
+            } else {
+                let idx = center.floor();
+                let frac = (center - idx) as f32;
+                sample(idx as isize) * (1.0 - frac) + sample(idx as isize + 1) * frac
+            });
+            self.next_output += 1;
+        }
+
+        // Drop input that no later output reaches back to
+        let center = self.next_output as f64 / ratio;
+        let needed = (center - behind).ceil().max(0.0) as u64;
+        let done = needed.saturating_sub(self.first_input).min(self.pending.len() as u64);
+        self.pending.drain(..done as usize);
+        self.first_input += done;
+        result
+    }
+}
 
+fn resample_linear(data: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
     let ratio = to_rate as f64 / from_rate as f64;
     let new_len = (data.len() as f64 * ratio) as usize;
     let mut result = Vec::with_capacity(new_len);
@@ -318,6 +1013,182 @@ fn resample(data: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
     result
 }
 
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 {
+        1.0
+    } else {
+        let px = std::f64::consts::PI * x;
+        px.sin() / px
+    }
+}
+
+/// Blackman window over `x` in [-1, 1] (zero outside)
+fn blackman(x: f64) -> f64 {
+    if x.abs() >= 1.0 {
+        return 0.0;
+    }
+    let phase = std::f64::consts::PI * (x + 1.0);
+    0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos()
+}
+
+/// High-pass cutoff: removes DC offset and rumble, keeps the speech band
+const HIGHPASS_CUTOFF_HZ: f32 = 80.0;
+/// Peak level recordings are normalized to (leaves headroom below clipping)
+const NORMALIZE_TARGET_PEAK: f32 = 0.9;
+/// Most a recording is amplified (about +26 dB)
+const NORMALIZE_MAX_GAIN: f32 = 20.0;
+
+/// Clean-up applied to a recording before transcription
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PreprocessOptions {
+    /// Rate of the samples being processed
+    pub sample_rate: u32,
+    /// First-order high-pass at `HIGHPASS_CUTOFF_HZ` (removes DC offset and rumble)
+    pub highpass: bool,
+    /// Scale the peak to `NORMALIZE_TARGET_PEAK`
+    pub normalize: bool,
+    /// Recordings with an RMS below this are silence and aren't amplified, so the
+    /// hallucination filter still sees them as silent
+    pub silence_rms: f32,
+}
+
+impl Default for PreprocessOptions {
+    fn default() -> Self {
+        Self {
+            sample_rate: crate::backend_loader::DEFAULT_SAMPLE_RATE,
+            highpass: false,
+            normalize: false,
+            silence_rms: 0.0,
+        }
+    }
+}
+
+impl PreprocessOptions {
+    pub fn is_enabled(&self) -> bool {
+        self.highpass || self.normalize
+    }
+}
+
+/// High-pass and/or peak-normalize a recording
+pub fn preprocess(samples: &[f32], opts: &PreprocessOptions) -> Vec<f32> {
+    let mut out = samples.to_vec();
+    if opts.highpass {
+        highpass(&mut out, HIGHPASS_CUTOFF_HZ, opts.sample_rate);
+    }
+    if opts.normalize && crate::always_listen::frame_rms(&out) >= opts.silence_rms {
+        normalize_peak(&mut out, NORMALIZE_TARGET_PEAK, NORMALIZE_MAX_GAIN);
+    }
+    out
+}
+
+/// First-order RC high-pass, in place. Starts from the first sample so a DC
+/// offset doesn't turn into a click at the start.
+fn highpass(samples: &mut [f32], cutoff_hz: f32, sample_rate: u32) {
+    let Some(&first) = samples.first() else {
+        return;
+    };
+    let rc = 1.0 / (std::f32::consts::TAU * cutoff_hz);
+    let dt = 1.0 / sample_rate as f32;
+    let alpha = rc / (rc + dt);
+    let (mut prev_in, mut prev_out) = (first, 0.0);
+    for sample in samples.iter_mut() {
+        let out = alpha * (prev_out + *sample - prev_in);
+        prev_in = *sample;
+        prev_out = out;
+        *sample = out;
+    }
+}
+
+/// Scale so the loudest sample reaches `target`, amplifying by at most `max_gain`
+fn normalize_peak(samples: &mut [f32], target: f32, max_gain: f32) {
+    let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    if peak == 0.0 {
+        return;
+    }
+    let gain = (target / peak).min(max_gain);
+    samples.iter_mut().for_each(|s| *s *= gain);
+}
+
+/// Noise gate defaults: open quickly so word onsets survive, close slowly so
+/// trailing consonants aren't chopped
+pub const DEFAULT_GATE_ATTACK_MS: u64 = 5;
+pub const DEFAULT_GATE_RELEASE_MS: u64 = 200;
+/// Window of the level detector that drives the gate
+const GATE_DETECTOR_MS: f32 = 10.0;
+
+/// Noise gate settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoiseGateConfig {
+    /// Level (RMS over ~10 ms) below which audio is muted
+    pub threshold: f32,
+    /// Time for the gate to open once the level crosses the threshold
+    pub attack_ms: u64,
+    /// Time for the gate to close after the level drops
+    pub release_ms: u64,
+}
+
+/// Mutes audio whose level stays below a threshold (fan hum, keyboard clatter),
+/// fading in and out so the cut doesn't click. State carries across `process`
+/// calls, so a stream can be fed frame by frame.
+#[derive(Debug, Clone)]
+pub struct NoiseGate {
+    threshold: f32,
+    detector_coeff: f32,
+    attack_coeff: f32,
+    release_coeff: f32,
+    /// Smoothed mean square of the input
+    level: f32,
+    /// Current gain, 0.0 (closed) to 1.0 (open)
+    gain: f32,
+}
+
+impl NoiseGate {
+    pub fn new(config: NoiseGateConfig, sample_rate: u32) -> Self {
+        // One-pole smoothing coefficient for a time constant in ms
+        let coeff = |ms: f32| {
+            let samples = ms * sample_rate as f32 / 1000.0;
+            if samples < 1.0 {
+                0.0
+            } else {
+                (-1.0 / samples).exp()
+            }
+        };
+        Self {
+            threshold: config.threshold,
+            detector_coeff: coeff(GATE_DETECTOR_MS),
+            attack_coeff: coeff(config.attack_ms as f32),
+            release_coeff: coeff(config.release_ms as f32),
+            level: 0.0,
+            gain: 0.0,
+        }
+    }
+
+    /// Gate the next block of samples
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        let threshold_sq = self.threshold * self.threshold;
+        samples
+            .iter()
+            .map(|&sample| {
+                self.level = self.detector_coeff * self.level
+                    + (1.0 - self.detector_coeff) * sample * sample;
+                let (target, coeff) = if self.level >= threshold_sq {
+                    (1.0, self.attack_coeff)
+                } else {
+                    (0.0, self.release_coeff)
+                };
+                self.gain = coeff * self.gain + (1.0 - coeff) * target;
+                sample * self.gain
+            })
+            .collect()
+    }
+
+    /// Close the gate and forget the level (e.g. after a pause)
+    pub fn reset(&mut self) {
+        self.level = 0.0;
+        self.gain = 0.0;
+    }
+}
+
 /// Simple energy-based Voice Activity Detection
 #[allow(dead_code)]
 pub fn detect_voice_activity(samples: &[f32], threshold: f32) -> bool {
@@ -333,6 +1204,168 @@ pub fn detect_voice_activity(samples: &[f32], threshold: f32) -> bool {
 mod tests {
     use super::*;
 
+    /// Build a WAV file in memory
+    fn wav_bytes(channels: u16, sample_rate: u32, bits: u16, data: &[u8]) -> Vec<u8> {
+        let tag = if bits == 32 { WAVE_FORMAT_IEEE_FLOAT } else { WAVE_FORMAT_PCM };
+        wav_bytes_tagged(tag, channels, sample_rate, bits, data)
+    }
+
+    fn wav_bytes_tagged(
+        tag: u16,
+        channels: u16,
+        sample_rate: u32,
+        bits: u16,
+        data: &[u8],
+    ) -> Vec<u8> {
+        let block_align = channels * bits / 8;
+        let mut out = Vec::new();
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        out.extend_from_slice(b"WAVEfmt ");
+        out.extend_from_slice(&16u32.to_le_bytes());
+        out.extend_from_slice(&tag.to_le_bytes());
+        out.extend_from_slice(&channels.to_le_bytes());
+        out.extend_from_slice(&sample_rate.to_le_bytes());
+        out.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        out.extend_from_slice(&block_align.to_le_bytes());
+        out.extend_from_slice(&bits.to_le_bytes());
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        out.extend_from_slice(data);
+        out
+    }
+
+    #[test]
+    fn test_parse_wav_pcm16_and_pcm24() {
+        let pcm16: Vec<u8> = [16384i16, -32768].iter().flat_map(|s| s.to_le_bytes()).collect();
+        let wav = parse_wav(&wav_bytes(1, 16000, 16, &pcm16)).unwrap();
+        assert_eq!((wav.channels, wav.sample_rate), (1, 16000));
+        assert_eq!(wav.samples, vec![0.5, -1.0]);
+
+        // 0x400000 = +0.5, 0xC00000 = -0.5
+        let pcm24 = [0x00, 0x00, 0x40, 0x00, 0x00, 0xC0];
+        let wav = parse_wav(&wav_bytes(1, 48000, 24, &pcm24)).unwrap();
+        assert_eq!(wav.samples, vec![0.5, -0.5]);
+
+        assert!(parse_wav(&wav_bytes(1, 16000, 8, &[0, 0])).is_err());
+        assert!(parse_wav(b"not a wav file").is_err());
+    }
+
+    #[test]
+    fn test_decode_wav_mono_and_stereo() {
+        let frames = [(0.5f32, -0.5f32), (0.25, 0.75), (-1.0, 0.0)];
+
+        let mono16: Vec<u8> = frames
+            .iter()
+            .flat_map(|(l, _)| ((l * 32768.0) as i16).to_le_bytes())
+            .collect();
+        let decoded = decode_bytes(&wav_bytes(1, 16000, 16, &mono16), 16000).unwrap();
+        assert_eq!(decoded, vec![0.5, 0.25, -1.0]);
+
+        // Stereo channels are averaged
+        let stereo16: Vec<u8> = frames
+            .iter()
+            .flat_map(|(l, r)| [*l, *r])
+            .flat_map(|s| ((s * 32768.0) as i16).to_le_bytes())
+            .collect();
+        let decoded = decode_bytes(&wav_bytes(2, 16000, 16, &stereo16), 16000).unwrap();
+        assert_eq!(decoded.len(), 3);
+        for (got, (l, r)) in decoded.iter().zip(frames) {
+            assert!((got - (l + r) / 2.0).abs() < 1e-4, "{} vs {}", got, (l + r) / 2.0);
+        }
+
+        let stereo32: Vec<u8> = frames
+            .iter()
+            .flat_map(|(l, r)| [*l, *r])
+            .flat_map(f32::to_le_bytes)
+            .collect();
+        let decoded = decode_bytes(&wav_bytes(2, 16000, 32, &stereo32), 16000).unwrap();
+        assert_eq!(decoded, vec![0.0, 0.5, -0.5]);
+
+        let mono32: Vec<u8> = frames.iter().flat_map(|(l, _)| l.to_le_bytes()).collect();
+        let decoded = decode_bytes(&wav_bytes(1, 16000, 32, &mono32), 16000).unwrap();
+        assert_eq!(decoded, vec![0.5, 0.25, -1.0]);
+
+        // One second at 48kHz comes out as one second at 16kHz
+        let second = vec![0u8; 48000 * 2 * 2];
+        let decoded = decode_bytes(&wav_bytes(2, 48000, 16, &second), 16000).unwrap();
+        assert_eq!(decoded.len(), 16000);
+    }
+
+    #[test]
+    fn test_decode_wav_rejects_compressed() {
+        // IMA ADPCM
+        let err = parse_wav(&wav_bytes_tagged(0x11, 1, 16000, 4, &[0; 8])).err().unwrap();
+        assert!(err.to_string().contains("Compressed WAV"), "{}", err);
+        // 32-bit integer PCM isn't handled either
+        assert!(parse_wav(&wav_bytes_tagged(WAVE_FORMAT_PCM, 1, 16000, 32, &[0; 8])).is_err());
+    }
+
+    /// 0.5s of 16-bit stereo at 8kHz: 440Hz at half scale on the left, 660Hz at
+    /// quarter scale on the right
+    const TONE_FLAC: &[u8] = include_bytes!("../tests/fixtures/tone.flac");
+    /// 1s of stereo silence at 22.05kHz
+    const SILENCE_OGG: &[u8] = include_bytes!("../tests/fixtures/silence.ogg");
+
+    #[test]
+    fn test_detect_audio_format() {
+        assert_eq!(AudioFormat::detect(TONE_FLAC, None), Some(AudioFormat::Flac));
+        assert_eq!(AudioFormat::detect(SILENCE_OGG, Some("wav")), Some(AudioFormat::OggVorbis));
+        assert_eq!(AudioFormat::detect(b"RIFF", None), Some(AudioFormat::Wav));
+        assert_eq!(AudioFormat::detect(b"", Some("FLAC")), Some(AudioFormat::Flac));
+        assert_eq!(AudioFormat::detect(b"ID3", Some("mp3")), None);
+        assert_eq!(AudioFormat::detect(b"ID3", None), None);
+    }
+
+    #[test]
+    fn test_decode_flac() {
+        let decoded = decode_bytes(TONE_FLAC, 8000).unwrap();
+        assert_eq!(decoded.len(), 4000);
+        for (i, sample) in decoded.iter().enumerate() {
+            let t = i as f32 / 8000.0;
+            let left = (std::f32::consts::TAU * 440.0 * t).sin() * 0.5;
+            let right = (std::f32::consts::TAU * 660.0 * t).sin() * 0.25;
+            assert!((sample - (left + right) / 2.0).abs() < 1e-3, "sample {}", i);
+        }
+        assert_eq!(decode_bytes(TONE_FLAC, 16000).unwrap().len(), 8000);
+    }
+
+    #[test]
+    fn test_decode_ogg_vorbis() {
+        let decoded = decode_bytes(SILENCE_OGG, 16000).unwrap();
+        assert!((15_500..=16_500).contains(&decoded.len()), "{} samples", decoded.len());
+        assert!(decoded.iter().all(|s| s.abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_decode_truncated_files() {
+        let wav = wav_bytes(1, 16000, 16, &[0x10; 3200]);
+        for file in [TONE_FLAC, SILENCE_OGG, &wav] {
+            let full = decode_bytes(file, 16000).unwrap().len();
+            for len in [0, 3, 4, 40, file.len() / 3, file.len() / 2, file.len() - 1] {
+                // The audio before the cut or an error, never a panic
+                if let Ok(decoded) = decode_bytes(&file[..len], 16000) {
+                    assert!(decoded.len() <= full, "{} of {} bytes", len, file.len());
+                }
+            }
+        }
+        // Half of the FLAC file still gives the first part of the tone
+        let half = decode_bytes(&TONE_FLAC[..TONE_FLAC.len() / 2], 8000).unwrap();
+        assert!((1000..4000).contains(&half.len()), "{} samples", half.len());
+    }
+
+    #[test]
+    fn test_decode_rejects_unknown_formats() {
+        let err = decode_bytes(b"ID3\x04\0\0junk", 16000).unwrap_err();
+        assert!(err.to_string().contains("Unsupported audio format"), "{}", err);
+
+        let path = std::env::temp_dir().join(format!("stt-decode-{}.mp3", std::process::id()));
+        std::fs::write(&path, b"ID3\x04\0\0junk").unwrap();
+        let err = decode(&path, 16000).unwrap_err();
+        let _ = std::fs::remove_file(&path);
+        assert!(format!("{:#}", err).contains("Unsupported audio format (.mp3)"), "{:#}", err);
+    }
+
     #[test]
     fn test_convert_to_mono_mono_input() {
         let input = vec![0.5f32, -0.3, 0.8, -0.2];
@@ -387,6 +1420,83 @@ mod tests {
         assert_eq!(result.len(), 50);
     }
 
+    #[test]
+    fn test_resample_to_8k() {
+        // 1 second at 48kHz -> 1 second at 8kHz
+        let input = vec![0.25f32; 48000];
+        let result = resample(&input, 48000, 8000);
+        assert_eq!(result.len(), 8000);
+        assert!(result.iter().all(|&s| (s - 0.25).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_resample_to_24k() {
+        // 16kHz -> 24kHz is 1.5x the samples; 44.1kHz -> 24kHz for a second of audio
+        let input = vec![0.0f32; 16000];
+        assert_eq!(resample(&input, 16000, 24000).len(), 24000);
+
+        let input = vec![0.0f32; 44100];
+        assert_eq!(resample(&input, 44100, 24000).len(), 24000);
+    }
+
+    fn sine(freq: f32, rate: u32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / rate as f32).sin())
+            .collect()
+    }
+
+    /// RMS away from the edges, where the filter sees repeated samples
+    fn inner_rms(samples: &[f32]) -> f32 {
+        let inner = &samples[samples.len() / 10..samples.len() * 9 / 10];
+        (inner.iter().map(|s| s * s).sum::<f32>() / inner.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_resample_downsample_attenuates_above_nyquist() {
+        // 10kHz is above the 8kHz Nyquist of 16kHz audio and would alias to 6kHz
+        for from_rate in [48000, 44100] {
+            let input = sine(10_000.0, from_rate, from_rate as usize / 2);
+            let filtered = inner_rms(&resample(&input, from_rate, 16000));
+            let linear = inner_rms(&resample_linear(&input, from_rate, 16000));
+            assert!(linear > 0.3, "linear interpolation should alias ({})", linear);
+            assert!(filtered < 0.02, "{}Hz: alias RMS {}", from_rate, filtered);
+            assert!(filtered < linear / 20.0);
+        }
+    }
+
+    #[test]
+    fn test_resample_downsample_keeps_speech_band() {
+        let input = sine(1_000.0, 48000, 24000);
+        let output = resample(&input, 48000, 16000);
+        assert_eq!(output.len(), 8000);
+        // Unit sine RMS is 1/sqrt(2)
+        assert!((inner_rms(&output) - std::f32::consts::FRAC_1_SQRT_2).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_stream_resampler_matches_one_shot() {
+        // 10ms callbacks come out the same as resampling the whole recording at once,
+        // apart from the few samples still waiting for input after them
+        let rates = [(48000, 16000), (44100, 16000), (8000, 16000), (11025, 16000), (44100, 48000)];
+        for (from_rate, to_rate) in rates {
+            let input = sine(440.0, from_rate, from_rate as usize);
+            let whole = resample(&input, from_rate, to_rate);
+            let mut resampler = StreamResampler::new(from_rate, to_rate);
+            let streamed: Vec<f32> = input
+                .chunks(from_rate as usize / 100)
+                .flat_map(|chunk| resampler.process(chunk))
+                .collect();
+            assert!(whole.len() - streamed.len() <= 20, "{} vs {}", streamed.len(), whole.len());
+            for (a, b) in streamed.iter().zip(&whole) {
+                assert!((a - b).abs() < 1e-4, "{}Hz: {} vs {}", from_rate, a, b);
+            }
+            assert!(resampler.pending.len() < 1000);
+        }
+
+        let mut same = StreamResampler::new(16000, 16000);
+        assert_eq!(same.process(&[0.5, -0.5]), vec![0.5, -0.5]);
+    }
+
     #[test]
     fn test_detect_voice_activity_silence() {
         let silence = vec![0.0f32; 100];
@@ -414,6 +1524,184 @@ mod tests {
         assert!(!detect_voice_activity(&empty, 0.01));
     }
 
+    #[test]
+    fn test_choose_sample_format_preference() {
+        use SampleFormat::*;
+        // A convertible default is kept even when a more preferred format is offered
+        assert_eq!(choose_sample_format(I16, &[F32, I16]), Some(I16));
+        assert_eq!(choose_sample_format(U16, &[]), Some(U16));
+        // Otherwise f32, then i16, then u16
+        assert_eq!(choose_sample_format(I32, &[I32, U16, I16, F32]), Some(F32));
+        assert_eq!(choose_sample_format(I32, &[I32, U16, I16]), Some(I16));
+        assert_eq!(choose_sample_format(F64, &[F64, U16]), Some(U16));
+        assert_eq!(choose_sample_format(I32, &[I32, U8]), None);
+    }
+
+    #[test]
+    fn test_append_capped_stops_at_limit() {
+        let mut buffer = Vec::new();
+        let chunk = vec![0.1f32; 300];
+
+        assert!(!append_capped(&mut buffer, &chunk, 1000));
+        assert!(!append_capped(&mut buffer, &chunk, 1000));
+        assert!(!append_capped(&mut buffer, &chunk, 1000));
+        assert_eq!(buffer.len(), 900);
+
+        // Crossing the limit truncates to exactly max_samples
+        assert!(append_capped(&mut buffer, &chunk, 1000));
+        assert_eq!(buffer.len(), 1000);
+
+        // Further audio is dropped
+        assert!(append_capped(&mut buffer, &chunk, 1000));
+        assert_eq!(buffer.len(), 1000);
+
+        // 0 = unlimited
+        let mut unlimited = Vec::new();
+        for _ in 0..10 {
+            assert!(!append_capped(&mut unlimited, &chunk, 0));
+        }
+        assert_eq!(unlimited.len(), 3000);
+    }
+
+    #[test]
+    fn test_pre_roll_buffer() {
+        let ramp = |range: std::ops::Range<usize>| range.map(|i| i as f32).collect::<Vec<_>>();
+        let mut pre_roll = PreRollBuffer::new(5);
+        pre_roll.push(&ramp(0..3));
+        assert_eq!(pre_roll.ordered(), ramp(0..3));
+        // Wrapping keeps the newest samples, oldest first
+        pre_roll.push(&ramp(3..9));
+        assert_eq!(pre_roll.ordered(), ramp(4..9));
+        pre_roll.clear();
+        assert!(pre_roll.ordered().is_empty());
+
+        let mut off = PreRollBuffer::new(0);
+        off.push(&ramp(0..3));
+        assert!(off.ordered().is_empty());
+    }
+
+    #[test]
+    fn test_recording_starts_with_pre_roll() {
+        // 300ms at 16kHz, fed in 10ms callbacks while idle
+        let sink = RecordingSink {
+            buffer: Arc::new(Mutex::new(Vec::new())),
+            recording: Arc::new(AtomicBool::new(false)),
+            pre_roll: Arc::new(Mutex::new(PreRollBuffer::new(4800))),
+            max_samples: 0,
+            on_limit: None,
+        };
+        let samples: Vec<f32> = (0..8000).map(|i| i as f32).collect();
+        for chunk in samples.chunks(160) {
+            sink.push(chunk);
+        }
+        assert!(sink.buffer.lock().is_empty());
+
+        start_from_pre_roll(&sink.pre_roll, &sink.buffer, &sink.recording);
+        sink.push(&[-1.0, -2.0]);
+        // The last 300ms before the start, in order, then the recording itself
+        let recorded = sink.buffer.lock().clone();
+        assert_eq!(recorded.len(), 4800 + 2);
+        assert_eq!(recorded[..4800], samples[8000 - 4800..]);
+        assert_eq!(recorded[4800..], [-1.0, -2.0]);
+        assert!(sink.pre_roll.lock().ordered().is_empty());
+    }
+
+    #[test]
+    fn test_recording_sink_signals_once() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let sink = RecordingSink {
+            buffer: Arc::new(Mutex::new(Vec::new())),
+            recording: Arc::new(AtomicBool::new(true)),
+            pre_roll: Arc::new(Mutex::new(PreRollBuffer::new(0))),
+            max_samples: 500,
+            on_limit: Some(Arc::new(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            })),
+        };
+
+        for _ in 0..5 {
+            sink.push(&[0.2f32; 200]);
+        }
+
+        assert_eq!(sink.buffer.lock().len(), 500);
+        assert!(!sink.recording.load(Ordering::SeqCst));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_preprocess_highpass_removes_dc() {
+        let tone = |i: usize| 0.1 * (std::f32::consts::TAU * 440.0 * i as f32 / 16000.0).sin();
+        let samples: Vec<f32> = (0..16000).map(|i| 0.5 + tone(i)).collect();
+        let opts = PreprocessOptions { highpass: true, ..Default::default() };
+        let out = preprocess(&samples, &opts);
+        assert_eq!(out.len(), samples.len());
+        // The offset is gone once the filter settles; the tone survives
+        let tail = &out[8000..];
+        let mean = tail.iter().sum::<f32>() / tail.len() as f32;
+        assert!(mean.abs() < 0.005, "mean {}", mean);
+        let peak = tail.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!(peak > 0.09 && peak < 0.11, "peak {}", peak);
+        assert!(out.iter().all(|s| s.abs() < 0.2));
+    }
+
+    #[test]
+    fn test_preprocess_normalize() {
+        let quiet: Vec<f32> = (0..1600).map(|i| 0.05 * (i as f32 * 0.3).sin()).collect();
+        let opts = PreprocessOptions { normalize: true, silence_rms: 0.01, ..Default::default() };
+        let out = preprocess(&quiet, &opts);
+        let peak = out.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!((peak - NORMALIZE_TARGET_PEAK).abs() < 1e-3, "peak {}", peak);
+        assert!(out.iter().all(|s| s.abs() <= 1.0));
+
+        // Near-silence stays as it is
+        let hiss: Vec<f32> = quiet.iter().map(|s| s * 0.1).collect();
+        assert_eq!(preprocess(&hiss, &opts), hiss);
+        // Gain is capped
+        let faint: Vec<f32> = quiet.iter().map(|s| s * 0.1).collect();
+        let opts = PreprocessOptions { silence_rms: 0.0, ..opts };
+        let peak = preprocess(&faint, &opts).iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!((peak - 0.005 * NORMALIZE_MAX_GAIN).abs() < 1e-3, "peak {}", peak);
+        assert!(preprocess(&[], &opts).is_empty());
+    }
+
+    #[test]
+    fn test_noise_gate() {
+        // Deterministic low-level noise
+        let mut seed = 12345u32;
+        let mut noise = move || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            ((seed >> 16) as f32 / 32768.0 - 1.0) * 0.004
+        };
+        let speech = |i: usize| 0.3 * (std::f32::consts::TAU * 200.0 * i as f32 / 16000.0).sin();
+        // 0.5s noise, 0.5s speech over noise, 1.5s noise
+        let input: Vec<f32> = (0..40000)
+            .map(|i| noise() + if (8000..16000).contains(&i) { speech(i) } else { 0.0 })
+            .collect();
+
+        let config = NoiseGateConfig {
+            threshold: 0.015,
+            attack_ms: DEFAULT_GATE_ATTACK_MS,
+            release_ms: DEFAULT_GATE_RELEASE_MS,
+        };
+        let mut gate = NoiseGate::new(config, 16000);
+        let output: Vec<f32> = input.chunks(480).flat_map(|frame| gate.process(frame)).collect();
+        assert_eq!(output.len(), input.len());
+        let rms = |s: &[f32]| crate::always_listen::frame_rms(s);
+
+        // The burst passes through untouched once the gate has opened
+        let (speech_in, speech_out) = (&input[8400..16000], &output[8400..16000]);
+        assert!((rms(speech_out) / rms(speech_in) - 1.0).abs() < 0.01);
+        // Noise before the burst, and once the gate has closed after it, is muted
+        assert!(rms(&output[..8000]) < rms(&input[..8000]) * 0.01);
+        assert!(rms(&output[32000..]) < rms(&input[32000..]) * 0.01);
+
+        // After a reset the gate starts closed again
+        gate.reset();
+        let quiet = gate.process(&input[..4800]);
+        assert!(rms(&quiet) < rms(&input[..4800]) * 0.01);
+    }
+
     #[test]
     fn test_audio_capture_creation() {
         // This test just verifies the AudioCapture struct can be created
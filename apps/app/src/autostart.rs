@@ -0,0 +1,153 @@
+//! Start the app when the user signs in to Windows
+//!
+//! Enabled by writing a value under `HKCU\Software\Microsoft\Windows\CurrentVersion\Run`
+//! that points at the current exe. The registry, not the config, is the source of truth:
+//! the user can also remove the entry from outside the app. Elsewhere this is a no-op.
+
+use anyhow::Result;
+use std::path::Path;
+
+/// Run value name; includes the exe stem so renamed copies register separately
+pub fn value_name(exe_stem: &str) -> String {
+    format!("SpeechToText-{}", exe_stem)
+}
+
+/// Command line stored in the Run value
+pub fn run_command(exe: &Path) -> String {
+    format!("\"{}\"", exe.display())
+}
+
+/// Whether this exe is registered to start at sign-in
+#[cfg(target_os = "windows")]
+pub fn is_enabled() -> bool {
+    let Ok(name) = crate::config::get_exe_stem().map(|stem| value_name(&stem)) else {
+        return false;
+    };
+    match run_key::read(&name) {
+        Ok(value) => value.is_some(),
+        Err(e) => {
+            tracing::warn!("Failed to read autostart entry: {:#}", e);
+            false
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn is_enabled() -> bool {
+    false
+}
+
+/// Add or remove the Run entry for the current exe
+#[cfg(target_os = "windows")]
+pub fn set_enabled(enabled: bool) -> Result<()> {
+    let name = value_name(&crate::config::get_exe_stem()?);
+    if enabled {
+        let command = run_command(&std::env::current_exe()?);
+        run_key::write(&name, &command)?;
+        tracing::info!("Autostart enabled: {}", command);
+    } else {
+        run_key::delete(&name)?;
+        tracing::info!("Autostart disabled");
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn set_enabled(_enabled: bool) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod run_key {
+    use anyhow::{Context, Result};
+    use windows::core::HSTRING;
+    use windows::Win32::Foundation::ERROR_FILE_NOT_FOUND;
+    use windows::Win32::System::Registry::{
+        RegCloseKey, RegDeleteValueW, RegOpenKeyExW, RegQueryValueExW, RegSetValueExW, HKEY,
+        HKEY_CURRENT_USER, KEY_QUERY_VALUE, KEY_SET_VALUE, REG_SAM_FLAGS, REG_SZ,
+    };
+
+    const RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
+
+    /// Open handle to the Run key, closed on drop
+    struct RunKey(HKEY);
+
+    impl RunKey {
+        fn open(access: REG_SAM_FLAGS) -> Result<Self> {
+            let mut key = HKEY::default();
+            unsafe {
+                RegOpenKeyExW(HKEY_CURRENT_USER, &HSTRING::from(RUN_KEY), 0, access, &mut key)
+            }
+            .ok()
+            .context("Failed to open the Run registry key")?;
+            Ok(Self(key))
+        }
+    }
+
+    impl Drop for RunKey {
+        fn drop(&mut self) {
+            unsafe {
+                let _ = RegCloseKey(self.0);
+            }
+        }
+    }
+
+    pub fn read(name: &str) -> Result<Option<String>> {
+        let key = RunKey::open(KEY_QUERY_VALUE)?;
+        let name = HSTRING::from(name);
+        let mut size = 0u32;
+        let size_ptr = Some(&mut size as *mut u32);
+        let status = unsafe { RegQueryValueExW(key.0, &name, None, None, None, size_ptr) };
+        if status == ERROR_FILE_NOT_FOUND {
+            return Ok(None);
+        }
+        status.ok()?;
+        let mut data = vec![0u16; (size as usize).div_ceil(2)];
+        unsafe {
+            RegQueryValueExW(
+                key.0,
+                &name,
+                None,
+                None,
+                Some(data.as_mut_ptr().cast()),
+                Some(&mut size as *mut u32),
+            )
+        }
+        .ok()?;
+        data.truncate(size as usize / 2);
+        while data.last() == Some(&0) {
+            data.pop();
+        }
+        Ok(Some(String::from_utf16_lossy(&data)))
+    }
+
+    pub fn write(name: &str, value: &str) -> Result<()> {
+        let key = RunKey::open(KEY_SET_VALUE)?;
+        let wide: Vec<u16> = value.encode_utf16().chain(Some(0)).collect();
+        let bytes: Vec<u8> = wide.iter().flat_map(|c| c.to_le_bytes()).collect();
+        unsafe { RegSetValueExW(key.0, &HSTRING::from(name), 0, REG_SZ, Some(&bytes)) }
+            .ok()
+            .context("Failed to write the Run registry value")
+    }
+
+    pub fn delete(name: &str) -> Result<()> {
+        let key = RunKey::open(KEY_SET_VALUE)?;
+        let status = unsafe { RegDeleteValueW(key.0, &HSTRING::from(name)) };
+        if status == ERROR_FILE_NOT_FOUND {
+            return Ok(());
+        }
+        status.ok().context("Failed to remove the Run registry value")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_entry() {
+        assert_eq!(value_name("app"), "SpeechToText-app");
+        let exe = Path::new(r"C:\Program Files\Speech\app.exe");
+        assert_eq!(run_command(exe), r#""C:\Program Files\Speech\app.exe""#);
+    }
+}
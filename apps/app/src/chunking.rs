@@ -0,0 +1,237 @@
+//! Long recordings transcribed in pieces
+//!
+//! A push-to-talk held for minutes would otherwise reach the model as one buffer,
+//! and latency grows with it. Audio longer than `chunk_seconds` is split at pauses
+//! found by the always-listen VAD and the chunks are transcribed one after another.
+//! Where no pause is found the audio is cut anyway, with the chunks overlapping a
+//! little so no word is lost in the cut; words both transcripts share at that seam
+//! are kept once.
+
+use crate::always_listen::VadEngine;
+use std::ops::Range;
+
+/// VAD frame length, as in always-listen (480 samples at 16kHz)
+const FRAME_MS: usize = 30;
+
+/// Shortest pause the audio is split at
+pub const MIN_PAUSE_MS: usize = 300;
+
+/// Audio both chunks get when a cut has to be made without a pause
+pub const OVERLAP_MS: usize = 1000;
+
+/// Most words compared when removing the repeat at an overlapping seam
+const MAX_OVERLAP_WORDS: usize = 12;
+
+/// Whether the VAD hears voice in each frame of `audio`
+fn voiced_frames(audio: &[f32], sample_rate: u32, threshold: f32, frame: usize) -> Vec<bool> {
+    let mut vad = VadEngine::new(threshold, frame).with_sample_rate(sample_rate);
+    audio.chunks_exact(frame).map(|f| vad.process(f).0).collect()
+}
+
+/// Middle of the last pause of at least `min_frames` silent frames between samples
+/// `from` and `to`, as a sample index
+fn last_pause(
+    voiced: &[bool],
+    frame: usize,
+    from: usize,
+    to: usize,
+    min_frames: usize,
+) -> Option<usize> {
+    let (first, end) = (from.div_ceil(frame), (to / frame).min(voiced.len()));
+    let window = voiced.get(first..end).unwrap_or_default();
+    let mut best = None;
+    let mut run_start = None;
+    // A voiced frame past the end closes a pause still open there
+    for (i, &voice) in window.iter().chain([&true]).enumerate() {
+        match (voice, run_start) {
+            (false, None) => run_start = Some(i),
+            (true, Some(start)) => {
+                if i - start >= min_frames {
+                    best = Some((first + (start + i) / 2) * frame);
+                }
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+    best
+}
+
+/// Split `audio` into chunks of at most `max_samples`, cutting in pauses (VAD
+/// `threshold`) where it can. Each chunk is at least half full unless it is the
+/// last. A chunk that had to be cut without a pause shares `OVERLAP_MS` with the
+/// next one. Audio no longer than `max_samples`, or a `max_samples` of 0, is one chunk.
+pub fn split_at_silences(
+    audio: &[f32],
+    sample_rate: u32,
+    threshold: f32,
+    max_samples: usize,
+) -> Vec<Range<usize>> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    if max_samples == 0 || audio.len() <= max_samples {
+        chunks.push(start..audio.len());
+        return chunks;
+    }
+    let ms_to_samples = |ms: usize| ms * sample_rate as usize / 1000;
+    let frame = ms_to_samples(FRAME_MS).max(1);
+    let min_frames = ms_to_samples(MIN_PAUSE_MS).div_ceil(frame);
+    // Always less than half a chunk, so every chunk moves the start forward
+    let overlap = ms_to_samples(OVERLAP_MS).min((max_samples / 2).saturating_sub(1));
+    let voiced = voiced_frames(audio, sample_rate, threshold, frame);
+
+    while audio.len() - start > max_samples {
+        let limit = start + max_samples;
+        match last_pause(&voiced, frame, start + max_samples / 2, limit, min_frames) {
+            Some(split) => {
+                chunks.push(start..split);
+                start = split;
+            }
+            None => {
+                chunks.push(start..limit);
+                start = limit - overlap;
+            }
+        }
+    }
+    chunks.push(start..audio.len());
+    chunks
+}
+
+/// Lowercase letters and digits of a word, so "Fox," matches "fox"
+fn normalize_word(word: &str) -> String {
+    word.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// Join the transcripts of two chunks. If the chunks overlapped, the longest run of
+/// words that ends `previous` and starts `next` is kept once.
+pub fn join_chunks(previous: &str, next: &str, overlapped: bool) -> String {
+    let (previous, next) = (previous.trim(), next.trim());
+    let next_words: Vec<&str> = next.split_whitespace().collect();
+    let mut skip = 0;
+    if overlapped {
+        let tail: Vec<String> = previous.split_whitespace().map(normalize_word).collect();
+        let head: Vec<String> = next_words.iter().map(|w| normalize_word(w)).collect();
+        let most = MAX_OVERLAP_WORDS.min(tail.len()).min(head.len());
+        skip = (1..=most)
+            .rev()
+            .find(|&n| tail[tail.len() - n..] == head[..n])
+            .unwrap_or(0);
+    }
+    let rest = next_words[skip..].join(" ");
+    match (previous.is_empty(), rest.is_empty()) {
+        (_, true) => previous.to_string(),
+        (true, false) => rest,
+        (false, false) => format!("{} {}", previous, rest),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+
+    /// Vowel-like signal the VAD hears as voice: 150Hz with harmonics
+    fn speech(ms: usize) -> Vec<f32> {
+        (0..ms * 16)
+            .map(|i| {
+                let t = i as f32 / RATE as f32;
+                let sum: f32 = (1..=20)
+                    .map(|h| (2.0 * std::f32::consts::PI * 150.0 * h as f32 * t).sin() / h as f32)
+                    .sum();
+                sum * 0.15
+            })
+            .collect()
+    }
+
+    fn silence(ms: usize) -> Vec<f32> {
+        vec![0.0; ms * 16]
+    }
+
+    fn pattern(parts: &[(bool, usize)]) -> Vec<f32> {
+        let part = |&(voice, ms): &(bool, usize)| if voice { speech(ms) } else { silence(ms) };
+        parts.iter().flat_map(part).collect()
+    }
+
+    fn seconds(chunks: &[Range<usize>]) -> Vec<(f32, f32)> {
+        let s = |n: usize| (n as f32 / RATE as f32 * 10.0).round() / 10.0;
+        chunks.iter().map(|c| (s(c.start), s(c.end))).collect()
+    }
+
+    #[test]
+    fn test_short_audio_is_one_chunk() {
+        let audio = pattern(&[(true, 2000)]);
+        let whole = |chunks: Vec<Range<usize>>, len| chunks.len() == 1 && chunks[0] == (0..len);
+        assert!(whole(split_at_silences(&audio, RATE, 0.015, 16000 * 3), audio.len()));
+        // Chunking off
+        assert!(whole(split_at_silences(&audio, RATE, 0.015, 0), audio.len()));
+        assert!(whole(split_at_silences(&[], RATE, 0.015, 16000), 0));
+    }
+
+    #[test]
+    fn test_splits_in_pauses() {
+        // 4s speech, 1s pause, 4s speech, 1s pause, 4s speech; chunks of at most 6s
+        let audio =
+            pattern(&[(true, 4000), (false, 1000), (true, 4000), (false, 1000), (true, 4000)]);
+        let chunks = split_at_silences(&audio, RATE, 0.015, 16000 * 6);
+        assert_eq!(chunks.len(), 3);
+        // Each cut falls inside a pause and the chunks neither overlap nor leave gaps
+        let cuts = seconds(&chunks);
+        assert!((4.0..=5.0).contains(&cuts[0].1), "{:?}", cuts);
+        assert!((9.0..=10.0).contains(&cuts[1].1), "{:?}", cuts);
+        assert_eq!(chunks[0].start, 0);
+        assert_eq!(chunks[2].end, audio.len());
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+        assert!(chunks.iter().all(|c| c.len() <= 16000 * 6));
+    }
+
+    #[test]
+    fn test_prefers_the_last_pause() {
+        // Pauses at 3s and 4.5s both fit a 6s chunk; the later one keeps chunks long
+        let audio =
+            pattern(&[(true, 3000), (false, 500), (true, 1000), (false, 500), (true, 3000)]);
+        let chunks = split_at_silences(&audio, RATE, 0.015, 16000 * 6);
+        let cuts = seconds(&chunks);
+        assert_eq!(chunks.len(), 2);
+        assert!((4.5..=5.0).contains(&cuts[0].1), "{:?}", cuts);
+    }
+
+    #[test]
+    fn test_short_gaps_and_early_pauses_are_not_used() {
+        // A 100ms gap is within a word, and a pause in the first half of a chunk
+        // would leave it too short: both are cut through with an overlap instead
+        let audio =
+            pattern(&[(true, 500), (false, 1000), (true, 3000), (false, 100), (true, 4000)]);
+        let chunks = split_at_silences(&audio, RATE, 0.015, 16000 * 6);
+        assert_eq!(seconds(&chunks), vec![(0.0, 6.0), (5.0, 8.6)]);
+    }
+
+    #[test]
+    fn test_no_pause_cuts_with_overlap() {
+        let audio = pattern(&[(true, 14000)]);
+        let chunks = split_at_silences(&audio, RATE, 0.015, 16000 * 5);
+        assert_eq!(seconds(&chunks), vec![(0.0, 5.0), (4.0, 9.0), (8.0, 13.0), (12.0, 14.0)]);
+        // Silence has no voice to split around either, so it is cut the same way
+        let audio = pattern(&[(false, 7000)]);
+        assert_eq!(split_at_silences(&audio, RATE, 0.015, 16000 * 5).len(), 2);
+    }
+
+    #[test]
+    fn test_join_chunks() {
+        assert_eq!(join_chunks("Hello there.", "How are you?", false), "Hello there. How are you?");
+        // Without an overlap a repeated word is real speech and is kept
+        assert_eq!(join_chunks("I said that", "that is fine", false), "I said that that is fine");
+        assert_eq!(join_chunks("", " Hi ", false), "Hi");
+        assert_eq!(join_chunks("Hi", "", true), "Hi");
+
+        // Words heard in both chunks are kept once, ignoring case and punctuation
+        assert_eq!(
+            join_chunks("the quick brown fox", "Brown fox, jumps over", true),
+            "the quick brown fox jumps over"
+        );
+        assert_eq!(join_chunks("one two", "three four", true), "one two three four");
+        assert_eq!(join_chunks("a b", "a b", true), "a b");
+    }
+}
@@ -0,0 +1,174 @@
+//! Headless command-line transcription
+//!
+//! `app.exe transcribe <file> [--language en] [--json]` transcribes a WAV, FLAC
+//! or Ogg Vorbis file with the configured backend and model, prints the result
+//! to stdout, and exits without starting the tray app.
+
+use crate::audio;
+use crate::backend_loader::{Backend, Transcript, TranscribeRequest};
+use crate::config::{get_backends_dir, set_dir_overrides, setup_cuda_env, Config};
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+pub const USAGE: &str = "Usage: app transcribe <audio file> [--language <code>] [--json]";
+
+/// Options for `transcribe`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscribeArgs {
+    pub audio_path: PathBuf,
+    /// Language code (`None` uses the backend default)
+    pub language: Option<String>,
+    /// Print a JSON object with segments instead of plain text
+    pub json: bool,
+}
+
+/// Parse the arguments following the `transcribe` subcommand
+pub fn parse_transcribe_args(args: &[String]) -> Result<TranscribeArgs> {
+    let mut audio_path = None;
+    let mut language = None;
+    let mut json = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--language" | "-l" => {
+                let code = iter.next().context("--language needs a value")?;
+                language = Some(code.clone());
+            }
+            _ if arg.starts_with("--language=") => {
+                language = Some(arg["--language=".len()..].to_string());
+            }
+            _ if arg.starts_with('-') => anyhow::bail!("Unknown option '{}'", arg),
+            _ if audio_path.is_none() => audio_path = Some(PathBuf::from(arg)),
+            _ => anyhow::bail!("Unexpected argument '{}'", arg),
+        }
+    }
+
+    Ok(TranscribeArgs {
+        audio_path: audio_path.context("Missing audio file")?,
+        language: language.filter(|l| !l.is_empty()),
+        json,
+    })
+}
+
+/// Transcribe a file with the configured model and print the result
+pub fn run_transcribe(args: &TranscribeArgs) -> Result<()> {
+    let config = Config::load().context("No config found - run the app once to choose a model")?;
+    config.check_dirs()?;
+    set_dir_overrides(&config);
+    setup_cuda_env(&config);
+
+    let backend_dir = get_backends_dir()?.join(&config.backend_id);
+    let backend = Backend::open(&backend_dir)?;
+    let options = backend
+        .model_options(&config.model_name, config.compute_type.as_deref())
+        .with_cpu_threads(config.cpu_threads);
+    let (mut model, _) = backend
+        .create_model_with_options(&config.model_path, config.use_gpu, &options)
+        .with_context(|| format!("Failed to load model {}", config.model_path.display()))?;
+    model.set_min_samples(config.min_audio_samples(model.sample_rate()));
+    model.set_max_repeats(config.max_repeats);
+
+    let sample_rate = backend.required_sample_rate(&config.model_name);
+    let audio = audio::decode(&args.audio_path, sample_rate)?;
+
+    let allowed_languages = config.allowed_languages();
+    let request = TranscribeRequest {
+        language: args.language.as_deref(),
+        timestamps: args.json,
+        initial_prompt: config.initial_prompt(),
+        allowed_languages: &allowed_languages,
+        decode: config.decoding,
+        ..Default::default()
+    };
+    let transcript = model.transcribe_with(&audio, &request)?;
+
+    if args.json {
+        println!("{}", transcript_json(&transcript));
+    } else {
+        println!("{}", transcript.text);
+    }
+    Ok(())
+}
+
+/// JSON output: text, detected language, and timed segments
+fn transcript_json(transcript: &Transcript) -> serde_json::Value {
+    let segments: Vec<_> = transcript
+        .segments
+        .iter()
+        .map(|s| {
+            serde_json::json!({
+                "start_ms": s.start_ms,
+                "end_ms": s.end_ms,
+                "text": s.text,
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "text": transcript.text,
+        "language": transcript.detected_language,
+        "segments": segments,
+    })
+}
+
+/// Send output to the console that launched us (release builds have no console of their own)
+#[cfg(target_os = "windows")]
+pub fn attach_parent_console() {
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    unsafe {
+        let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn attach_parent_console() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend_loader::TranscriptSegment;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_transcribe_args() {
+        let parsed = parse_transcribe_args(&args(&["clip.wav"])).unwrap();
+        assert_eq!(parsed.audio_path, PathBuf::from("clip.wav"));
+        assert_eq!(parsed.language, None);
+        assert!(!parsed.json);
+
+        let parsed =
+            parse_transcribe_args(&args(&["--json", "clip.wav", "--language", "en"])).unwrap();
+        assert_eq!(parsed.language.as_deref(), Some("en"));
+        assert!(parsed.json);
+
+        let parsed = parse_transcribe_args(&args(&["clip.wav", "--language=de"])).unwrap();
+        assert_eq!(parsed.language.as_deref(), Some("de"));
+
+        assert!(parse_transcribe_args(&args(&[])).is_err());
+        assert!(parse_transcribe_args(&args(&["clip.wav", "--language"])).is_err());
+        assert!(parse_transcribe_args(&args(&["clip.wav", "--verbose"])).is_err());
+        assert!(parse_transcribe_args(&args(&["a.wav", "b.wav"])).is_err());
+    }
+
+    #[test]
+    fn test_transcript_json() {
+        let transcript = Transcript {
+            text: "Hello there.".to_string(),
+            segments: vec![TranscriptSegment {
+                start_ms: 0,
+                end_ms: 1200,
+                text: "Hello there.".to_string(),
+            }],
+            detected_language: Some("en".to_string()),
+            ..Default::default()
+        };
+        let json = transcript_json(&transcript);
+        assert_eq!(json["text"], "Hello there.");
+        assert_eq!(json["language"], "en");
+        assert_eq!(json["segments"][0]["end_ms"], 1200);
+    }
+}
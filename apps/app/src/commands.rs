@@ -0,0 +1,266 @@
+//! Spoken dictation commands
+//!
+//! With `dictation_commands` on, phrases such as "comma", "new line" or "delete
+//! that" are acted on instead of typed. The phrases come from `command_phrases`
+//! (e.g. to use another language) or default to the English set below.
+
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+
+/// Keys a command can press
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandKey {
+    Enter,
+    Tab,
+    Backspace,
+}
+
+/// What a spoken command does
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum CommandAction {
+    /// Insert text; punctuation attaches to the previous word
+    Insert { text: String },
+    /// Press a key
+    Key { key: CommandKey },
+    /// Take back the previous result (or what this one typed before the command)
+    DeleteLast,
+}
+
+/// A phrase and the action it triggers, e.g.
+/// `{ "phrase": "neue Zeile", "action": "key", "key": "enter" }`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandPhrase {
+    /// Spoken words, matched ignoring case and punctuation
+    pub phrase: String,
+    #[serde(flatten)]
+    pub action: CommandAction,
+}
+
+/// Built-in English phrases that insert text
+const DEFAULT_INSERTS: &[(&str, &str)] = &[
+    ("comma", ","),
+    ("period", "."),
+    ("full stop", "."),
+    ("question mark", "?"),
+    ("exclamation mark", "!"),
+    ("exclamation point", "!"),
+    ("colon", ":"),
+    ("semicolon", ";"),
+    ("new line", "\n"),
+    ("new paragraph", "\n\n"),
+];
+
+/// Built-in English phrases for keys and deleting
+const DEFAULT_ACTIONS: &[(&str, Option<CommandKey>)] = &[
+    ("press enter", Some(CommandKey::Enter)),
+    ("press tab", Some(CommandKey::Tab)),
+    ("backspace", Some(CommandKey::Backspace)),
+    ("delete that", None),
+    ("scratch that", None),
+];
+
+/// The commands used when `command_phrases` is empty
+pub fn default_phrases() -> Vec<CommandPhrase> {
+    let inserts = DEFAULT_INSERTS.iter().map(|&(phrase, text)| CommandPhrase {
+        phrase: phrase.to_string(),
+        action: CommandAction::Insert { text: text.to_string() },
+    });
+    let actions = DEFAULT_ACTIONS.iter().map(|&(phrase, key)| CommandPhrase {
+        phrase: phrase.to_string(),
+        action: key.map_or(CommandAction::DeleteLast, |key| CommandAction::Key { key }),
+    });
+    inserts.chain(actions).collect()
+}
+
+/// Backspaces that erase `text` once typed: one per character, with "\r\n" counting
+/// as the single Enter it was typed as
+pub fn backspace_count(text: &str) -> usize {
+    text.chars().filter(|&c| c != '\r').count()
+}
+
+/// Lowercase letters and digits of a word: "Comma," -> "comma"
+fn normalize_word(word: &str) -> String {
+    word.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// Keystrokes that carry out a result: erase `backspaces` characters already
+/// typed, then type `text`
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Edit {
+    pub backspaces: usize,
+    pub text: String,
+}
+
+impl Edit {
+    /// Plain text with nothing to erase
+    pub fn text(text: String) -> Self {
+        Self { backspaces: 0, text }
+    }
+
+    fn push_word(&mut self, word: &str) {
+        if !self.text.is_empty() && !self.text.ends_with(['\n', '\t']) {
+            self.text.push(' ');
+        }
+        self.text.push_str(word);
+    }
+
+    fn insert(&mut self, text: &str) {
+        // Spoken punctuation replaces any Whisper put after the word ("Hello, comma")
+        if text.chars().all(|c| c.is_ascii_punctuation()) {
+            let kept = self.text.trim_end_matches([',', '.', ';', ':', '!', '?']).len();
+            self.text.truncate(kept);
+        }
+        self.text.push_str(text);
+    }
+
+    /// Carry out `action`; `previous` is what the last result typed and is used up
+    /// by deleting it
+    fn act(&mut self, action: &CommandAction, previous: &mut usize) {
+        match action {
+            CommandAction::Insert { text } => self.insert(text),
+            CommandAction::Key { key: CommandKey::Enter } => self.insert("\n"),
+            CommandAction::Key { key: CommandKey::Tab } => self.insert("\t"),
+            CommandAction::Key { key: CommandKey::Backspace } => {
+                if self.text.pop().is_none() {
+                    self.backspaces += 1;
+                    *previous = previous.saturating_sub(1);
+                }
+            }
+            CommandAction::DeleteLast if self.text.is_empty() => {
+                self.backspaces += std::mem::take(previous);
+            }
+            CommandAction::DeleteLast => self.text.clear(),
+        }
+    }
+}
+
+/// Command phrases ready for matching, longest first so "new paragraph" isn't
+/// read as a word followed by something else
+pub struct Commands {
+    phrases: Vec<(Vec<String>, CommandAction)>,
+}
+
+impl Commands {
+    pub fn new(phrases: &[CommandPhrase]) -> Self {
+        let mut phrases: Vec<(Vec<String>, CommandAction)> = phrases
+            .iter()
+            .map(|p| {
+                let words = p.phrase.split_whitespace().map(normalize_word);
+                (words.filter(|w| !w.is_empty()).collect::<Vec<_>>(), p.action.clone())
+            })
+            .filter(|(words, _)| !words.is_empty())
+            .collect();
+        phrases.sort_by_key(|(words, _)| std::cmp::Reverse(words.len()));
+        Self { phrases }
+    }
+
+    /// The configured commands, or `None` if they are turned off
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if !config.dictation_commands {
+            return None;
+        }
+        if config.command_phrases.is_empty() {
+            Some(Self::new(&default_phrases()))
+        } else {
+            Some(Self::new(&config.command_phrases))
+        }
+    }
+
+    /// The command starting at the first of `words` (normalized), and how many
+    /// words it spans
+    fn match_at(&self, words: &[String]) -> Option<(usize, &CommandAction)> {
+        self.phrases
+            .iter()
+            .find(|(phrase, _)| words.starts_with(phrase))
+            .map(|(phrase, action)| (phrase.len(), action))
+    }
+
+    /// Turn a result into keystrokes. `previous` is the number of characters the
+    /// last result typed, which "delete that" erases.
+    pub fn apply(&self, text: &str, previous: usize) -> Edit {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let normalized: Vec<String> = words.iter().map(|w| normalize_word(w)).collect();
+        let mut previous = previous;
+        let mut edit = Edit::default();
+        let mut i = 0;
+        while i < words.len() {
+            match self.match_at(&normalized[i..]) {
+                Some((len, action)) => {
+                    edit.act(action, &mut previous);
+                    i += len;
+                }
+                None => {
+                    edit.push_word(words[i]);
+                    i += 1;
+                }
+            }
+        }
+        edit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(backspaces: usize, text: &str) -> Edit {
+        Edit { backspaces, text: text.to_string() }
+    }
+
+    #[test]
+    fn test_phrases_to_actions() {
+        let commands = Commands::new(&default_phrases());
+        assert_eq!(commands.apply("Hello comma world period", 0), edit(0, "Hello, world."));
+        // Whisper's own punctuation around the spoken command is replaced
+        assert_eq!(commands.apply("Hello, comma, world. Period.", 0), edit(0, "Hello, world."));
+        assert_eq!(commands.apply("Dear Sam, New line. Thanks", 0), edit(0, "Dear Sam,\nThanks"));
+        assert_eq!(commands.apply("one new paragraph two", 0), edit(0, "one\n\ntwo"));
+        assert_eq!(commands.apply("Press Enter.", 0), edit(0, "\n"));
+        assert_eq!(commands.apply("no commands here", 0), edit(0, "no commands here"));
+        assert_eq!(commands.apply("", 0), edit(0, ""));
+
+        // "delete that" erases the previous result once, or this result's text so far
+        assert_eq!(commands.apply("Delete that.", 12), edit(12, ""));
+        assert_eq!(commands.apply("delete that delete that", 12), edit(12, ""));
+        assert_eq!(commands.apply("oops delete that fine", 12), edit(0, "fine"));
+        assert_eq!(commands.apply("Scratch that. Hi", 3), edit(3, "Hi"));
+        assert_eq!(commands.apply("ab backspace", 5), edit(0, "a"));
+        assert_eq!(commands.apply("backspace backspace", 5), edit(2, ""));
+    }
+
+    #[test]
+    fn test_custom_phrases() {
+        let json = r#"[
+            { "phrase": "Neue Zeile", "action": "key", "key": "enter" },
+            { "phrase": "Komma", "action": "insert", "text": "," },
+            { "phrase": "lösch das", "action": "delete_last" }
+        ]"#;
+        let phrases: Vec<CommandPhrase> = serde_json::from_str(json).unwrap();
+        assert_eq!(phrases[2].action, CommandAction::DeleteLast);
+        let commands = Commands::new(&phrases);
+        assert_eq!(commands.apply("Hallo Komma Welt neue Zeile", 0), edit(0, "Hallo, Welt\n"));
+        assert_eq!(commands.apply("Lösch das!", 4), edit(4, ""));
+        // Only the configured phrases apply
+        assert_eq!(commands.apply("comma", 0), edit(0, "comma"));
+
+        let mut config = Config::default();
+        assert!(Commands::from_config(&config).is_none());
+        config.dictation_commands = true;
+        let commands = Commands::from_config(&config).unwrap();
+        assert_eq!(commands.apply("a comma b", 0), edit(0, "a, b"));
+    }
+
+    #[test]
+    fn test_backspace_count() {
+        assert_eq!(backspace_count(""), 0);
+        assert_eq!(backspace_count("hello "), 6);
+        // Characters, not bytes
+        assert_eq!(backspace_count("café"), 4);
+        assert_eq!(backspace_count("日本語"), 3);
+        assert_eq!(backspace_count("naïve 😀"), 7);
+        // One Enter was typed for each line break
+        assert_eq!(backspace_count("a\r\nb\n"), 4);
+    }
+}
@@ -1,9 +1,18 @@
+use crate::always_listen::{self, AlwaysListenConfig};
+use crate::audio::{self, NoiseGateConfig};
+use crate::backend_loader::{ms_to_samples, DecodeOptions};
+use crate::commands::CommandPhrase;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::RwLock;
 use tracing::info;
 
+/// `backends_dir` / `models_dir` from the loaded config (see `set_dir_overrides`)
+static BACKENDS_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+static MODELS_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     /// Backend ID (e.g., "whisper-ct2" or "whisper-cpp")
@@ -13,6 +22,12 @@ pub struct Config {
     pub model_path: PathBuf,
     #[serde(default)]
     pub use_gpu: bool,
+    /// Precision for ct2 models, such as "int8" (None = the manifest default)
+    #[serde(default)]
+    pub compute_type: Option<String>,
+    /// CPU threads used for inference (0 = the inference library's default)
+    #[serde(default)]
+    pub cpu_threads: u32,
     /// Path to CUDA installation (auto-detected if not set)
     #[serde(default)]
     pub cuda_path: Option<PathBuf>,
@@ -24,19 +39,473 @@ pub struct Config {
     pub overlay_x: Option<i32>,
     #[serde(default)]
     pub overlay_y: Option<i32>,
+    /// Keep the overlay above other windows (including fullscreen games)
+    #[serde(default = "default_overlay_always_on_top")]
+    pub overlay_always_on_top: bool,
+    /// Let clicks pass through the overlay to the window underneath (disables dragging)
+    #[serde(default)]
+    pub overlay_click_through: bool,
+    /// Show each result under the overlay's status for a few seconds
+    #[serde(default)]
+    pub overlay_show_text: bool,
+    /// Beep when recording starts and stops
+    #[serde(default)]
+    pub audio_cues: bool,
+    /// Output device for the beeps (None = system default)
+    #[serde(default)]
+    pub audio_cue_device: Option<String>,
     pub hotkey_push_to_talk: String,
+    /// Push-to-talk records while the key is held (false = press to start, press again to stop)
+    #[serde(default = "default_push_to_talk_hold")]
+    pub push_to_talk_hold: bool,
     pub hotkey_always_listen: String,
+    /// Mute toggle: drops results and disables listening until pressed again
+    #[serde(default = "default_hotkey_mute")]
+    pub hotkey_mute: String,
+    /// Pauses and resumes always-listening without turning it off; empty = no hotkey
+    #[serde(default)]
+    pub hotkey_pause_listen: String,
+    /// Time allowed between the two taps of a `DoubleTap+` hotkey (milliseconds)
+    #[serde(default = "default_double_tap_ms")]
+    pub double_tap_ms: u64,
     #[serde(default)]
     pub input_device_name: Option<String>,
     /// Silence timeout for always-listen mode (milliseconds)
     #[serde(default = "default_silence_timeout_ms")]
     pub silence_timeout_ms: u64,
+    /// Recordings shorter than this are skipped without transcribing (milliseconds)
+    #[serde(default = "default_min_audio_ms")]
+    pub min_audio_ms: u64,
+    /// Push-to-talk recordings auto-stop after this many seconds (0 = unlimited)
+    #[serde(default = "default_max_recording_seconds")]
+    pub max_recording_seconds: u64,
+    /// Push-to-talk: audio kept from just before the key press and prepended to the
+    /// recording (milliseconds, 0 = off). Keeps the microphone open while idle.
+    #[serde(default)]
+    pub pre_roll_ms: u64,
+    /// Recordings longer than this are transcribed in pieces split at pauses, so
+    /// latency stays bounded (seconds, 0 = never)
+    #[serde(default = "default_chunk_seconds")]
+    pub chunk_seconds: u64,
+    /// Run a tiny inference on silence whenever the model has gone unused for
+    /// `keep_warm_minutes`, so the first transcription after a long idle isn't slow
+    #[serde(default)]
+    pub keep_warm: bool,
+    /// Minutes the model may sit unused before a keep-warm run (at least 1)
+    #[serde(default = "default_keep_warm_minutes")]
+    pub keep_warm_minutes: u64,
+    /// High-pass recordings at 80 Hz before transcribing (removes DC offset and rumble)
+    #[serde(default)]
+    pub highpass_enabled: bool,
+    /// Normalize the peak level of recordings before transcribing
+    #[serde(default)]
+    pub normalize_enabled: bool,
+    /// Always-listen: join consecutive results with a space, or a newline after a
+    /// pause at least this long (milliseconds, 0 = type each result as-is)
+    #[serde(default)]
+    pub always_listen_paragraph_gap_ms: u64,
+    /// Type always-listen results segment by segment while they are decoded
+    #[serde(default)]
+    pub incremental_typing: bool,
+    /// Ambient mic level measured by setup calibration (sets the VAD threshold)
+    #[serde(default)]
+    pub vad_noise_floor: Option<f32>,
+    /// Always-listen speech threshold (frame RMS); overrides the one derived from
+    /// `vad_noise_floor`
+    #[serde(default)]
+    pub vad_threshold: Option<f32>,
+    /// Always-listen: audio kept from before speech was detected (milliseconds)
+    #[serde(default = "default_vad_pre_roll_ms")]
+    pub vad_pre_roll_ms: u64,
+    /// Always-listen: speech needed before a recording starts (milliseconds)
+    #[serde(default = "default_vad_min_speech_ms")]
+    pub vad_min_speech_ms: u64,
+    /// Always-listen: mute audio quieter than this level (RMS) before transcribing,
+    /// e.g. fan or keyboard noise between words (None = no gate)
+    #[serde(default)]
+    pub noise_gate_threshold: Option<f32>,
+    /// How fast the noise gate opens (milliseconds)
+    #[serde(default = "default_noise_gate_attack_ms")]
+    pub noise_gate_attack_ms: u64,
+    /// How fast the noise gate closes again (milliseconds)
+    #[serde(default = "default_noise_gate_release_ms")]
+    pub noise_gate_release_ms: u64,
+    /// Words or phrases to bias transcription toward (e.g. names, jargon)
+    #[serde(default)]
+    pub initial_prompt: String,
+    /// CTranslate2 decoder tweaks, e.g. `{ "suppress_blank": false }` when the first
+    /// word of a recording goes missing (default: the library's settings)
+    #[serde(default)]
+    pub decoding: DecodeOptions,
+    /// `[from, to]` pairs replaced in each result (whole words, ignoring case)
+    #[serde(default)]
+    pub replacements: Vec<(String, String)>,
+    /// Drop spaces before punctuation, collapse doubled spaces and capitalize the
+    /// first letter of each result (see `text_format::normalize`)
+    #[serde(default)]
+    pub fix_common_errors: bool,
+    /// Act on spoken commands ("comma", "new line", "delete that") instead of
+    /// typing them (see `commands`)
+    #[serde(default)]
+    pub dictation_commands: bool,
+    /// Phrases for `dictation_commands`, e.g. in another language (empty = the
+    /// built-in English ones)
+    #[serde(default)]
+    pub command_phrases: Vec<CommandPhrase>,
+    /// Results Whisper invents for silence ("Thank you.", "[BLANK_AUDIO]"), dropped
+    /// when the recording is quieter than `hallucination_max_rms`
+    #[serde(default = "default_hallucination_phrases")]
+    pub hallucination_phrases: Vec<String>,
+    #[serde(default = "default_hallucination_max_rms")]
+    pub hallucination_max_rms: f32,
+    /// Results whose backend-reported no-speech probability is above this are
+    /// dropped (1.0 = never; backends that can't report it always pass)
+    #[serde(default = "default_no_speech_threshold")]
+    pub no_speech_threshold: f32,
+    /// A word or short phrase said more than this many times in a row is taken for
+    /// a decoding loop and the result is cut after its first saying (0 = off)
+    #[serde(default = "default_max_repeats")]
+    pub max_repeats: usize,
+    /// How transcribed text is delivered (typed or clipboard)
+    #[serde(default)]
+    pub output_mode: OutputMode,
+    /// In `clipboard_only` mode, add each result on a new line after the text
+    /// already on the clipboard instead of replacing it
+    #[serde(default)]
+    pub clipboard_append: bool,
+    /// How "typed" text reaches the window: simulated keystrokes or a clipboard paste
+    #[serde(default)]
+    pub type_mode: TypeMode,
+    /// Typed after each push-to-talk result (`none`, `space` or `newline`); part of
+    /// the pasted text in clipboard-paste mode
+    #[serde(default)]
+    pub trailing_char: TrailingChar,
+    /// Bring back the window that had focus when push-to-talk started before
+    /// typing the result, e.g. after clicking the overlay
+    #[serde(default)]
+    pub restore_focus: bool,
+    /// Per-application overrides, matched against the foreground process
+    #[serde(default)]
+    pub profiles: Vec<AppProfile>,
+    /// Model files downloaded at the same time
+    #[serde(default = "default_download_concurrency")]
+    pub download_concurrency: usize,
+    /// Recent transcripts kept for the tray's History window (0 = off)
+    #[serde(default = "default_history_size")]
+    pub history_size: usize,
+    /// Save the history next to the config so it survives restarts
+    #[serde(default = "default_history_persist")]
+    pub history_persist: bool,
+    /// Languages auto-detect may choose from, e.g. `["en", "de"]` (empty = any). A
+    /// single entry is used as the language; a profile's `language` still wins.
+    #[serde(default)]
+    pub allowed_languages: Vec<String>,
+    /// Languages listed in the tray's Language menu, next to Auto
+    #[serde(default = "default_quick_languages")]
+    pub quick_languages: Vec<String>,
+    /// Language last picked in the tray's Language menu (None = auto-detect). A
+    /// profile's `language` still wins.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Second backend/model for languages the main model doesn't cover
+    #[serde(default)]
+    pub fallback_model: Option<LanguageFallback>,
+    /// Append every transcription as a JSON line to this file (None = off)
+    #[serde(default)]
+    pub transcript_log_path: Option<PathBuf>,
+    /// Folder the tray's Save Last Transcript writes to (None = `transcripts` next
+    /// to the exe)
+    #[serde(default)]
+    pub transcript_export_dir: Option<PathBuf>,
+    /// Start the app at Windows sign-in (mirrors the registry entry the wizard writes)
+    #[serde(default)]
+    pub autostart: bool,
+    /// Look for backends here instead of `backends` next to the exe
+    #[serde(default)]
+    pub backends_dir: Option<PathBuf>,
+    /// Download and load models here instead of `models` next to the exe
+    #[serde(default)]
+    pub models_dir: Option<PathBuf>,
+}
+
+/// A backend/model that takes over for languages outside `primary_languages`,
+/// e.g. a multilingual whisper.cpp model behind a fast English-only one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageFallback {
+    pub backend_id: String,
+    /// Model id from the fallback backend's manifest
+    pub model_name: String,
+    /// Languages the main model handles; anything else goes to the fallback
+    #[serde(default = "default_primary_languages")]
+    pub primary_languages: Vec<String>,
+    /// Load at startup instead of on the first utterance that needs it
+    #[serde(default)]
+    pub preload: bool,
+}
+
+impl LanguageFallback {
+    /// True if speech in `language` should be transcribed by the fallback model
+    pub fn handles(&self, language: &str) -> bool {
+        !self.primary_languages.iter().any(|l| l.eq_ignore_ascii_case(language))
+    }
+}
+
+/// How transcribed text is delivered to the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputMode {
+    /// Type the whole string into the active window in one go
+    #[default]
+    Text,
+    /// Type one key press per character (for apps that drop fast input)
+    Keys,
+    /// Only copy to the clipboard; never send keystrokes (also read as "clipboard")
+    #[serde(alias = "clipboard")]
+    ClipboardOnly,
+}
+
+/// How the typer gets text into the active window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypeMode {
+    /// Simulate keyboard input
+    #[default]
+    Keystrokes,
+    /// Put the text on the clipboard, send Ctrl+V, then restore the clipboard
+    ClipboardPaste,
+}
+
+/// Character typed after each push-to-talk result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrailingChar {
+    /// Type the result as-is
+    #[default]
+    None,
+    Space,
+    /// Enter, so each result goes on its own line
+    Newline,
+}
+
+impl TrailingChar {
+    /// Text appended to the result
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TrailingChar::None => "",
+            TrailingChar::Space => " ",
+            TrailingChar::Newline => "\n",
+        }
+    }
+}
+
+/// Settings applied when a specific application has focus
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AppProfile {
+    /// Executable file name, e.g. "slack.exe" or "slack" ("*" matches any app)
+    pub exe: String,
+    /// Transcription language override (e.g. "es")
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Model id override (must belong to the configured backend)
+    #[serde(default)]
+    pub model_name: Option<String>,
+    /// Output mode override
+    #[serde(default, alias = "type_mode")]
+    pub output_mode: Option<OutputMode>,
+    /// Translate speech to English (multilingual models on whisper.cpp)
+    #[serde(default)]
+    pub translate: bool,
+    /// Keystrokes or clipboard paste for this app (overrides `type_mode`)
+    #[serde(default)]
+    pub typing: Option<TypeMode>,
+    /// Type a space after each push-to-talk result, so the next one doesn't run
+    /// into it (always-listen results are already joined with spaces)
+    #[serde(default)]
+    pub trailing_space: Option<bool>,
+    /// Never type into this app; recordings made while it has focus are dropped
+    #[serde(default)]
+    pub disable: bool,
+}
+
+impl AppProfile {
+    /// Trailing character for this app: `trailing_space` overrides the config's
+    pub fn trailing_char(&self, default: TrailingChar) -> TrailingChar {
+        match self.trailing_space {
+            Some(true) => TrailingChar::Space,
+            Some(false) => TrailingChar::None,
+            None => default,
+        }
+    }
+}
+
+/// Profile used when nothing matches: no overrides
+static DEFAULT_PROFILE: AppProfile = AppProfile {
+    exe: String::new(),
+    language: None,
+    model_name: None,
+    output_mode: None,
+    translate: false,
+    typing: None,
+    trailing_space: None,
+    disable: false,
+};
+
+/// Whether a profile's `exe` names this executable: case-insensitive, and the
+/// ".exe" extension may be left off ("code" matches "Code.exe")
+pub fn exe_matches(pattern: &str, exe: &str) -> bool {
+    let strip = |name: &str| -> String {
+        let name = name.to_ascii_lowercase();
+        match name.strip_suffix(".exe") {
+            Some(stem) => stem.to_string(),
+            None => name,
+        }
+    };
+    !pattern.is_empty() && strip(pattern) == strip(exe)
 }
 
 fn default_silence_timeout_ms() -> u64 {
     2000 // 2 seconds default
 }
 
+fn default_min_audio_ms() -> u64 {
+    100 // Accidental hotkey taps are shorter than this
+}
+
+fn default_max_recording_seconds() -> u64 {
+    120 // 2 minutes
+}
+
+fn default_chunk_seconds() -> u64 {
+    30 // The window Whisper decodes at a time
+}
+
+fn default_keep_warm_minutes() -> u64 {
+    5
+}
+
+fn default_download_concurrency() -> usize {
+    3
+}
+
+fn default_hallucination_phrases() -> Vec<String> {
+    [
+        "Thank you.",
+        "Thanks for watching!",
+        "Thank you for watching.",
+        "Please subscribe.",
+        "Bye.",
+        "you",
+        "[BLANK_AUDIO]",
+        "(silence)",
+        "[Music]",
+        "Subtitles by the Amara.org community",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+fn default_hallucination_max_rms() -> f32 {
+    0.01
+}
+
+fn default_no_speech_threshold() -> f32 {
+    1.0
+}
+
+fn default_max_repeats() -> usize {
+    crate::backend_loader::DEFAULT_MAX_REPEATS
+}
+
+fn default_vad_pre_roll_ms() -> u64 {
+    AlwaysListenConfig::default().pre_roll_duration_ms
+}
+
+fn default_vad_min_speech_ms() -> u64 {
+    AlwaysListenConfig::default().min_speech_duration_ms
+}
+
+fn default_noise_gate_attack_ms() -> u64 {
+    audio::DEFAULT_GATE_ATTACK_MS
+}
+
+fn default_noise_gate_release_ms() -> u64 {
+    audio::DEFAULT_GATE_RELEASE_MS
+}
+
+fn default_history_size() -> usize {
+    50
+}
+
+fn default_history_persist() -> bool {
+    true
+}
+
+fn default_primary_languages() -> Vec<String> {
+    vec!["en".to_string()]
+}
+
+fn default_quick_languages() -> Vec<String> {
+    ["en", "es", "fr", "de"].map(String::from).to_vec()
+}
+
+/// Languages offered in the setup wizard, as (code, name)
+pub const COMMON_LANGUAGES: [(&str, &str); 16] = [
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fr", "French"),
+    ("de", "German"),
+    ("it", "Italian"),
+    ("pt", "Portuguese"),
+    ("nl", "Dutch"),
+    ("pl", "Polish"),
+    ("ru", "Russian"),
+    ("uk", "Ukrainian"),
+    ("tr", "Turkish"),
+    ("ar", "Arabic"),
+    ("hi", "Hindi"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("zh", "Chinese"),
+];
+
+/// Name of a language code ("de" -> "German"), or the code itself if it isn't
+/// in `COMMON_LANGUAGES`
+pub fn language_name(code: &str) -> &str {
+    COMMON_LANGUAGES
+        .iter()
+        .find(|(c, _)| c.eq_ignore_ascii_case(code))
+        .map_or(code, |(_, name)| name)
+}
+
+/// Lowercase codes without blanks or repeats
+fn normalize_languages<'a>(languages: impl IntoIterator<Item = &'a String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for language in languages {
+        let language = language.trim().to_ascii_lowercase();
+        if !language.is_empty() && !normalized.contains(&language) {
+            normalized.push(language);
+        }
+    }
+    normalized
+}
+
+fn default_overlay_always_on_top() -> bool {
+    true
+}
+
+fn default_push_to_talk_hold() -> bool {
+    true
+}
+
+fn default_hotkey_mute() -> String {
+    "Control+Shift+Backquote".to_string()
+}
+
+fn default_double_tap_ms() -> u64 {
+    300
+}
+
 fn default_backend_id() -> String {
     "whisper-ct2".to_string()
 }
@@ -48,15 +517,71 @@ impl Default for Config {
             model_name: "whisper-tiny-en".to_string(),
             model_path: get_models_dir().unwrap_or_default().join("whisper-tiny-en"),
             use_gpu: false,
+            compute_type: None,
+            cpu_threads: 0,
             cuda_path: None,
             cudnn_path: None,
             overlay_visible: true,
             overlay_x: None,
             overlay_y: None,
+            overlay_always_on_top: default_overlay_always_on_top(),
+            overlay_click_through: false,
+            overlay_show_text: false,
+            audio_cues: false,
+            audio_cue_device: None,
             hotkey_push_to_talk: "Backquote".to_string(),
+            push_to_talk_hold: default_push_to_talk_hold(),
             hotkey_always_listen: "Control+Backquote".to_string(),
+            hotkey_mute: default_hotkey_mute(),
+            hotkey_pause_listen: String::new(),
+            double_tap_ms: default_double_tap_ms(),
             input_device_name: None,
             silence_timeout_ms: default_silence_timeout_ms(),
+            min_audio_ms: default_min_audio_ms(),
+            max_recording_seconds: default_max_recording_seconds(),
+            pre_roll_ms: 0,
+            chunk_seconds: default_chunk_seconds(),
+            keep_warm: false,
+            keep_warm_minutes: default_keep_warm_minutes(),
+            highpass_enabled: false,
+            normalize_enabled: false,
+            always_listen_paragraph_gap_ms: 0,
+            incremental_typing: false,
+            vad_noise_floor: None,
+            vad_threshold: None,
+            vad_pre_roll_ms: default_vad_pre_roll_ms(),
+            vad_min_speech_ms: default_vad_min_speech_ms(),
+            noise_gate_threshold: None,
+            noise_gate_attack_ms: default_noise_gate_attack_ms(),
+            noise_gate_release_ms: default_noise_gate_release_ms(),
+            initial_prompt: String::new(),
+            decoding: DecodeOptions::default(),
+            replacements: Vec::new(),
+            fix_common_errors: false,
+            dictation_commands: false,
+            command_phrases: Vec::new(),
+            hallucination_phrases: default_hallucination_phrases(),
+            hallucination_max_rms: default_hallucination_max_rms(),
+            no_speech_threshold: default_no_speech_threshold(),
+            max_repeats: default_max_repeats(),
+            output_mode: OutputMode::default(),
+            clipboard_append: false,
+            type_mode: TypeMode::default(),
+            trailing_char: TrailingChar::default(),
+            restore_focus: false,
+            profiles: Vec::new(),
+            download_concurrency: default_download_concurrency(),
+            history_size: default_history_size(),
+            history_persist: default_history_persist(),
+            allowed_languages: Vec::new(),
+            quick_languages: default_quick_languages(),
+            language: None,
+            fallback_model: None,
+            transcript_log_path: None,
+            transcript_export_dir: None,
+            backends_dir: None,
+            models_dir: None,
+            autostart: false,
         }
     }
 }
@@ -81,14 +606,27 @@ pub fn get_exe_stem() -> Result<String> {
     Ok(stem.to_string())
 }
 
-/// Get the models directory (next to exe)
+/// Get the models directory (next to exe unless the config overrides it)
 pub fn get_models_dir() -> Result<PathBuf> {
-    Ok(get_exe_dir()?.join("models"))
+    match MODELS_DIR.read().unwrap_or_else(|e| e.into_inner()).clone() {
+        Some(dir) => Ok(dir),
+        None => Ok(get_exe_dir()?.join("models")),
+    }
 }
 
-/// Get the backends directory (next to exe)
+/// Get the backends directory (next to exe unless the config overrides it)
 pub fn get_backends_dir() -> Result<PathBuf> {
-    Ok(get_exe_dir()?.join("backends"))
+    match BACKENDS_DIR.read().unwrap_or_else(|e| e.into_inner()).clone() {
+        Some(dir) => Ok(dir),
+        None => Ok(get_exe_dir()?.join("backends")),
+    }
+}
+
+/// Make `get_backends_dir` and `get_models_dir` use the config's directories.
+/// Call once the config is loaded; check them first with `Config::check_dirs`.
+pub fn set_dir_overrides(config: &Config) {
+    *BACKENDS_DIR.write().unwrap_or_else(|e| e.into_inner()) = config.backends_dir.clone();
+    *MODELS_DIR.write().unwrap_or_else(|e| e.into_inner()) = config.models_dir.clone();
 }
 
 /// Get the config file path (next to exe)
@@ -97,6 +635,58 @@ pub fn get_config_path() -> Result<PathBuf> {
     Ok(get_exe_dir()?.join(format!("config-{}.json", stem)))
 }
 
+/// Get the transcript history file path (next to the config)
+pub fn get_history_path() -> Result<PathBuf> {
+    let stem = get_exe_stem()?;
+    Ok(get_exe_dir()?.join(format!("history-{}.json", stem)))
+}
+
+/// Get the path of a named config profile: `config-<stem>-<name>.json`
+pub fn get_profile_config_path(name: &str) -> Result<PathBuf> {
+    validate_profile_name(name)?;
+    let stem = get_exe_stem()?;
+    Ok(get_exe_dir()?.join(format!("config-{}-{}.json", stem, name)))
+}
+
+/// Profile name of a `config-<stem>-<name>.json` file, if it is one
+fn profile_name_from_file<'a>(file_name: &'a str, stem: &str) -> Option<&'a str> {
+    file_name
+        .strip_prefix("config-")?
+        .strip_prefix(stem)?
+        .strip_prefix('-')?
+        .strip_suffix(".json")
+        .filter(|name| validate_profile_name(name).is_ok())
+}
+
+/// Profile name for a file picked in a save dialog: the `<name>` of a
+/// `config-<stem>-<name>.json` file, otherwise the file name without `.json`
+pub fn profile_name_from_path(path: &Path) -> Result<String> {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    if let Some(name) = profile_name_from_file(file_name, &get_exe_stem()?) {
+        return Ok(name.to_string());
+    }
+    let name = file_name.strip_suffix(".json").unwrap_or(file_name);
+    validate_profile_name(name)?;
+    Ok(name.to_string())
+}
+
+/// Profile names become part of a file name: letters, digits, '-', '_' and spaces only
+pub fn validate_profile_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() {
+        anyhow::bail!("Profile name is empty");
+    }
+    if name != name.trim() {
+        anyhow::bail!("Profile name can't start or end with a space");
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_alphanumeric() || matches!(c, '-' | '_' | ' ')))
+    {
+        anyhow::bail!("Profile name can't contain '{}'", c);
+    }
+    Ok(())
+}
+
 fn get_legacy_config_path() -> Result<PathBuf> {
     Ok(get_exe_dir()?.join("config.json"))
 }
@@ -472,6 +1062,18 @@ impl Config {
         self.model_path.exists()
     }
 
+    /// Fail if `backends_dir` or `models_dir` is set to a folder that doesn't exist,
+    /// rather than finding no backends or models there
+    pub fn check_dirs(&self) -> Result<()> {
+        let dirs = [("backends_dir", &self.backends_dir), ("models_dir", &self.models_dir)];
+        for (key, dir) in dirs {
+            if let Some(dir) = dir.as_deref().filter(|dir| !dir.is_dir()) {
+                anyhow::bail!("The {} folder in the config doesn't exist: {}", key, dir.display());
+            }
+        }
+        Ok(())
+    }
+
     /// Try to load config from file
     pub fn load() -> Result<Self> {
         let config_path = get_config_path()?;
@@ -502,6 +1104,36 @@ impl Config {
         Ok(())
     }
 
+    /// Names of the saved config profiles next to the exe, sorted
+    pub fn list_profiles() -> Result<Vec<String>> {
+        let stem = get_exe_stem()?;
+        let mut names: Vec<String> = fs::read_dir(get_exe_dir()?)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let file_name = entry.file_name();
+                profile_name_from_file(file_name.to_str()?, &stem).map(str::to_string)
+            })
+            .collect();
+        names.sort_by_key(|name| name.to_lowercase());
+        Ok(names)
+    }
+
+    /// Load a named config profile
+    pub fn load_profile(name: &str) -> Result<Self> {
+        let path = get_profile_config_path(name)?;
+        let content = fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("Profile '{}' not found: {}", name, e))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save this config as a named profile (overwrites a profile of the same name)
+    pub fn save_as_profile(&self, name: &str) -> Result<()> {
+        let path = get_profile_config_path(name)?;
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
     /// Create config for a specific model
     pub fn for_model(
         backend_id: &str,
@@ -520,17 +1152,152 @@ impl Config {
             model_name: model_name.to_string(),
             model_path,
             use_gpu,
+            compute_type: None,
+            cpu_threads: 0,
             cuda_path,
             cudnn_path,
             overlay_visible: true,
             overlay_x: None,
             overlay_y: None,
+            overlay_always_on_top: default_overlay_always_on_top(),
+            overlay_click_through: false,
+            overlay_show_text: false,
+            audio_cues: false,
+            audio_cue_device: None,
             hotkey_push_to_talk: hotkey_push_to_talk.to_string(),
+            push_to_talk_hold: default_push_to_talk_hold(),
             hotkey_always_listen: hotkey_always_listen.to_string(),
+            hotkey_mute: default_hotkey_mute(),
+            hotkey_pause_listen: String::new(),
+            double_tap_ms: default_double_tap_ms(),
             input_device_name,
             silence_timeout_ms,
+            min_audio_ms: default_min_audio_ms(),
+            max_recording_seconds: default_max_recording_seconds(),
+            pre_roll_ms: 0,
+            chunk_seconds: default_chunk_seconds(),
+            keep_warm: false,
+            keep_warm_minutes: default_keep_warm_minutes(),
+            highpass_enabled: false,
+            normalize_enabled: false,
+            always_listen_paragraph_gap_ms: 0,
+            incremental_typing: false,
+            vad_noise_floor: None,
+            vad_threshold: None,
+            vad_pre_roll_ms: default_vad_pre_roll_ms(),
+            vad_min_speech_ms: default_vad_min_speech_ms(),
+            noise_gate_threshold: None,
+            noise_gate_attack_ms: default_noise_gate_attack_ms(),
+            noise_gate_release_ms: default_noise_gate_release_ms(),
+            initial_prompt: String::new(),
+            decoding: DecodeOptions::default(),
+            replacements: Vec::new(),
+            fix_common_errors: false,
+            dictation_commands: false,
+            command_phrases: Vec::new(),
+            hallucination_phrases: default_hallucination_phrases(),
+            hallucination_max_rms: default_hallucination_max_rms(),
+            no_speech_threshold: default_no_speech_threshold(),
+            max_repeats: default_max_repeats(),
+            output_mode: OutputMode::default(),
+            clipboard_append: false,
+            type_mode: TypeMode::default(),
+            trailing_char: TrailingChar::default(),
+            restore_focus: false,
+            profiles: Vec::new(),
+            download_concurrency: default_download_concurrency(),
+            history_size: default_history_size(),
+            history_persist: default_history_persist(),
+            allowed_languages: Vec::new(),
+            quick_languages: default_quick_languages(),
+            language: None,
+            fallback_model: None,
+            transcript_log_path: None,
+            transcript_export_dir: None,
+            backends_dir: None,
+            models_dir: None,
+            autostart: false,
+        }
+    }
+
+    /// The vocabulary prompt, or `None` if it is blank
+    pub fn initial_prompt(&self) -> Option<&str> {
+        Some(self.initial_prompt.trim()).filter(|p| !p.is_empty())
+    }
+
+    /// Folder for Save Last Transcript: `transcript_export_dir`, or `transcripts`
+    /// next to the exe
+    pub fn transcript_export_dir(&self) -> Result<PathBuf> {
+        match &self.transcript_export_dir {
+            Some(dir) => Ok(dir.clone()),
+            None => Ok(get_exe_dir()?.join("transcripts")),
+        }
+    }
+
+    /// `allowed_languages` as lowercase codes, without blanks or repeats
+    pub fn allowed_languages(&self) -> Vec<String> {
+        normalize_languages(&self.allowed_languages)
+    }
+
+    /// The tray's Language menu entries: `quick_languages`, plus the picked
+    /// `language` if it isn't among them
+    pub fn quick_languages(&self) -> Vec<String> {
+        normalize_languages(self.quick_languages.iter().chain(&self.language))
+    }
+
+    /// Minimum recording length in samples at the model's `sample_rate`
+    pub fn min_audio_samples(&self, sample_rate: u32) -> usize {
+        ms_to_samples(self.min_audio_ms, sample_rate)
+    }
+
+    /// Maximum push-to-talk recording length in samples at the model's `sample_rate`
+    /// (0 = unlimited)
+    pub fn max_recording_samples(&self, sample_rate: u32) -> usize {
+        ms_to_samples(self.max_recording_seconds * 1000, sample_rate)
+    }
+
+    /// Push-to-talk pre-roll length, clamped like the always-listen one
+    pub fn pre_roll_ms(&self) -> u64 {
+        let range = always_listen::PRE_ROLL_MS_RANGE;
+        self.pre_roll_ms.clamp(*range.start(), *range.end())
+    }
+
+    /// Always-listen VAD settings for audio at the model's `sample_rate`, clamped
+    /// to the ranges the setup wizard offers
+    pub fn always_listen_config(&self, sample_rate: u32) -> AlwaysListenConfig {
+        use always_listen::{MIN_SPEECH_MS_RANGE, POST_SILENCE_MS_RANGE, PRE_ROLL_MS_RANGE};
+        let clamp = |value: u64, range: std::ops::RangeInclusive<u64>| {
+            value.clamp(*range.start(), *range.end())
+        };
+        AlwaysListenConfig {
+            pre_roll_duration_ms: clamp(self.vad_pre_roll_ms, PRE_ROLL_MS_RANGE),
+            min_speech_duration_ms: clamp(self.vad_min_speech_ms, MIN_SPEECH_MS_RANGE),
+            post_silence_duration_ms: clamp(self.silence_timeout_ms, POST_SILENCE_MS_RANGE),
+            vad_threshold: always_listen::effective_threshold(
+                self.vad_threshold,
+                self.vad_noise_floor,
+            ),
+            frame_samples: ms_to_samples(always_listen::FRAME_MS, sample_rate),
+            min_audio_samples: self.min_audio_samples(sample_rate),
+            sample_rate,
+            noise_gate: self.noise_gate_threshold.map(|threshold| NoiseGateConfig {
+                threshold,
+                attack_ms: self.noise_gate_attack_ms,
+                release_ms: self.noise_gate_release_ms,
+            }),
+            ..AlwaysListenConfig::default()
         }
     }
+
+    /// Find the profile for an executable name (case-insensitive).
+    /// Falls back to a "*" profile if present, then to an empty profile.
+    pub fn resolve_profile<'a>(&'a self, exe: &str) -> &'a AppProfile {
+        self.profiles
+            .iter()
+            .find(|p| p.exe != "*" && exe_matches(&p.exe, exe))
+            .or_else(|| self.profiles.iter().find(|p| p.exe == "*"))
+            .unwrap_or(&DEFAULT_PROFILE)
+    }
 }
 
 #[cfg(test)]
@@ -844,6 +1611,386 @@ mod tests {
         let _ = detected;
     }
 
+    #[test]
+    fn test_resolve_profile_match() {
+        let config = Config {
+            profiles: vec![
+                AppProfile {
+                    exe: "Slack.exe".to_string(),
+                    language: Some("es".to_string()),
+                    model_name: None,
+                    output_mode: Some(OutputMode::Keys),
+                    translate: true,
+                    ..AppProfile::default()
+                },
+                AppProfile {
+                    exe: "Code.exe".to_string(),
+                    language: Some("en".to_string()),
+                    model_name: Some("whisper-base-en".to_string()),
+                    output_mode: None,
+                    translate: false,
+                    ..AppProfile::default()
+                },
+                AppProfile {
+                    exe: "WindowsTerminal".to_string(),
+                    typing: Some(TypeMode::ClipboardPaste),
+                    trailing_space: Some(false),
+                    ..AppProfile::default()
+                },
+                AppProfile {
+                    exe: "keepass.exe".to_string(),
+                    disable: true,
+                    ..AppProfile::default()
+                },
+            ],
+            ..Config::default()
+        };
+
+        let slack = config.resolve_profile("slack.exe");
+        assert_eq!(slack.language.as_deref(), Some("es"));
+        assert_eq!(slack.output_mode, Some(OutputMode::Keys));
+        assert!(slack.translate);
+
+        let code = config.resolve_profile("Code.exe");
+        assert_eq!(code.model_name.as_deref(), Some("whisper-base-en"));
+        assert_eq!(code.typing, None);
+        assert!(!code.disable);
+
+        // The extension is optional in the profile
+        let terminal = config.resolve_profile("windowsterminal.exe");
+        assert_eq!(terminal.typing, Some(TypeMode::ClipboardPaste));
+        assert_eq!(terminal.trailing_space, Some(false));
+        assert!(config.resolve_profile("KeePass.exe").disable);
+        assert!(!config.resolve_profile("keepassxc.exe").disable);
+    }
+
+    #[test]
+    fn test_exe_matches() {
+        assert!(exe_matches("slack.exe", "Slack.exe"));
+        assert!(exe_matches("slack", "slack.exe"));
+        assert!(exe_matches("SLACK.EXE", "slack"));
+        assert!(!exe_matches("slack", "slacker.exe"));
+        assert!(!exe_matches("code.exe", "code.exe.bak"));
+        assert!(!exe_matches("", ""));
+    }
+
+    #[test]
+    fn test_resolve_profile_fallback() {
+        let mut config = Config::default();
+
+        // No profiles: empty default with no overrides
+        let profile = config.resolve_profile("notepad.exe");
+        assert!(profile.language.is_none());
+        assert!(profile.model_name.is_none());
+        assert!(profile.output_mode.is_none());
+
+        // Wildcard profile is used when nothing else matches
+        config.profiles.push(AppProfile {
+            exe: "*".to_string(),
+            language: Some("de".to_string()),
+            model_name: None,
+            output_mode: None,
+            translate: false,
+            ..AppProfile::default()
+        });
+        assert_eq!(config.resolve_profile("notepad.exe").language.as_deref(), Some("de"));
+        assert_eq!(config.resolve_profile("").language.as_deref(), Some("de"));
+    }
+
+    #[test]
+    fn test_profiles_deserialize_missing() {
+        // Older configs have no profiles field
+        let json = r#"{
+            "model_name": "m",
+            "model_path": "models/m",
+            "overlay_visible": true,
+            "hotkey_push_to_talk": "Backquote",
+            "hotkey_always_listen": "Control+Backquote"
+        }"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert!(config.profiles.is_empty());
+        assert_eq!(config.min_audio_ms, 100);
+        assert_eq!(config.always_listen_paragraph_gap_ms, 0);
+        assert!(!config.incremental_typing);
+        assert_eq!(config.max_recording_seconds, 120);
+        assert_eq!(config.pre_roll_ms, 0);
+        assert_eq!(config.chunk_seconds, 30);
+        assert!(!config.keep_warm);
+        assert_eq!(config.keep_warm_minutes, 5);
+        assert!(!config.highpass_enabled && !config.normalize_enabled);
+        assert_eq!(config.max_recording_samples(16000), 120 * 16000);
+        assert_eq!(config.min_audio_samples(16000), 1600);
+        assert_eq!(config.hotkey_mute, "Control+Shift+Backquote");
+        assert_eq!(config.hotkey_pause_listen, "");
+        assert_eq!(config.double_tap_ms, 300);
+        assert_eq!(config.vad_noise_floor, None);
+        assert_eq!(config.noise_gate_threshold, None);
+        assert_eq!(config.noise_gate_release_ms, 200);
+        assert_eq!(config.always_listen_config(16000).noise_gate, None);
+        assert_eq!(config.initial_prompt(), None);
+        assert_eq!(config.download_concurrency, 3);
+        assert!(config.push_to_talk_hold);
+        assert!(config.overlay_always_on_top);
+        assert!(!config.overlay_click_through);
+        assert!(!config.overlay_show_text);
+        assert!(!config.audio_cues);
+        assert_eq!(config.audio_cue_device, None);
+        assert_eq!(config.history_size, 50);
+        assert!(config.history_persist);
+        assert_eq!(config.fallback_model, None);
+        assert_eq!(config.transcript_log_path, None);
+        assert!(!config.autostart);
+        assert_eq!(config.backends_dir, None);
+        assert_eq!(config.models_dir, None);
+        assert!(config.check_dirs().is_ok());
+        assert_eq!(config.compute_type, None);
+        assert_eq!(config.cpu_threads, 0);
+        assert!(config.replacements.is_empty());
+        assert!(!config.fix_common_errors);
+        assert!(config.hallucination_phrases.iter().any(|p| p == "[BLANK_AUDIO]"));
+        assert_eq!(config.hallucination_max_rms, 0.01);
+        assert_eq!(config.no_speech_threshold, 1.0);
+        assert_eq!(config.vad_threshold, None);
+        let al_config = config.always_listen_config(16000);
+        let defaults = AlwaysListenConfig::default();
+        assert_eq!(al_config.pre_roll_duration_ms, defaults.pre_roll_duration_ms);
+        assert_eq!(al_config.min_speech_duration_ms, defaults.min_speech_duration_ms);
+        assert_eq!(al_config.post_silence_duration_ms, defaults.post_silence_duration_ms);
+        assert_eq!(al_config.vad_threshold, defaults.vad_threshold);
+        assert_eq!(al_config.frame_samples, defaults.frame_samples);
+        assert_eq!(al_config.min_audio_samples, defaults.min_audio_samples);
+
+        let json = r#"{
+            "model_name": "m",
+            "model_path": "models/m",
+            "overlay_visible": true,
+            "hotkey_push_to_talk": "Backquote",
+            "hotkey_always_listen": "Control+Backquote",
+            "profiles": [{ "exe": "slack.exe", "language": "es", "type_mode": "keys" }],
+            "replacements": [["jason", "JSON"]]
+        }"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.profiles.len(), 1);
+        assert_eq!(config.replacements, vec![("jason".to_string(), "JSON".to_string())]);
+        // "type_mode" is accepted as an alias for "output_mode"
+        assert_eq!(config.profiles[0].output_mode, Some(OutputMode::Keys));
+        assert!(!config.profiles[0].translate);
+        assert_eq!(config.output_mode, OutputMode::Text);
+    }
+
+    #[test]
+    fn test_language_fallback() {
+        let json = r#"{ "backend_id": "whisper-cpp", "model_name": "whisper-small" }"#;
+        let fallback: LanguageFallback = serde_json::from_str(json).unwrap();
+        assert_eq!(fallback.primary_languages, vec!["en"]);
+        assert!(!fallback.preload);
+        assert!(!fallback.handles("en"));
+        assert!(!fallback.handles("EN"));
+        assert!(fallback.handles("de"));
+
+        let fallback = LanguageFallback {
+            primary_languages: vec!["en".to_string(), "fr".to_string()],
+            ..fallback
+        };
+        assert!(!fallback.handles("fr"));
+        assert!(fallback.handles("ja"));
+    }
+
+    #[test]
+    fn test_allowed_languages() {
+        let mut config = Config::default();
+        assert!(config.allowed_languages().is_empty());
+        config.allowed_languages = vec![" EN".into(), "de".into(), "".into(), "en".into()];
+        assert_eq!(config.allowed_languages(), vec!["en", "de"]);
+
+        let json = r#"{ "model_name": "m", "model_path": "m", "overlay_visible": true,
+            "hotkey_push_to_talk": "F1", "hotkey_always_listen": "F2",
+            "allowed_languages": ["fr"] }"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.allowed_languages, vec!["fr"]);
+    }
+
+    #[test]
+    fn test_quick_languages() {
+        let mut config = Config::default();
+        assert_eq!(config.quick_languages(), vec!["en", "es", "fr", "de"]);
+        config.quick_languages = vec!["EN".into(), " ja".into(), "en".into()];
+        assert_eq!(config.quick_languages(), vec!["en", "ja"]);
+        // A language picked before the list changed stays selectable
+        config.language = Some("it".into());
+        assert_eq!(config.quick_languages(), vec!["en", "ja", "it"]);
+
+        assert_eq!(language_name("de"), "German");
+        assert_eq!(language_name("JA"), "Japanese");
+        assert_eq!(language_name("sw"), "sw");
+    }
+
+    #[test]
+    fn test_always_listen_config_clamped() {
+        let config = Config {
+            vad_threshold: Some(0.04),
+            vad_noise_floor: Some(0.001),
+            vad_pre_roll_ms: 60_000,
+            pre_roll_ms: 60_000,
+            vad_min_speech_ms: 0,
+            silence_timeout_ms: 1500,
+            ..Config::default()
+        };
+        let al_config = config.always_listen_config(16000);
+        assert_eq!(al_config.vad_threshold, 0.04);
+        assert_eq!(al_config.pre_roll_duration_ms, *always_listen::PRE_ROLL_MS_RANGE.end());
+        assert_eq!(al_config.min_speech_duration_ms, *always_listen::MIN_SPEECH_MS_RANGE.start());
+        assert_eq!(al_config.post_silence_duration_ms, 1500);
+        assert_eq!(al_config.min_audio_samples, config.min_audio_samples(16000));
+        assert_eq!(config.pre_roll_ms(), *always_listen::PRE_ROLL_MS_RANGE.end());
+    }
+
+    #[test]
+    fn test_sample_counts_follow_the_model_rate() {
+        let config = Config { min_audio_ms: 250, max_recording_seconds: 60, ..Config::default() };
+        assert_eq!(config.min_audio_samples(8000), 2000);
+        assert_eq!(config.min_audio_samples(24000), 6000);
+        assert_eq!(config.max_recording_samples(8000), 60 * 8000);
+        assert_eq!(config.max_recording_samples(24000), 60 * 24000);
+        let unlimited = Config { max_recording_seconds: 0, ..Config::default() };
+        assert_eq!(unlimited.max_recording_samples(24000), 0);
+
+        // Always-listen frames and minimums are durations, not sample counts
+        let al_config = config.always_listen_config(8000);
+        assert_eq!(al_config.sample_rate, 8000);
+        assert_eq!(al_config.frame_samples, 240);
+        assert_eq!(al_config.min_audio_samples, 2000);
+        assert_eq!(config.always_listen_config(24000).frame_samples, 720);
+    }
+
+    #[test]
+    fn test_output_mode_serialization() {
+        let config = Config {
+            output_mode: OutputMode::ClipboardOnly,
+            ..Config::default()
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains("\"output_mode\":\"clipboard_only\""));
+
+        let loaded: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.output_mode, OutputMode::ClipboardOnly);
+        assert!(!loaded.clipboard_append);
+
+        let json = json
+            .replace("\"clipboard_only\"", "\"clipboard\"")
+            .replace("\"clipboard_append\":false", "\"clipboard_append\":true");
+        let loaded: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.output_mode, OutputMode::ClipboardOnly);
+        assert!(loaded.clipboard_append);
+    }
+
+    #[test]
+    fn test_type_mode_serialization() {
+        let config = Config {
+            type_mode: TypeMode::ClipboardPaste,
+            ..Config::default()
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains("\"type_mode\":\"clipboard_paste\""));
+        let loaded: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.type_mode, TypeMode::ClipboardPaste);
+
+        // Older configs keep typing keystrokes
+        assert_eq!(Config::default().type_mode, TypeMode::Keystrokes);
+    }
+
+    #[test]
+    fn test_check_dirs() {
+        let dir = std::env::temp_dir().join("stt_test_check_dirs");
+        fs::create_dir_all(&dir).unwrap();
+        let mut config = Config { backends_dir: Some(dir.clone()), ..Config::default() };
+        assert!(config.check_dirs().is_ok());
+
+        config.models_dir = Some(dir.join("missing"));
+        let err = config.check_dirs().unwrap_err().to_string();
+        assert!(err.contains("models_dir") && err.contains("missing"), "{}", err);
+
+        // A file isn't a folder
+        let file = dir.join("file.txt");
+        fs::write(&file, "x").unwrap();
+        config.models_dir = None;
+        config.backends_dir = Some(file);
+        assert!(config.check_dirs().unwrap_err().to_string().contains("backends_dir"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_trailing_char() {
+        let config = Config { trailing_char: TrailingChar::Newline, ..Config::default() };
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains("\"trailing_char\":\"newline\""));
+        let loaded: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.trailing_char, TrailingChar::Newline);
+        assert_eq!(config.trailing_char.as_str(), "\n");
+        assert_eq!(Config::default().trailing_char, TrailingChar::None);
+        assert_eq!(TrailingChar::None.as_str(), "");
+
+        // A profile's trailing_space wins over the config
+        let mut profile = AppProfile::default();
+        assert_eq!(profile.trailing_char(TrailingChar::Newline), TrailingChar::Newline);
+        profile.trailing_space = Some(true);
+        assert_eq!(profile.trailing_char(TrailingChar::Newline), TrailingChar::Space);
+        profile.trailing_space = Some(false);
+        assert_eq!(profile.trailing_char(TrailingChar::Space), TrailingChar::None);
+    }
+
+    #[test]
+    fn test_profile_name_from_file() {
+        assert_eq!(profile_name_from_file("config-app-laptop.json", "app"), Some("laptop"));
+        assert_eq!(profile_name_from_file("config-app-GPU desk.json", "app"), Some("GPU desk"));
+        // The default config and other exes' configs aren't profiles
+        assert_eq!(profile_name_from_file("config-app.json", "app"), None);
+        assert_eq!(profile_name_from_file("config-other-laptop.json", "app"), None);
+        assert_eq!(profile_name_from_file("config-app-.json", "app"), None);
+        assert_eq!(profile_name_from_file("config-app-laptop.json.bak", "app"), None);
+    }
+
+    #[test]
+    fn test_validate_profile_name() {
+        assert!(validate_profile_name("laptop").is_ok());
+        assert!(validate_profile_name("Work PC_2").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name(" laptop").is_err());
+        assert!(validate_profile_name("../laptop").is_err());
+        assert!(validate_profile_name("a/b").is_err());
+    }
+
+    #[test]
+    fn test_initial_prompt_blank_is_none() {
+        let mut config = Config {
+            initial_prompt: "  ".to_string(),
+            ..Config::default()
+        };
+        assert_eq!(config.initial_prompt(), None);
+
+        config.initial_prompt = " Kubernetes, kubectl ".to_string();
+        assert_eq!(config.initial_prompt(), Some("Kubernetes, kubectl"));
+    }
+
+    #[test]
+    fn test_decoding_options() {
+        // Only the tweaks that are set change; the rest keep the library defaults
+        let decoding: DecodeOptions =
+            serde_json::from_str(r#"{ "suppress_blank": false, "max_initial_timestamp_ms": 2000 }"#)
+                .unwrap();
+        let expected = DecodeOptions {
+            suppress_blank: Some(false),
+            no_repeat_ngram_size: 0,
+            max_initial_timestamp_ms: 2000,
+        };
+        assert_eq!(decoding, expected);
+
+        let config = Config { decoding, ..Config::default() };
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(serde_json::from_str::<Config>(&json).unwrap().decoding, expected);
+        assert_eq!(Config::default().decoding, DecodeOptions::default());
+    }
+
     #[test]
     fn test_config_gpu_toggle() {
         // Test toggling GPU on/off
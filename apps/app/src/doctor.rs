@@ -0,0 +1,330 @@
+//! Setup self-check
+//!
+//! `app.exe --doctor` goes through what the app needs before it can transcribe:
+//! the config, the backend DLL, the CUDA libraries, the model files, the
+//! microphone and a one-second test transcription. It prints a line per check
+//! with a hint for anything that failed, and exits non-zero if a critical check
+//! failed, so a broken install can be diagnosed without reading the log.
+
+use crate::audio::AudioCapture;
+use crate::backend_loader::{Backend, DeviceUsed};
+use crate::config::{
+    detect_cuda_path, detect_cudnn_path, get_backends_dir, get_config_path, set_dir_overrides,
+    setup_cuda_env, validate_cuda_path, validate_cudnn_path, Config,
+};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// How long the microphone check records
+const MIC_TEST: Duration = Duration::from_millis(300);
+
+const CONFIG: &str = "Config";
+const BACKEND: &str = "Backend";
+const GPU: &str = "GPU libraries";
+const MODEL_FILES: &str = "Model files";
+const MICROPHONE: &str = "Microphone";
+const TEST_TRANSCRIPTION: &str = "Test transcription";
+
+/// How a check came out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    /// Works, but not as configured (e.g. on the CPU instead of the GPU)
+    Warn,
+    /// The app can't transcribe until this is fixed
+    Fail,
+    /// Not run because a check it depends on failed
+    Skipped,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Status::Pass => "PASS",
+            Status::Warn => "WARN",
+            Status::Fail => "FAIL",
+            Status::Skipped => "SKIP",
+        }
+    }
+}
+
+/// Result of one check
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+    /// What to do about a warning or failure
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, status: Status::Pass, detail: detail.into(), hint: None }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: &str) -> Self {
+        Self { name, status: Status::Warn, detail: detail.into(), hint: Some(hint.to_string()) }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: &str) -> Self {
+        Self { name, status: Status::Fail, detail: detail.into(), hint: Some(hint.to_string()) }
+    }
+
+    fn skipped(name: &'static str) -> Self {
+        let detail = "not checked, needs the checks above".to_string();
+        Self { name, status: Status::Skipped, detail, hint: None }
+    }
+}
+
+/// The report printed to the console
+pub fn report(checks: &[Check]) -> String {
+    let width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    let mut out = String::new();
+    for check in checks {
+        out += &format!(
+            "[{}] {:<width$}  {}\n",
+            check.status.label(),
+            check.name,
+            check.detail,
+            width = width
+        );
+        if let Some(hint) = &check.hint {
+            out += &format!("       {:<width$}  Fix: {}\n", "", hint, width = width);
+        }
+    }
+    let count = |status| checks.iter().filter(|c| c.status == status).count();
+    let (failed, warned) = (count(Status::Fail), count(Status::Warn));
+    out += &match (failed, warned) {
+        (0, 0) => "\nAll checks passed.\n".to_string(),
+        (0, n) => format!("\nReady to transcribe, with {} warning(s).\n", n),
+        (n, _) => format!("\n{} critical check(s) failed.\n", n),
+    };
+    out
+}
+
+/// Process exit code: 1 if any critical check failed
+pub fn exit_code(checks: &[Check]) -> i32 {
+    i32::from(checks.iter().any(|c| c.status == Status::Fail))
+}
+
+/// Run every check, print the report and return the exit code
+pub fn run_doctor() -> i32 {
+    let checks = run_checks();
+    print!("{}", report(&checks));
+    exit_code(&checks)
+}
+
+fn run_checks() -> Vec<Check> {
+    let config_path = get_config_path().map(|p| p.display().to_string()).unwrap_or_default();
+    let config = match Config::load().and_then(|config| config.check_dirs().map(|_| config)) {
+        Ok(config) => config,
+        Err(e) => {
+            let mut checks = vec![Check::fail(
+                CONFIG,
+                format!("{:#} ({})", e, config_path),
+                "Run the app to go through setup, or fix the folder or JSON error it names",
+            )];
+            let rest = [BACKEND, GPU, MODEL_FILES, MICROPHONE, TEST_TRANSCRIPTION];
+            checks.extend(rest.into_iter().map(Check::skipped));
+            return checks;
+        }
+    };
+    let mut checks = vec![Check::pass(CONFIG, config_path)];
+    set_dir_overrides(&config);
+    setup_cuda_env(&config);
+
+    let backend_dir = get_backends_dir().map(|dir| dir.join(&config.backend_id));
+    let backend = backend_dir.and_then(|dir| Backend::open(&dir));
+    checks.push(match &backend {
+        Ok(backend) => {
+            Check::pass(BACKEND, format!("{} {}", backend.display_name, backend.manifest.version))
+        }
+        Err(e) => Check::fail(
+            BACKEND,
+            format!("{} - {:#}", config.backend_id, e),
+            "Reinstall the app; the backend folder needs its DLL and manifest.json",
+        ),
+    });
+
+    let cuda_path = config.cuda_path.clone().or_else(detect_cuda_path);
+    let cudnn_path = config.cudnn_path.clone().or_else(detect_cudnn_path);
+    checks.push(check_gpu(config.use_gpu, cuda_path.as_deref(), cudnn_path.as_deref()));
+
+    let model_files = check_model_files(&config);
+    let model_files_ok = model_files.status == Status::Pass;
+    checks.push(model_files);
+    checks.push(check_microphone(config.input_device_name.as_deref()));
+
+    checks.push(match &backend {
+        Ok(backend) if model_files_ok => check_transcription(&config, backend),
+        _ => Check::skipped(TEST_TRANSCRIPTION),
+    });
+    checks
+}
+
+/// CUDA and cuDNN, when the GPU is enabled. Missing libraries only warn: the
+/// model then loads on the CPU.
+fn check_gpu(use_gpu: bool, cuda_path: Option<&Path>, cudnn_path: Option<&Path>) -> Check {
+    const HINT: &str = "Pick the CUDA and cuDNN folders in Settings, or turn off the GPU there";
+    if !use_gpu {
+        return Check::pass(GPU, "not used, the GPU is turned off in the config");
+    }
+    let Some(cuda_path) = cuda_path else {
+        return Check::warn(GPU, "CUDA not found, models will load on the CPU", HINT);
+    };
+    if !validate_cuda_path(cuda_path) {
+        let detail = format!("no cudart64_*.dll under {}", cuda_path.join("bin").display());
+        return Check::warn(GPU, detail, HINT);
+    }
+    match cudnn_path {
+        Some(cudnn_path) if validate_cudnn_path(cudnn_path) => Check::pass(
+            GPU,
+            format!("CUDA {}, cuDNN {}", cuda_path.display(), cudnn_path.display()),
+        ),
+        Some(cudnn_path) => {
+            let detail = format!("no cudnn*.dll under {}", cudnn_path.join("bin").display());
+            Check::warn(GPU, detail, HINT)
+        }
+        None => Check::warn(GPU, "cuDNN not found, models may load on the CPU", HINT),
+    }
+}
+
+fn check_model_files(config: &Config) -> Check {
+    const HINT: &str = "Open Settings from the tray menu and download the model again";
+    let path = config.model_path.display();
+    match crate::missing_model_files(config) {
+        Ok(missing) if !missing.is_empty() => {
+            Check::fail(MODEL_FILES, format!("missing {} in {}", missing.join(", "), path), HINT)
+        }
+        Ok(_) if !config.model_exists() => {
+            Check::fail(MODEL_FILES, format!("{} not found", path), HINT)
+        }
+        Ok(_) => Check::pass(MODEL_FILES, format!("{} ({})", config.model_name, path)),
+        Err(e) => Check::fail(MODEL_FILES, format!("{:#}", e), HINT),
+    }
+}
+
+/// Open the input device and record from it briefly
+fn check_microphone(device_name: Option<&str>) -> Check {
+    const HINT: &str = "Plug in a microphone, pick one in Settings, and check that Windows' \
+                        microphone privacy settings allow desktop apps";
+    let result = AudioCapture::new_with_device(device_name).and_then(|mut capture| {
+        capture.start_recording()?;
+        std::thread::sleep(MIC_TEST);
+        capture.stop_recording()?;
+        Ok(capture)
+    });
+    let capture = match result {
+        Ok(capture) => capture,
+        Err(e) => return Check::fail(MICROPHONE, format!("{:#}", e), HINT),
+    };
+    let opened = capture.current_device_name();
+    match device_name {
+        Some(name) if name != opened => Check::warn(
+            MICROPHONE,
+            format!("'{}' not found, using '{}'", name, opened),
+            "Pick an available microphone in Settings",
+        ),
+        _ if capture.is_low_sample_rate() => Check::warn(
+            MICROPHONE,
+            format!("'{}' only captures below 16kHz", opened),
+            "Use a microphone or headset that records at 16kHz or more",
+        ),
+        _ => Check::pass(MICROPHONE, opened),
+    }
+}
+
+/// Load the model and transcribe a second of silence
+fn check_transcription(config: &Config, backend: &Backend) -> Check {
+    let options = backend
+        .model_options(&config.model_name, config.compute_type.as_deref())
+        .with_cpu_threads(config.cpu_threads);
+    let started = Instant::now();
+    let (model, device) =
+        match backend.create_model_with_options(&config.model_path, config.use_gpu, &options) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                return Check::fail(
+                    TEST_TRANSCRIPTION,
+                    format!("the model failed to load: {:#}", e),
+                    "Download the model again, or turn off the GPU in Settings",
+                )
+            }
+        };
+    let load_time = started.elapsed();
+
+    let silence = vec![0.0; backend.required_sample_rate(&config.model_name) as usize];
+    let started = Instant::now();
+    if let Err(e) = model.transcribe(&silence) {
+        return Check::fail(
+            TEST_TRANSCRIPTION,
+            format!("transcribing failed: {:#}", e),
+            "Turn off the GPU in Settings to rule out the CUDA libraries",
+        );
+    }
+    let detail = format!(
+        "loaded in {:.1}s, 1s of audio took {}ms on {}",
+        load_time.as_secs_f32(),
+        started.elapsed().as_millis(),
+        device
+    );
+    if config.use_gpu && device == DeviceUsed::Cpu {
+        Check::warn(
+            TEST_TRANSCRIPTION,
+            detail,
+            "The GPU is turned on but the model loaded on the CPU; see GPU libraries above",
+        )
+    } else {
+        Check::pass(TEST_TRANSCRIPTION, detail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_and_exit_code() {
+        let mut checks = vec![
+            Check::pass(CONFIG, "config-app.json"),
+            Check::warn(GPU, "CUDA not found", "Pick the CUDA folder"),
+        ];
+        let text = report(&checks);
+        assert!(text.starts_with("[PASS] Config         config-app.json\n"), "{}", text);
+        assert!(text.contains("[WARN] GPU libraries  CUDA not found\n"), "{}", text);
+        assert!(text.contains("\n                      Fix: Pick the CUDA folder\n"), "{}", text);
+        assert!(text.ends_with("with 1 warning(s).\n"), "{}", text);
+        // Warnings and skipped checks don't fail the run
+        checks.push(Check::skipped(TEST_TRANSCRIPTION));
+        assert_eq!(exit_code(&checks), 0);
+
+        checks.push(Check::fail(MICROPHONE, "No input device available", "Plug one in"));
+        assert_eq!(exit_code(&checks), 1);
+        assert!(report(&checks).ends_with("1 critical check(s) failed.\n"));
+        assert!(report(&checks[..1]).ends_with("All checks passed.\n"));
+    }
+
+    #[test]
+    fn test_check_gpu() {
+        let dir = std::env::temp_dir().join(format!("stt-doctor-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let (cuda, cudnn) = (dir.join("cuda"), dir.join("cudnn"));
+        std::fs::create_dir_all(cuda.join("bin")).unwrap();
+        std::fs::create_dir_all(cudnn.join("bin/12.9")).unwrap();
+
+        assert_eq!(check_gpu(false, None, None).status, Status::Pass);
+        assert_eq!(check_gpu(true, None, None).status, Status::Warn);
+        let check = check_gpu(true, Some(&cuda), Some(&cudnn));
+        assert!(check.status == Status::Warn && check.detail.contains("cudart64"), "{:?}", check);
+
+        std::fs::write(cuda.join("bin/cudart64_12.dll"), "").unwrap();
+        let check = check_gpu(true, Some(&cuda), Some(&cudnn));
+        assert!(check.status == Status::Warn && check.detail.contains("cudnn"), "{:?}", check);
+        assert_eq!(check_gpu(true, Some(&cuda), None).status, Status::Warn);
+
+        std::fs::write(cudnn.join("bin/12.9/cudnn64_9.dll"), "").unwrap();
+        assert_eq!(check_gpu(true, Some(&cuda), Some(&cudnn)).status, Status::Pass);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
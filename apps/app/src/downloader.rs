@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
 use std::fs::{self, File};
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
@@ -8,14 +9,24 @@ use tracing::info;
 
 use crate::backend_loader::ManifestModel;
 
-/// Download progress tracking
+const MB: u64 = 1024 * 1024;
+
+/// Free space kept beyond a model's size (also covers `size_mb` being rounded)
+const DISK_SPACE_MARGIN_MB: u64 = 200;
+
+/// Download progress tracking (aggregated across files downloaded in parallel)
 pub struct DownloadProgress {
     pub downloaded: Arc<AtomicU64>,
     pub total: Arc<AtomicU64>,
-    pub current_file: Arc<AtomicUsize>,
+    /// Files whose size is known (response received)
+    pub sized_files: Arc<AtomicUsize>,
+    /// Files fully written to disk
+    pub completed_files: Arc<AtomicUsize>,
     pub total_files: usize,
     pub finished: Arc<AtomicBool>,
     pub error: Arc<parking_lot::Mutex<Option<String>>>,
+    /// Highest progress reported so far, in 1/1000ths (keeps the bar monotonic)
+    reported_permille: AtomicU64,
 }
 
 impl DownloadProgress {
@@ -23,13 +34,31 @@ impl DownloadProgress {
         Self {
             downloaded: Arc::new(AtomicU64::new(0)),
             total: Arc::new(AtomicU64::new(0)),
-            current_file: Arc::new(AtomicUsize::new(0)),
+            sized_files: Arc::new(AtomicUsize::new(0)),
+            completed_files: Arc::new(AtomicUsize::new(0)),
             total_files,
             finished: Arc::new(AtomicBool::new(false)),
             error: Arc::new(parking_lot::Mutex::new(None)),
+            reported_permille: AtomicU64::new(0),
         }
     }
 
+    /// Record a file's size once its response arrives
+    pub fn add_file_size(&self, bytes: u64) {
+        self.total.fetch_add(bytes, Ordering::Relaxed);
+        self.sized_files.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record bytes written for any file
+    pub fn add_downloaded(&self, bytes: u64) {
+        self.downloaded.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Record a finished file
+    pub fn file_completed(&self) {
+        self.completed_files.fetch_add(1, Ordering::Relaxed);
+    }
+
     pub fn get_progress(&self) -> (u64, u64) {
         (
             self.downloaded.load(Ordering::Relaxed),
@@ -37,13 +66,29 @@ impl DownloadProgress {
         )
     }
 
+    /// Completed file count and total file count
     pub fn get_file_progress(&self) -> (usize, usize) {
         (
-            self.current_file.load(Ordering::Relaxed),
+            self.completed_files.load(Ordering::Relaxed),
             self.total_files,
         )
     }
 
+    /// Overall progress from 0.0 to 1.0. Never decreases, even while file
+    /// sizes are still arriving from parallel downloads.
+    pub fn fraction(&self) -> f64 {
+        let (downloaded, total) = self.get_progress();
+        let permille = aggregate_permille(
+            downloaded,
+            total,
+            self.sized_files.load(Ordering::Relaxed),
+            self.completed_files.load(Ordering::Relaxed),
+            self.total_files,
+        );
+        let previous = self.reported_permille.fetch_max(permille, Ordering::Relaxed);
+        previous.max(permille) as f64 / 1000.0
+    }
+
     pub fn is_finished(&self) -> bool {
         self.finished.load(Ordering::Relaxed)
     }
@@ -53,6 +98,24 @@ impl DownloadProgress {
     }
 }
 
+/// Progress in 1/1000ths. Uses the byte ratio once every file's size is known;
+/// before that the total is incomplete, so fall back to the completed file ratio.
+fn aggregate_permille(
+    downloaded: u64,
+    total: u64,
+    sized_files: usize,
+    completed_files: usize,
+    total_files: usize,
+) -> u64 {
+    if total_files == 0 {
+        return 0;
+    }
+    if sized_files >= total_files && total > 0 {
+        return (downloaded.min(total) * 1000) / total;
+    }
+    (completed_files.min(total_files) as u64 * 1000) / total_files as u64
+}
+
 /// Download a single file with progress tracking
 fn download_file(url: &str, dest: &Path, progress: &DownloadProgress) -> Result<()> {
     // Create parent directory if needed
@@ -78,7 +141,7 @@ fn download_file(url: &str, dest: &Path, progress: &DownloadProgress) -> Result<
     }
 
     let content_length = response.content_length().unwrap_or(0);
-    progress.total.fetch_add(content_length, Ordering::Relaxed);
+    progress.add_file_size(content_length);
 
     let mut file = File::create(dest).context("Failed to create file")?;
 
@@ -91,7 +154,7 @@ fn download_file(url: &str, dest: &Path, progress: &DownloadProgress) -> Result<
             break;
         }
         file.write_all(&buffer[..read]).context("Failed to write to file")?;
-        progress.downloaded.fetch_add(read as u64, Ordering::Relaxed);
+        progress.add_downloaded(read as u64);
     }
 
     file.flush().context("Failed to flush file")?;
@@ -99,6 +162,97 @@ fn download_file(url: &str, dest: &Path, progress: &DownloadProgress) -> Result<
     Ok(())
 }
 
+/// Bytes taken by a manifest `size_mb`
+pub fn size_mb_to_bytes(size_mb: u32) -> u64 {
+    size_mb as u64 * MB
+}
+
+/// Bytes free to this user on the volume holding `path`. The nearest existing
+/// folder is queried, so `path` needn't exist yet.
+#[cfg(target_os = "windows")]
+pub fn free_disk_space(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let dir = path.ancestors().find(|p| p.is_dir())?;
+    let wide: Vec<u16> = dir.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut free = 0u64;
+    unsafe { GetDiskFreeSpaceExW(PCWSTR(wide.as_ptr()), Some(&mut free), None, None) }.ok()?;
+    Some(free)
+}
+
+/// Not queried elsewhere; downloads go ahead unchecked
+#[cfg(not(target_os = "windows"))]
+pub fn free_disk_space(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Why `needed` bytes don't fit in `free`, if they don't
+fn space_shortfall(needed: u64, free: u64) -> Option<String> {
+    let gb = |bytes: u64| bytes as f64 / (1024.0 * MB as f64);
+    (free < needed).then(|| format!("Need {:.1} GB, only {:.1} GB free", gb(needed), gb(free)))
+}
+
+/// Refuse a download that can't fit on the models drive. Files it would replace
+/// count as free space; if free space can't be read the download goes ahead.
+fn check_disk_space(model: &ManifestModel, dest_dir: &Path) -> Result<()> {
+    let Some(free) = free_disk_space(dest_dir) else {
+        return Ok(());
+    };
+    let replaced: u64 = model
+        .files
+        .iter()
+        .filter_map(|f| fs::metadata(dest_dir.join(f)).ok())
+        .map(|m| m.len())
+        .sum();
+    let needed = size_mb_to_bytes(model.size_mb) + DISK_SPACE_MARGIN_MB * MB;
+    match space_shortfall(needed, free.saturating_add(replaced)) {
+        Some(msg) => anyhow::bail!("{}", msg),
+        None => Ok(()),
+    }
+}
+
+/// Delete what a failed download wrote, and the model folder if that empties it
+fn remove_partial_download<'a>(dest_dir: &Path, files: impl IntoIterator<Item = &'a PathBuf>) {
+    for path in files {
+        let _ = fs::remove_file(path);
+    }
+    let _ = fs::remove_dir(dest_dir);
+}
+
+/// Expected SHA256 (lowercase hex) for a file, accepting "sha256:<hash>" or a bare hash
+fn expected_sha256<'a>(model: &'a ManifestModel, filename: &str) -> Option<&'a str> {
+    let entry = model.checksums.as_ref()?.get(filename)?.trim();
+    Some(entry.strip_prefix("sha256:").unwrap_or(entry))
+}
+
+/// SHA256 of a file as lowercase hex
+fn file_sha256(path: &Path) -> Result<String> {
+    let mut file = File::open(path)
+        .with_context(|| format!("Failed to open {} for hashing", path.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).context("Failed to read file for hashing")?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Check a downloaded file against its expected hash, deleting it on mismatch
+fn verify_checksum(path: &Path, filename: &str, expected: &str) -> Result<()> {
+    let actual = file_sha256(path)?;
+    if actual.eq_ignore_ascii_case(expected) {
+        return Ok(());
+    }
+    let _ = fs::remove_file(path);
+    anyhow::bail!("Checksum failed for {}", filename)
+}
+
 /// Get file download URL based on backend type
 fn get_preprocessor_repo(model: &ManifestModel) -> Option<String> {
     let folder = model.folder_name.to_lowercase();
@@ -195,16 +349,19 @@ pub fn download_manifest_model(
     model: &ManifestModel,
     dest_dir: &Path,
     progress: Arc<DownloadProgress>,
+    concurrency: usize,
 ) -> Result<()> {
+    check_disk_space(model, dest_dir)?;
+
     // Create model directory
     fs::create_dir_all(dest_dir).context("Failed to create models directory")?;
 
-    for (i, filename) in model.files.iter().enumerate() {
+    // Validate every file before starting any download
+    let mut jobs = Vec::with_capacity(model.files.len());
+    for filename in &model.files {
         // Validate filename for path traversal
         validate_filename(filename)?;
 
-        progress.current_file.store(i + 1, Ordering::Relaxed);
-
         let url = get_file_url(backend_id, model, filename);
         let dest_path = dest_dir.join(filename);
 
@@ -227,18 +384,89 @@ pub fn download_manifest_model(
             ));
         }
 
-        download_file(&url, &dest_path, &progress)?;
+        jobs.push((filename, url, dest_path));
+    }
+
+    // Files this attempt has started writing, removed again if it fails
+    let started: Vec<AtomicBool> = jobs.iter().map(|_| AtomicBool::new(false)).collect();
+    let result = run_parallel(jobs.len(), concurrency, |i| {
+        let (filename, url, dest_path) = &jobs[i];
+        started[i].store(true, Ordering::Relaxed);
+        download_file(url, dest_path, &progress)?;
+        if let Some(expected) = expected_sha256(model, filename) {
+            verify_checksum(dest_path, filename, expected)?;
+            info!("Checksum OK for {}", filename);
+        }
+        progress.file_completed();
+        Ok(())
+    })
+    .and_then(|_| verify_download(model, dest_dir));
+    if let Err(e) = result {
+        let written = jobs.iter().zip(&started).filter(|(_, s)| s.load(Ordering::Relaxed));
+        remove_partial_download(dest_dir, written.map(|((_, _, path), _)| path));
+        return Err(e);
     }
 
     progress.finished.store(true, Ordering::Relaxed);
     Ok(())
 }
 
-/// Start model download in a background thread (for manifest models)
+/// Check a finished download against the manifest: every file is there and the
+/// weights file isn't empty
+fn verify_download(model: &ManifestModel, dest_dir: &Path) -> Result<()> {
+    let missing = model.missing_files(dest_dir);
+    if !missing.is_empty() {
+        anyhow::bail!("Download incomplete, missing {}", missing.join(", "));
+    }
+    if let Some(primary) = model.primary_file() {
+        let len = fs::metadata(dest_dir.join(primary)).map_or(0, |m| m.len());
+        if len == 0 {
+            anyhow::bail!("Downloaded {} is empty", primary);
+        }
+    }
+    Ok(())
+}
+
+/// Run `job(0..count)` on up to `workers` threads. Stops handing out new jobs
+/// after the first failure and returns that error.
+fn run_parallel<F>(count: usize, workers: usize, job: F) -> Result<()>
+where
+    F: Fn(usize) -> Result<()> + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let first_error = parking_lot::Mutex::new(None);
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers.min(count).max(1) {
+            scope.spawn(|| loop {
+                if first_error.lock().is_some() {
+                    break;
+                }
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= count {
+                    break;
+                }
+                if let Err(e) = job(i) {
+                    first_error.lock().get_or_insert(e);
+                    break;
+                }
+            });
+        }
+    });
+
+    match first_error.into_inner() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Start model download in a background thread (for manifest models),
+/// fetching up to `concurrency` files at once
 pub fn start_manifest_model_download(
     backend_id: &str,
     model: &ManifestModel,
     dest_dir: PathBuf,
+    concurrency: usize,
 ) -> Arc<DownloadProgress> {
     let progress = Arc::new(DownloadProgress::new(model.files.len()));
     let progress_clone = Arc::clone(&progress);
@@ -252,16 +480,203 @@ pub fn start_manifest_model_download(
             &model_clone,
             &dest_dir,
             Arc::clone(&progress_clone),
+            concurrency,
         ) {
             *progress_clone.error.lock() = Some(e.to_string());
             progress_clone.finished.store(true, Ordering::Relaxed);
-            return;
-        }
-        
-        if model_clone.checksums.is_some() {
-            info!("Checksum verification disabled; skipping.");
         }
     });
 
     progress
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aggregate_progress_parallel_callbacks() {
+        // Five files of different sizes reporting from separate threads
+        let sizes: [u64; 5] = [150_000_000, 2_000, 5_000, 400_000, 1_000];
+        let progress = Arc::new(DownloadProgress::new(sizes.len()));
+
+        let mut last = 0.0;
+        std::thread::scope(|scope| {
+            for &size in &sizes {
+                let progress = Arc::clone(&progress);
+                scope.spawn(move || {
+                    progress.add_file_size(size);
+                    let mut remaining = size;
+                    while remaining > 0 {
+                        let chunk = remaining.min(64 * 1024);
+                        progress.add_downloaded(chunk);
+                        remaining -= chunk;
+                    }
+                    progress.file_completed();
+                });
+            }
+
+            // Sample concurrently: progress must never go backwards
+            while progress.get_file_progress().0 < sizes.len() {
+                let fraction = progress.fraction();
+                assert!(fraction >= last, "progress went backwards: {} < {}", fraction, last);
+                assert!(fraction <= 1.0);
+                last = fraction;
+            }
+        });
+
+        let total: u64 = sizes.iter().sum();
+        assert_eq!(progress.get_progress(), (total, total));
+        assert_eq!(progress.get_file_progress(), (5, 5));
+        assert_eq!(progress.fraction(), 1.0);
+    }
+
+    #[test]
+    fn test_aggregate_permille_waits_for_all_sizes() {
+        // Small file done while the large file's size is unknown: file ratio, not 100%
+        assert_eq!(aggregate_permille(2_000, 2_000, 1, 1, 2), 500);
+        // All sizes known: byte ratio
+        assert_eq!(aggregate_permille(50, 100, 2, 1, 2), 500);
+        assert_eq!(aggregate_permille(100, 100, 2, 2, 2), 1000);
+        // Never above 100% even if a server under-reported its size
+        assert_eq!(aggregate_permille(150, 100, 2, 1, 2), 1000);
+        assert_eq!(aggregate_permille(0, 0, 0, 0, 0), 0);
+    }
+
+    #[test]
+    fn test_progress_fraction_is_monotonic() {
+        let progress = DownloadProgress::new(2);
+        progress.add_file_size(1000);
+        progress.add_downloaded(1000);
+        progress.file_completed();
+        assert_eq!(progress.fraction(), 0.5);
+
+        // Second file's size arrives: byte ratio drops to ~0.001 but the bar holds
+        progress.add_file_size(1_000_000);
+        assert_eq!(progress.fraction(), 0.5);
+
+        progress.add_downloaded(1_000_000);
+        progress.file_completed();
+        assert_eq!(progress.fraction(), 1.0);
+    }
+
+    #[test]
+    fn test_run_parallel_stops_on_error() {
+        let ran = AtomicUsize::new(0);
+        let result = run_parallel(20, 1, |i| {
+            ran.fetch_add(1, Ordering::Relaxed);
+            if i == 3 {
+                anyhow::bail!("file {} failed", i);
+            }
+            Ok(())
+        });
+        assert_eq!(result.unwrap_err().to_string(), "file 3 failed");
+        assert_eq!(ran.load(Ordering::Relaxed), 4);
+
+        let ran = AtomicUsize::new(0);
+        run_parallel(7, 3, |_| {
+            ran.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(ran.load(Ordering::Relaxed), 7);
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        let dir = std::env::temp_dir().join("app_test_checksum");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("model.bin");
+        // SHA256 of "abc"
+        let abc = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        fs::write(&path, b"abc").unwrap();
+        assert_eq!(file_sha256(&path).unwrap(), abc);
+        assert!(verify_checksum(&path, "model.bin", &abc.to_uppercase()).is_ok());
+        assert!(path.exists());
+
+        // Mismatch: error names the file and the bad download is removed
+        let wrong = "0".repeat(64);
+        let err = verify_checksum(&path, "model.bin", &wrong).unwrap_err();
+        assert_eq!(err.to_string(), "Checksum failed for model.bin");
+        assert!(!path.exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_verify_download() {
+        let dir = std::env::temp_dir().join("app_test_verify_download");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let json = r#"{"id": "m", "display_name": "M", "folder_name": "m", "size_mb": 1,
+            "hf_repo": "", "download_url": "", "files": ["model.bin", "config.json"],
+            "is_english_only": false}"#;
+        let model: ManifestModel = serde_json::from_str(json).unwrap();
+
+        fs::write(dir.join("model.bin"), b"").unwrap();
+        let err = verify_download(&model, &dir).unwrap_err();
+        assert_eq!(err.to_string(), "Download incomplete, missing config.json");
+
+        fs::write(dir.join("config.json"), b"{}").unwrap();
+        let err = verify_download(&model, &dir).unwrap_err();
+        assert_eq!(err.to_string(), "Downloaded model.bin is empty");
+
+        fs::write(dir.join("model.bin"), b"weights").unwrap();
+        assert!(verify_download(&model, &dir).is_ok());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_disk_space() {
+        assert_eq!(size_mb_to_bytes(0), 0);
+        assert_eq!(size_mb_to_bytes(75), 75 * 1024 * 1024);
+        // No overflow for the largest size a manifest can declare
+        assert_eq!(size_mb_to_bytes(u32::MAX), u32::MAX as u64 * 1024 * 1024);
+
+        let gb = 1024 * MB;
+        assert_eq!(space_shortfall(3 * gb, 10 * gb), None);
+        assert_eq!(space_shortfall(3 * gb, 3 * gb), None);
+        assert_eq!(
+            space_shortfall(3 * gb, gb + gb / 2).as_deref(),
+            Some("Need 3.0 GB, only 1.5 GB free")
+        );
+
+        // Only Windows is queried; a folder that doesn't exist yet uses its parent
+        let free = free_disk_space(&std::env::temp_dir().join("app_test_missing/model"));
+        assert_eq!(free.is_some(), cfg!(target_os = "windows"));
+    }
+
+    #[test]
+    fn test_remove_partial_download() {
+        let dir = std::env::temp_dir().join("app_test_partial_download");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let partial = dir.join("model.bin");
+        fs::write(&partial, b"half").unwrap();
+        remove_partial_download(&dir, [&partial]);
+        assert!(!dir.exists());
+
+        // Other files (e.g. from an earlier download) keep the folder
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&partial, b"half").unwrap();
+        fs::write(dir.join("config.json"), b"{}").unwrap();
+        remove_partial_download(&dir, [&partial]);
+        assert!(!partial.exists() && dir.join("config.json").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_expected_sha256_forms() {
+        let json = r#"{"id": "m", "display_name": "M", "folder_name": "m", "size_mb": 1,
+            "hf_repo": "", "download_url": "", "files": ["a", "b", "c"],
+            "is_english_only": false,
+            "checksums": {"a": "sha256:abc123", "b": "def456"}}"#;
+        let model: ManifestModel = serde_json::from_str(json).unwrap();
+        assert_eq!(expected_sha256(&model, "a"), Some("abc123"));
+        assert_eq!(expected_sha256(&model, "b"), Some("def456"));
+        assert_eq!(expected_sha256(&model, "c"), None);
+    }
+}
@@ -0,0 +1,118 @@
+//! The window a push-to-talk transcript is meant for
+//!
+//! The foreground window is remembered when recording starts and brought back to
+//! the front before typing, so clicking the overlay or tray in between doesn't
+//! send the text somewhere else. Elsewhere there is no window to remember.
+
+#[cfg(target_os = "windows")]
+use std::path::PathBuf;
+#[cfg(target_os = "windows")]
+use windows::core::PWSTR;
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::{CloseHandle, HWND};
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Threading::{
+    AttachThreadInput, GetCurrentThreadId, OpenProcess, QueryFullProcessImageNameW,
+    PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetForegroundWindow, GetWindowThreadProcessId, IsIconic, IsWindow, SetForegroundWindow,
+    ShowWindow, SW_RESTORE,
+};
+
+/// A top-level window, kept as a plain handle value so it can cross threads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FocusTarget(isize);
+
+#[cfg(target_os = "windows")]
+impl FocusTarget {
+    fn hwnd(self) -> HWND {
+        HWND(self.0 as *mut std::ffi::c_void)
+    }
+
+    /// The window that currently has focus
+    pub fn foreground() -> Option<Self> {
+        let hwnd = unsafe { GetForegroundWindow() };
+        (!hwnd.0.is_null()).then_some(Self(hwnd.0 as isize))
+    }
+
+    /// Executable name (e.g. "slack.exe") of the process that owns the window
+    pub fn exe_name(self) -> Option<String> {
+        unsafe {
+            let mut pid: u32 = 0;
+            GetWindowThreadProcessId(self.hwnd(), Some(&mut pid as *mut u32));
+            if pid == 0 {
+                return None;
+            }
+
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+            let mut buf = [0u16; 260];
+            let mut len = buf.len() as u32;
+            let result = QueryFullProcessImageNameW(
+                process,
+                PROCESS_NAME_WIN32,
+                PWSTR(buf.as_mut_ptr()),
+                &mut len,
+            );
+            let _ = CloseHandle(process);
+            result.ok()?;
+
+            let path = PathBuf::from(String::from_utf16_lossy(&buf[..len as usize]));
+            path.file_name().and_then(|n| n.to_str()).map(|n| n.to_string())
+        }
+    }
+
+    /// Whether the window still exists
+    pub fn exists(self) -> bool {
+        unsafe { IsWindow(self.hwnd()).as_bool() }
+    }
+
+    /// Bring the window back to the front. False if it was closed or Windows
+    /// wouldn't hand over focus.
+    pub fn restore(self) -> bool {
+        if !self.exists() {
+            return false;
+        }
+        let hwnd = self.hwnd();
+        unsafe {
+            let foreground = GetForegroundWindow();
+            if foreground == hwnd {
+                return true;
+            }
+            if IsIconic(hwnd).as_bool() {
+                let _ = ShowWindow(hwnd, SW_RESTORE);
+            }
+            // Only the thread that owns the foreground window may change it, so
+            // share its input state for the duration of the switch
+            let current = GetCurrentThreadId();
+            let owner = GetWindowThreadProcessId(foreground, None);
+            let attached =
+                owner != 0 && owner != current && AttachThreadInput(current, owner, true).as_bool();
+            let _ = SetForegroundWindow(hwnd);
+            if attached {
+                let _ = AttachThreadInput(current, owner, false);
+            }
+            GetForegroundWindow() == hwnd
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+impl FocusTarget {
+    pub fn foreground() -> Option<Self> {
+        None
+    }
+
+    pub fn exe_name(self) -> Option<String> {
+        None
+    }
+
+    pub fn exists(self) -> bool {
+        false
+    }
+
+    pub fn restore(self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,307 @@
+//! The 6x7 bitmap font and drawing primitives for the app's own windows
+//!
+//! The setup wizard, the overlay and the history window draw straight into
+//! softbuffer pixel buffers with these. Each glyph is seven rows of six bits,
+//! the leftmost pixel in the high bit. Accented letters are drawn as their base
+//! letter; anything else without a glyph is drawn as a box, so it shows as missing.
+
+/// Horizontal advance of one character, in pixels
+pub const CHAR_WIDTH: u32 = 8;
+
+/// Height of a glyph, in pixels
+pub const CHAR_HEIGHT: u32 = 7;
+
+/// Drawn for characters the font has no glyph for
+const MISSING_GLYPH: [u8; 7] = [0x3F, 0x21, 0x21, 0x21, 0x21, 0x21, 0x3F];
+
+/// The ASCII character drawn for an accented letter or typographic punctuation
+fn fold_char(ch: char) -> Option<char> {
+    let folded = match ch {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => 'A',
+        'ç' => 'c',
+        'Ç' => 'C',
+        'è' | 'é' | 'ê' | 'ë' => 'e',
+        'È' | 'É' | 'Ê' | 'Ë' => 'E',
+        'ì' | 'í' | 'î' | 'ï' => 'i',
+        'Ì' | 'Í' | 'Î' | 'Ï' => 'I',
+        'ñ' => 'n',
+        'Ñ' => 'N',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => 'o',
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' => 'O',
+        'ù' | 'ú' | 'û' | 'ü' => 'u',
+        'Ù' | 'Ú' | 'Û' | 'Ü' => 'U',
+        'ý' | 'ÿ' => 'y',
+        'Ý' => 'Y',
+        '‘' | '’' => '\'',
+        '“' | '”' => '"',
+        '–' | '—' => '-',
+        _ => return None,
+    };
+    Some(folded)
+}
+
+/// `text` word-wrapped into at most `max_lines` lines of `max_chars` characters.
+/// Words longer than a line are split; text that doesn't fit ends in "...".
+pub fn wrap_text(text: &str, max_chars: usize, max_lines: usize) -> Vec<String> {
+    if max_chars == 0 || max_lines == 0 {
+        return Vec::new();
+    }
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let chars: Vec<char> = word.chars().collect();
+        for chunk in chars.chunks(max_chars) {
+            let len = line.chars().count();
+            if len > 0 && len + 1 + chunk.len() > max_chars {
+                lines.push(std::mem::take(&mut line));
+            } else if len > 0 {
+                line.push(' ');
+            }
+            line.extend(chunk);
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        let last = &mut lines[max_lines - 1];
+        let kept: String = last.chars().take(max_chars.saturating_sub(3)).collect();
+        *last = format!("{}...", kept.trim_end());
+    }
+    lines
+}
+
+pub fn draw_rect(buffer: &mut [u32], buf_width: u32, x: u32, y: u32, w: u32, h: u32, color: u32) {
+    for dy in 0..h {
+        for dx in 0..w {
+            let px = x + dx;
+            let py = y + dy;
+            if px < buf_width {
+                let idx = (py * buf_width + px) as usize;
+                if idx < buffer.len() {
+                    buffer[idx] = color;
+                }
+            }
+        }
+    }
+}
+
+pub fn draw_text(buffer: &mut [u32], buf_width: u32, x: u32, y: u32, text: &str, color: u32) {
+    let chars: Vec<char> = text.chars().collect();
+    for (i, ch) in chars.iter().enumerate() {
+        draw_char(buffer, buf_width, x + (i as u32 * CHAR_WIDTH), y, *ch, color);
+    }
+}
+
+fn draw_char(buffer: &mut [u32], buf_width: u32, x: u32, y: u32, ch: char, color: u32) {
+    let bitmap = get_char_bitmap(ch);
+    for (row, bits) in bitmap.iter().enumerate() {
+        for col in 0..6 {
+            if (bits >> (5 - col)) & 1 == 1 {
+                let px = x + col;
+                let py = y + row as u32;
+                if px < buf_width {
+                    let idx = (py * buf_width + px) as usize;
+                    if idx < buffer.len() {
+                        buffer[idx] = color;
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn get_char_bitmap(ch: char) -> [u8; 7] {
+    match ch {
+        'A' => [0x1E, 0x21, 0x21, 0x3F, 0x21, 0x21, 0x21],
+        'B' => [0x3E, 0x21, 0x21, 0x3E, 0x21, 0x21, 0x3E],
+        'C' => [0x1E, 0x21, 0x20, 0x20, 0x20, 0x21, 0x1E],
+        'D' => [0x3C, 0x22, 0x21, 0x21, 0x21, 0x22, 0x3C],
+        'E' => [0x3F, 0x20, 0x20, 0x3E, 0x20, 0x20, 0x3F],
+        'F' => [0x3F, 0x20, 0x20, 0x3E, 0x20, 0x20, 0x20],
+        'G' => [0x1E, 0x21, 0x20, 0x27, 0x21, 0x21, 0x1E],
+        'H' => [0x21, 0x21, 0x21, 0x3F, 0x21, 0x21, 0x21],
+        'I' => [0x1C, 0x08, 0x08, 0x08, 0x08, 0x08, 0x1C],
+        'J' => [0x01, 0x01, 0x01, 0x01, 0x21, 0x21, 0x1E],
+        'K' => [0x21, 0x22, 0x24, 0x38, 0x24, 0x22, 0x21],
+        'L' => [0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x3F],
+        'M' => [0x21, 0x33, 0x2D, 0x21, 0x21, 0x21, 0x21],
+        'N' => [0x21, 0x31, 0x29, 0x25, 0x23, 0x21, 0x21],
+        'O' => [0x1E, 0x21, 0x21, 0x21, 0x21, 0x21, 0x1E],
+        'P' => [0x3E, 0x21, 0x21, 0x3E, 0x20, 0x20, 0x20],
+        'Q' => [0x1E, 0x21, 0x21, 0x21, 0x25, 0x22, 0x1D],
+        'R' => [0x3E, 0x21, 0x21, 0x3E, 0x24, 0x22, 0x21],
+        'S' => [0x1E, 0x21, 0x20, 0x1E, 0x01, 0x21, 0x1E],
+        'T' => [0x3F, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08],
+        'U' => [0x21, 0x21, 0x21, 0x21, 0x21, 0x21, 0x1E],
+        'V' => [0x21, 0x21, 0x21, 0x21, 0x12, 0x12, 0x0C],
+        'W' => [0x21, 0x21, 0x21, 0x21, 0x2D, 0x33, 0x21],
+        'X' => [0x21, 0x12, 0x0C, 0x0C, 0x0C, 0x12, 0x21],
+        'Y' => [0x21, 0x21, 0x12, 0x0C, 0x08, 0x08, 0x08],
+        'Z' => [0x3F, 0x02, 0x04, 0x08, 0x10, 0x20, 0x3F],
+        'a' => [0x00, 0x00, 0x1E, 0x01, 0x1F, 0x21, 0x1F],
+        'b' => [0x20, 0x20, 0x3E, 0x21, 0x21, 0x21, 0x3E],
+        'c' => [0x00, 0x00, 0x1E, 0x20, 0x20, 0x20, 0x1E],
+        'd' => [0x01, 0x01, 0x1F, 0x21, 0x21, 0x21, 0x1F],
+        'e' => [0x00, 0x00, 0x1E, 0x21, 0x3F, 0x20, 0x1E],
+        'f' => [0x06, 0x08, 0x1E, 0x08, 0x08, 0x08, 0x08],
+        'g' => [0x00, 0x1F, 0x21, 0x21, 0x1F, 0x01, 0x1E],
+        'h' => [0x20, 0x20, 0x3E, 0x21, 0x21, 0x21, 0x21],
+        'i' => [0x08, 0x00, 0x18, 0x08, 0x08, 0x08, 0x1C],
+        'j' => [0x02, 0x00, 0x06, 0x02, 0x02, 0x22, 0x1C],
+        'k' => [0x20, 0x20, 0x22, 0x24, 0x38, 0x24, 0x22],
+        'l' => [0x18, 0x08, 0x08, 0x08, 0x08, 0x08, 0x1C],
+        'm' => [0x00, 0x00, 0x36, 0x2D, 0x21, 0x21, 0x21],
+        'n' => [0x00, 0x00, 0x3E, 0x21, 0x21, 0x21, 0x21],
+        'o' => [0x00, 0x00, 0x1E, 0x21, 0x21, 0x21, 0x1E],
+        'p' => [0x00, 0x3E, 0x21, 0x21, 0x3E, 0x20, 0x20],
+        'q' => [0x00, 0x1F, 0x21, 0x21, 0x1F, 0x01, 0x01],
+        'r' => [0x00, 0x00, 0x2E, 0x30, 0x20, 0x20, 0x20],
+        's' => [0x00, 0x00, 0x1E, 0x20, 0x1E, 0x01, 0x3E],
+        't' => [0x08, 0x08, 0x1E, 0x08, 0x08, 0x08, 0x06],
+        'u' => [0x00, 0x00, 0x21, 0x21, 0x21, 0x21, 0x1F],
+        'v' => [0x00, 0x00, 0x21, 0x21, 0x12, 0x12, 0x0C],
+        'w' => [0x00, 0x00, 0x21, 0x21, 0x21, 0x2D, 0x12],
+        'x' => [0x00, 0x00, 0x21, 0x12, 0x0C, 0x12, 0x21],
+        'y' => [0x00, 0x21, 0x21, 0x1F, 0x01, 0x21, 0x1E],
+        'z' => [0x00, 0x00, 0x3F, 0x02, 0x0C, 0x10, 0x3F],
+        '0' => [0x1E, 0x21, 0x23, 0x25, 0x29, 0x31, 0x1E],
+        '1' => [0x08, 0x18, 0x08, 0x08, 0x08, 0x08, 0x1C],
+        '2' => [0x1E, 0x21, 0x01, 0x0E, 0x10, 0x20, 0x3F],
+        '3' => [0x1E, 0x21, 0x01, 0x0E, 0x01, 0x21, 0x1E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x3F, 0x02, 0x02],
+        '5' => [0x3F, 0x20, 0x3E, 0x01, 0x01, 0x21, 0x1E],
+        '6' => [0x0E, 0x10, 0x20, 0x3E, 0x21, 0x21, 0x1E],
+        '7' => [0x3F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x1E, 0x21, 0x21, 0x1E, 0x21, 0x21, 0x1E],
+        '9' => [0x1E, 0x21, 0x21, 0x1F, 0x01, 0x02, 0x1C],
+        ' ' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x10],
+        ':' => [0x00, 0x00, 0x08, 0x00, 0x00, 0x08, 0x00],
+        '!' => [0x08, 0x08, 0x08, 0x08, 0x08, 0x00, 0x08],
+        '?' => [0x1E, 0x21, 0x01, 0x0E, 0x08, 0x00, 0x08],
+        '-' => [0x00, 0x00, 0x00, 0x1E, 0x00, 0x00, 0x00],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3F],
+        '(' => [0x04, 0x08, 0x10, 0x10, 0x10, 0x08, 0x04],
+        ')' => [0x10, 0x08, 0x04, 0x04, 0x04, 0x08, 0x10],
+        '[' => [0x1C, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1C],
+        ']' => [0x1C, 0x04, 0x04, 0x04, 0x04, 0x04, 0x1C],
+        '*' => [0x00, 0x08, 0x2A, 0x1C, 0x2A, 0x08, 0x00],
+        '/' => [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x00],
+        '%' => [0x31, 0x32, 0x04, 0x08, 0x13, 0x23, 0x00],
+        '`' => [0x10, 0x08, 0x04, 0x00, 0x00, 0x00, 0x00],
+        '\'' => [0x08, 0x08, 0x10, 0x00, 0x00, 0x00, 0x00],
+        '"' => [0x14, 0x14, 0x28, 0x00, 0x00, 0x00, 0x00],
+        '+' => [0x00, 0x08, 0x08, 0x3E, 0x08, 0x08, 0x00],
+        '=' => [0x00, 0x00, 0x3E, 0x00, 0x3E, 0x00, 0x00],
+        '<' => [0x02, 0x04, 0x08, 0x10, 0x08, 0x04, 0x02],
+        '>' => [0x10, 0x08, 0x04, 0x02, 0x04, 0x08, 0x10],
+        '^' => [0x08, 0x14, 0x22, 0x00, 0x00, 0x00, 0x00],
+        ';' => [0x00, 0x00, 0x08, 0x00, 0x00, 0x08, 0x10],
+        '@' => [0x1E, 0x21, 0x2D, 0x2B, 0x2E, 0x20, 0x1E],
+        '#' => [0x12, 0x12, 0x3F, 0x12, 0x3F, 0x12, 0x12],
+        '&' => [0x18, 0x24, 0x28, 0x10, 0x2A, 0x24, 0x1A],
+        '$' => [0x08, 0x1E, 0x28, 0x1C, 0x0A, 0x3C, 0x08],
+        '{' => [0x06, 0x08, 0x08, 0x10, 0x08, 0x08, 0x06],
+        '}' => [0x18, 0x04, 0x04, 0x02, 0x04, 0x04, 0x18],
+        '|' => [0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08],
+        '~' => [0x00, 0x00, 0x19, 0x26, 0x00, 0x00, 0x00],
+        '\\' => [0x20, 0x10, 0x08, 0x04, 0x02, 0x01, 0x00],
+        _ if ch.is_whitespace() => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        _ => fold_char(ch).map_or(MISSING_GLYPH, get_char_bitmap),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_rect() {
+        let mut buffer = vec![0u32; 100 * 100]; // 100x100 buffer
+
+        // Draw a red rectangle
+        draw_rect(&mut buffer, 100, 10, 10, 20, 20, 0xFFFF0000);
+
+        // Check corners
+        assert_eq!(buffer[10 * 100 + 10], 0xFFFF0000); // Top-left
+        assert_eq!(buffer[10 * 100 + 29], 0xFFFF0000); // Top-right
+        assert_eq!(buffer[29 * 100 + 10], 0xFFFF0000); // Bottom-left
+        assert_eq!(buffer[29 * 100 + 29], 0xFFFF0000); // Bottom-right
+
+        // Check outside rectangle is unchanged
+        assert_eq!(buffer[9 * 100 + 9], 0);
+        assert_eq!(buffer[30 * 100 + 30], 0);
+    }
+
+    #[test]
+    fn test_draw_text() {
+        let mut buffer = vec![0u32; 200 * 50];
+
+        // Draw "ABC" at position (10, 10)
+        draw_text(&mut buffer, 200, 10, 10, "ABC", 0xFFFFFFFF);
+
+        // Just verify it doesn't panic - visual verification would need screenshot
+        // Check that some pixels were written
+        let has_content = buffer.iter().any(|&p| p != 0);
+        assert!(has_content, "Text rendering should write pixels");
+    }
+
+    #[test]
+    fn test_get_char_bitmap_coverage() {
+        // Every printable ASCII character gets a full-height glyph that fits the cell
+        for ch in ' '..='~' {
+            let bitmap = get_char_bitmap(ch);
+            assert_eq!(bitmap.len(), CHAR_HEIGHT as usize);
+            assert!(bitmap.iter().all(|&row| row < 0x40), "{:?} is wider than 6 pixels", ch);
+        }
+        // Nothing but the space is drawn blank, punctuation included
+        for ch in '!'..='~' {
+            assert!(get_char_bitmap(ch).iter().any(|&row| row != 0), "{:?} is blank", ch);
+        }
+        for ch in [';', '@', '#', '&', '$', '{', '}', '|', '~', '\\'] {
+            assert_ne!(get_char_bitmap(ch), MISSING_GLYPH, "{:?}", ch);
+        }
+    }
+
+    #[test]
+    fn test_glyph_fallbacks() {
+        // Accented letters and typographic punctuation borrow the ASCII glyph
+        assert_eq!(get_char_bitmap('é'), get_char_bitmap('e'));
+        assert_eq!(get_char_bitmap('Ü'), get_char_bitmap('U'));
+        assert_eq!(get_char_bitmap('ñ'), get_char_bitmap('n'));
+        assert_eq!(get_char_bitmap('’'), get_char_bitmap('\''));
+        assert_eq!(get_char_bitmap('—'), get_char_bitmap('-'));
+        // Other characters show up as a box rather than a gap
+        for ch in ['日', '€', 'ß', '😀', '\u{7f}'] {
+            assert_eq!(get_char_bitmap(ch), MISSING_GLYPH, "{:?}", ch);
+        }
+        // Whitespace stays blank
+        assert_eq!(get_char_bitmap('\t'), [0; 7]);
+        assert_eq!(get_char_bitmap('\u{a0}'), [0; 7]);
+    }
+
+    #[test]
+    fn test_wrap_text() {
+        assert_eq!(wrap_text("Hello world", 20, 3), ["Hello world"]);
+        assert_eq!(wrap_text("the quick brown fox", 10, 3), ["the quick", "brown fox"]);
+        assert_eq!(wrap_text("  spaced\n out  ", 20, 3), ["spaced out"]);
+        assert!(wrap_text("", 10, 3).is_empty());
+        assert!(wrap_text("text", 0, 3).is_empty());
+
+        // Words longer than a line are split across lines
+        assert_eq!(wrap_text("ab abcdefgh", 5, 3), ["ab", "abcde", "fgh"]);
+        // Characters, not bytes
+        assert_eq!(wrap_text("café naïve", 5, 3), ["café", "naïve"]);
+
+        // Too many lines: the last one kept ends in an ellipsis that fits the line
+        let lines = wrap_text("one two three four five six", 9, 2);
+        assert_eq!(lines, ["one two", "three..."]);
+        let lines = wrap_text("aaaaaaaaa bbbbbbbbb ccc", 9, 2);
+        assert_eq!(lines, ["aaaaaaaaa", "bbbbbb..."]);
+        assert!(lines.iter().all(|l| l.chars().count() <= 9));
+    }
+}
@@ -0,0 +1,149 @@
+//! Recent transcripts, shown by the tray's History window
+//!
+//! Entries are kept newest-last in a ring buffer and, unless persistence is
+//! turned off, saved as a JSON array next to the config after each push.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// One transcript and when it was produced
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub text: String,
+    /// Unix time in seconds
+    pub timestamp: u64,
+}
+
+pub struct History {
+    entries: VecDeque<HistoryEntry>,
+    /// Maximum entries kept (0 = history off)
+    capacity: usize,
+    /// File the history is saved to (`None` = memory only)
+    path: Option<PathBuf>,
+}
+
+impl History {
+    /// History holding at most `capacity` entries, loaded from and saved to `path` if given.
+    /// A missing or unreadable file starts an empty history.
+    pub fn open(capacity: usize, path: Option<PathBuf>) -> Self {
+        let mut history = Self { entries: VecDeque::new(), capacity, path };
+        if let Some(path) = history.path.as_ref().filter(|p| p.exists()) {
+            match load_entries(path) {
+                Ok(entries) => history.entries = entries.into(),
+                Err(e) => warn!("Ignoring transcript history {}: {:#}", path.display(), e),
+            }
+        }
+        history.truncate();
+        history
+    }
+
+    /// Record a transcript (and save the file, if persistent)
+    pub fn push(&mut self, text: &str) {
+        if self.capacity == 0 || text.trim().is_empty() {
+            return;
+        }
+        self.entries.push_back(HistoryEntry { text: text.to_string(), timestamp: unix_now() });
+        self.truncate();
+        if let Err(e) = self.save() {
+            warn!("Failed to save transcript history: {:#}", e);
+        }
+    }
+
+    /// Entries, newest first
+    pub fn recent(&self) -> Vec<HistoryEntry> {
+        self.entries.iter().rev().cloned().collect()
+    }
+
+    /// The most recent entry
+    pub fn latest(&self) -> Option<&HistoryEntry> {
+        self.entries.back()
+    }
+
+    fn truncate(&mut self) {
+        while self.entries.len() > self.capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else { return Ok(()) };
+        let json = serde_json::to_string_pretty(&self.entries)?;
+        std::fs::write(path, json).with_context(|| format!("writing {}", path.display()))
+    }
+}
+
+fn load_entries(path: &std::path::Path) -> Result<Vec<HistoryEntry>> {
+    let json = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// "just now", "5 min ago", "3 h ago", "2 d ago"
+pub fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} min ago", seconds / 60),
+        3600..=86_399 => format!("{} h ago", seconds / 3600),
+        _ => format!("{} d ago", seconds / 86_400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("stt-history-{}-{}.json", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_push_keeps_newest() {
+        let mut history = History::open(2, None);
+        history.push("one");
+        history.push("   ");
+        history.push("two");
+        history.push("three");
+        let texts: Vec<_> = history.recent().into_iter().map(|e| e.text).collect();
+        assert_eq!(texts, vec!["three", "two"]);
+        assert_eq!(history.latest().map(|e| e.text.as_str()), Some("three"));
+
+        let mut off = History::open(0, None);
+        off.push("secret");
+        assert!(off.recent().is_empty());
+        assert!(off.latest().is_none());
+    }
+
+    #[test]
+    fn test_history_persists() {
+        let path = temp_path("persist");
+        let mut history = History::open(5, Some(path.clone()));
+        history.push("hello");
+        history.push("world");
+
+        let reopened = History::open(1, Some(path.clone()));
+        assert_eq!(reopened.recent().len(), 1);
+        assert_eq!(reopened.recent()[0].text, "world");
+        assert!(reopened.recent()[0].timestamp > 0);
+
+        // A corrupt file starts an empty history
+        std::fs::write(&path, "not json").unwrap();
+        assert!(History::open(5, Some(path.clone())).recent().is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(0), "just now");
+        assert_eq!(format_age(59), "just now");
+        assert_eq!(format_age(60), "1 min ago");
+        assert_eq!(format_age(7200), "2 h ago");
+        assert_eq!(format_age(3 * 86_400 + 5), "3 d ago");
+    }
+}
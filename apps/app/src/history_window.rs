@@ -0,0 +1,191 @@
+//! Tray "History" window: recent transcripts, click one to copy it
+//!
+//! Drawn with the shared bitmap font into a softbuffer surface, in the setup
+//! wizard's colors.
+
+use crate::font::{draw_rect, draw_text};
+use crate::history::{format_age, unix_now, HistoryEntry};
+use crate::setup::{
+    truncate_chars, BG_COLOR, BUTTON_COLOR, BUTTON_HOVER, DIM_TEXT, HEADER_BG, TEXT_COLOR,
+};
+use anyhow::Result;
+use softbuffer::Surface;
+use std::num::NonZeroU32;
+use std::rc::Rc;
+use tao::dpi::LogicalSize;
+use tao::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+use tao::event_loop::EventLoopWindowTarget;
+use tao::window::{Window, WindowBuilder, WindowId};
+
+const WINDOW_WIDTH: u32 = 500;
+const WINDOW_HEIGHT: u32 = 420;
+const LIST_TOP: u32 = 60;
+const ROW_HEIGHT: u32 = 44;
+const VISIBLE_ROWS: usize = 7;
+/// Characters of a transcript shown per row
+const ROW_CHARS: usize = 52;
+
+/// What the app should do after a history window event
+pub enum HistoryAction {
+    None,
+    /// Copy this transcript to the clipboard
+    Copy(String),
+    Close,
+}
+
+pub struct HistoryWindow {
+    window: Rc<Window>,
+    surface: Surface<Rc<Window>, Rc<Window>>,
+    /// Newest first
+    entries: Vec<HistoryEntry>,
+    scroll: usize,
+    hovered: Option<usize>,
+    mouse_pos: (f64, f64),
+    status: String,
+}
+
+impl HistoryWindow {
+    pub fn new<T>(event_loop: &EventLoopWindowTarget<T>, entries: Vec<HistoryEntry>) -> Result<Self> {
+        let window = WindowBuilder::new()
+            .with_title("Transcript History")
+            .with_inner_size(LogicalSize::new(WINDOW_WIDTH, WINDOW_HEIGHT))
+            .with_resizable(false)
+            .build(event_loop)
+            .map_err(|e| anyhow::anyhow!("Failed to create history window: {}", e))?;
+
+        let window = Rc::new(window);
+        let context = softbuffer::Context::new(window.clone())
+            .map_err(|e| anyhow::anyhow!("Failed to create softbuffer context: {}", e))?;
+        let surface = Surface::new(&context, window.clone())
+            .map_err(|e| anyhow::anyhow!("Failed to create softbuffer surface: {}", e))?;
+
+        let mut history_window = Self {
+            window,
+            surface,
+            entries: Vec::new(),
+            scroll: 0,
+            hovered: None,
+            mouse_pos: (0.0, 0.0),
+            status: String::new(),
+        };
+        history_window.set_entries(entries);
+        Ok(history_window)
+    }
+
+    pub fn window_id(&self) -> WindowId {
+        self.window.id()
+    }
+
+    /// Show fresh entries (scrolled to the newest) and bring the window to the front
+    pub fn set_entries(&mut self, entries: Vec<HistoryEntry>) {
+        self.entries = entries;
+        self.scroll = 0;
+        self.status = "Click a transcript to copy it".to_string();
+        self.window.set_focus();
+        self.window.request_redraw();
+    }
+
+    /// Show the result of a copy request
+    pub fn set_status(&mut self, status: &str) {
+        self.status = status.to_string();
+        self.window.request_redraw();
+    }
+
+    pub fn handle_event(&mut self, event: &WindowEvent) -> HistoryAction {
+        match event {
+            WindowEvent::CloseRequested => return HistoryAction::Close,
+            WindowEvent::CursorMoved { position, .. } => {
+                self.mouse_pos = (position.x, position.y);
+                let hovered = self.row_at(self.mouse_pos);
+                if hovered != self.hovered {
+                    self.hovered = hovered;
+                    self.window.request_redraw();
+                }
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let lines = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => -y as i32,
+                    MouseScrollDelta::PixelDelta(pos) => -(pos.y / 20.0) as i32,
+                    _ => 0,
+                };
+                let max_scroll = self.entries.len().saturating_sub(VISIBLE_ROWS) as i32;
+                self.scroll = (self.scroll as i32 + lines).clamp(0, max_scroll) as usize;
+                self.hovered = self.row_at(self.mouse_pos);
+                self.window.request_redraw();
+            }
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+                ..
+            } => {
+                if let Some(idx) = self.row_at(self.mouse_pos) {
+                    return HistoryAction::Copy(self.entries[idx].text.clone());
+                }
+            }
+            _ => {}
+        }
+        HistoryAction::None
+    }
+
+    /// Index into `entries` of the row under the cursor
+    fn row_at(&self, (x, y): (f64, f64)) -> Option<usize> {
+        if !(20.0..480.0).contains(&x) || y < LIST_TOP as f64 {
+            return None;
+        }
+        let offset = (y - LIST_TOP as f64) as u32;
+        let row = (offset / ROW_HEIGHT) as usize;
+        // The gap below each row isn't clickable
+        if row >= VISIBLE_ROWS || offset % ROW_HEIGHT >= ROW_HEIGHT - 4 {
+            return None;
+        }
+        Some(self.scroll + row).filter(|&idx| idx < self.entries.len())
+    }
+
+    pub fn redraw(&mut self) {
+        let size = self.window.inner_size();
+        let (Some(w), Some(h)) = (NonZeroU32::new(size.width), NonZeroU32::new(size.height)) else {
+            return;
+        };
+        if self.surface.resize(w, h).is_err() {
+            return;
+        }
+        let Ok(mut buffer) = self.surface.buffer_mut() else { return };
+        let width = size.width;
+
+        buffer.fill(BG_COLOR);
+        draw_rect(&mut buffer, width, 0, 0, width, 50, HEADER_BG);
+        let title = format!("Transcript History ({})", self.entries.len());
+        draw_text(&mut buffer, width, 20, 20, &title, TEXT_COLOR);
+
+        if self.entries.is_empty() {
+            draw_text(&mut buffer, width, 30, LIST_TOP + 20, "Nothing transcribed yet", DIM_TEXT);
+        }
+
+        let now = unix_now();
+        let visible = self.entries.iter().enumerate().skip(self.scroll).take(VISIBLE_ROWS);
+        for (row, (idx, entry)) in visible.enumerate() {
+            let y = LIST_TOP + row as u32 * ROW_HEIGHT;
+            let bg = if self.hovered == Some(idx) { BUTTON_HOVER } else { BUTTON_COLOR };
+            draw_rect(&mut buffer, width, 20, y, 460, ROW_HEIGHT - 4, bg);
+
+            let text = entry.text.replace(['\r', '\n'], " ");
+            let shown = truncate_chars(&text, ROW_CHARS);
+            let line = if shown.len() < text.len() { format!("{}...", shown) } else { text.clone() };
+            draw_text(&mut buffer, width, 30, y + 8, &line, TEXT_COLOR);
+            let age = format_age(now.saturating_sub(entry.timestamp));
+            draw_text(&mut buffer, width, 30, y + 24, &age, DIM_TEXT);
+        }
+
+        // Scroll indicators
+        if self.scroll > 0 {
+            draw_text(&mut buffer, width, 484, LIST_TOP + 4, "^", DIM_TEXT);
+        }
+        if self.scroll + VISIBLE_ROWS < self.entries.len() {
+            let y = LIST_TOP + VISIBLE_ROWS as u32 * ROW_HEIGHT - 14;
+            draw_text(&mut buffer, width, 484, y, "v", DIM_TEXT);
+        }
+
+        draw_text(&mut buffer, width, 20, WINDOW_HEIGHT - 30, &self.status, DIM_TEXT);
+        let _ = buffer.present();
+    }
+}
@@ -1,16 +1,21 @@
 use anyhow::Result;
+use crossbeam_channel::{Receiver, Sender};
 use global_hotkey::{
     hotkey::{Code, HotKey, Modifiers},
     GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState,
 };
+use std::sync::OnceLock;
 
 pub struct HotkeyManager {
     #[allow(dead_code)]
     manager: GlobalHotKeyManager,
     push_to_talk_id: u32,
     always_listen_id: u32,
+    mute_id: u32,
+    pause_listen_id: Option<u32>,
     push_to_talk_display: String,
     always_listen_display: String,
+    mute_display: String,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -18,13 +23,182 @@ pub enum HotkeyAction {
     PushToTalkPressed,
     PushToTalkReleased,
     AlwaysListenToggle,
+    MuteToggle,
+    PauseListenToggle,
+}
+
+/// A parsed hotkey string
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Binding {
+    /// Keyboard combination registered with global-hotkey
+    Key(HotKey),
+    /// Mouse button watched by a low-level mouse hook (Windows only)
+    Mouse(MouseHotkey),
+    /// Modifier tapped twice, watched by a low-level keyboard hook (Windows only)
+    DoubleTap(DoubleTapHotkey),
+}
+
+impl Binding {
+    /// Id carried by the `GlobalHotKeyEvent`s this binding produces
+    fn id(&self) -> u32 {
+        match self {
+            Binding::Key(hotkey) => hotkey.id(),
+            Binding::Mouse(mouse) => mouse.id(),
+            Binding::DoubleTap(tap) => tap.id(),
+        }
+    }
+
+    fn mods(&self) -> Modifiers {
+        match self {
+            Binding::Key(hotkey) => hotkey.mods,
+            Binding::Mouse(mouse) => mouse.mods,
+            Binding::DoubleTap(tap) => tap.modifier,
+        }
+    }
+}
+
+/// Mouse buttons that can be bound (left/right would break normal clicking)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MouseButton {
+    Middle = 3,
+    Back = 4,
+    Forward = 5,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MouseHotkey {
+    mods: Modifiers,
+    button: MouseButton,
+}
+
+impl MouseHotkey {
+    /// Mouse ids set the top bit; keyboard ids are hashes from global-hotkey
+    fn id(&self) -> u32 {
+        0x8000_0000 | ((self.button as u32) << 16) | (self.mods.bits() & 0xFFFF)
+    }
+}
+
+/// "DoubleTap+Control": the press after a quick tap of the modifier acts as the hotkey
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DoubleTapHotkey {
+    /// A single modifier flag
+    modifier: Modifiers,
+}
+
+impl DoubleTapHotkey {
+    /// Shares the mouse id space; the 0x10 "button" keeps them apart
+    fn id(&self) -> u32 {
+        0x8000_0000 | (0x10 << 16) | (self.modifier.bits() & 0xFFFF)
+    }
+}
+
+/// Time allowed between the two taps of a `DoubleTap+` hotkey
+pub const DEFAULT_DOUBLE_TAP_MS: u64 = 300;
+
+/// Spots double taps in a stream of keyboard events.
+///
+/// A tap is a modifier pressed and released on its own within the window; a second press
+/// of the same modifier within the window of that release reports `Pressed`, and its
+/// release reports `Released` (so a double-tap-and-hold works as push-to-talk).
+/// Any other key in between cancels, so `Ctrl+C` then `Ctrl+V` is not a double tap.
+#[derive(Debug)]
+struct DoubleTapTracker {
+    window_ms: u64,
+    /// Modifier currently down on its own, and when it went down
+    held: Option<(Modifiers, u64)>,
+    /// The last completed tap and when it was released
+    last_tap: Option<(Modifiers, u64)>,
+    /// Modifier whose double tap is being held
+    active: Option<Modifiers>,
+}
+
+impl DoubleTapTracker {
+    const fn new(window_ms: u64) -> Self {
+        Self { window_ms, held: None, last_tap: None, active: None }
+    }
+
+    /// Feed one key event; `modifier` is `None` for any non-modifier key
+    fn feed(
+        &mut self,
+        modifier: Option<Modifiers>,
+        pressed: bool,
+        at_ms: u64,
+    ) -> Option<(Modifiers, HotKeyState)> {
+        let Some(modifier) = modifier else {
+            if pressed {
+                self.held = None;
+                self.last_tap = None;
+            }
+            return None;
+        };
+        let within = |since: u64| at_ms.saturating_sub(since) <= self.window_ms;
+        if pressed {
+            match self.held {
+                // Auto-repeat while held down
+                Some((held, _)) if held == modifier => return None,
+                // Two modifiers together are a combination, not a tap
+                Some(_) => {
+                    self.held = None;
+                    self.last_tap = None;
+                    return None;
+                }
+                None => {}
+            }
+            if self.active == Some(modifier) {
+                return None;
+            }
+            self.held = Some((modifier, at_ms));
+            if let Some((tapped, released_at)) = self.last_tap.take() {
+                if tapped == modifier && within(released_at) {
+                    self.active = Some(modifier);
+                    return Some((modifier, HotKeyState::Pressed));
+                }
+            }
+            return None;
+        }
+        if self.active == Some(modifier) {
+            self.active = None;
+            self.held = None;
+            return Some((modifier, HotKeyState::Released));
+        }
+        self.last_tap = match self.held.take() {
+            Some((held, pressed_at)) if held == modifier && within(pressed_at) => {
+                Some((modifier, at_ms))
+            }
+            _ => None,
+        };
+        None
+    }
+}
+
+/// Hotkey events from global-hotkey and the mouse hook, merged into one channel
+fn event_channel() -> &'static (Sender<GlobalHotKeyEvent>, Receiver<GlobalHotKeyEvent>) {
+    static CHANNEL: OnceLock<(Sender<GlobalHotKeyEvent>, Receiver<GlobalHotKeyEvent>)> =
+        OnceLock::new();
+    CHANNEL.get_or_init(|| {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let forward = tx.clone();
+        GlobalHotKeyEvent::set_event_handler(Some(move |event| {
+            let _ = forward.send(event);
+        }));
+        (tx, rx)
+    })
 }
 
 impl HotkeyManager {
-    pub fn from_config(push_to_talk_str: &str, always_listen_str: &str) -> Result<Self> {
+    pub fn from_config(
+        push_to_talk_str: &str,
+        always_listen_str: &str,
+        mute_str: &str,
+        pause_listen_str: &str,
+        double_tap_ms: u64,
+    ) -> Result<Self> {
         let manager = GlobalHotKeyManager::new()
             .map_err(|e| anyhow::anyhow!("Failed to create hotkey manager: {}", e))?;
 
+        // Route events through our channel before anything can fire
+        event_channel();
+
         // Parse push-to-talk hotkey
         let push_to_talk = parse_hotkey(push_to_talk_str)?;
         let push_to_talk_id = push_to_talk.id();
@@ -33,27 +207,69 @@ impl HotkeyManager {
         let always_listen = parse_hotkey(always_listen_str)?;
         let always_listen_id = always_listen.id();
 
-        manager
-            .register(push_to_talk)
-            .map_err(|e| anyhow::anyhow!("Failed to register push-to-talk hotkey: {}", e))?;
+        // Parse mute hotkey
+        let mute = parse_hotkey(mute_str)?;
+        let mute_id = mute.id();
 
-        manager
-            .register(always_listen)
-            .map_err(|e| anyhow::anyhow!("Failed to register always-listen hotkey: {}", e))?;
+        // Parse the optional pause-listen hotkey (empty = none)
+        let pause_listen = match pause_listen_str.trim() {
+            "" => None,
+            s => Some(parse_hotkey(s)?),
+        };
+        let pause_listen_id = pause_listen.as_ref().map(Binding::id);
+
+        let mut bindings = vec![
+            ("push-to-talk", push_to_talk_str, push_to_talk),
+            ("always-listen", always_listen_str, always_listen),
+            ("mute", mute_str, mute),
+        ];
+        if let Some(pause_listen) = pause_listen {
+            bindings.push(("pause-listen", pause_listen_str, pause_listen));
+        }
+        let named: Vec<_> = bindings.iter().map(|(name, _, binding)| (*name, binding)).collect();
+        check_distinct(&named)?;
+
+        let mut mouse_hotkeys = Vec::new();
+        let mut double_taps = Vec::new();
+        for (name, display, binding) in bindings {
+            match binding {
+                Binding::Key(hotkey) => manager.register(hotkey).map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to register {} hotkey {} (another app may already use it): {}",
+                        name,
+                        format_hotkey_display(display),
+                        e
+                    )
+                })?,
+                Binding::Mouse(mouse) => mouse_hotkeys.push(mouse),
+                Binding::DoubleTap(tap) => double_taps.push(tap),
+            }
+        }
+        mouse_hook::watch(mouse_hotkeys)?;
+        key_hook::watch(double_taps, double_tap_ms)?;
 
         let push_to_talk_display = format_hotkey_display(push_to_talk_str);
         let always_listen_display = format_hotkey_display(always_listen_str);
+        let mute_display = format_hotkey_display(mute_str);
 
         println!("Hotkeys registered:");
         println!("  {} - Push-to-talk toggle", push_to_talk_display);
         println!("  {} - Always-listening mode toggle", always_listen_display);
+        println!("  {} - Mute toggle", mute_display);
+        if pause_listen_id.is_some() {
+            let display = format_hotkey_display(pause_listen_str);
+            println!("  {} - Pause/resume always-listening", display);
+        }
 
         Ok(Self {
             manager,
             push_to_talk_id,
             always_listen_id,
+            mute_id,
+            pause_listen_id,
             push_to_talk_display,
             always_listen_display,
+            mute_display,
         })
     }
 
@@ -65,6 +281,15 @@ impl HotkeyManager {
         self.always_listen_id
     }
 
+    pub fn mute_id(&self) -> u32 {
+        self.mute_id
+    }
+
+    /// `None` when no pause-listen hotkey is configured
+    pub fn pause_listen_id(&self) -> Option<u32> {
+        self.pause_listen_id
+    }
+
     #[allow(dead_code)]
     pub fn push_to_talk_display(&self) -> &str {
         &self.push_to_talk_display
@@ -75,17 +300,37 @@ impl HotkeyManager {
         &self.always_listen_display
     }
 
-    pub fn receiver() -> crossbeam_channel::Receiver<GlobalHotKeyEvent> {
-        GlobalHotKeyEvent::receiver().clone()
+    #[allow(dead_code)]
+    pub fn mute_display(&self) -> &str {
+        &self.mute_display
+    }
+
+    pub fn receiver() -> Receiver<GlobalHotKeyEvent> {
+        event_channel().1.clone()
+    }
+}
+
+/// Reject configs that bind two actions to the same key combination
+fn check_distinct(hotkeys: &[(&str, &Binding)]) -> Result<()> {
+    for (i, (name, hotkey)) in hotkeys.iter().enumerate() {
+        if let Some((other, _)) = hotkeys[..i].iter().find(|(_, h)| h.id() == hotkey.id()) {
+            anyhow::bail!(
+                "The {} and {} hotkeys use the same key combination; hotkeys must differ",
+                other,
+                name
+            );
+        }
     }
+    Ok(())
 }
 
-/// Parse a hotkey string like "Control+Backquote" or "F2" into a HotKey
-fn parse_hotkey(s: &str) -> Result<HotKey> {
+/// Parse a hotkey string like "Control+Backquote", "F2", "Mouse4" or "DoubleTap+Control"
+fn parse_hotkey(s: &str) -> Result<Binding> {
     let parts: Vec<&str> = s.split('+').collect();
 
     let mut modifiers = Modifiers::empty();
-    let mut key_code: Option<Code> = None;
+    let mut key: Option<&str> = None;
+    let mut double_tap = false;
 
     for part in parts {
         let part = part.trim();
@@ -94,17 +339,62 @@ fn parse_hotkey(s: &str) -> Result<HotKey> {
             "alt" => modifiers |= Modifiers::ALT,
             "shift" => modifiers |= Modifiers::SHIFT,
             "super" | "win" | "meta" => modifiers |= Modifiers::SUPER,
+            "doubletap" => double_tap = true,
             _ => {
                 // This should be the key code
-                key_code = Some(parse_key_code(part)?);
+                key = Some(part);
             }
         }
     }
 
-    let code = key_code.ok_or_else(|| anyhow::anyhow!("No key code found in hotkey string: {}", s))?;
+    if double_tap {
+        return parse_double_tap(s, key, modifiers);
+    }
+
+    let key = key.ok_or_else(|| anyhow::anyhow!("No key code found in hotkey string: {}", s))?;
 
+    if let Some(button) = parse_mouse_button(key)? {
+        return Ok(Binding::Mouse(MouseHotkey { mods: modifiers, button }));
+    }
+    let code = parse_key_code(key)?;
     let mods = if modifiers.is_empty() { None } else { Some(modifiers) };
-    Ok(HotKey::new(mods, code))
+    Ok(Binding::Key(HotKey::new(mods, code)))
+}
+
+/// "DoubleTap+<modifier>": exactly one modifier and nothing else
+fn parse_double_tap(s: &str, key: Option<&str>, modifiers: Modifiers) -> Result<Binding> {
+    let single = [Modifiers::CONTROL, Modifiers::ALT, Modifiers::SHIFT, Modifiers::SUPER];
+    if key.is_some() || !single.contains(&modifiers) {
+        anyhow::bail!("{} must be DoubleTap plus one modifier, e.g. DoubleTap+Control", s);
+    }
+    Ok(Binding::DoubleTap(DoubleTapHotkey { modifier: modifiers }))
+}
+
+/// Check that a hotkey string can be bound (used by the setup wizard)
+pub fn validate_hotkey(s: &str) -> Result<()> {
+    parse_hotkey(s).map(|_| ())
+}
+
+/// Whether two hotkey strings bind the same combination (e.g. "Ctrl+`" and "Control+Backquote")
+pub fn same_hotkey(a: &str, b: &str) -> bool {
+    matches!((parse_hotkey(a), parse_hotkey(b)), (Ok(a), Ok(b)) if a.id() == b.id())
+}
+
+/// Parse a mouse button name (`Ok(None)` if `s` isn't one)
+fn parse_mouse_button(s: &str) -> Result<Option<MouseButton>> {
+    let button = match s {
+        "Mouse3" | "MouseMiddle" => MouseButton::Middle,
+        "Mouse4" | "MouseBack" => MouseButton::Back,
+        "Mouse5" | "MouseForward" => MouseButton::Forward,
+        "Mouse1" | "Mouse2" | "MouseLeft" | "MouseRight" => {
+            anyhow::bail!("The left and right mouse buttons can't be used as hotkeys")
+        }
+        _ if s.starts_with("Mouse") => {
+            anyhow::bail!("Unsupported mouse button: {} (use Mouse3, Mouse4 or Mouse5)", s)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(button))
 }
 
 /// Parse a key name to a Code
@@ -159,6 +449,25 @@ fn parse_key_code(s: &str) -> Result<Code> {
         "F10" => Code::F10,
         "F11" => Code::F11,
         "F12" => Code::F12,
+        "F13" => Code::F13,
+        "F14" => Code::F14,
+        "F15" => Code::F15,
+        "F16" => Code::F16,
+        "F17" => Code::F17,
+        "F18" => Code::F18,
+        "F19" => Code::F19,
+        "F20" => Code::F20,
+        "F21" => Code::F21,
+        "F22" => Code::F22,
+        "F23" => Code::F23,
+        "F24" => Code::F24,
+        "MediaPlayPause" => Code::MediaPlayPause,
+        "MediaStop" => Code::MediaStop,
+        "MediaTrackNext" => Code::MediaTrackNext,
+        "MediaTrackPrevious" => Code::MediaTrackPrevious,
+        "AudioVolumeMute" | "VolumeMute" => Code::AudioVolumeMute,
+        "AudioVolumeUp" | "VolumeUp" => Code::AudioVolumeUp,
+        "AudioVolumeDown" | "VolumeDown" => Code::AudioVolumeDown,
         "Space" => Code::Space,
         "Tab" => Code::Tab,
         "CapsLock" => Code::CapsLock,
@@ -194,6 +503,251 @@ fn parse_key_code(s: &str) -> Result<Code> {
     Ok(code)
 }
 
+/// Low-level mouse hook that turns bound mouse buttons into hotkey events
+#[cfg(target_os = "windows")]
+mod mouse_hook {
+    use super::{event_channel, MouseButton, MouseHotkey};
+    use anyhow::Result;
+    use global_hotkey::{hotkey::Modifiers, GlobalHotKeyEvent, HotKeyState};
+    use parking_lot::{const_mutex, Mutex};
+    use std::sync::Once;
+    use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        GetAsyncKeyState, VIRTUAL_KEY, VK_CONTROL, VK_LWIN, VK_MENU, VK_RWIN, VK_SHIFT,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CallNextHookEx, GetMessageW, SetWindowsHookExW, MSG, MSLLHOOKSTRUCT, WH_MOUSE_LL,
+        WM_MBUTTONDOWN, WM_MBUTTONUP, WM_XBUTTONDOWN, WM_XBUTTONUP,
+    };
+
+    // High word of MSLLHOOKSTRUCT::mouseData for WM_XBUTTON*
+    const XBUTTON1: u32 = 1;
+    const XBUTTON2: u32 = 2;
+
+    struct HookState {
+        bindings: Vec<MouseHotkey>,
+        /// Pressed bindings, so the release matches even if modifiers changed
+        held: Vec<MouseHotkey>,
+    }
+
+    static STATE: Mutex<HookState> = const_mutex(HookState {
+        bindings: Vec::new(),
+        held: Vec::new(),
+    });
+    static INSTALL: Once = Once::new();
+
+    /// Replace the watched mouse bindings, installing the hook on first use
+    pub fn watch(hotkeys: Vec<MouseHotkey>) -> Result<()> {
+        let needed = !hotkeys.is_empty();
+        let mut state = STATE.lock();
+        state.bindings = hotkeys;
+        state.held.clear();
+        drop(state);
+        if needed {
+            INSTALL.call_once(|| {
+                std::thread::spawn(run_hook);
+            });
+        }
+        Ok(())
+    }
+
+    fn run_hook() {
+        if let Err(e) = unsafe { SetWindowsHookExW(WH_MOUSE_LL, Some(hook_proc), None, 0) } {
+            tracing::error!("Failed to install mouse hook: {}", e);
+            return;
+        }
+        // Low-level hooks run on the installing thread, which must pump messages
+        let mut msg = MSG::default();
+        while unsafe { GetMessageW(&mut msg, None, 0, 0) }.as_bool() {}
+    }
+
+    unsafe extern "system" fn hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if code >= 0 {
+            let info = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+            if let Some((button, pressed)) = button_event(wparam.0 as u32, info.mouseData) {
+                if let Some(event) = handle_button(button, pressed) {
+                    let _ = event_channel().0.send(event);
+                    // Swallow the click so a bound side button doesn't also go "back"
+                    return LRESULT(1);
+                }
+            }
+        }
+        CallNextHookEx(None, code, wparam, lparam)
+    }
+
+    fn button_event(message: u32, mouse_data: u32) -> Option<(MouseButton, bool)> {
+        let button = match message {
+            WM_MBUTTONDOWN | WM_MBUTTONUP => MouseButton::Middle,
+            WM_XBUTTONDOWN | WM_XBUTTONUP => match mouse_data >> 16 {
+                XBUTTON1 => MouseButton::Back,
+                XBUTTON2 => MouseButton::Forward,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some((button, matches!(message, WM_MBUTTONDOWN | WM_XBUTTONDOWN)))
+    }
+
+    fn handle_button(button: MouseButton, pressed: bool) -> Option<GlobalHotKeyEvent> {
+        let mut state = STATE.lock();
+        let (hotkey, hotkey_state) = if pressed {
+            let mods = current_modifiers();
+            let hotkey = *state
+                .bindings
+                .iter()
+                .find(|h| h.button == button && h.mods == mods)?;
+            state.held.push(hotkey);
+            (hotkey, HotKeyState::Pressed)
+        } else {
+            let index = state.held.iter().position(|h| h.button == button)?;
+            (state.held.remove(index), HotKeyState::Released)
+        };
+        Some(GlobalHotKeyEvent { id: hotkey.id(), state: hotkey_state })
+    }
+
+    fn current_modifiers() -> Modifiers {
+        let down = |vk: VIRTUAL_KEY| unsafe { GetAsyncKeyState(vk.0 as i32) } < 0;
+        let mut mods = Modifiers::empty();
+        if down(VK_CONTROL) {
+            mods |= Modifiers::CONTROL;
+        }
+        if down(VK_MENU) {
+            mods |= Modifiers::ALT;
+        }
+        if down(VK_SHIFT) {
+            mods |= Modifiers::SHIFT;
+        }
+        if down(VK_LWIN) || down(VK_RWIN) {
+            mods |= Modifiers::SUPER;
+        }
+        mods
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod mouse_hook {
+    use super::MouseHotkey;
+    use anyhow::Result;
+
+    pub fn watch(hotkeys: Vec<MouseHotkey>) -> Result<()> {
+        if !hotkeys.is_empty() {
+            anyhow::bail!("Mouse button hotkeys are only supported on Windows");
+        }
+        Ok(())
+    }
+}
+
+/// Low-level keyboard hook that turns double-tapped modifiers into hotkey events.
+/// Only installed when a `DoubleTap+` hotkey is configured; keys always pass through.
+#[cfg(target_os = "windows")]
+mod key_hook {
+    use super::{event_channel, DoubleTapHotkey, DoubleTapTracker, DEFAULT_DOUBLE_TAP_MS};
+    use anyhow::Result;
+    use global_hotkey::{hotkey::Modifiers, GlobalHotKeyEvent};
+    use parking_lot::{const_mutex, Mutex};
+    use std::sync::Once;
+    use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        VIRTUAL_KEY, VK_CONTROL, VK_LCONTROL, VK_LMENU, VK_LSHIFT, VK_LWIN, VK_MENU, VK_RCONTROL,
+        VK_RMENU, VK_RSHIFT, VK_RWIN, VK_SHIFT,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CallNextHookEx, GetMessageW, SetWindowsHookExW, KBDLLHOOKSTRUCT, LLKHF_INJECTED, MSG,
+        WH_KEYBOARD_LL, WM_KEYDOWN, WM_KEYUP, WM_SYSKEYDOWN, WM_SYSKEYUP,
+    };
+
+    struct HookState {
+        bindings: Vec<DoubleTapHotkey>,
+        tracker: DoubleTapTracker,
+    }
+
+    static STATE: Mutex<HookState> = const_mutex(HookState {
+        bindings: Vec::new(),
+        tracker: DoubleTapTracker::new(DEFAULT_DOUBLE_TAP_MS),
+    });
+    static INSTALL: Once = Once::new();
+
+    /// Replace the watched double taps, installing the hook on first use
+    pub fn watch(hotkeys: Vec<DoubleTapHotkey>, window_ms: u64) -> Result<()> {
+        let needed = !hotkeys.is_empty();
+        let mut state = STATE.lock();
+        state.bindings = hotkeys;
+        state.tracker = DoubleTapTracker::new(window_ms);
+        drop(state);
+        if needed {
+            INSTALL.call_once(|| {
+                std::thread::spawn(run_hook);
+            });
+        }
+        Ok(())
+    }
+
+    fn run_hook() {
+        if let Err(e) = unsafe { SetWindowsHookExW(WH_KEYBOARD_LL, Some(hook_proc), None, 0) } {
+            tracing::error!("Failed to install keyboard hook: {}", e);
+            return;
+        }
+        // Low-level hooks run on the installing thread, which must pump messages
+        let mut msg = MSG::default();
+        while unsafe { GetMessageW(&mut msg, None, 0, 0) }.as_bool() {}
+    }
+
+    unsafe extern "system" fn hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if code >= 0 {
+            let info = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+            // Ignore our own typed output
+            if !info.flags.contains(LLKHF_INJECTED) {
+                let pressed = match wparam.0 as u32 {
+                    WM_KEYDOWN | WM_SYSKEYDOWN => Some(true),
+                    WM_KEYUP | WM_SYSKEYUP => Some(false),
+                    _ => None,
+                };
+                if let Some(pressed) = pressed {
+                    let modifier = modifier_for(VIRTUAL_KEY(info.vkCode as u16));
+                    if let Some(event) = handle_key(modifier, pressed, info.time as u64) {
+                        let _ = event_channel().0.send(event);
+                    }
+                }
+            }
+        }
+        CallNextHookEx(None, code, wparam, lparam)
+    }
+
+    fn modifier_for(vk: VIRTUAL_KEY) -> Option<Modifiers> {
+        match vk {
+            VK_CONTROL | VK_LCONTROL | VK_RCONTROL => Some(Modifiers::CONTROL),
+            VK_MENU | VK_LMENU | VK_RMENU => Some(Modifiers::ALT),
+            VK_SHIFT | VK_LSHIFT | VK_RSHIFT => Some(Modifiers::SHIFT),
+            VK_LWIN | VK_RWIN => Some(Modifiers::SUPER),
+            _ => None,
+        }
+    }
+
+    fn handle_key(
+        modifier: Option<Modifiers>,
+        pressed: bool,
+        at_ms: u64,
+    ) -> Option<GlobalHotKeyEvent> {
+        let mut state = STATE.lock();
+        let (modifier, hotkey_state) = state.tracker.feed(modifier, pressed, at_ms)?;
+        let hotkey = state.bindings.iter().find(|h| h.modifier == modifier)?;
+        Some(GlobalHotKeyEvent { id: hotkey.id(), state: hotkey_state })
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod key_hook {
+    use super::DoubleTapHotkey;
+    use anyhow::Result;
+
+    pub fn watch(hotkeys: Vec<DoubleTapHotkey>, _window_ms: u64) -> Result<()> {
+        if !hotkeys.is_empty() {
+            anyhow::bail!("Double-tap hotkeys are only supported on Windows");
+        }
+        Ok(())
+    }
+}
+
 /// Format hotkey for display (more user-friendly)
 fn format_hotkey_display(s: &str) -> String {
     s.replace("Control", "Ctrl")
@@ -203,25 +757,48 @@ fn format_hotkey_display(s: &str) -> String {
 }
 
 /// Check hotkey event given the IDs
-/// Push-to-talk: responds to both press and release
-/// Always-listen: only responds to press (toggle)
 pub fn check_hotkey_event(
     event: &GlobalHotKeyEvent,
     push_to_talk_id: u32,
     always_listen_id: u32,
+    mute_id: u32,
+    pause_listen_id: Option<u32>,
+) -> Option<HotkeyAction> {
+    hotkey_action(
+        event.id,
+        event.state,
+        push_to_talk_id,
+        always_listen_id,
+        mute_id,
+        pause_listen_id,
+    )
+}
+
+/// Map a hotkey id and state to an action
+/// Push-to-talk: responds to both press and release
+/// Always-listen, mute and pause-listen: only respond to press (toggle)
+fn hotkey_action(
+    id: u32,
+    state: HotKeyState,
+    push_to_talk_id: u32,
+    always_listen_id: u32,
+    mute_id: u32,
+    pause_listen_id: Option<u32>,
 ) -> Option<HotkeyAction> {
-    if event.id == push_to_talk_id {
-        match event.state {
+    if id == push_to_talk_id {
+        match state {
             HotKeyState::Pressed => Some(HotkeyAction::PushToTalkPressed),
             HotKeyState::Released => Some(HotkeyAction::PushToTalkReleased),
         }
-    } else if event.id == always_listen_id {
-        // Only toggle on press, ignore release
-        if event.state == HotKeyState::Pressed {
-            Some(HotkeyAction::AlwaysListenToggle)
-        } else {
-            None
-        }
+    } else if state != HotKeyState::Pressed {
+        // Toggles ignore release
+        None
+    } else if id == always_listen_id {
+        Some(HotkeyAction::AlwaysListenToggle)
+    } else if id == mute_id {
+        Some(HotkeyAction::MuteToggle)
+    } else if Some(id) == pause_listen_id {
+        Some(HotkeyAction::PauseListenToggle)
     } else {
         None
     }
@@ -235,26 +812,26 @@ mod tests {
     fn test_parse_hotkey_simple() {
         let hotkey = parse_hotkey("Backquote").unwrap();
         // Just verify it parses successfully and has no modifiers
-        assert!(hotkey.mods.is_empty());
+        assert!(hotkey.mods().is_empty());
     }
 
     #[test]
     fn test_parse_hotkey_with_modifier() {
         let hotkey = parse_hotkey("Control+Backquote").unwrap();
-        assert!(hotkey.mods.contains(Modifiers::CONTROL));
+        assert!(hotkey.mods().contains(Modifiers::CONTROL));
     }
 
     #[test]
     fn test_parse_hotkey_multiple_modifiers() {
         let hotkey = parse_hotkey("Control+Shift+F1").unwrap();
-        assert!(hotkey.mods.contains(Modifiers::CONTROL));
-        assert!(hotkey.mods.contains(Modifiers::SHIFT));
+        assert!(hotkey.mods().contains(Modifiers::CONTROL));
+        assert!(hotkey.mods().contains(Modifiers::SHIFT));
     }
 
     #[test]
     fn test_parse_hotkey_alt_modifier() {
         let hotkey = parse_hotkey("Alt+Space").unwrap();
-        assert!(hotkey.mods.contains(Modifiers::ALT));
+        assert!(hotkey.mods().contains(Modifiers::ALT));
     }
 
     #[test]
@@ -294,6 +871,25 @@ mod tests {
         assert_eq!(parse_key_code("Right").unwrap(), Code::ArrowRight);
     }
 
+    #[test]
+    fn test_parse_media_keys_and_mouse_buttons() {
+        assert_eq!(parse_key_code("MediaPlayPause").unwrap(), Code::MediaPlayPause);
+        assert_eq!(parse_key_code("VolumeMute").unwrap(), Code::AudioVolumeMute);
+        assert_eq!(parse_key_code("F24").unwrap(), Code::F24);
+
+        let mouse = parse_hotkey("Control+Mouse4").unwrap();
+        assert!(matches!(mouse, Binding::Mouse(m) if m.button == MouseButton::Back));
+        assert!(mouse.mods().contains(Modifiers::CONTROL));
+        assert_ne!(mouse.id(), parse_hotkey("Mouse4").unwrap().id());
+        assert_ne!(mouse.id(), parse_hotkey("Control+Mouse5").unwrap().id());
+        assert!(matches!(parse_hotkey("Mouse3").unwrap(), Binding::Mouse(_)));
+
+        let err = validate_hotkey("Mouse1").unwrap_err();
+        assert!(err.to_string().contains("left and right"));
+        assert!(validate_hotkey("Mouse9").is_err());
+        assert!(validate_hotkey("Shift+MediaStop").is_ok());
+    }
+
     #[test]
     fn test_parse_key_code_unknown() {
         assert!(parse_key_code("UnknownKey").is_err());
@@ -310,8 +906,135 @@ mod tests {
 
     #[test]
     fn test_hotkey_action_equality() {
-        assert_eq!(HotkeyAction::PushToTalk, HotkeyAction::PushToTalk);
-        assert_eq!(HotkeyAction::AlwaysListen, HotkeyAction::AlwaysListen);
-        assert_ne!(HotkeyAction::PushToTalk, HotkeyAction::AlwaysListen);
+        assert_eq!(
+            HotkeyAction::PushToTalkPressed,
+            HotkeyAction::PushToTalkPressed
+        );
+        assert_eq!(HotkeyAction::MuteToggle, HotkeyAction::MuteToggle);
+        assert_ne!(HotkeyAction::AlwaysListenToggle, HotkeyAction::MuteToggle);
+    }
+
+    #[test]
+    fn test_hotkey_action_dispatch() {
+        let (ptt, al, mute, pause) = (1, 2, 3, 4);
+        let action = |id, state| hotkey_action(id, state, ptt, al, mute, Some(pause));
+
+        assert_eq!(
+            action(ptt, HotKeyState::Pressed),
+            Some(HotkeyAction::PushToTalkPressed)
+        );
+        assert_eq!(
+            action(ptt, HotKeyState::Released),
+            Some(HotkeyAction::PushToTalkReleased)
+        );
+        assert_eq!(
+            action(al, HotKeyState::Pressed),
+            Some(HotkeyAction::AlwaysListenToggle)
+        );
+        assert_eq!(action(al, HotKeyState::Released), None);
+        assert_eq!(
+            action(mute, HotKeyState::Pressed),
+            Some(HotkeyAction::MuteToggle)
+        );
+        assert_eq!(action(mute, HotKeyState::Released), None);
+        assert_eq!(
+            action(pause, HotKeyState::Pressed),
+            Some(HotkeyAction::PauseListenToggle)
+        );
+        assert_eq!(action(pause, HotKeyState::Released), None);
+        assert_eq!(action(99, HotKeyState::Pressed), None);
+        // Without a pause-listen hotkey its id means nothing
+        assert_eq!(hotkey_action(pause, HotKeyState::Pressed, ptt, al, mute, None), None);
+    }
+
+    #[test]
+    fn test_check_distinct_hotkeys() {
+        let ptt = parse_hotkey("Backquote").unwrap();
+        let al = parse_hotkey("Control+Backquote").unwrap();
+        let mute = parse_hotkey("Control+Shift+Backquote").unwrap();
+        assert!(check_distinct(&[
+            ("push-to-talk", &ptt),
+            ("always-listen", &al),
+            ("mute", &mute)
+        ])
+        .is_ok());
+
+        let dup = parse_hotkey("Ctrl+`").unwrap();
+        let err = check_distinct(&[
+            ("push-to-talk", &ptt),
+            ("always-listen", &al),
+            ("mute", &dup),
+        ])
+        .unwrap_err();
+        assert!(err.to_string().contains("always-listen and mute"));
+    }
+
+    #[test]
+    fn test_parse_double_tap() {
+        let tap = parse_hotkey("DoubleTap+Control").unwrap();
+        assert!(matches!(tap, Binding::DoubleTap(t) if t.modifier == Modifiers::CONTROL));
+        assert_ne!(tap.id(), parse_hotkey("DoubleTap+Shift").unwrap().id());
+        assert_ne!(tap.id(), parse_hotkey("Control+Mouse3").unwrap().id());
+        assert!(same_hotkey("doubletap+ctrl", "DoubleTap+Control"));
+
+        assert!(validate_hotkey("DoubleTap").is_err());
+        assert!(validate_hotkey("DoubleTap+Control+Shift").is_err());
+        assert!(validate_hotkey("DoubleTap+Control+KeyA").is_err());
+    }
+
+    #[test]
+    fn test_double_tap_tracker() {
+        let ctrl = Some(Modifiers::CONTROL);
+        let shift = Some(Modifiers::SHIFT);
+        let run = |events: &[(Option<Modifiers>, bool, u64)]| {
+            let mut tracker = DoubleTapTracker::new(300);
+            events
+                .iter()
+                .filter_map(|&(key, pressed, at)| tracker.feed(key, pressed, at))
+                .map(|(_, state)| state)
+                .collect::<Vec<_>>()
+        };
+        let pressed_released = vec![HotKeyState::Pressed, HotKeyState::Released];
+
+        // Tap, tap (with auto-repeat while held)
+        let double = [
+            (ctrl, true, 0),
+            (ctrl, false, 80),
+            (ctrl, true, 200),
+            (ctrl, true, 230),
+            (ctrl, false, 1500),
+        ];
+        assert_eq!(run(&double), pressed_released);
+        // Second press too late
+        assert!(run(&[(ctrl, true, 0), (ctrl, false, 80), (ctrl, true, 500)]).is_empty());
+        // First press held too long to be a tap
+        assert!(run(&[(ctrl, true, 0), (ctrl, false, 400), (ctrl, true, 500)]).is_empty());
+        // Ctrl+C then Ctrl+V
+        let shortcut = [
+            (ctrl, true, 0),
+            (None, true, 20),
+            (None, false, 40),
+            (ctrl, false, 60),
+            (ctrl, true, 100),
+            (None, true, 120),
+        ];
+        assert!(run(&shortcut).is_empty());
+        // Different modifiers, or two held together
+        assert!(run(&[(ctrl, true, 0), (ctrl, false, 50), (shift, true, 100)]).is_empty());
+        let chord = [(ctrl, true, 0), (shift, true, 10), (shift, false, 30), (ctrl, false, 40)];
+        assert!(run(&[&chord[..], &[(ctrl, true, 100)]].concat()).is_empty());
+        // A third tap doesn't re-trigger after the hotkey is released
+        let triple = [&double[..], &[(ctrl, true, 1600)]].concat();
+        assert_eq!(run(&triple), pressed_released);
+    }
+
+    #[test]
+    fn test_same_hotkey() {
+        assert!(same_hotkey("Ctrl+`", "Control+Backquote"));
+        assert!(same_hotkey("Shift+Control+F2", "Control+Shift+F2"));
+        assert!(same_hotkey("Mouse4", "MouseBack"));
+        assert!(!same_hotkey("Backquote", "Control+Backquote"));
+        assert!(!same_hotkey("Mouse4", "Mouse5"));
+        assert!(!same_hotkey("NotAKey", "NotAKey"));
     }
 }
@@ -0,0 +1,76 @@
+//! Keeping the model warm while the app sits idle
+//!
+//! After a long idle the first transcription can be much slower: the weights have
+//! been paged out or the CUDA context has gone cold. With `keep_warm` on, a tiny
+//! inference on silence runs every `keep_warm_minutes` that the model goes unused.
+//! It is skipped while anything is recording or transcribing, and the backend's
+//! model lock makes a transcription started meanwhile wait for it to finish.
+
+use crate::backend_loader::Model;
+use anyhow::Result;
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+
+/// How often the keep-warm thread checks whether a run is due
+pub const KEEP_WARM_POLL: Duration = Duration::from_secs(1);
+
+/// When the model was last used, by a transcription or a keep-warm run
+pub struct LastUse(Mutex<Instant>);
+
+impl Default for LastUse {
+    fn default() -> Self {
+        Self(Mutex::new(Instant::now()))
+    }
+}
+
+impl LastUse {
+    /// Record a use now
+    pub fn touch(&self) {
+        *self.0.lock() = Instant::now();
+    }
+
+    /// How long the model has gone unused
+    pub fn idle_for(&self) -> Duration {
+        self.0.lock().elapsed()
+    }
+}
+
+/// Whether a keep-warm run is due after the model went unused for `idle_for`.
+/// Never while `busy` (recording or transcribing), nor with a zero `interval`.
+pub fn is_due(idle_for: Duration, interval: Duration, busy: bool) -> bool {
+    !busy && !interval.is_zero() && idle_for >= interval
+}
+
+/// Run a tiny inference: the backend's warmup export, or a transcription of a
+/// second of silence if it has none. Returns how long it took.
+pub fn warm(model: &Model, sample_rate: u32) -> Result<Duration> {
+    let elapsed = model.warmup()?;
+    if !elapsed.is_zero() {
+        return Ok(elapsed);
+    }
+    let started = Instant::now();
+    model.transcribe(&vec![0.0; sample_rate as usize])?;
+    Ok(started.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_due() {
+        let interval = Duration::from_secs(300);
+        assert!(!is_due(Duration::from_secs(299), interval, false));
+        assert!(is_due(interval, interval, false));
+        assert!(is_due(Duration::from_secs(3600), interval, false));
+        // Never while recording or transcribing, however long the model sat unused
+        assert!(!is_due(Duration::from_secs(3600), interval, true));
+        assert!(!is_due(Duration::from_secs(3600), Duration::ZERO, false));
+
+        let last_use = LastUse::default();
+        assert!(last_use.idle_for() < interval);
+        assert!(!is_due(last_use.idle_for(), interval, false));
+        last_use.touch();
+        assert!(last_use.idle_for() < Duration::from_secs(1));
+    }
+}
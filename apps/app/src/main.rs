@@ -3,30 +3,55 @@
 
 mod always_listen;
 mod audio;
+mod autostart;
 mod backend_loader;
+mod chunking;
+mod cli;
+mod commands;
 mod config;
+mod doctor;
 mod downloader;
+mod focus;
+mod font;
+mod history;
+mod history_window;
 mod hotkeys;
+mod keep_warm;
 mod overlay;
+mod post_process;
+mod server;
 mod setup;
+mod shutdown;
+mod sound;
+mod text_field;
+mod text_format;
+mod transcript_export;
+mod transcript_log;
 mod tray;
 mod typer;
 
-use anyhow::Result;
-use backend_loader::LoadedBackend;
-use config::{get_exe_stem, setup_cuda_env, Config};
+use anyhow::{Context, Result};
+use backend_loader::{Backend, BackendRegistry};
+use always_listen::{AlwaysListenState, ParagraphJoiner};
+use config::{get_exe_stem, setup_cuda_env, AppProfile, Config, OutputMode};
 use cpal::traits::StreamTrait;
+use focus::FocusTarget;
+use history::History;
+use history_window::{HistoryAction, HistoryWindow};
 use hotkeys::{check_hotkey_event, HotkeyAction, HotkeyManager};
 use overlay::Overlay;
+use sound::{Cue, SoundCues};
 use parking_lot::Mutex;
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
-use tao::event::{ElementState, Event, MouseButton, WindowEvent};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tao::event::{ElementState, Event, MouseButton, StartCause, WindowEvent};
 use tao::event_loop::{ControlFlow, EventLoopBuilder};
 use tracing::{debug, error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use tray::AppStatus;
+use typer::TextSink;
 #[cfg(target_os = "windows")]
 use windows::Win32::Foundation::{CloseHandle, GetLastError, ERROR_ALREADY_EXISTS, HANDLE, HWND, POINT};
 #[cfg(target_os = "windows")]
@@ -41,12 +66,26 @@ use windows::Win32::UI::WindowsAndMessaging::{
 #[cfg(target_os = "windows")]
 use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
 
+/// Overlay level meter refresh interval while the mic is live
+const LEVEL_METER_INTERVAL: Duration = Duration::from_millis(50);
+/// Delay between attempts to reopen a disconnected microphone
+const AUDIO_RECONNECT_INTERVAL: Duration = Duration::from_secs(3);
+/// Transcriptions averaged for the tray tooltip's real-time factor
+const RTF_AVERAGE_WINDOW: usize = 20;
+/// How long exit waits for background threads and for each running transcription
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum AppMode {
     Idle,
-    Recording,
+    Recording {
+        /// Window to type into once the recording is transcribed (`restore_focus`)
+        target: Option<FocusTarget>,
+    },
     Processing,
     AlwaysListening,
+    /// The tray's Model menu is loading a new default model
+    LoadingModel,
 }
 
 /// Initialize logging with file output (and console in debug builds)
@@ -150,6 +189,30 @@ fn show_overlay_context_menu(hwnd: HWND) -> Option<u32> {
 }
 
 fn main() -> Result<()> {
+    // Headless mode: `app transcribe <file.wav> [--language xx] [--json]`
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("transcribe") {
+        cli::attach_parent_console();
+        let args = match cli::parse_transcribe_args(&args[1..]) {
+            Ok(args) => args,
+            Err(e) => {
+                eprintln!("{}\n{}", e, cli::USAGE);
+                std::process::exit(2);
+            }
+        };
+        if let Err(e) = cli::run_transcribe(&args) {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // `app --doctor`: check the setup end to end and print a report
+    if args.first().map(String::as_str) == Some("--doctor") {
+        cli::attach_parent_console();
+        std::process::exit(doctor::run_doctor());
+    }
+
     // Check for --setup-only flag (used when opening settings from running app)
     // This runs just the setup wizard without acquiring the mutex
     if std::env::args().any(|arg| arg == "--setup-only") {
@@ -185,10 +248,10 @@ fn main() -> Result<()> {
     let log_name = format!("app-{}.log", get_exe_stem().unwrap_or_else(|_| "app".to_string()));
     // Create a file appender that writes to app-<exe>.log
     let file_appender = tracing_appender::rolling::never(&log_dir, log_name.clone());
-    let (file_writer, _log_guard) = tracing_appender::non_blocking(file_appender);
+    let (file_writer, log_guard) = tracing_appender::non_blocking(file_appender);
 
     // Set up logging with both console (for debug builds) and file output
-    // Note: _log_guard must be kept alive for the duration of the program
+    // Note: log_guard must be kept alive until the app exits; run_app drops it last
     init_logging(file_writer);
 
     info!("========================================");
@@ -199,6 +262,19 @@ fn main() -> Result<()> {
     // Check if config exists and model is available
     let config = match Config::load() {
         Ok(cfg) => {
+            if let Err(e) = cfg.check_dirs() {
+                error!("{:#}", e);
+                show_error_dialog(
+                    "Folder Not Found",
+                    &format!(
+                        "{:#}\n\nCreate the folder, or fix or remove the setting in:\n{}",
+                        e,
+                        config::get_config_path()?.display()
+                    ),
+                );
+                return Err(e);
+            }
+            config::set_dir_overrides(&cfg);
             let model_complete = cfg.model_exists() && model_files_complete(&cfg).unwrap_or(false);
             if model_complete {
                 info!("Config loaded. Backend: {}", cfg.backend_id);
@@ -216,7 +292,7 @@ fn main() -> Result<()> {
         }
     };
 
-    run_app(config)
+    run_app(config, log_guard)
 }
 
 fn run_setup_and_get_config() -> Result<Config> {
@@ -225,10 +301,20 @@ fn run_setup_and_get_config() -> Result<Config> {
 }
 
 fn model_files_complete(config: &Config) -> Result<bool> {
+    let missing = missing_model_files(config)?;
+    if !missing.is_empty() {
+        warn!("Missing model files in {}: {}", config.model_path.display(), missing.join(", "));
+    }
+    Ok(missing.is_empty())
+}
+
+/// Files of the configured model that aren't in its folder (none if the backend or
+/// model isn't in a manifest, so there's nothing to compare against)
+fn missing_model_files(config: &Config) -> Result<Vec<String>> {
     let backend_dir = config::get_backends_dir()?.join(&config.backend_id);
     let manifest_path = backend_dir.join("manifest.json");
     if !manifest_path.exists() {
-        return Ok(true);
+        return Ok(Vec::new());
     }
 
     let manifest = backend_loader::BackendManifest::load(&manifest_path)?;
@@ -240,19 +326,11 @@ fn model_files_complete(config: &Config) -> Result<bool> {
                 config.model_name,
                 manifest_path.display()
             );
-            return Ok(true);
+            return Ok(Vec::new());
         }
     };
 
-    for filename in &model.files {
-        let file_path = config.model_path.join(filename);
-        if !file_path.exists() {
-            warn!("Missing model file: {}", file_path.display());
-            return Ok(false);
-        }
-    }
-
-    Ok(true)
+    Ok(model.missing_files(&config.model_path).into_iter().map(str::to_string).collect())
 }
 
 /// Show an error dialog to the user (Windows native message box)
@@ -282,31 +360,579 @@ fn show_error_dialog(title: &str, message: &str) {
     error!("{}: {}", title, message);
 }
 
+/// Show an informational dialog to the user (Windows native message box)
+#[cfg(windows)]
+fn show_info_dialog(title: &str, message: &str) {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        MessageBoxW, MB_ICONINFORMATION, MB_OK,
+    };
+    use windows::core::HSTRING;
+
+    let title_wide = HSTRING::from(title);
+    let message_wide = HSTRING::from(message);
+
+    unsafe {
+        let _ = MessageBoxW(
+            None,
+            &message_wide,
+            &title_wide,
+            MB_OK | MB_ICONINFORMATION,
+        );
+    }
+}
+
+/// Non-Windows fallback just logs the message
+#[cfg(not(windows))]
+fn show_info_dialog(title: &str, message: &str) {
+    info!("{}: {}", title, message);
+}
+
+/// Transcript history from the config; turning persistence off deletes a saved history
+fn open_history(config: &Config) -> History {
+    let path = match config::get_history_path() {
+        Ok(path) => path,
+        Err(e) => {
+            warn!("No history file: {}", e);
+            return History::open(config.history_size, None);
+        }
+    };
+    if config.history_persist && config.history_size > 0 {
+        History::open(config.history_size, Some(path))
+    } else {
+        let _ = std::fs::remove_file(&path);
+        History::open(config.history_size, None)
+    }
+}
+
+/// Models shared between the default config and per-application profiles
+struct ModelSet {
+    /// Loaded backend DLLs, kept for the life of the app
+    backends: BackendRegistry,
+    backend: Arc<Backend>,
+    /// Swapped at runtime by the tray's Model menu
+    default_model: Mutex<Arc<backend_loader::Model>>,
+    default_model_name: Mutex<String>,
+    use_gpu: bool,
+    min_samples: usize,
+    /// Phrase repeats that cut a result short as a decoding loop
+    max_repeats: usize,
+    /// CPU threads for every model loaded (0 = library default)
+    cpu_threads: u32,
+    /// Profile models loaded on first use, keyed by model id
+    profile_models: Mutex<HashMap<String, Arc<backend_loader::Model>>>,
+    /// Timing of the most recent transcription (any model)
+    last_timing: Mutex<Option<backend_loader::TranscribeTiming>>,
+    /// Device the most recent transcription ran on
+    last_device: Mutex<Option<String>>,
+    /// Recent wall-clock real-time factors, averaged in the tray tooltip
+    rtf_average: Mutex<backend_loader::RollingRtf>,
+    /// Recent transcripts for the tray's History window
+    history: Mutex<History>,
+    /// Type always-listen segments as they are decoded instead of the whole result at the end
+    incremental_typing: bool,
+    /// Typed after each push-to-talk result, unless the app's profile overrides it
+    trailing_char: config::TrailingChar,
+    /// Clipboard results are added after what is already copied instead of replacing it
+    clipboard_append: bool,
+    /// JSON-lines record of every transcription, if configured
+    transcript_log: Option<Mutex<transcript_log::TranscriptLog>>,
+    /// Vocabulary prompt from the config
+    initial_prompt: Option<String>,
+    /// Decoder tweaks from the config
+    decoding: backend_loader::DecodeOptions,
+    /// Languages auto-detect may choose from (empty = any)
+    allowed_languages: Vec<String>,
+    /// Picked in the tray's Language menu (None = auto-detect); profiles override it
+    language: Mutex<Option<String>>,
+    /// Hallucination filter, word replacements and clean-up applied to each result
+    post_processor: post_process::PostProcessor,
+    /// Spoken punctuation and editing commands, if turned on
+    commands: Option<commands::Commands>,
+    /// Characters the last result typed, erased by "delete that"
+    last_typed: Mutex<usize>,
+    /// Rate captured audio is resampled to
+    sample_rate: u32,
+    /// Longer recordings are transcribed in chunks split at pauses (0 = never)
+    chunk_samples: usize,
+    /// VAD threshold used to find the pauses
+    chunk_vad_threshold: f32,
+    /// Backend/model for languages the default model doesn't cover
+    language_fallback: Option<config::LanguageFallback>,
+    /// The fallback model and the backend that owns it, once loaded
+    fallback: OnceLock<Option<(Arc<backend_loader::Model>, Arc<Backend>)>>,
+    /// Transcriptions still running, waited for on exit
+    in_flight: shutdown::InFlight,
+    /// When a transcription last finished, for `keep_warm`
+    last_use: keep_warm::LastUse,
+}
+
+impl ModelSet {
+    /// Get the model for a profile, loading it on first use.
+    /// Falls back to the default model if the profile model can't be loaded.
+    fn model_for(&self, profile: &AppProfile) -> Arc<backend_loader::Model> {
+        let model_name = match profile.model_name.as_deref() {
+            Some(name) if name != *self.default_model_name.lock() => name,
+            _ => return self.default_model(),
+        };
+
+        if let Some(model) = self.profile_models.lock().get(model_name) {
+            return Arc::clone(model);
+        }
+
+        // Load without holding the cache lock, so other profiles (and the default
+        // model) keep transcribing meanwhile
+        match self.load_manifest_model(&self.backend, model_name) {
+            Ok(model) => {
+                let mut cache = self.profile_models.lock();
+                // Another caller may have loaded it first; keep theirs so only one
+                // copy stays in memory
+                if let Some(model) = cache.get(model_name) {
+                    return Arc::clone(model);
+                }
+                // Or it became the default model while this one was loading
+                if model_name == *self.default_model_name.lock() {
+                    return self.default_model();
+                }
+                let model = Arc::new(model);
+                cache.insert(model_name.to_string(), Arc::clone(&model));
+                model
+            }
+            Err(e) => {
+                warn!("Failed to load profile model '{}': {:#}. Using default model.", model_name, e);
+                self.default_model()
+            }
+        }
+    }
+
+    fn default_model(&self) -> Arc<backend_loader::Model> {
+        Arc::clone(&self.default_model.lock())
+    }
+
+    /// Load `model_id` from the backend and make it the default model. The old model
+    /// is freed once transcriptions still using it finish; on failure it stays active.
+    fn switch_default_model(&self, model_id: &str) -> Result<()> {
+        let rate = self.backend.required_sample_rate(model_id);
+        if rate != self.sample_rate {
+            anyhow::bail!(
+                "'{}' needs {}Hz audio but the microphone is set up for {}Hz. \
+                 Choose it in Settings instead.",
+                model_id,
+                rate,
+                self.sample_rate
+            );
+        }
+        let model = self.load_manifest_model(&self.backend, model_id)?;
+        if let Err(e) = model.warmup() {
+            warn!("Model warmup failed: {}", e);
+        }
+        *self.default_model.lock() = Arc::new(model);
+        *self.default_model_name.lock() = model_id.to_string();
+        // Don't keep a second copy if a profile loaded it earlier
+        self.profile_models.lock().remove(model_id);
+        Ok(())
+    }
+
+    /// The language fallback model, loaded on first use. `None` if none is
+    /// configured or it failed to load (the failure is logged once).
+    fn fallback_model(&self) -> Option<Arc<backend_loader::Model>> {
+        let fallback = self.language_fallback.as_ref()?;
+        let loaded = self.fallback.get_or_init(|| match self.load_fallback(fallback) {
+            Ok(loaded) => Some(loaded),
+            Err(e) => {
+                warn!("Language fallback disabled: {:#}", e);
+                None
+            }
+        });
+        loaded.as_ref().map(|(model, _)| Arc::clone(model))
+    }
+
+    fn load_fallback(
+        &self,
+        fallback: &config::LanguageFallback,
+    ) -> Result<(Arc<backend_loader::Model>, Arc<Backend>)> {
+        let backend = self.backends.load(&fallback.backend_id)?;
+        // Audio is captured at the main model's rate
+        let rate = backend.required_sample_rate(&fallback.model_name);
+        if rate != self.sample_rate {
+            anyhow::bail!(
+                "'{}' needs {}Hz audio but the main model uses {}Hz",
+                fallback.model_name,
+                rate,
+                self.sample_rate
+            );
+        }
+        info!("Loading fallback model '{}' ({})", fallback.model_name, backend.display_name);
+        let model = self.load_manifest_model(&backend, &fallback.model_name)?;
+        Ok((Arc::new(model), backend))
+    }
+
+    /// Transcribe with the profile's model, handing languages it doesn't cover to the
+    /// fallback model: a requested language is routed up front, a detected one by
+    /// transcribing again. Returns the model that produced the result.
+    fn transcribe_routed(
+        &self,
+        profile: &AppProfile,
+        audio: &[f32],
+        request: &backend_loader::TranscribeRequest,
+        on_segment: Option<&mut dyn FnMut(&str, bool)>,
+    ) -> (Arc<backend_loader::Model>, Result<backend_loader::Transcript>) {
+        let fallback_for = |language: &str| {
+            let fallback = self.language_fallback.as_ref()?;
+            fallback.handles(language).then(|| self.fallback_model()).flatten()
+        };
+
+        if let Some(model) = request.language().and_then(fallback_for) {
+            debug!("Routing '{}' to the fallback model", request.language().unwrap_or_default());
+            let result = model.transcribe_with(audio, request);
+            return (model, result);
+        }
+
+        let model = self.model_for(profile);
+        let result = match on_segment {
+            Some(on_segment) => model.transcribe_with_segments(audio, request, on_segment),
+            None => model.transcribe_with(audio, request),
+        };
+        if request.language().is_none() {
+            let detected = result.as_ref().ok().and_then(|t| t.detected_language.clone());
+            if let Some(language) = detected {
+                if let Some(fallback) = fallback_for(&language) {
+                    info!("Detected '{}', transcribing again with the fallback model", language);
+                    let request = backend_loader::TranscribeRequest {
+                        language: Some(&language),
+                        ..*request
+                    };
+                    let result = fallback.transcribe_with(audio, &request);
+                    return (fallback, result);
+                }
+            }
+        }
+        (model, result)
+    }
+
+    /// `transcribe_routed`, but audio longer than `chunk_samples` is split at pauses
+    /// and transcribed a chunk at a time. The first chunk's language is used for the
+    /// rest. Chunked results aren't streamed to `on_segment`.
+    fn transcribe_chunked(
+        &self,
+        profile: &AppProfile,
+        audio: &[f32],
+        request: &backend_loader::TranscribeRequest,
+        on_segment: Option<&mut dyn FnMut(&str, bool)>,
+    ) -> (Arc<backend_loader::Model>, Result<backend_loader::Transcript>) {
+        let chunks = chunking::split_at_silences(
+            audio,
+            self.sample_rate,
+            self.chunk_vad_threshold,
+            self.chunk_samples,
+        );
+        if chunks.len() == 1 {
+            return self.transcribe_routed(profile, audio, request, on_segment);
+        }
+
+        info!("Transcribing in {} chunks", chunks.len());
+        let mut merged = backend_loader::Transcript::default();
+        let mut logprob_sum = 0.0;
+        let mut no_speech_prob = 1.0f32;
+        let mut last: Option<(Arc<backend_loader::Model>, usize)> = None;
+        for (i, range) in chunks.iter().enumerate() {
+            let language = request.language().or(merged.detected_language.as_deref());
+            let chunk_request = backend_loader::TranscribeRequest { language, ..*request };
+            let (model, result) =
+                self.transcribe_routed(profile, &audio[range.clone()], &chunk_request, None);
+            let transcript = match result {
+                Ok(transcript) => transcript,
+                Err(e) => return (model, Err(e.context(format!("chunk {}", i + 1)))),
+            };
+            info!("Transcribed chunk {} of {}", i + 1, chunks.len());
+
+            let overlapped = last.as_ref().is_some_and(|(_, end)| range.start < *end);
+            merged.text = chunking::join_chunks(&merged.text, &transcript.text, overlapped);
+            let offset_ms = (range.start as u64 * 1000 / self.sample_rate as u64) as i64;
+            merged.segments.extend(transcript.segments.into_iter().map(|mut segment| {
+                segment.start_ms += offset_ms;
+                segment.end_ms += offset_ms;
+                segment
+            }));
+            if merged.detected_language.is_none() {
+                merged.detected_language = transcript.detected_language;
+            }
+            logprob_sum += transcript.avg_logprob * range.len() as f32;
+            // Speech anywhere means the recording held speech
+            no_speech_prob = no_speech_prob.min(transcript.no_speech_prob);
+            last = Some((model, range.end));
+        }
+        merged.avg_logprob = logprob_sum / chunks.iter().map(|c| c.len()).sum::<usize>() as f32;
+        merged.no_speech_prob = no_speech_prob;
+        let (model, _) = last.expect("at least two chunks");
+        (model, Ok(merged))
+    }
+
+    /// Load a model listed in `backend`'s manifest from the models directory
+    fn load_manifest_model(
+        &self,
+        backend: &Backend,
+        model_id: &str,
+    ) -> Result<backend_loader::Model> {
+        let manifest_model = backend
+            .models()
+            .iter()
+            .find(|m| m.id == model_id)
+            .with_context(|| format!("model '{}' not found in backend manifest", model_id))?;
+        let model_path = config::get_models_dir()?.join(&manifest_model.folder_name);
+        info!("Loading model '{}' from {}", model_id, model_path.display());
+        let options = backend_loader::ModelOptions::for_model(Some(manifest_model), None)
+            .with_cpu_threads(self.cpu_threads);
+        let (mut model, _) =
+            backend.create_model_with_options(&model_path, self.use_gpu, &options)?;
+        model.set_min_samples(self.min_samples);
+        model.set_max_repeats(self.max_repeats);
+        Ok(model)
+    }
+}
+
+/// Resolve the profile for `target`, or for the application that currently has focus.
+/// The returned profile always has `output_mode` and `typing` set (config defaults
+/// if not overridden).
+fn resolve_active_profile(config: &Config, target: Option<FocusTarget>) -> AppProfile {
+    let exe = target
+        .or_else(FocusTarget::foreground)
+        .and_then(FocusTarget::exe_name)
+        .unwrap_or_default();
+    let profile = config.resolve_profile(&exe);
+    if !profile.exe.is_empty() {
+        debug!("Using profile '{}' for foreground app '{}'", profile.exe, exe);
+    }
+    let mut profile = profile.clone();
+    profile.output_mode = Some(profile.output_mode.unwrap_or(config.output_mode));
+    profile.typing = Some(profile.typing.unwrap_or(config.type_mode));
+    profile
+}
+
+/// The foreground window to type the next push-to-talk result into, if `restore_focus` is on
+fn focus_target(config: &Config) -> Option<FocusTarget> {
+    if config.restore_focus {
+        FocusTarget::foreground()
+    } else {
+        None
+    }
+}
+
+/// Put the text back where the user was when they started talking. Returns the
+/// output mode to use: clipboard if that window was closed.
+fn refocus(focus: Option<FocusTarget>, output_mode: OutputMode) -> OutputMode {
+    match focus {
+        Some(target) if output_mode != OutputMode::ClipboardOnly && !target.restore() => {
+            if target.exists() {
+                warn!("Couldn't refocus the original window; typing into the active one");
+                output_mode
+            } else {
+                info!("The original window was closed; copying to the clipboard");
+                OutputMode::ClipboardOnly
+            }
+        }
+        _ => output_mode,
+    }
+}
+
 /// Transcription worker that processes audio and types the result
+#[allow(clippy::too_many_arguments)]
 fn transcribe_and_type(
     audio_data: Vec<f32>,
-    model: Arc<backend_loader::Model>,
+    models: Arc<ModelSet>,
+    profile: AppProfile,
+    focus: Option<FocusTarget>,
     typer: Arc<Mutex<typer::Typer>>,
-    _state: Arc<Mutex<AppMode>>,
+    joiner: Option<Arc<Mutex<ParagraphJoiner>>>,
+    muted: Arc<AtomicBool>,
     proxy: tao::event_loop::EventLoopProxy<UserEvent>,
     app_status: AppStatus,
 ) {
+    let in_flight = models.in_flight.enter();
     std::thread::spawn(move || {
+        let _in_flight = in_flight;
+        if profile.disable {
+            info!("Typing is disabled for '{}' - dropping the recording", profile.exe);
+            let _ = proxy.send_event(UserEvent::TranscriptionComplete(app_status));
+            return;
+        }
         info!(
             "Transcribing {} samples (~{:.1}s of audio)...",
             audio_data.len(),
-            audio_data.len() as f32 / 16000.0
+            audio_data.len() as f32 / models.sample_rate as f32
         );
 
-        match model.transcribe(&audio_data) {
+        let output_mode = profile.output_mode.unwrap_or_default();
+        let type_mode = profile.typing.unwrap_or_default();
+
+        // A profile's language wins over the one picked in the tray
+        let language = profile.language.clone().or_else(|| models.language.lock().clone());
+        let request = backend_loader::TranscribeRequest {
+            language: language.as_deref(),
+            translate: profile.translate,
+            initial_prompt: models.initial_prompt.as_deref(),
+            allowed_languages: &models.allowed_languages,
+            decode: models.decoding,
+            ..Default::default()
+        };
+        let audio_rms = always_listen::frame_rms(&audio_data);
+        // Always-listen results can be typed segment by segment as they are decoded.
+        // Not with a language fallback, which may transcribe the audio a second time.
+        // Nor with dictation commands, which can change text that was already decoded.
+        let incremental = models.incremental_typing
+            && models.commands.is_none()
+            && joiner.is_some()
+            && output_mode != OutputMode::ClipboardOnly
+            && models.language_fallback.is_none();
+        let mut typed = typer::IncrementalOutput::default();
+        let mut type_segment = |segment: &str, is_final: bool| {
+            if is_final || muted.load(Ordering::SeqCst) {
+                return;
+            }
+            let segment = models.post_processor.apply(segment, audio_rms);
+            let first = typed.is_empty();
+            let mut delta = typed.segment(&segment);
+            if first {
+                if let Some(joiner) = &joiner {
+                    delta = joiner.lock().join(&delta);
+                }
+            }
+            let mut typer = typer.lock();
+            typer.set_type_mode(type_mode);
+            if let Err(e) = typer::deliver_text(&mut *typer, &delta, output_mode, false) {
+                error!("Failed to output text: {}", e);
+            }
+        };
+        let on_segment = incremental.then_some(&mut type_segment as &mut dyn FnMut(&str, bool));
+        let started = Instant::now();
+        let (model, result) =
+            models.transcribe_chunked(&profile, &audio_data, &request, on_segment);
+        let transcribe_ms = started.elapsed().as_millis() as u64;
+        models.last_use.touch();
+        let audio_s = audio_data.len() as f64 / models.sample_rate as f64;
+        if result.is_ok() {
+            let rtf = if audio_s > 0.0 { transcribe_ms as f64 / 1000.0 / audio_s } else { 0.0 };
+            info!(transcribe_ms, audio_s, rtf, "Transcription latency");
+            models.rtf_average.lock().push(rtf);
+        }
+        let language = result
+            .as_ref()
+            .ok()
+            .and_then(|t| t.detected_language.clone())
+            .or_else(|| language.clone());
+        let result = result.map(|t| {
+            if let Some(lang) = t.detected_language.as_deref() {
+                debug!("Language: {}", lang);
+            }
+            debug!("avg_logprob {:.2}, no_speech_prob {:.2}", t.avg_logprob, t.no_speech_prob);
+            if models.post_processor.is_no_speech(t.no_speech_prob) && !t.text.is_empty() {
+                info!("Dropping \"{}\" (no_speech_prob {:.2})", t.text, t.no_speech_prob);
+                return String::new();
+            }
+            t.text
+        });
+        if let Some(timing) = model.last_timing() {
+            *models.last_timing.lock() = Some(timing);
+        }
+        let device = model.device_used();
+        if let Some(device) = device.clone() {
+            *models.last_device.lock() = Some(device);
+        }
+
+        match result {
+            Ok(text) if muted.load(Ordering::SeqCst) => {
+                if !text.is_empty() {
+                    info!("Muted - dropping result");
+                }
+            }
             Ok(text) => {
+                let text = models.post_processor.apply(&text, audio_rms);
+                // Spoken commands become punctuation, key presses and deletions
+                let edit = match &models.commands {
+                    Some(commands) => commands.apply(&text, *models.last_typed.lock()),
+                    None => commands::Edit::text(text),
+                };
+                let text = edit.text;
+                if edit.backspaces > 0 {
+                    let output_mode = refocus(focus, output_mode);
+                    let mut typer = typer.lock();
+                    match typer::erase_typed(&mut *typer, edit.backspaces, output_mode) {
+                        Ok(true) => {
+                            info!("Erased {} characters", edit.backspaces);
+                            let mut last_typed = models.last_typed.lock();
+                            *last_typed = last_typed.saturating_sub(edit.backspaces);
+                        }
+                        Ok(false) => info!("Nothing typed to erase"),
+                        Err(e) => error!("Failed to erase text: {}", e),
+                    }
+                }
                 if !text.is_empty() {
                     info!("Result: \"{}\"", text);
-                    info!("Typing into active window...");
-                    if let Err(e) = typer.lock().type_text(&text) {
-                        error!("Failed to type: {}", e);
+                    models.history.lock().push(&text);
+                    let _ = proxy.send_event(UserEvent::ShowTranscript(text.clone()));
+                    let output_mode = refocus(focus, output_mode);
+                    // Push-to-talk results can end with a space or newline for the next
+                    // one (joined always-listen results are already separated)
+                    let trailing = match joiner {
+                        None if output_mode != OutputMode::ClipboardOnly => {
+                            profile.trailing_char(models.trailing_char).as_str()
+                        }
+                        _ => "",
+                    };
+                    let output = if !typed.is_empty() {
+                        // Only the words the streamed segments didn't already type
+                        if let Some(joiner) = &joiner {
+                            joiner.lock().mark_end();
+                        }
+                        typed.finish(&text)
+                    } else {
+                        // Join always-listen results into paragraphs (not for clipboard copies)
+                        match joiner {
+                            Some(joiner) if output_mode != OutputMode::ClipboardOnly => {
+                                joiner.lock().join(&text)
+                            }
+                            _ => text.clone(),
+                        }
+                    };
+                    // Appended to the text itself, so a paste includes it and no
+                    // separate keystroke is sent
+                    let output = output + trailing;
+                    let delivered = {
+                        let mut typer = typer.lock();
+                        typer.set_type_mode(type_mode);
+                        let append = models.clipboard_append;
+                        typer::deliver_text(&mut *typer, &output, output_mode, append)
+                    };
+                    // What "delete that" would erase next
+                    *models.last_typed.lock() = match delivered {
+                        Ok(Some(typer::Delivery::Clipboard)) => {
+                            info!("Copied to clipboard");
+                            let _ = proxy.send_event(UserEvent::CopiedToClipboard);
+                            0
+                        }
+                        Ok(_) => {
+                            info!("Typed into active window");
+                            commands::backspace_count(&output)
+                        }
+                        Err(e) => {
+                            error!("Failed to output text: {}", e);
+                            0
+                        }
+                    };
+                    // Logged after typing so a slow disk never delays the text
+                    if let Some(log) = &models.transcript_log {
+                        let mut record =
+                            transcript_log::TranscriptRecord::now(&text, audio_s, transcribe_ms);
+                        record.device = device.as_deref();
+                        record.language = language.as_deref();
+                        let mut log = log.lock();
+                        if let Err(e) = log.append(&record) {
+                            warn!("Failed to write {}: {:#}", log.path().display(), e);
+                        }
                     }
-                } else {
+                } else if edit.backspaces == 0
+                    && !backend_loader::is_audio_too_short(audio_data.len(), models.min_samples)
+                {
+                    // Too-short audio is already logged by the model
                     info!("No speech detected");
                 }
             }
@@ -319,7 +945,10 @@ fn transcribe_and_type(
     });
 }
 
-fn run_app(mut config: Config) -> Result<()> {
+fn run_app(
+    mut config: Config,
+    log_guard: tracing_appender::non_blocking::WorkerGuard,
+) -> Result<()> {
     // Set up CUDA environment if GPU is enabled
     setup_cuda_env(&config);
 
@@ -339,11 +968,9 @@ fn run_app(mut config: Config) -> Result<()> {
         }
     };
 
-    // Load backend
-    let backend_dir = config::get_backends_dir()?.join(&config.backend_id);
-    info!("Loading backend from: {}", backend_dir.display());
-
-    let backend = match LoadedBackend::load(&backend_dir) {
+    // Load backend (the registry keeps it, and any fallback backend, loaded)
+    let backends = BackendRegistry::new(config::get_backends_dir()?);
+    let backend = match backends.load(&config.backend_id) {
         Ok(be) => {
             info!("Backend loaded: {}", be.display_name);
             be
@@ -356,7 +983,7 @@ fn run_app(mut config: Config) -> Result<()> {
                     "Failed to load backend '{}':\n{}\n\nPlease ensure the backend files are in:\n{}",
                     config.backend_id,
                     e,
-                    backend_dir.display()
+                    backends.backend_dir(&config.backend_id).display()
                 ),
             );
             return Err(e);
@@ -381,76 +1008,129 @@ fn run_app(mut config: Config) -> Result<()> {
         backend.supports_cuda_runtime()
     );
 
-    for filename in [
-        "model.bin",
-        "config.json",
-        "preprocessor_config.json",
-        "tokenizer.json",
-        "vocabulary.txt",
-    ] {
-        let path = config.model_path.join(filename);
-        info!("Model file check: {} exists={}", path.display(), path.exists());
-    }
-
-    // Create model (with GPU->CPU fallback)
-    let model = match backend.create_model(&config.model_path, config.use_gpu) {
-        Ok(m) => {
-            let device_used = if config.use_gpu { "CUDA" } else { "CPU" };
+    // Create model (with GPU->CPU fallback). The backend checks the model folder
+    // and names any missing file.
+    let options = backend
+        .model_options(&config.model_name, config.compute_type.as_deref())
+        .with_cpu_threads(config.cpu_threads);
+    let mut model = match backend.create_model_with_options(
+        &config.model_path,
+        config.use_gpu,
+        &options,
+    ) {
+        Ok((m, device_used)) => {
+            config.use_gpu = device_used.is_gpu();
             info!(
                 "Model ready (use_gpu={}, backend_cuda={}, device_used={})",
                 config.use_gpu,
                 backend.supports_cuda_runtime(),
                 device_used
             );
-            Arc::new(m)
+            m
         }
         Err(e) => {
-            if config.use_gpu {
-                warn!(
-                    "GPU model load failed: {}. Retrying on CPU...",
-                    e
-                );
-                match backend.create_model(&config.model_path, false) {
-                    Ok(m) => {
-                        config.use_gpu = false;
-                        info!(
-                            "Model ready (use_gpu=false, backend_cuda={}, device_used=CPU)",
-                            backend.supports_cuda_runtime()
-                        );
-                        Arc::new(m)
-                    }
-                    Err(cpu_e) => {
-                        error!("Failed to create model (GPU then CPU): {}", cpu_e);
-                        show_error_dialog(
-                            "Model Error",
-                            &format!(
-                                "Failed to load model '{}'.\n\nGPU error:\n{}\n\nCPU error:\n{}\n\nPlease try re-downloading the model from settings.",
-                                config.model_path.display(),
-                                e,
-                                cpu_e
-                            ),
-                        );
-                        return Err(cpu_e);
-                    }
-                }
-            } else {
-                error!("Failed to create model: {}", e);
-                show_error_dialog(
-                    "Model Error",
-                    &format!(
-                        "Failed to load model '{}':\n{}\n\nPlease try re-downloading the model from settings.",
-                        config.model_path.display(),
-                        e
-                    ),
-                );
-                return Err(e);
-            }
+            error!("Failed to create model: {:#}", e);
+            show_error_dialog(
+                "Model Error",
+                &format!(
+                    "Failed to load model '{}':\n{:#}\n\n{}",
+                    config.model_path.display(),
+                    e,
+                    backend_loader::model_load_advice(&e)
+                ),
+            );
+            return Err(e);
         }
     };
+    model.set_min_samples(config.min_audio_samples(model.sample_rate()));
+    model.set_max_repeats(config.max_repeats);
+
+    // Audio is resampled to whatever the active model expects
+    let sample_rate = backend.required_sample_rate(&config.model_name);
+    if sample_rate != backend_loader::DEFAULT_SAMPLE_RATE {
+        info!("Model '{}' requires {}Hz audio", config.model_name, sample_rate);
+    }
+    audio_capture.lock().set_target_sample_rate(sample_rate);
+    let mic_warning = {
+        let capture = audio_capture.lock();
+        capture.is_low_sample_rate().then(|| {
+            let rate = capture.effective_sample_rate();
+            warn!("Microphone captures {}Hz, below the model's {}Hz", rate, sample_rate);
+            format!("mic only {}Hz", rate)
+        })
+    };
+
+    let models = Arc::new(ModelSet {
+        backends,
+        backend: Arc::clone(&backend),
+        default_model: Mutex::new(Arc::new(model)),
+        default_model_name: Mutex::new(config.model_name.clone()),
+        use_gpu: config.use_gpu,
+        min_samples: config.min_audio_samples(sample_rate),
+        max_repeats: config.max_repeats,
+        cpu_threads: config.cpu_threads,
+        profile_models: Mutex::new(HashMap::new()),
+        last_timing: Mutex::new(None),
+        last_device: Mutex::new(None),
+        rtf_average: Mutex::new(backend_loader::RollingRtf::new(RTF_AVERAGE_WINDOW)),
+        history: Mutex::new(open_history(&config)),
+        incremental_typing: config.incremental_typing,
+        trailing_char: config.trailing_char,
+        clipboard_append: config.clipboard_append,
+        transcript_log: config.transcript_log_path.clone().map(|path| {
+            info!("Logging transcripts to {}", path.display());
+            Mutex::new(transcript_log::TranscriptLog::new(path, transcript_log::MAX_LOG_BYTES))
+        }),
+        initial_prompt: config.initial_prompt().map(str::to_string),
+        decoding: config.decoding,
+        allowed_languages: config.allowed_languages(),
+        language: Mutex::new(config.language.clone()),
+        post_processor: post_process::PostProcessor::from_config(&config),
+        commands: commands::Commands::from_config(&config),
+        last_typed: Mutex::new(0),
+        sample_rate,
+        chunk_samples: config.chunk_seconds as usize * sample_rate as usize,
+        chunk_vad_threshold: config.always_listen_config(sample_rate).vad_threshold,
+        language_fallback: config.fallback_model.clone(),
+        fallback: OnceLock::new(),
+        in_flight: shutdown::InFlight::default(),
+        last_use: keep_warm::LastUse::default(),
+    });
+    if config.fallback_model.as_ref().is_some_and(|f| f.preload) {
+        let models = Arc::clone(&models);
+        std::thread::spawn(move || models.fallback_model());
+    }
+
+    // Optional local HTTP API for other tools: --serve <port>
+    let args: Vec<String> = std::env::args().collect();
+    let serve_port = server::parse_serve_port(&args).unwrap_or_else(|e| {
+        warn!("{:#}", e);
+        None
+    });
+    if let Some(port) = serve_port {
+        let server_models = Arc::clone(&models);
+        let server =
+            server::TranscribeServer::new(move || server_models.default_model(), sample_rate);
+        if let Err(e) = server.spawn(port) {
+            error!("Failed to start transcription server: {:#}", e);
+            show_error_dialog("Server Error", &format!("{:#}", e));
+        }
+    }
+
+    // Warm up in the background so startup isn't blocked
+    {
+        let model = models.default_model();
+        std::thread::spawn(move || match model.warmup() {
+            Ok(elapsed) if elapsed.is_zero() => debug!("Backend has no warmup export"),
+            Ok(elapsed) => info!("Model warmed up in {:?}", elapsed),
+            Err(e) => warn!("Model warmup failed: {}", e),
+        });
+    }
 
     let typer = match typer::Typer::new() {
-        Ok(t) => {
-            info!("Keyboard typer ready");
+        Ok(mut t) => {
+            t.set_type_mode(config.type_mode);
+            info!("Keyboard typer ready ({:?})", config.type_mode);
             Arc::new(Mutex::new(t))
         }
         Err(e) => {
@@ -463,14 +1143,52 @@ fn run_app(mut config: Config) -> Result<()> {
         }
     };
 
+    // Joins consecutive always-listen results (push-to-talk results are typed as-is)
+    let paragraph_joiner = Arc::new(Mutex::new(ParagraphJoiner::new(Duration::from_millis(
+        config.always_listen_paragraph_gap_ms,
+    ))));
+
+    // Set by the mute hotkey: results are dropped and listening is disabled
+    let muted = Arc::new(AtomicBool::new(false));
+
     // Create event loop
     let event_loop = EventLoopBuilder::<UserEvent>::with_user_event().build();
     let proxy = event_loop.create_proxy();
 
+    // Optional high-pass/normalization before transcribing (both recording paths)
+    let preprocess_options = audio::PreprocessOptions {
+        sample_rate: models.sample_rate,
+        highpass: config.highpass_enabled,
+        normalize: config.normalize_enabled,
+        silence_rms: config.hallucination_max_rms,
+    };
+    audio_capture.lock().set_preprocess(preprocess_options);
+
+    // Auto-stop push-to-talk if the key is held past the limit
+    {
+        let limit_proxy = Mutex::new(proxy.clone());
+        audio_capture.lock().set_recording_limit(
+            config.max_recording_samples(sample_rate),
+            Some(Arc::new(move || {
+                let _ = limit_proxy.lock().send_event(UserEvent::RecordingLimitReached);
+            })),
+        );
+    }
+
+    // Keep a little audio from before each push-to-talk key press
+    if config.pre_roll_ms() > 0 {
+        if let Err(e) = audio_capture.lock().set_pre_roll(config.pre_roll_ms()) {
+            warn!("Failed to start the push-to-talk pre-roll: {}", e);
+        }
+    }
+
     // Initialize hotkeys from config
     let hotkey_manager = match HotkeyManager::from_config(
         &config.hotkey_push_to_talk,
         &config.hotkey_always_listen,
+        &config.hotkey_mute,
+        &config.hotkey_pause_listen,
+        config.double_tap_ms,
     ) {
         Ok(hm) => {
             info!("Hotkey manager ready");
@@ -486,15 +1204,33 @@ fn run_app(mut config: Config) -> Result<()> {
                 ),
             );
             // Fall back to default hotkeys
-            HotkeyManager::from_config("Backquote", "Control+Backquote")?
+            HotkeyManager::from_config(
+                "Backquote",
+                "Control+Backquote",
+                "Control+Shift+Backquote",
+                "",
+                config.double_tap_ms,
+            )?
         }
     };
     let push_to_talk_id = hotkey_manager.push_to_talk_id();
     let always_listen_id = hotkey_manager.always_listen_id();
+    let mute_id = hotkey_manager.mute_id();
+    let pause_listen_id = hotkey_manager.pause_listen_id();
     let hotkey_receiver = HotkeyManager::receiver();
 
     // Initialize tray
-    let mut tray_manager = match tray::TrayManager::new() {
+    let profiles = Config::list_profiles().unwrap_or_else(|e| {
+        warn!("Failed to list config profiles: {}", e);
+        Vec::new()
+    });
+    let mut tray_manager = match tray::TrayManager::new(
+        &profiles,
+        config.overlay_always_on_top,
+        config.overlay_click_through,
+        config.audio_cues,
+        config.output_mode == OutputMode::ClipboardOnly,
+    ) {
         Ok(tm) => tm,
         Err(e) => {
             error!("Failed to initialize tray: {}", e);
@@ -506,13 +1242,50 @@ fn run_app(mut config: Config) -> Result<()> {
             return Err(e);
         }
     };
+    if mic_warning.is_some() {
+        tray_manager.set_mic_warning(mic_warning);
+        tray_manager.set_status(AppStatus::Idle);
+    }
+    let downloaded_models: Vec<(String, String)> = match config::get_models_dir() {
+        Ok(dir) => backend
+            .models()
+            .iter()
+            .filter(|m| m.is_downloaded(&dir))
+            .map(|m| (m.id.clone(), m.display_name.clone()))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    if let Err(e) = tray_manager.set_models(&downloaded_models, &config.model_name) {
+        warn!("Failed to build the Model menu: {}", e);
+    }
+    // A persisted history may already have something to save
+    tray_manager.set_save_transcript_enabled(models.history.lock().latest().is_some());
+    let quick_languages = config.quick_languages();
+    if let Err(e) = tray_manager.set_languages(&quick_languages, config.language.as_deref()) {
+        warn!("Failed to build the Language menu: {}", e);
+    }
     let menu_receiver = tray::TrayManager::menu_receiver();
     let show_overlay_id = tray_manager.show_overlay_id.clone();
+    let overlay_on_top_id = tray_manager.overlay_on_top_id.clone();
+    let overlay_click_through_id = tray_manager.overlay_click_through_id.clone();
+    let audio_cues_id = tray_manager.audio_cues_id.clone();
+    let clipboard_only_id = tray_manager.clipboard_only_id.clone();
+    let always_listen_menu_id = tray_manager.always_listen_id.clone();
+    let last_timing_id = tray_manager.last_timing_id.clone();
+    let history_id = tray_manager.history_id.clone();
+    let save_transcript_id = tray_manager.save_transcript_id.clone();
     let settings_id = tray_manager.settings_id.clone();
+    let save_profile_id = tray_manager.save_profile_id.clone();
     let exit_id = tray_manager.exit_id.clone();
 
     // Initialize overlay with saved position
-    let mut overlay = match Overlay::new(&event_loop, config.overlay_x, config.overlay_y) {
+    let mut overlay = match Overlay::new(
+        &event_loop,
+        config.overlay_x,
+        config.overlay_y,
+        config.overlay_always_on_top,
+        config.overlay_click_through,
+    ) {
         Ok(ov) => ov,
         Err(e) => {
             error!("Failed to create overlay: {}", e);
@@ -525,6 +1298,7 @@ fn run_app(mut config: Config) -> Result<()> {
         }
     };
     overlay.set_status(AppStatus::Idle);
+    overlay.set_level_meter(audio_capture.lock().level_meter());
 
     info!("Overlay window created");
     info!("System tray icon created");
@@ -536,45 +1310,75 @@ fn run_app(mut config: Config) -> Result<()> {
     // App state
     let state = Arc::new(Mutex::new(AppMode::Idle));
     let running = Arc::new(AtomicBool::new(true));
+    // Background threads, stopped by clearing `running` and joined on exit
+    let mut workers = shutdown::Workers::default();
+
+    // Redraw the level meter at a steady rate while the mic is live
+    let meter_active = overlay.meter_active();
+    let proxy_meter = proxy.clone();
+    let running_meter = Arc::clone(&running);
+    workers.spawn("level-meter", move || {
+        while running_meter.load(Ordering::SeqCst) {
+            std::thread::sleep(LEVEL_METER_INTERVAL);
+            if meter_active.load(Ordering::Relaxed)
+                && proxy_meter.send_event(UserEvent::LevelMeterTick).is_err()
+            {
+                break;
+            }
+        }
+    });
 
     // Always-listen state
     let always_listen_active = Arc::new(AtomicBool::new(false));
+    // Paused by the pause-listen hotkey: the stream keeps running, speech is ignored
+    let always_listen_paused = Arc::new(AtomicBool::new(false));
+    // Always-listen is recording speech or handing it off (no keep-warm runs then)
+    let always_listen_busy = Arc::new(AtomicBool::new(false));
     let (audio_tx, audio_rx) = crossbeam_channel::bounded::<Vec<f32>>(100);
     let (result_tx, _result_rx) = crossbeam_channel::bounded::<Vec<f32>>(10);
 
     // Spawn always-listen processing thread
     let always_listen_running = Arc::clone(&running);
     let always_listen_active_thread = Arc::clone(&always_listen_active);
+    let always_listen_paused_thread = Arc::clone(&always_listen_paused);
+    let always_listen_busy_thread = Arc::clone(&always_listen_busy);
+    let al_device_lost = audio_capture.lock().device_lost_flag();
     let al_proxy = proxy.clone();
-    let silence_timeout_ms = config.silence_timeout_ms;
+    let al_config = config.always_listen_config(sample_rate);
+    info!(
+        "Always-listen VAD: threshold {:.4}, pre-roll {}ms, min speech {}ms, silence {}ms",
+        al_config.vad_threshold,
+        al_config.pre_roll_duration_ms,
+        al_config.min_speech_duration_ms,
+        al_config.post_silence_duration_ms
+    );
 
-    std::thread::spawn(move || {
-        use always_listen::{AlwaysListenConfig, AlwaysListenController, AlwaysListenState};
+    workers.spawn("always-listen", move || {
+        use always_listen::AlwaysListenController;
 
-        let mut al_config = AlwaysListenConfig::default();
-        al_config.post_silence_duration_ms = silence_timeout_ms;
         let controller = AlwaysListenController::new(al_config, audio_rx, result_tx);
 
-        // Track previous state to detect changes
-        let mut last_was_recording = false;
+        let mut last_was_paused = false;
 
         while always_listen_running.load(Ordering::SeqCst) {
+            // A dead stream delivers nothing: pause and let the main loop reconnect
+            if always_listen_active_thread.load(Ordering::SeqCst)
+                && al_device_lost.load(Ordering::SeqCst)
+            {
+                always_listen_active_thread.store(false, Ordering::SeqCst);
+                let _ = al_proxy.send_event(UserEvent::AudioDeviceLost);
+            }
+
             // Only process when always-listen is active
             if always_listen_active_thread.load(Ordering::SeqCst) {
-                if controller.state() == AlwaysListenState::Paused {
+                let paused = always_listen_paused_thread.load(Ordering::SeqCst);
+                if paused != last_was_paused {
+                    let _ = if paused { controller.pause() } else { controller.resume() };
+                    last_was_paused = paused;
+                } else if !paused && controller.state() == AlwaysListenState::Paused {
                     let _ = controller.start();
                 }
 
-                // Check for state changes (recording vs listening)
-                let current_state = controller.state();
-                let is_recording = matches!(current_state, AlwaysListenState::Recording { .. });
-
-                if is_recording != last_was_recording {
-                    // State changed - notify main thread
-                    let _ = al_proxy.send_event(UserEvent::AlwaysListenStateChange(is_recording));
-                    last_was_recording = is_recording;
-                }
-
                 // Check for transcription results
                 if let Some(audio_data) = controller.try_recv_result() {
                     debug!("Received {} samples from always-listen", audio_data.len());
@@ -586,7 +1390,17 @@ fn run_app(mut config: Config) -> Result<()> {
                 if controller.state() != AlwaysListenState::Paused {
                     let _ = controller.stop();
                 }
-                last_was_recording = false;
+                last_was_paused = false;
+            }
+
+            // Pass on speech detection and hand-off as they happen
+            while let Some(al_state) = controller.try_recv_state() {
+                let busy = matches!(
+                    al_state,
+                    AlwaysListenState::Recording { .. } | AlwaysListenState::Processing
+                );
+                always_listen_busy_thread.store(busy, Ordering::SeqCst);
+                let _ = al_proxy.send_event(UserEvent::AlwaysListenState(al_state));
             }
 
             std::thread::sleep(Duration::from_millis(10));
@@ -617,11 +1431,16 @@ fn run_app(mut config: Config) -> Result<()> {
     // Spawn hotkey listener thread
     let proxy_hotkey = proxy.clone();
     let running_hotkey = Arc::clone(&running);
-    std::thread::spawn(move || {
+    workers.spawn("hotkeys", move || {
         while running_hotkey.load(Ordering::SeqCst) {
             if let Ok(event) = hotkey_receiver.recv_timeout(Duration::from_millis(100)) {
-                if let Some(action) = check_hotkey_event(&event, push_to_talk_id, always_listen_id)
-                {
+                if let Some(action) = check_hotkey_event(
+                    &event,
+                    push_to_talk_id,
+                    always_listen_id,
+                    mute_id,
+                    pause_listen_id,
+                ) {
                     let _ = proxy_hotkey.send_event(UserEvent::Hotkey(action));
                 }
             }
@@ -634,7 +1453,7 @@ fn run_app(mut config: Config) -> Result<()> {
     // Spawn menu listener thread
     let proxy_menu = proxy.clone();
     let running_menu = Arc::clone(&running);
-    std::thread::spawn(move || {
+    workers.spawn("menu", move || {
         while running_menu.load(Ordering::SeqCst) {
             if let Ok(event) = menu_receiver.recv_timeout(Duration::from_millis(100)) {
                 let _ = proxy_menu.send_event(UserEvent::Menu(event.id));
@@ -642,12 +1461,90 @@ fn run_app(mut config: Config) -> Result<()> {
         }
     });
 
+    // Keep the model warm with a tiny inference whenever it sits unused
+    if config.keep_warm {
+        let interval = Duration::from_secs(config.keep_warm_minutes.max(1) * 60);
+        info!("Keeping the model warm every {} minute(s) of idle", interval.as_secs() / 60);
+        let running_warm = Arc::clone(&running);
+        let models_warm = Arc::clone(&models);
+        let state_warm = Arc::clone(&state);
+        let always_listen_busy_warm = Arc::clone(&always_listen_busy);
+        workers.spawn("keep-warm", move || {
+            while running_warm.load(Ordering::SeqCst) {
+                std::thread::sleep(keep_warm::KEEP_WARM_POLL);
+                let busy = match *state_warm.lock() {
+                    AppMode::Idle => false,
+                    AppMode::AlwaysListening => always_listen_busy_warm.load(Ordering::SeqCst),
+                    _ => true,
+                } || models_warm.in_flight.count() > 0;
+                if !keep_warm::is_due(models_warm.last_use.idle_for(), interval, busy) {
+                    continue;
+                }
+                // Counted like a transcription, so exit waits for it
+                let _in_flight = models_warm.in_flight.enter();
+                match keep_warm::warm(&models_warm.default_model(), models_warm.sample_rate) {
+                    Ok(elapsed) => debug!("Kept the model warm ({:?})", elapsed),
+                    Err(e) => warn!("Keep-warm inference failed: {}", e),
+                }
+                models_warm.last_use.touch();
+            }
+        });
+    }
+
     // Clone for event loop
-    let always_listen_stream_for_loop = always_listen_stream;
+    // Taken apart in order on exit; see Event::LoopDestroyed
+    let mut models_for_loop = Some(models);
+    let mut workers_for_loop = Some(workers);
+    let mut log_guard = Some(log_guard);
+    let mut always_listen_stream_for_loop = always_listen_stream;
     let always_listen_stream_running_for_loop = always_listen_stream_running;
+    // Used to rebuild the always-listen stream after the mic reconnects
+    let reconnect_audio_tx = audio_tx;
+    // Mic reconnection in progress, and whether to restart always-listen once it succeeds
+    let mut reconnecting_audio = false;
+    let mut resume_always_listen = false;
+
+    let mut history_window: Option<HistoryWindow> = None;
+    let mut sound_cues = SoundCues::new(config.audio_cue_device.clone(), config.audio_cues);
+    // Output mode the tray's clipboard toggle switches back to
+    let mut typing_output_mode = match config.output_mode {
+        OutputMode::ClipboardOnly => OutputMode::default(),
+        mode => mode,
+    };
+    // When the overlay animation's next frame is due
+    let mut next_animation_frame = Instant::now();
 
     // Run event loop
-    event_loop.run(move |event, _, control_flow| {
+    event_loop.run(move |event, event_loop, control_flow| {
+        // The loop never returns, so shut down here: stop the threads, let running
+        // transcriptions finish, unload the models, then flush the log last
+        if let Event::LoopDestroyed = event {
+            info!("Shutting down...");
+            running.store(false, Ordering::SeqCst);
+            always_listen_active.store(false, Ordering::SeqCst);
+            always_listen_stream_running_for_loop.store(false, Ordering::SeqCst);
+            always_listen_stream_for_loop = None;
+            if let Some(workers) = workers_for_loop.take() {
+                let stuck = workers.join(SHUTDOWN_TIMEOUT);
+                if !stuck.is_empty() {
+                    warn!("Threads still running at exit: {}", stuck.join(", "));
+                }
+            }
+            if let Some(models) = models_for_loop.take() {
+                if !models.in_flight.wait_idle(SHUTDOWN_TIMEOUT) {
+                    warn!("Exiting with {} transcription(s) unfinished", models.in_flight.count());
+                }
+                // Frees the models and backends (and their CUDA contexts); a
+                // transcription that outlived the wait keeps its own reference
+                drop(models);
+            }
+            info!("Shutdown complete");
+            drop(log_guard.take());
+            return;
+        }
+        let Some(models) = &models_for_loop else {
+            return;
+        };
         // Rename for convenience in the loop
         let always_listen_stream = &always_listen_stream_for_loop;
         let always_listen_stream_running = &always_listen_stream_running_for_loop;
@@ -657,16 +1554,42 @@ fn run_app(mut config: Config) -> Result<()> {
             Event::UserEvent(user_event) => match user_event {
                 UserEvent::Hotkey(action) => {
                     let mut mode = state.lock();
+                    if muted.load(Ordering::SeqCst) && action != HotkeyAction::MuteToggle {
+                        if action != HotkeyAction::PushToTalkReleased {
+                            info!("Muted - ignoring {:?}", action);
+                        }
+                        tray_manager.set_always_listen_checked(false);
+                        return;
+                    }
+                    // Press-to-toggle: a second press stops the recording, releases do nothing
+                    let action = match action {
+                        HotkeyAction::PushToTalkPressed
+                            if !config.push_to_talk_hold
+                                && matches!(*mode, AppMode::Recording { .. }) =>
+                        {
+                            HotkeyAction::PushToTalkReleased
+                        }
+                        HotkeyAction::PushToTalkReleased if !config.push_to_talk_hold => return,
+                        other => other,
+                    };
                     match action {
                         HotkeyAction::PushToTalkPressed => match *mode {
                             AppMode::Idle => {
-                                // Start recording (hold to record)
-                                info!("RECORDING... (release to stop)");
+                                // Start recording
+                                if config.push_to_talk_hold {
+                                    info!("RECORDING... (release to stop)");
+                                } else {
+                                    info!("RECORDING... (press again to stop)");
+                                }
                                 if let Err(e) = audio_capture.lock().start_recording() {
                                     error!("Failed to start recording: {}", e);
+                                    if audio_capture.lock().is_device_lost() {
+                                        let _ = proxy.send_event(UserEvent::AudioDeviceLost);
+                                    }
                                     return;
                                 }
-                                *mode = AppMode::Recording;
+                                *mode = AppMode::Recording { target: focus_target(&config) };
+                                sound_cues.play(Cue::Start);
                                 tray_manager.set_status(AppStatus::Recording);
                                 overlay.set_status(AppStatus::Recording);
                             }
@@ -678,9 +1601,13 @@ fn run_app(mut config: Config) -> Result<()> {
                                 // Start push-to-talk recording
                                 if let Err(e) = audio_capture.lock().start_recording() {
                                     error!("Failed to start recording: {}", e);
+                                    if audio_capture.lock().is_device_lost() {
+                                        let _ = proxy.send_event(UserEvent::AudioDeviceLost);
+                                    }
                                     return;
                                 }
-                                *mode = AppMode::Recording;
+                                *mode = AppMode::Recording { target: focus_target(&config) };
+                                sound_cues.play(Cue::Start);
                                 tray_manager.set_status(AppStatus::Recording);
                                 overlay.set_status(AppStatus::Recording);
                             }
@@ -689,10 +1616,37 @@ fn run_app(mut config: Config) -> Result<()> {
                             }
                         },
                         HotkeyAction::PushToTalkReleased => {
-                            if *mode == AppMode::Recording {
+                            if let AppMode::Recording { target } = *mode {
                                 // Stop recording and transcribe
-                                info!("Released. Processing...");
-                                let audio_data = audio_capture.lock().stop_recording();
+                                info!("Stopped. Processing...");
+                                let audio_data = match audio_capture.lock().stop_recording() {
+                                    Ok(audio_data) => audio_data,
+                                    Err(e) => {
+                                        error!("{}", e);
+                                        *mode = AppMode::Idle;
+                                        tray_manager.set_status(AppStatus::Idle);
+                                        overlay.set_status(AppStatus::Idle);
+                                        let _ = proxy.send_event(UserEvent::AudioDeviceLost);
+                                        return;
+                                    }
+                                };
+                                sound_cues.play(Cue::Stop);
+
+                                // An accidental tap isn't worth a trip to the model
+                                if backend_loader::is_audio_too_short(
+                                    audio_data.len(),
+                                    models.min_samples,
+                                ) {
+                                    info!(
+                                        "Recording too short ({} ms < {} ms), discarding",
+                                        audio_data.len() as u64 * 1000 / models.sample_rate as u64,
+                                        config.min_audio_ms
+                                    );
+                                    *mode = AppMode::Idle;
+                                    tray_manager.set_status(AppStatus::Idle);
+                                    overlay.set_status(AppStatus::Idle);
+                                    return;
+                                }
 
                                 *mode = AppMode::Processing;
                                 drop(mode);
@@ -700,9 +1654,12 @@ fn run_app(mut config: Config) -> Result<()> {
                                 // Transcribe in background
                                 transcribe_and_type(
                                     audio_data,
-                                    Arc::clone(&model),
+                                    Arc::clone(models),
+                                    resolve_active_profile(&config, target),
+                                    target,
                                     Arc::clone(&typer),
-                                    Arc::clone(&state),
+                                    None,
+                                    Arc::clone(&muted),
                                     proxy.clone(),
                                     AppStatus::Idle,
                                 );
@@ -713,6 +1670,7 @@ fn run_app(mut config: Config) -> Result<()> {
                             match *mode {
                                 AppMode::Idle => {
                                     info!("Starting always-listen mode...");
+                                    always_listen_paused.store(false, Ordering::SeqCst);
                                     always_listen_active.store(true, Ordering::SeqCst);
                                     always_listen_stream_running.store(true, Ordering::SeqCst);
                                     // Start the audio stream if available
@@ -721,21 +1679,25 @@ fn run_app(mut config: Config) -> Result<()> {
                                             error!("Failed to start always-listen audio stream: {}", e);
                                             always_listen_active.store(false, Ordering::SeqCst);
                                             always_listen_stream_running.store(false, Ordering::SeqCst);
+                                            tray_manager.set_always_listen_checked(false);
                                             return;
                                         }
                                     }
                                     *mode = AppMode::AlwaysListening;
+                                    sound_cues.play(Cue::Start);
                                     tray_manager.set_status(AppStatus::AlwaysListening);
                                     overlay.set_status(AppStatus::AlwaysListening);
                                 }
                                 AppMode::AlwaysListening => {
                                     info!("Stopping always-listen mode...");
+                                    sound_cues.play(Cue::Stop);
                                     always_listen_active.store(false, Ordering::SeqCst);
                                     always_listen_stream_running.store(false, Ordering::SeqCst);
                                     // Pause the audio stream
                                     if let Some(ref stream) = always_listen_stream {
                                         let _ = stream.pause();
                                     }
+                                    paragraph_joiner.lock().reset();
                                     *mode = AppMode::Idle;
                                     tray_manager.set_status(AppStatus::Idle);
                                     overlay.set_status(AppStatus::Idle);
@@ -744,41 +1706,299 @@ fn run_app(mut config: Config) -> Result<()> {
                                     warn!("Cannot toggle always-listen mode while recording or processing");
                                 }
                             }
+                            tray_manager.set_always_listen_checked(*mode == AppMode::AlwaysListening);
                         }
+                        HotkeyAction::MuteToggle => {
+                            let now_muted = !muted.fetch_xor(true, Ordering::SeqCst);
+                            if now_muted {
+                                info!("Muted - listening and typing disabled");
+                                if matches!(*mode, AppMode::Recording { .. }) {
+                                    // Discard the in-progress recording
+                                    let _ = audio_capture.lock().stop_recording();
+                                }
+                                // Stop always-listen (also when it's waiting on a transcription)
+                                always_listen_active.store(false, Ordering::SeqCst);
+                                always_listen_stream_running.store(false, Ordering::SeqCst);
+                                if let Some(ref stream) = always_listen_stream {
+                                    let _ = stream.pause();
+                                }
+                                paragraph_joiner.lock().reset();
+                                // A transcription in flight is dropped when it finishes
+                                if !matches!(*mode, AppMode::Processing | AppMode::LoadingModel) {
+                                    *mode = AppMode::Idle;
+                                }
+                                tray_manager.set_always_listen_checked(false);
+                                tray_manager.set_status(AppStatus::Muted);
+                                overlay.set_status(AppStatus::Muted);
+                            } else {
+                                info!("Unmuted");
+                                let status = match *mode {
+                                    AppMode::Idle => AppStatus::Idle,
+                                    AppMode::LoadingModel => AppStatus::LoadingModel,
+                                    _ => AppStatus::Processing,
+                                };
+                                tray_manager.set_status(status);
+                                overlay.set_status(status);
+                            }
+                        }
+                        HotkeyAction::PauseListenToggle => {
+                            if !always_listen_active.load(Ordering::SeqCst) {
+                                info!("Always-listen is off - nothing to pause");
+                                return;
+                            }
+                            // The stream keeps running; the controller drops its audio
+                            let was_paused = always_listen_paused.fetch_xor(true, Ordering::SeqCst);
+                            let now_paused = !was_paused;
+                            if now_paused {
+                                info!("Always-listen paused");
+                            } else {
+                                info!("Always-listen resumed");
+                            }
+                            // While a result is transcribing, its completion shows the new status
+                            if *mode == AppMode::AlwaysListening {
+                                let status = if now_paused {
+                                    AppStatus::Paused
+                                } else {
+                                    AppStatus::AlwaysListening
+                                };
+                                tray_manager.set_status(status);
+                                overlay.set_status(status);
+                            }
+                        }
+                    }
+                }
+                UserEvent::RecordingLimitReached => {
+                    let mut mode = state.lock();
+                    if let AppMode::Recording { target } = *mode {
+                        warn!(
+                            "Recording auto-stopped after {}s limit. Processing...",
+                            config.max_recording_seconds
+                        );
+                        let audio_data = match audio_capture.lock().stop_recording() {
+                            Ok(audio_data) => audio_data,
+                            Err(e) => {
+                                error!("{}", e);
+                                *mode = AppMode::Idle;
+                                tray_manager.set_status(AppStatus::Idle);
+                                overlay.set_status(AppStatus::Idle);
+                                let _ = proxy.send_event(UserEvent::AudioDeviceLost);
+                                return;
+                            }
+                        };
+                        sound_cues.play(Cue::Stop);
+
+                        *mode = AppMode::Processing;
+                        drop(mode);
+                        tray_manager.set_status(AppStatus::Processing);
+                        overlay.set_status(AppStatus::Processing);
+
+                        transcribe_and_type(
+                            audio_data,
+                            Arc::clone(models),
+                            resolve_active_profile(&config, target),
+                            target,
+                            Arc::clone(&typer),
+                            None,
+                            Arc::clone(&muted),
+                            proxy.clone(),
+                            AppStatus::Idle,
+                        );
                     }
                 }
+                UserEvent::AudioDeviceLost => {
+                    let mut mode = state.lock();
+                    let listening = *mode == AppMode::AlwaysListening
+                        || (*mode == AppMode::Processing
+                            && always_listen_active.load(Ordering::SeqCst));
+                    if listening {
+                        // Pause always-listen until the mic is back
+                        resume_always_listen = true;
+                        always_listen_active.store(false, Ordering::SeqCst);
+                        always_listen_stream_running.store(false, Ordering::SeqCst);
+                        if let Some(ref stream) = always_listen_stream {
+                            let _ = stream.pause();
+                        }
+                        paragraph_joiner.lock().reset();
+                        tray_manager.set_always_listen_checked(false);
+                        if *mode == AppMode::AlwaysListening {
+                            *mode = AppMode::Idle;
+                            tray_manager.set_status(AppStatus::Idle);
+                            overlay.set_status(AppStatus::Idle);
+                        }
+                    }
+                    drop(mode);
+                    if reconnecting_audio {
+                        return;
+                    }
+                    reconnecting_audio = true;
+                    warn!("Microphone lost, trying to reconnect...");
+                    // Don't block the event loop on the message box
+                    std::thread::spawn(|| {
+                        show_error_dialog(
+                            "Microphone Disconnected",
+                            "The microphone stopped responding. The app will keep trying to \
+                             reconnect; plug it back in or choose another device in Settings.",
+                        );
+                    });
+                    let _ = proxy.send_event(UserEvent::RetryAudioReconnect);
+                }
+                UserEvent::RetryAudioReconnect => {
+                    if let Err(e) = audio_capture.lock().reconnect() {
+                        debug!("Microphone reconnect failed: {}", e);
+                        let retry_proxy = proxy.clone();
+                        std::thread::spawn(move || {
+                            std::thread::sleep(AUDIO_RECONNECT_INTERVAL);
+                            let _ = retry_proxy.send_event(UserEvent::RetryAudioReconnect);
+                        });
+                        return;
+                    }
+                    reconnecting_audio = false;
+                    // The old stream belonged to the lost device
+                    always_listen_stream_for_loop = match audio_capture
+                        .lock()
+                        .create_always_listen_stream(
+                            reconnect_audio_tx.clone(),
+                            Arc::clone(always_listen_stream_running),
+                        ) {
+                        Ok(stream) => Some(stream),
+                        Err(e) => {
+                            error!("Failed to recreate always-listen audio stream: {}", e);
+                            None
+                        }
+                    };
+                    let idle = *state.lock() == AppMode::Idle;
+                    if std::mem::take(&mut resume_always_listen)
+                        && idle
+                        && !muted.load(Ordering::SeqCst)
+                    {
+                        info!("Resuming always-listen mode");
+                        let toggle = UserEvent::Hotkey(HotkeyAction::AlwaysListenToggle);
+                        let _ = proxy.send_event(toggle);
+                    }
+                }
+                UserEvent::ModelSwitched { model_id, error } => {
+                    match error {
+                        None => {
+                            info!("Model switched to '{}'", model_id);
+                            let manifest =
+                                models.backend.models().iter().find(|m| m.id == model_id);
+                            if let (Some(m), Ok(dir)) = (manifest, config::get_models_dir()) {
+                                config.model_path = dir.join(&m.folder_name);
+                            }
+                            config.model_name = model_id;
+                            if let Err(e) = config.save() {
+                                error!("Failed to save config: {}", e);
+                            }
+                        }
+                        Some(e) => {
+                            error!("Failed to switch model to '{}': {}", model_id, e);
+                            let message = format!(
+                                "Failed to load model '{}':\n{}\n\nStill using '{}'.",
+                                model_id, e, config.model_name
+                            );
+                            std::thread::spawn(move || show_error_dialog("Model Error", &message));
+                        }
+                    }
+                    tray_manager.set_active_model(&config.model_name);
+                    let mut mode = state.lock();
+                    if *mode == AppMode::LoadingModel {
+                        *mode = AppMode::Idle;
+                    }
+                    let status = if muted.load(Ordering::SeqCst) {
+                        AppStatus::Muted
+                    } else {
+                        AppStatus::Idle
+                    };
+                    tray_manager.set_status(status);
+                    overlay.set_status(status);
+                }
                 UserEvent::AlwaysListenAudio(audio_data) => {
+                    // Speech that finished right as mute was pressed
+                    if muted.load(Ordering::SeqCst) {
+                        return;
+                    }
                     // Handle always-listen audio for transcription
                     *state.lock() = AppMode::Processing;
                     tray_manager.set_status(AppStatus::Processing);
                     overlay.set_status(AppStatus::Processing);
 
+                    let audio_data = if preprocess_options.is_enabled() {
+                        audio::preprocess(&audio_data, &preprocess_options)
+                    } else {
+                        audio_data
+                    };
+
                     // Transcribe the audio
                     transcribe_and_type(
                         audio_data,
-                        Arc::clone(&model),
+                        Arc::clone(models),
+                        resolve_active_profile(&config, None),
+                        None,
                         Arc::clone(&typer),
-                        Arc::clone(&state),
+                        Some(Arc::clone(&paragraph_joiner)),
+                        Arc::clone(&muted),
                         proxy.clone(),
                         AppStatus::AlwaysListening,
                     );
                 }
-                UserEvent::AlwaysListenStateChange(is_recording) => {
-                    // Update UI when always-listen starts/stops recording speech
+                UserEvent::AlwaysListenState(al_state) => {
+                    // Follow the controller while nothing else owns the status: a
+                    // transcription in flight or always-listen being off takes precedence
                     let mode = *state.lock();
                     if mode == AppMode::AlwaysListening {
-                        if is_recording {
-                            tray_manager.set_status(AppStatus::AlwaysListeningRecording);
-                            overlay.set_status(AppStatus::AlwaysListeningRecording);
+                        let status = if always_listen_paused.load(Ordering::SeqCst) {
+                            AppStatus::Paused
                         } else {
-                            tray_manager.set_status(AppStatus::AlwaysListening);
-                            overlay.set_status(AppStatus::AlwaysListening);
-                        }
+                            always_listen_status(al_state)
+                        };
+                        tray_manager.set_status(status);
+                        overlay.set_status(status);
                     }
                 }
                 UserEvent::Menu(menu_id) => {
-                    if menu_id == show_overlay_id {
+                    if menu_id == always_listen_menu_id {
+                        // Same path as the hotkey
+                        let _ = proxy.send_event(UserEvent::Hotkey(HotkeyAction::AlwaysListenToggle));
+                    } else if menu_id == show_overlay_id {
                         overlay.toggle_visibility();
+                    } else if menu_id == overlay_on_top_id || menu_id == overlay_click_through_id {
+                        if menu_id == overlay_on_top_id {
+                            config.overlay_always_on_top = !config.overlay_always_on_top;
+                            overlay.set_always_on_top(config.overlay_always_on_top);
+                        } else {
+                            config.overlay_click_through = !config.overlay_click_through;
+                            overlay.set_click_through(config.overlay_click_through);
+                        }
+                        tray_manager.set_overlay_checked(
+                            config.overlay_always_on_top,
+                            config.overlay_click_through,
+                        );
+                        if let Err(e) = config.save() {
+                            error!("Failed to save config: {}", e);
+                        }
+                    } else if menu_id == audio_cues_id {
+                        config.audio_cues = !config.audio_cues;
+                        tray_manager.set_audio_cues_checked(config.audio_cues);
+                        sound_cues.set_enabled(config.audio_cues);
+                        // Let the user hear what they turned on
+                        sound_cues.play(Cue::Start);
+                        if let Err(e) = config.save() {
+                            error!("Failed to save config: {}", e);
+                        }
+                    } else if menu_id == clipboard_only_id {
+                        // Turning it off goes back to the typing mode used before
+                        config.output_mode = if config.output_mode == OutputMode::ClipboardOnly {
+                            typing_output_mode
+                        } else {
+                            typing_output_mode = config.output_mode;
+                            OutputMode::ClipboardOnly
+                        };
+                        let clipboard_only = config.output_mode == OutputMode::ClipboardOnly;
+                        info!("Copy to clipboard instead of typing: {}", clipboard_only);
+                        tray_manager.set_clipboard_only_checked(clipboard_only);
+                        if let Err(e) = config.save() {
+                            error!("Failed to save config: {}", e);
+                        }
                     } else if menu_id == settings_id {
                         // Save current state before opening settings
                         info!("Opening settings...");
@@ -794,6 +2014,51 @@ fn run_app(mut config: Config) -> Result<()> {
                                 .arg("--setup-only")
                                 .spawn();
                         }
+                    } else if menu_id == history_id {
+                        let entries = models.history.lock().recent();
+                        match history_window.as_mut() {
+                            Some(window) => window.set_entries(entries),
+                            None => match HistoryWindow::new(event_loop, entries) {
+                                Ok(window) => history_window = Some(window),
+                                Err(e) => error!("{}", e),
+                            },
+                        }
+                    } else if menu_id == save_transcript_id {
+                        let latest = models.history.lock().latest().cloned();
+                        let Some(entry) = latest else {
+                            return;
+                        };
+                        let saved = config
+                            .transcript_export_dir()
+                            .and_then(|dir| transcript_export::save(&dir, &entry));
+                        match saved {
+                            Ok(path) => {
+                                info!("Saved the last transcript to {}", path.display());
+                                if let Some(dir) = path.parent() {
+                                    let _ = open::that(dir);
+                                }
+                            }
+                            Err(e) => show_error_dialog(
+                                "Save Last Transcript",
+                                &format!("Failed to save the transcript:\n{:#}", e),
+                            ),
+                        }
+                    } else if menu_id == last_timing_id {
+                        let device = models.last_device.lock().clone();
+                        let device = device
+                            .unwrap_or_else(|| if models.use_gpu { "CUDA" } else { "CPU" }.into());
+                        let message = match *models.last_timing.lock() {
+                            Some(timing) => format!(
+                                "Audio: {:.2}s ({} samples)\nInference: {} ms\nReal-time factor: {:.3}\nDevice: {}",
+                                timing.audio_seconds,
+                                timing.samples,
+                                timing.inference_ms,
+                                timing.rtf(),
+                                device
+                            ),
+                            None => "No transcription yet.".to_string(),
+                        };
+                        show_info_dialog("Last Transcription Timing", &message);
                     } else if menu_id == exit_id {
                         info!("Exiting...");
                         // Stop always-listen
@@ -811,16 +2076,145 @@ fn run_app(mut config: Config) -> Result<()> {
                         }
                         running.store(false, Ordering::SeqCst);
                         *control_flow = ControlFlow::Exit;
+                    } else if menu_id == save_profile_id {
+                        // Profiles always live next to the exe; the dialog just picks the name
+                        let mut dialog = rfd::FileDialog::new()
+                            .set_title("Save Profile As")
+                            .add_filter("Config profile", &["json"])
+                            .set_file_name(format!("config-{}-profile.json", get_exe_stem().unwrap_or_default()));
+                        if let Ok(dir) = config::get_exe_dir() {
+                            dialog = dialog.set_directory(dir);
+                        }
+                        let Some(path) = dialog.save_file() else {
+                            return;
+                        };
+                        let (x, y) = overlay.get_position();
+                        config.overlay_x = Some(x);
+                        config.overlay_y = Some(y);
+                        let saved = config::profile_name_from_path(&path).and_then(|name| {
+                            config.save_as_profile(&name)?;
+                            tray_manager.add_profile(&name)?;
+                            Ok(name)
+                        });
+                        match saved {
+                            Ok(name) => info!("Saved config profile '{}'", name),
+                            Err(e) => show_error_dialog(
+                                "Save Profile",
+                                &format!("Failed to save profile:\n{}", e),
+                            ),
+                        }
+                    } else if let Some(model_id) = tray_manager.model_for(&menu_id).map(str::to_string) {
+                        let mut mode = state.lock();
+                        if *mode != AppMode::Idle {
+                            warn!("Stop recording or listening before switching models");
+                            tray_manager.set_active_model(&config.model_name);
+                            return;
+                        }
+                        if model_id == config.model_name {
+                            tray_manager.set_active_model(&config.model_name);
+                            return;
+                        }
+                        info!("Switching model to '{}'...", model_id);
+                        *mode = AppMode::LoadingModel;
+                        tray_manager.set_status(AppStatus::LoadingModel);
+                        overlay.set_status(AppStatus::LoadingModel);
+                        let models = Arc::clone(models);
+                        let switch_proxy = proxy.clone();
+                        std::thread::spawn(move || {
+                            let error = models
+                                .switch_default_model(&model_id)
+                                .err()
+                                .map(|e| format!("{:#}", e));
+                            let event = UserEvent::ModelSwitched { model_id, error };
+                            let _ = switch_proxy.send_event(event);
+                        });
+                    } else if let Some(language) =
+                        tray_manager.language_for(&menu_id).map(|l| l.map(str::to_string))
+                    {
+                        // Used from the next transcription on; saved on exit
+                        info!("Language: {}", language.as_deref().unwrap_or("auto-detect"));
+                        tray_manager.set_active_language(language.as_deref());
+                        *models.language.lock() = language.clone();
+                        config.language = language;
+                    } else if let Some(name) = tray_manager.profile_for(&menu_id).map(str::to_string) {
+                        // Switching copies the profile over the active config, then relaunches
+                        let switched = Config::load_profile(&name).and_then(|mut profile| {
+                            let (x, y) = overlay.get_position();
+                            profile.overlay_x = Some(x);
+                            profile.overlay_y = Some(y);
+                            profile.save()?;
+                            Ok(profile)
+                        });
+                        match switched {
+                            Ok(profile) => {
+                                info!("Switching to config profile '{}', restarting...", name);
+                                always_listen_active.store(false, Ordering::SeqCst);
+                                always_listen_stream_running.store(false, Ordering::SeqCst);
+                                if let Some(ref stream) = always_listen_stream {
+                                    let _ = stream.pause();
+                                }
+                                // Exit paths save `config`; keep them from overwriting the profile
+                                config = profile;
+                                if let Ok(exe) = std::env::current_exe() {
+                                    let mut command = std::process::Command::new(exe);
+                                    command.arg("--delay-start");
+                                    if let Some(port) = serve_port {
+                                        command.arg("--serve").arg(port.to_string());
+                                    }
+                                    let _ = command.spawn();
+                                }
+                                running.store(false, Ordering::SeqCst);
+                                *control_flow = ControlFlow::Exit;
+                            }
+                            Err(e) => show_error_dialog(
+                                "Switch Profile",
+                                &format!("Failed to load profile '{}':\n{}", name, e),
+                            ),
+                        }
+                    }
+                }
+                UserEvent::ShowTranscript(text) => {
+                    if config.overlay_show_text {
+                        overlay.show_text(&text);
                     }
                 }
+                UserEvent::CopiedToClipboard => {
+                    // Briefly flash "Copied" on the overlay to confirm the copy
+                    overlay.set_flash(true);
+                    let flash_proxy = proxy.clone();
+                    std::thread::spawn(move || {
+                        std::thread::sleep(Duration::from_millis(800));
+                        let _ = flash_proxy.send_event(UserEvent::FlashDone);
+                    });
+                }
+                UserEvent::FlashDone => {
+                    overlay.set_flash(false);
+                }
+                UserEvent::LevelMeterTick => {
+                    overlay.request_redraw();
+                }
                 UserEvent::TranscriptionComplete(target_status) => {
+                    tray_manager.set_device(models.last_device.lock().clone());
+                    let has_transcript = models.history.lock().latest().is_some();
+                    tray_manager.set_save_transcript_enabled(has_transcript);
+                    tray_manager.set_average_rtf(models.rtf_average.lock().average());
                     let mode = *state.lock();
                     if mode == AppMode::Processing {
                         // Return to previous state
-                        if target_status == AppStatus::AlwaysListening {
+                        if muted.load(Ordering::SeqCst) {
+                            // Mute already stopped listening; stay idle
+                            *state.lock() = AppMode::Idle;
+                        } else if target_status == AppStatus::AlwaysListening
+                            && always_listen_active.load(Ordering::SeqCst)
+                        {
                             *state.lock() = AppMode::AlwaysListening;
-                            tray_manager.set_status(AppStatus::AlwaysListening);
-                            overlay.set_status(AppStatus::AlwaysListening);
+                            let status = if always_listen_paused.load(Ordering::SeqCst) {
+                                AppStatus::Paused
+                            } else {
+                                AppStatus::AlwaysListening
+                            };
+                            tray_manager.set_status(status);
+                            overlay.set_status(status);
                         } else {
                             *state.lock() = AppMode::Idle;
                             tray_manager.set_status(AppStatus::Idle);
@@ -830,6 +2224,19 @@ fn run_app(mut config: Config) -> Result<()> {
                     info!("Ready for next recording");
                 }
             },
+            Event::WindowEvent { window_id, event, .. }
+                if history_window.as_ref().is_some_and(|w| w.window_id() == window_id) =>
+            {
+                let Some(window) = history_window.as_mut() else { return };
+                match window.handle_event(&event) {
+                    HistoryAction::Copy(text) => match typer.lock().set_clipboard(&text) {
+                        Ok(()) => window.set_status("Copied to clipboard"),
+                        Err(e) => window.set_status(&format!("Copy failed: {}", e)),
+                    },
+                    HistoryAction::Close => history_window = None,
+                    HistoryAction::None => {}
+                }
+            }
             Event::WindowEvent {
                 event: WindowEvent::CloseRequested,
                 window_id,
@@ -912,9 +2319,29 @@ fn run_app(mut config: Config) -> Result<()> {
                     }
                 }
             }
+            Event::NewEvents(StartCause::ResumeTimeReached { .. }) => {
+                if overlay.is_animating() {
+                    overlay.request_redraw();
+                }
+            }
+            Event::RedrawEventsCleared => {
+                // Wake up ~10 times a second while the overlay animates; otherwise
+                // sleep until the next event
+                if overlay.is_animating() && *control_flow == ControlFlow::Wait {
+                    let now = Instant::now();
+                    if next_animation_frame <= now {
+                        next_animation_frame = now + overlay::ANIMATION_FRAME;
+                    }
+                    *control_flow = ControlFlow::WaitUntil(next_animation_frame);
+                }
+            }
             Event::RedrawRequested(window_id) => {
                 if window_id == overlay.window_id() {
                     overlay.handle_redraw();
+                } else if let Some(window) = history_window.as_mut() {
+                    if window.window_id() == window_id {
+                        window.redraw();
+                    }
                 }
             }
             _ => {}
@@ -928,5 +2355,30 @@ enum UserEvent {
     Menu(tray_icon::menu::MenuId),
     TranscriptionComplete(AppStatus),
     AlwaysListenAudio(Vec<f32>),
-    AlwaysListenStateChange(bool), // true = recording, false = listening
+    /// The always-listen controller changed state (speech detected, handed off, ...)
+    AlwaysListenState(AlwaysListenState),
+    /// A result to show on the overlay (if `overlay_show_text` is on)
+    ShowTranscript(String),
+    CopiedToClipboard,
+    FlashDone,
+    LevelMeterTick,
+    RecordingLimitReached,
+    /// The microphone stream failed (e.g. the device was unplugged)
+    AudioDeviceLost,
+    /// Try to reopen the microphone
+    RetryAudioReconnect,
+    /// The tray's Model menu finished loading `model_id` (`error` = kept the old model)
+    ModelSwitched { model_id: String, error: Option<String> },
+}
+
+/// Tray and overlay status for an always-listen controller state
+fn always_listen_status(state: AlwaysListenState) -> AppStatus {
+    match state {
+        AlwaysListenState::Listening => AppStatus::AlwaysListening,
+        AlwaysListenState::Detecting { .. } | AlwaysListenState::Recording { .. } => {
+            AppStatus::AlwaysListeningRecording
+        }
+        AlwaysListenState::Processing => AppStatus::Processing,
+        AlwaysListenState::Paused => AppStatus::Paused,
+    }
 }
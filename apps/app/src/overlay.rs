@@ -1,9 +1,13 @@
+use crate::font;
 use crate::tray::AppStatus;
 use anyhow::Result;
 use image::GenericImageView;
 use softbuffer::Surface;
 use std::num::NonZeroU32;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tao::{
     dpi::{LogicalSize, PhysicalPosition},
     event_loop::EventLoopWindowTarget,
@@ -11,10 +15,38 @@ use tao::{
 };
 #[cfg(target_os = "windows")]
 use tao::platform::windows::WindowExtWindows;
+use tracing::warn;
 
 // Default overlay dimensions
 const OVERLAY_WIDTH: u32 = 120;
 const OVERLAY_HEIGHT: u32 = 50;
+// Fill color while flashing to confirm a clipboard copy
+const FLASH_COLOR: u32 = 0xFF3366DD;
+/// Shown in the middle of the overlay while it flashes
+const FLASH_LABEL: &str = "Copied";
+// Level meter along the bottom edge
+const METER_TRACK_COLOR: u32 = 0xFF303030;
+const METER_FILL_COLOR: u32 = 0xFFF0F0F0;
+/// Input levels at or below this read as an empty meter
+const METER_FLOOR_DB: f32 = -60.0;
+/// Time between animation frames while recording or processing (10 fps)
+pub const ANIMATION_FRAME: Duration = Duration::from_millis(100);
+/// Dots cycled while processing, and the recording pulse
+const ANIMATION_DOT_COLOR: u32 = 0xFFFFFFFF;
+const ANIMATION_DOT_DIM_COLOR: u32 = 0xFF8A6A00;
+const ANIMATION_DOT_SIZE: usize = 6;
+/// The recording dot blinks on for this many frames, then off for as many
+const RECORDING_BLINK_FRAMES: u32 = 5;
+/// Width of the overlay while it shows a transcript under the status
+const TEXT_PANEL_WIDTH: u32 = 240;
+const TEXT_MAX_LINES: usize = 3;
+const TEXT_LINE_HEIGHT: u32 = font::CHAR_HEIGHT + 5;
+const TEXT_PADDING: u32 = 6;
+const TEXT_BG_COLOR: u32 = 0xFF202020;
+const TEXT_COLOR: u32 = 0xFFF0F0F0;
+/// How long a transcript stays on the overlay before fading, and how long the fade takes
+const TEXT_SHOW: Duration = Duration::from_secs(4);
+const TEXT_FADE: Duration = Duration::from_secs(1);
 const WINDOW_ICON_PNG: &[u8] = include_bytes!("../assets/mic_gray.png");
 
 fn load_window_icon() -> Option<Icon> {
@@ -25,26 +57,84 @@ fn load_window_icon() -> Option<Icon> {
     Icon::from_rgba(rgba, width, height).ok()
 }
 
+/// A transcript shown under the status, wrapped to the panel
+struct ShownText {
+    lines: Vec<String>,
+    since: Instant,
+}
+
 pub struct Overlay {
     window: Rc<Window>,
     surface: Surface<Rc<Window>, Rc<Window>>,
     visible: bool,
     status: AppStatus,
+    flash: bool,
+    /// Live input RMS (f32 bits) from the audio capture
+    level: Option<Arc<AtomicU32>>,
+    /// Set while the level meter is shown, so a timer can drive redraws
+    meter_active: Arc<AtomicBool>,
+    /// When the current status started; the animation phase counts frames from here
+    animation_start: Instant,
+    /// Animation frame drawn last
+    phase: u32,
+    text: Option<ShownText>,
     width: u32,
     height: u32,
 }
 
+/// Whether the mic is live (and the level meter drawn) in this status
+fn shows_level(status: AppStatus) -> bool {
+    matches!(
+        status,
+        AppStatus::Recording | AppStatus::AlwaysListening | AppStatus::AlwaysListeningRecording
+    )
+}
+
+/// Whether the overlay animates (and needs a redraw timer) in this status
+fn animates(status: AppStatus) -> bool {
+    matches!(
+        status,
+        AppStatus::Recording | AppStatus::AlwaysListeningRecording | AppStatus::Processing
+    )
+}
+
+/// Meter fill (0..=1) for an RMS level, on a dB scale so quiet speech still moves it
+/// `from` blended toward `to`, `amount` from 0 (all `from`) to 1 (all `to`)
+fn blend(from: u32, to: u32, amount: f32) -> u32 {
+    let channel = |shift: u32| {
+        let (a, b) = ((from >> shift) & 0xFF, (to >> shift) & 0xFF);
+        let mixed = a as f32 + (b as f32 - a as f32) * amount.clamp(0.0, 1.0);
+        (mixed.round() as u32) << shift
+    };
+    0xFF000000 | channel(16) | channel(8) | channel(0)
+}
+
+/// How far a transcript shown `elapsed` ago has faded (0 = fully visible)
+fn text_fade(elapsed: Duration) -> f32 {
+    elapsed.saturating_sub(TEXT_SHOW).as_secs_f32() / TEXT_FADE.as_secs_f32()
+}
+
+pub fn level_fraction(rms: f32) -> f32 {
+    if rms <= 0.0 || !rms.is_finite() {
+        return 0.0;
+    }
+    let db = 20.0 * rms.log10();
+    ((db - METER_FLOOR_DB) / -METER_FLOOR_DB).clamp(0.0, 1.0)
+}
+
 impl Overlay {
     pub fn new<T>(
         event_loop: &EventLoopWindowTarget<T>,
         saved_x: Option<i32>,
         saved_y: Option<i32>,
+        always_on_top: bool,
+        click_through: bool,
     ) -> Result<Self> {
         let window = WindowBuilder::new()
             .with_title("Idle")
             .with_inner_size(LogicalSize::new(OVERLAY_WIDTH as f64, OVERLAY_HEIGHT as f64))
             .with_decorations(false)
-            .with_always_on_top(true)
+            .with_always_on_top(always_on_top)
             .with_window_icon(load_window_icon())
             .with_resizable(false)
             .build(event_loop)
@@ -80,14 +170,46 @@ impl Overlay {
             surface,
             visible: true,
             status: AppStatus::Idle,
+            flash: false,
+            level: None,
+            meter_active: Arc::new(AtomicBool::new(false)),
+            animation_start: Instant::now(),
+            phase: 0,
+            text: None,
             width: size.width,
             height: size.height,
         };
 
+        if click_through {
+            overlay.set_click_through(true);
+        }
         overlay.render();
         Ok(overlay)
     }
 
+    pub fn set_always_on_top(&self, always_on_top: bool) {
+        self.window.set_always_on_top(always_on_top);
+    }
+
+    /// Let mouse clicks pass through to the window below (no dragging or context menu)
+    pub fn set_click_through(&self, click_through: bool) {
+        // On Windows this toggles WS_EX_TRANSPARENT | WS_EX_LAYERED
+        if let Err(e) = self.window.set_ignore_cursor_events(click_through) {
+            warn!("Failed to set overlay click-through: {}", e);
+            return;
+        }
+        // A layered window isn't drawn until it has layer attributes
+        #[cfg(target_os = "windows")]
+        if click_through {
+            use windows::Win32::Foundation::{COLORREF, HWND};
+            use windows::Win32::UI::WindowsAndMessaging::{SetLayeredWindowAttributes, LWA_ALPHA};
+            let hwnd = HWND(self.hwnd() as *mut std::ffi::c_void);
+            unsafe {
+                let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), 255, LWA_ALPHA);
+            }
+        }
+    }
+
     /// Start dragging the window (call on mouse down)
     pub fn start_drag(&self) {
         let _ = self.window.drag_window();
@@ -113,8 +235,57 @@ impl Overlay {
         self.visible
     }
 
+    /// Show a level meter fed by `level` while recording or listening
+    pub fn set_level_meter(&mut self, level: Arc<AtomicU32>) {
+        self.level = Some(level);
+        self.meter_active.store(shows_level(self.status), Ordering::Relaxed);
+    }
+
+    /// True while the level meter needs periodic redraws
+    pub fn meter_active(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.meter_active)
+    }
+
+    pub fn request_redraw(&self) {
+        self.window.request_redraw();
+    }
+
+    /// True while the status has an animation (or a transcript is fading) and
+    /// needs periodic redraws
+    pub fn is_animating(&self) -> bool {
+        self.visible && (animates(self.status) || self.text.is_some())
+    }
+
+    /// Show a transcript under the status for a few seconds, widening the overlay
+    pub fn show_text(&mut self, text: &str) {
+        let max_chars = ((TEXT_PANEL_WIDTH - 2 * TEXT_PADDING) / font::CHAR_WIDTH) as usize;
+        let lines = font::wrap_text(text, max_chars, TEXT_MAX_LINES);
+        if lines.is_empty() {
+            return;
+        }
+        let height = OVERLAY_HEIGHT + 2 * TEXT_PADDING + lines.len() as u32 * TEXT_LINE_HEIGHT;
+        self.window
+            .set_inner_size(LogicalSize::new(TEXT_PANEL_WIDTH as f64, height as f64));
+        self.text = Some(ShownText { lines, since: Instant::now() });
+        self.render();
+    }
+
+    /// Drop the transcript and shrink back to the status alone
+    fn hide_text(&mut self) {
+        if self.text.take().is_some() {
+            self.window
+                .set_inner_size(LogicalSize::new(OVERLAY_WIDTH as f64, OVERLAY_HEIGHT as f64));
+        }
+    }
+
     pub fn set_status(&mut self, status: AppStatus) {
+        if status != self.status {
+            self.animation_start = Instant::now();
+            self.phase = 0;
+        }
         self.status = status;
+        self.meter_active
+            .store(self.level.is_some() && shows_level(status), Ordering::Relaxed);
 
         // Update window title with status text
         let title = match status {
@@ -123,12 +294,21 @@ impl Overlay {
             AppStatus::Processing => "Processing...",
             AppStatus::AlwaysListening => "Always On",
             AppStatus::AlwaysListeningRecording => "🎤 SPEAKING",
+            AppStatus::Muted => "Muted",
+            AppStatus::Paused => "Paused",
+            AppStatus::LoadingModel => "Loading model...",
         };
         self.window.set_title(title);
 
         self.render();
     }
 
+    /// Temporarily fill the overlay with the flash color (e.g. after a clipboard copy)
+    pub fn set_flash(&mut self, flash: bool) {
+        self.flash = flash;
+        self.render();
+    }
+
     pub fn window_id(&self) -> tao::window::WindowId {
         self.window.id()
     }
@@ -140,6 +320,13 @@ impl Overlay {
     }
 
     pub fn handle_redraw(&mut self) {
+        // Count frames from the clock, so extra redraws (meter ticks, resizes)
+        // don't speed the animation up
+        let elapsed = self.animation_start.elapsed().as_millis() / ANIMATION_FRAME.as_millis();
+        self.phase = elapsed as u32;
+        if self.text.as_ref().is_some_and(|t| t.since.elapsed() >= TEXT_SHOW + TEXT_FADE) {
+            self.hide_text();
+        }
         self.render();
     }
 
@@ -160,12 +347,19 @@ impl Overlay {
         }
 
         // Get the color based on status
-        let color = match self.status {
-            AppStatus::Idle => 0xFF505050,        // Dark gray
-            AppStatus::Recording => 0xFFDD3333,   // Red
-            AppStatus::Processing => 0xFFDDAA00,  // Yellow/Orange
-            AppStatus::AlwaysListening => 0xFF33AA33, // Green
-            AppStatus::AlwaysListeningRecording => 0xFFDD3333, // Red (same as Recording)
+        let color = if self.flash {
+            FLASH_COLOR
+        } else {
+            match self.status {
+                AppStatus::Idle => 0xFF505050,        // Dark gray
+                AppStatus::Recording => 0xFFDD3333,   // Red
+                AppStatus::Processing => 0xFFDDAA00,  // Yellow/Orange
+                AppStatus::AlwaysListening => 0xFF33AA33, // Green
+                AppStatus::AlwaysListeningRecording => 0xFFDD3333, // Red (same as Recording)
+                AppStatus::Muted => 0xFF5A3A6E,       // Purple
+                AppStatus::Paused => 0xFF3A5A8A,      // Blue
+                AppStatus::LoadingModel => 0xFFDDAA00, // Yellow/Orange (busy, like Processing)
+            }
         };
 
         // Fill the buffer
@@ -181,10 +375,15 @@ impl Overlay {
                 AppStatus::Processing => 0xFFFFCC00,
                 AppStatus::AlwaysListening => 0xFF55DD55,
                 AppStatus::AlwaysListeningRecording => 0xFFFF5555, // Red border
+                AppStatus::Muted => 0xFF8A5AAE,
+                AppStatus::Paused => 0xFF5A8AD0,
+                AppStatus::LoadingModel => 0xFFFFCC00,
             };
 
             let w = self.width as usize;
-            let h = self.height as usize;
+            // The status fills the top of the window; a transcript, if any, sits below
+            let status_height = OVERLAY_HEIGHT as f64 * self.window.scale_factor();
+            let h = (status_height.round() as usize).min(self.height as usize);
 
             // Top and bottom borders
             for x in 0..w {
@@ -206,11 +405,91 @@ impl Overlay {
                 }
             }
 
+            // Level meter: a track along the bottom, filled by the input level
+            // (an empty track means the mic hears nothing)
+            if let Some(level) = self.level.as_ref().filter(|_| shows_level(self.status)) {
+                let rms = f32::from_bits(level.load(Ordering::Relaxed));
+                if w > 8 && h > 8 {
+                    let track = w - 8;
+                    let filled = (level_fraction(rms) * track as f32) as usize;
+                    for y in h - 8..h - 4 {
+                        for x in 0..track {
+                            let color = if x < filled { METER_FILL_COLOR } else { METER_TRACK_COLOR };
+                            if let Some(pixel) = buffer.get_mut(y * w + 4 + x) {
+                                *pixel = color;
+                            }
+                        }
+                    }
+                }
+            }
+
+            if !self.flash && w > ANIMATION_DOT_SIZE * 8 && h > ANIMATION_DOT_SIZE * 2 {
+                let mut dot = |x: usize, y: usize, color: u32| {
+                    for row in y..y + ANIMATION_DOT_SIZE {
+                        let start = row * w + x;
+                        if let Some(line) = buffer.get_mut(start..start + ANIMATION_DOT_SIZE) {
+                            line.fill(color);
+                        }
+                    }
+                };
+                match self.status {
+                    // "Thinking": three dots in the middle, lit one after another
+                    AppStatus::Processing => {
+                        let gap = ANIMATION_DOT_SIZE * 2;
+                        let left = (w - (3 * ANIMATION_DOT_SIZE + 2 * gap)) / 2;
+                        let y = (h - ANIMATION_DOT_SIZE) / 2;
+                        for i in 0..3 {
+                            let color = if i == self.phase as usize % 3 {
+                                ANIMATION_DOT_COLOR
+                            } else {
+                                ANIMATION_DOT_DIM_COLOR
+                            };
+                            dot(left + i * (ANIMATION_DOT_SIZE + gap), y, color);
+                        }
+                    }
+                    // Blinking dot in the top-right corner while the mic records
+                    AppStatus::Recording | AppStatus::AlwaysListeningRecording
+                        if recording_dot_visible(self.phase) =>
+                    {
+                        dot(w - 4 - ANIMATION_DOT_SIZE, 4, ANIMATION_DOT_COLOR);
+                    }
+                    _ => {}
+                }
+            }
+
+            if self.flash {
+                let (x, y) = centered_label(FLASH_LABEL, w as u32, h as u32);
+                font::draw_text(&mut buffer, self.width, x, y, FLASH_LABEL, TEXT_COLOR);
+            }
+
+            if let Some(text) = &self.text {
+                let fade = text_fade(text.since.elapsed());
+                let color = blend(TEXT_COLOR, TEXT_BG_COLOR, fade);
+                if let Some(panel) = buffer.get_mut(h * w..) {
+                    panel.fill(TEXT_BG_COLOR);
+                }
+                for (i, line) in text.lines.iter().enumerate() {
+                    let y = h as u32 + TEXT_PADDING + i as u32 * TEXT_LINE_HEIGHT;
+                    font::draw_text(&mut buffer, self.width, TEXT_PADDING, y, line, color);
+                }
+            }
+
             let _ = buffer.present();
         }
     }
 }
 
+/// Top-left corner that centers `label` in a `width` x `height` area
+fn centered_label(label: &str, width: u32, height: u32) -> (u32, u32) {
+    let text_width = label.chars().count() as u32 * font::CHAR_WIDTH;
+    (width.saturating_sub(text_width) / 2, height.saturating_sub(font::CHAR_HEIGHT) / 2)
+}
+
+/// Whether the blinking recording dot is lit in animation frame `phase`
+fn recording_dot_visible(phase: u32) -> bool {
+    (phase / RECORDING_BLINK_FRAMES) % 2 == 0
+}
+
 // ============================================
 // Overlay Tests
 // ============================================
@@ -228,6 +507,8 @@ mod tests {
             AppStatus::Processing,
             AppStatus::AlwaysListening,
             AppStatus::AlwaysListeningRecording,
+            AppStatus::Muted,
+            AppStatus::Paused,
         ];
 
         // Each status should have a distinct color (AlwaysListeningRecording shares with Recording)
@@ -238,6 +519,9 @@ mod tests {
                 AppStatus::Processing => 0xFFDDAA00,
                 AppStatus::AlwaysListening => 0xFF33AA33,
                 AppStatus::AlwaysListeningRecording => 0xFFDD3333,
+                AppStatus::Muted => 0xFF5A3A6E,
+                AppStatus::Paused => 0xFF3A5A8A,
+                AppStatus::LoadingModel => 0xFFDDAA00,
             }
         }).collect();
         
@@ -249,6 +533,63 @@ mod tests {
         assert_ne!(colors[1], colors[2], "Recording and Processing should differ");
         assert_ne!(colors[1], colors[3], "Recording and AlwaysListening should differ");
         assert_ne!(colors[2], colors[3], "Processing and AlwaysListening should differ");
+        for (i, color) in colors[..5].iter().enumerate() {
+            assert_ne!(*color, colors[5], "Muted should differ from status {}", i);
+        }
+        for (i, color) in colors[..6].iter().enumerate() {
+            assert_ne!(*color, colors[6], "Paused should differ from status {}", i);
+        }
+    }
+
+    #[test]
+    fn test_text_fade() {
+        assert_eq!(blend(0xFFF0F0F0, 0xFF202020, 0.0), 0xFFF0F0F0);
+        assert_eq!(blend(0xFFF0F0F0, 0xFF202020, 1.0), 0xFF202020);
+        assert_eq!(blend(0xFF000000, 0xFF204060, 0.5), 0xFF102030);
+
+        assert_eq!(text_fade(Duration::ZERO), 0.0);
+        assert_eq!(text_fade(TEXT_SHOW), 0.0);
+        assert!((text_fade(TEXT_SHOW + TEXT_FADE / 2) - 0.5).abs() < 0.01);
+        assert!(text_fade(TEXT_SHOW + TEXT_FADE) >= 1.0);
+    }
+
+    #[test]
+    fn test_centered_label() {
+        // "Copied" is 48px wide and 7px tall in the 120x50 overlay
+        assert_eq!(centered_label(FLASH_LABEL, OVERLAY_WIDTH, OVERLAY_HEIGHT), (36, 21));
+        // Too small to fit: pinned to the corner
+        assert_eq!(centered_label(FLASH_LABEL, 20, 4), (0, 0));
+    }
+
+    #[test]
+    fn test_level_meter() {
+        assert_eq!(level_fraction(0.0), 0.0);
+        assert_eq!(level_fraction(f32::NAN), 0.0);
+        // Near-silence (a muted mic) leaves the bar empty
+        assert_eq!(level_fraction(0.0005), 0.0);
+        assert!((level_fraction(0.01) - 1.0 / 3.0).abs() < 0.01);
+        assert_eq!(level_fraction(1.0), 1.0);
+        assert_eq!(level_fraction(4.0), 1.0);
+
+        assert!(shows_level(AppStatus::Recording));
+        assert!(shows_level(AppStatus::AlwaysListening));
+        assert!(!shows_level(AppStatus::Processing));
+        assert!(!shows_level(AppStatus::Muted));
+        assert!(!shows_level(AppStatus::Paused));
+    }
+
+    #[test]
+    fn test_animation() {
+        assert!(animates(AppStatus::Processing));
+        assert!(animates(AppStatus::Recording));
+        assert!(animates(AppStatus::AlwaysListeningRecording));
+        assert!(!animates(AppStatus::Idle));
+        assert!(!animates(AppStatus::AlwaysListening));
+
+        // The dot blinks at 1 Hz at 10 fps
+        assert!((0..5).all(recording_dot_visible));
+        assert!(!(5..10).any(recording_dot_visible));
+        assert!(recording_dot_visible(10));
     }
 
     #[test]
@@ -268,6 +609,10 @@ mod tests {
             AppStatus::Recording => "🎤 LISTENING",
             AppStatus::Processing => "Processing...",
             AppStatus::AlwaysListening => "Always On",
+            AppStatus::AlwaysListeningRecording => "🎤 SPEAKING",
+            AppStatus::Muted => "Muted",
+            AppStatus::Paused => "Paused",
+            AppStatus::LoadingModel => "Loading model...",
         };
         assert_eq!(title_idle, "Idle");
         
@@ -276,6 +621,10 @@ mod tests {
             AppStatus::Recording => "🎤 LISTENING",
             AppStatus::Processing => "Processing...",
             AppStatus::AlwaysListening => "Always On",
+            AppStatus::AlwaysListeningRecording => "🎤 SPEAKING",
+            AppStatus::Muted => "Muted",
+            AppStatus::Paused => "Paused",
+            AppStatus::LoadingModel => "Loading model...",
         };
         assert_eq!(title_recording, "🎤 LISTENING");
     }
@@ -0,0 +1,191 @@
+//! Text clean-up applied to transcripts before they are typed
+//!
+//! Phrases Whisper invents for near-silent audio are dropped, user
+//! replacements fix words it consistently gets wrong (names, acronyms), and
+//! the optional built-in fixes tidy spacing and capitalization.
+
+use crate::config::Config;
+use crate::text_format;
+use tracing::info;
+
+/// Result clean-up settings from the config
+pub struct PostProcessor {
+    replacements: Vec<(String, String)>,
+    fix_common_errors: bool,
+    hallucination_phrases: Vec<String>,
+    hallucination_max_rms: f32,
+    no_speech_threshold: f32,
+}
+
+impl PostProcessor {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            replacements: config.replacements.clone(),
+            fix_common_errors: config.fix_common_errors,
+            hallucination_phrases: config.hallucination_phrases.clone(),
+            hallucination_max_rms: config.hallucination_max_rms,
+            no_speech_threshold: config.no_speech_threshold,
+        }
+    }
+
+    /// True if the backend thinks the audio held no speech, so the result shouldn't be typed
+    pub fn is_no_speech(&self, no_speech_prob: f32) -> bool {
+        no_speech_prob > self.no_speech_threshold
+    }
+
+    /// Clean up a result; a likely hallucination from near-silent audio becomes ""
+    pub fn apply(&self, text: &str, audio_rms: f32) -> String {
+        let phrases = &self.hallucination_phrases;
+        if is_hallucination(text, audio_rms, phrases, self.hallucination_max_rms) {
+            info!("Dropping \"{}\" (audio RMS {:.4} looks silent)", text, audio_rms);
+            return String::new();
+        }
+        post_process(text, &self.replacements, self.fix_common_errors)
+    }
+}
+
+/// True if the whole result is a blocklisted phrase and the audio was quieter than
+/// `max_rms`. Case, punctuation and brackets are ignored when comparing.
+pub fn is_hallucination(text: &str, audio_rms: f32, phrases: &[String], max_rms: f32) -> bool {
+    if audio_rms >= max_rms {
+        return false;
+    }
+    let text = normalize_phrase(text);
+    !text.is_empty() && phrases.iter().any(|p| normalize_phrase(p) == text)
+}
+
+/// Lowercase words only: "[BLANK_AUDIO]" -> "blank audio", " Thank you. " -> "thank you"
+fn normalize_phrase(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Apply whole-word, case-insensitive `replacements` in order, then the
+/// built-in fixes if `fix_common` is set
+pub fn post_process(text: &str, replacements: &[(String, String)], fix_common: bool) -> String {
+    let mut text = text.to_string();
+    for (from, to) in replacements {
+        text = replace_words(&text, from, to);
+    }
+    if fix_common {
+        text = text_format::normalize(&text);
+    }
+    text
+}
+
+/// Characters that make up a word (matches regex `\w`)
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn eq_ignore_case(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+/// Replace every case-insensitive occurrence of `from` that isn't part of a
+/// longer word ("cat" matches "Cat." but not "category")
+fn replace_words(text: &str, from: &str, to: &str) -> String {
+    let pattern: Vec<char> = from.chars().collect();
+    let (Some(&first), Some(&last)) = (pattern.first(), pattern.last()) else {
+        return text.to_string();
+    };
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let end = i + pattern.len();
+        let is_match = end <= chars.len()
+            && chars[i..end].iter().zip(&pattern).all(|(&a, &b)| eq_ignore_case(a, b))
+            // Only a pattern edge that is itself a word character needs a boundary
+            && !(is_word_char(first) && i > 0 && is_word_char(chars[i - 1]))
+            && !(is_word_char(last) && end < chars.len() && is_word_char(chars[end]));
+        if is_match {
+            out.push_str(to);
+            i = end;
+        } else {
+            out.push(chars[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(list: &[(&str, &str)]) -> Vec<(String, String)> {
+        list.iter().map(|(a, b)| (a.to_string(), b.to_string())).collect()
+    }
+
+    #[test]
+    fn test_replacements_match_whole_words() {
+        let replacements = pairs(&[("cat", "dog"), ("open ai", "OpenAI"), ("k8s", "Kubernetes")]);
+        assert_eq!(
+            post_process("Cat and category, CAT.", &replacements, false),
+            "dog and category, dog."
+        );
+        assert_eq!(post_process("scatter the cats", &replacements, false), "scatter the cats");
+        assert_eq!(post_process("I use Open AI daily", &replacements, false), "I use OpenAI daily");
+        assert_eq!(post_process("k8s_cluster k8s", &replacements, false), "k8s_cluster Kubernetes");
+        // Patterns ending in punctuation still match before a letter
+        assert_eq!(post_process("c++x", &pairs(&[("c++", "C++ ")]), false), "C++ x");
+        assert_eq!(post_process("", &replacements, false), "");
+        assert_eq!(post_process("cat", &pairs(&[("", "x")]), false), "cat");
+    }
+
+    #[test]
+    fn test_replacements_apply_in_order() {
+        let replacements = pairs(&[("jason", "JSON"), ("json", "json5")]);
+        assert_eq!(post_process("parse the jason", &replacements, false), "parse the json5");
+        assert_eq!(post_process("Straße", &pairs(&[("STRASSE", "x")]), false), "Straße");
+        assert_eq!(post_process("ÜBER alles", &pairs(&[("über", "over")]), false), "over alles");
+    }
+
+    #[test]
+    fn test_hallucinations_dropped_only_for_quiet_audio() {
+        let config = Config::default();
+        let processor = PostProcessor::from_config(&config);
+        let quiet = config.hallucination_max_rms / 4.0;
+        let loud = config.hallucination_max_rms * 4.0;
+
+        assert_eq!(processor.apply("Thank you.", quiet), "");
+        assert_eq!(processor.apply(" [BLANK_AUDIO] ", quiet), "");
+        assert_eq!(processor.apply("thank you!", quiet), "");
+        // Genuine speech passes through
+        assert_eq!(processor.apply("Thank you.", loud), "Thank you.");
+        assert_eq!(processor.apply("Thank you for the report.", quiet), "Thank you for the report.");
+
+        let phrases = vec!["...".to_string()];
+        assert!(!is_hallucination("", 0.0, &phrases, 0.01));
+        assert!(!is_hallucination("?", 0.0, &phrases, 0.01));
+    }
+
+    #[test]
+    fn test_no_speech_threshold() {
+        let mut config = Config::default();
+        // The default never drops a result
+        assert!(!PostProcessor::from_config(&config).is_no_speech(1.0));
+
+        config.no_speech_threshold = 0.6;
+        let processor = PostProcessor::from_config(&config);
+        assert!(processor.is_no_speech(0.9));
+        assert!(!processor.is_no_speech(0.6));
+        assert!(!processor.is_no_speech(0.0));
+    }
+
+    #[test]
+    fn test_common_fixes() {
+        assert_eq!(post_process("hello , world .", &[], true), "Hello, world.");
+        assert_eq!(post_process("  \"what ?", &[], true), "  \"What?");
+        assert_eq!(post_process("émile", &[], true), "Émile");
+        assert_eq!(post_process("hello , world .", &[], false), "hello , world .");
+        // Runs after the replacements
+        let replacements = pairs(&[("um", "")]);
+        assert_eq!(post_process("so um, yes", &replacements, true), "So, yes");
+        assert_eq!(post_process("...", &[], true), "...");
+    }
+}
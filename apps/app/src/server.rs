@@ -0,0 +1,467 @@
+//! Local HTTP transcription server (`--serve <port>`)
+//!
+//! `POST /transcribe` with a WAV, FLAC or Ogg Vorbis file or raw little-endian
+//! f32 samples at 16kHz mono returns `{"text", "device", "segments"?}`. Listens on
+//! 127.0.0.1 only and handles one upload or transcription at a time.
+
+use crate::audio;
+use crate::backend_loader::{Model, Transcript, TranscribeRequest, DEFAULT_SAMPLE_RATE};
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// Largest request body accepted (~10 minutes of raw f32 at 16kHz)
+const MAX_BODY_BYTES: usize = 40 * 1024 * 1024;
+/// Largest request line + headers accepted
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+/// Slow or stalled clients are dropped after this long
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// Connections beyond this are answered with 503 straight away
+const MAX_CONNECTIONS: usize = 8;
+
+/// Transcribes, returning the transcript and the device it ran on
+type TranscribeFn =
+    dyn Fn(&[f32], &TranscribeRequest) -> Result<(Transcript, Option<String>)> + Send + Sync;
+
+/// Port from `--serve <port>` (or `--serve=<port>`), if given
+pub fn parse_serve_port(args: &[String]) -> Result<Option<u16>> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value = if arg == "--serve" {
+            iter.next().context("--serve needs a port number")?.as_str()
+        } else if let Some(value) = arg.strip_prefix("--serve=") {
+            value
+        } else {
+            continue;
+        };
+        let port = value
+            .parse::<u16>()
+            .with_context(|| format!("Invalid --serve port '{}'", value))?;
+        return Ok(Some(port));
+    }
+    Ok(None)
+}
+
+/// A parsed HTTP request
+#[derive(Debug)]
+struct HttpRequest {
+    method: String,
+    path: String,
+    query: String,
+    content_type: String,
+    body: Vec<u8>,
+}
+
+/// An HTTP error response
+#[derive(Debug)]
+struct HttpError {
+    status: u16,
+    message: String,
+}
+
+impl HttpError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        Self { status, message: message.into() }
+    }
+}
+
+/// Read a request line and headers. The body is left unread; its Content-Length
+/// is returned alongside.
+fn read_head(reader: &mut impl BufRead) -> Result<(HttpRequest, usize), HttpError> {
+    let mut head = Vec::new();
+    loop {
+        let mut line = Vec::new();
+        let read = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|e| HttpError::new(400, format!("Failed to read request: {}", e)))?;
+        if read == 0 {
+            return Err(HttpError::new(400, "Connection closed mid-request"));
+        }
+        head.extend_from_slice(&line);
+        if head.len() > MAX_HEADER_BYTES {
+            return Err(HttpError::new(431, "Request headers too large"));
+        }
+        if line == b"\r\n" || line == b"\n" {
+            break;
+        }
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let mut lines = head.lines();
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = match (parts.next(), parts.next()) {
+        (Some(method), Some(target)) => (method.to_string(), target),
+        _ => return Err(HttpError::new(400, "Malformed request line")),
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let mut content_length = None;
+    let mut content_type = String::new();
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = Some(
+                value
+                    .parse::<usize>()
+                    .map_err(|_| HttpError::new(400, "Invalid Content-Length"))?,
+            );
+        } else if name.eq_ignore_ascii_case("content-type") {
+            content_type = value.to_ascii_lowercase();
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            return Err(HttpError::new(411, "Chunked uploads are not supported"));
+        }
+    }
+
+    let length = content_length.unwrap_or(0);
+    if length > MAX_BODY_BYTES {
+        return Err(HttpError::new(413, format!("Body larger than {} bytes", MAX_BODY_BYTES)));
+    }
+
+    let request = HttpRequest {
+        method,
+        path: path.to_string(),
+        query: query.to_string(),
+        content_type,
+        body: Vec::new(),
+    };
+    Ok((request, length))
+}
+
+/// Read a body of `length` bytes
+fn read_body(reader: &mut impl BufRead, length: usize) -> Result<Vec<u8>, HttpError> {
+    let mut body = vec![0u8; length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|e| HttpError::new(400, format!("Failed to read body: {}", e)))?;
+    Ok(body)
+}
+
+/// Value of `name` in a query string like `language=en&segments=1`
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('=').or(Some((pair, ""))))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+/// Audio from a request body: a WAV, FLAC or Ogg Vorbis file, or raw f32 samples
+/// at 16kHz mono
+fn decode_body(content_type: &str, body: &[u8], sample_rate: u32) -> Result<Vec<f32>, HttpError> {
+    if body.is_empty() {
+        return Err(HttpError::new(400, "Empty body"));
+    }
+    let is_file = [&b"RIFF"[..], b"fLaC", b"OggS"].iter().any(|magic| body.starts_with(magic))
+        || ["wav", "flac", "ogg"].iter().any(|kind| content_type.contains(kind));
+    if is_file {
+        return audio::decode_bytes(body, sample_rate)
+            .map_err(|e| HttpError::new(400, format!("{:#}", e)));
+    }
+    if !body.len().is_multiple_of(4) {
+        return Err(HttpError::new(
+            400,
+            "Raw audio must be little-endian f32 samples (length not a multiple of 4)",
+        ));
+    }
+    let samples: Vec<f32> = body
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    if samples.iter().any(|s| !s.is_finite()) {
+        return Err(HttpError::new(400, "Raw audio contains NaN or infinite samples"));
+    }
+    Ok(audio::resample(&samples, DEFAULT_SAMPLE_RATE, sample_rate))
+}
+
+fn status_text(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+fn write_response(
+    stream: &mut impl Write,
+    status: u16,
+    body: &serde_json::Value,
+) -> std::io::Result<()> {
+    let body = body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        status_text(status),
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// Serves transcription requests with the app's current model
+pub struct TranscribeServer {
+    transcribe: Box<TranscribeFn>,
+    sample_rate: u32,
+    /// Held while a request body is read and transcribed; a second request gets 503
+    busy: Mutex<()>,
+    /// Connections being handled
+    connections: AtomicUsize,
+}
+
+impl TranscribeServer {
+    /// `model` is asked for the model on every request, so switching models in the
+    /// tray also switches the server
+    pub fn new(model: impl Fn() -> Arc<Model> + Send + Sync + 'static, sample_rate: u32) -> Self {
+        Self::with_transcriber(
+            Box::new(move |audio, request| {
+                let model = model();
+                let transcript = model.transcribe_with(audio, request)?;
+                Ok((transcript, model.device_used()))
+            }),
+            sample_rate,
+        )
+    }
+
+    fn with_transcriber(transcribe: Box<TranscribeFn>, sample_rate: u32) -> Self {
+        Self {
+            transcribe,
+            sample_rate,
+            busy: Mutex::new(()),
+            connections: AtomicUsize::new(0),
+        }
+    }
+
+    /// Listen on 127.0.0.1:`port` (0 = any free port) in a background thread
+    pub fn spawn(self, port: u16) -> Result<SocketAddr> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .with_context(|| format!("Failed to listen on port {}", port))?;
+        let addr = listener.local_addr()?;
+        let server = Arc::new(self);
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(mut stream) => {
+                        // Only this thread adds connections, so the check can't race
+                        if server.connections.load(Ordering::SeqCst) >= MAX_CONNECTIONS {
+                            let body = serde_json::json!({ "error": "Too many connections" });
+                            let _ = write_response(&mut stream, 503, &body);
+                            continue;
+                        }
+                        server.connections.fetch_add(1, Ordering::SeqCst);
+                        let server = Arc::clone(&server);
+                        std::thread::spawn(move || {
+                            server.handle_connection(stream);
+                            server.connections.fetch_sub(1, Ordering::SeqCst);
+                        });
+                    }
+                    Err(e) => warn!("Server accept failed: {}", e),
+                }
+            }
+        });
+
+        info!("Transcription server listening on http://{}/transcribe", addr);
+        Ok(addr)
+    }
+
+    fn handle_connection(&self, mut stream: TcpStream) {
+        let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+        let peer = stream.peer_addr().ok();
+        let result = match stream.try_clone() {
+            Ok(read_half) => self.serve(&mut BufReader::new(read_half)),
+            Err(e) => Err(HttpError::new(500, e.to_string())),
+        };
+
+        let (status, body) = match result {
+            Ok(body) => (200, body),
+            Err(e) => (e.status, serde_json::json!({ "error": e.message })),
+        };
+        debug!("Server {:?}: {}", peer, status);
+        if let Err(e) = write_response(&mut stream, status, &body) {
+            debug!("Server failed to write response: {}", e);
+        }
+    }
+
+    /// Answer one request. A busy server refuses before reading the body, so
+    /// queued uploads don't pile up in memory.
+    fn serve(&self, reader: &mut impl BufRead) -> Result<serde_json::Value, HttpError> {
+        let (mut request, length) = read_head(reader)?;
+        if request.path != "/transcribe" {
+            return Err(HttpError::new(404, "Not found (use POST /transcribe)"));
+        }
+        if request.method != "POST" {
+            return Err(HttpError::new(405, "Use POST"));
+        }
+        let Some(_guard) = self.busy.try_lock() else {
+            return Err(HttpError::new(503, "Busy with another transcription"));
+        };
+        request.body = read_body(reader, length)?;
+        self.transcribe(&request)
+    }
+
+    fn transcribe(&self, request: &HttpRequest) -> Result<serde_json::Value, HttpError> {
+        let audio = decode_body(&request.content_type, &request.body, self.sample_rate)?;
+        let segments = matches!(query_param(&request.query, "segments"), Some("1" | "true"));
+        let language = query_param(&request.query, "language").filter(|l| !l.is_empty());
+        let options = TranscribeRequest {
+            language,
+            timestamps: segments,
+            ..Default::default()
+        };
+
+        let (transcript, device) = (self.transcribe)(&audio, &options).map_err(|e| {
+            error!("Server transcription failed: {:#}", e);
+            HttpError::new(500, format!("{:#}", e))
+        })?;
+
+        let mut body = serde_json::json!({
+            "text": transcript.text,
+            "device": device,
+        });
+        if segments {
+            body["segments"] = transcript
+                .segments
+                .iter()
+                .map(|s| {
+                    serde_json::json!({
+                        "start_ms": s.start_ms,
+                        "end_ms": s.end_ms,
+                        "text": s.text,
+                    })
+                })
+                .collect();
+        }
+        Ok(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend_loader::TranscriptSegment;
+    use std::io::{Cursor, Read};
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_serve_port() {
+        assert_eq!(parse_serve_port(&args(&["--delay-start"])).unwrap(), None);
+        assert_eq!(parse_serve_port(&args(&["--serve", "8765"])).unwrap(), Some(8765));
+        assert_eq!(parse_serve_port(&args(&["--serve=9000"])).unwrap(), Some(9000));
+        assert!(parse_serve_port(&args(&["--serve"])).is_err());
+        assert!(parse_serve_port(&args(&["--serve", "http"])).is_err());
+    }
+
+    #[test]
+    fn test_read_request() {
+        let raw = b"POST /transcribe?language=en&segments=1 HTTP/1.1\r\nHost: x\r\n\
+            Content-Type: Audio/WAV\r\nContent-Length: 4\r\n\r\nabcdEXTRA";
+        let mut reader = Cursor::new(&raw[..]);
+        let (request, length) = read_head(&mut reader).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/transcribe");
+        assert_eq!(query_param(&request.query, "language"), Some("en"));
+        assert_eq!(query_param(&request.query, "segments"), Some("1"));
+        assert_eq!(query_param(&request.query, "missing"), None);
+        assert_eq!(request.content_type, "audio/wav");
+        assert_eq!(read_body(&mut reader, length).unwrap(), b"abcd");
+
+        let truncated = b"POST /transcribe HTTP/1.1\r\nContent-Length: 10\r\n\r\nabc";
+        let mut reader = Cursor::new(&truncated[..]);
+        let (_, length) = read_head(&mut reader).unwrap();
+        assert_eq!(read_body(&mut reader, length).unwrap_err().status, 400);
+        let chunked = b"POST /transcribe HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n";
+        assert_eq!(read_head(&mut Cursor::new(&chunked[..])).unwrap_err().status, 411);
+    }
+
+    #[test]
+    fn test_decode_body() {
+        let raw: Vec<u8> = [0.5f32, -0.25].iter().flat_map(|s| s.to_le_bytes()).collect();
+        assert_eq!(decode_body("", &raw, 16000).unwrap(), vec![0.5, -0.25]);
+        assert_eq!(decode_body("", &raw[..5], 16000).unwrap_err().status, 400);
+        assert_eq!(decode_body("", &[], 16000).unwrap_err().status, 400);
+        assert_eq!(decode_body("audio/wav", b"RIFFjunk", 16000).unwrap_err().status, 400);
+        assert_eq!(decode_body("audio/flac", b"junk", 16000).unwrap_err().status, 400);
+        let nan = f32::NAN.to_le_bytes();
+        assert_eq!(decode_body("", &nan, 16000).unwrap_err().status, 400);
+    }
+
+    #[test]
+    fn test_busy_server_returns_503() {
+        let server = TranscribeServer::with_transcriber(
+            Box::new(|_, _| Ok((Transcript::default(), None))),
+            16000,
+        );
+        let mut raw = b"POST /transcribe HTTP/1.1\r\nContent-Length: 64\r\n\r\n".to_vec();
+        let head_len = raw.len();
+        raw.resize(head_len + 64, 0);
+        assert!(server.serve(&mut Cursor::new(&raw[..])).is_ok());
+
+        // Refused without reading the body
+        let _guard = server.busy.lock();
+        let mut reader = Cursor::new(&raw[..]);
+        assert_eq!(server.serve(&mut reader).unwrap_err().status, 503);
+        assert_eq!(reader.position() as usize, head_len);
+    }
+
+    #[test]
+    fn test_server_round_trip() {
+        let server = TranscribeServer::with_transcriber(
+            Box::new(|audio, request| {
+                let transcript = Transcript {
+                    text: format!("{} samples", audio.len()),
+                    segments: if request.timestamps {
+                        vec![TranscriptSegment { start_ms: 0, end_ms: 500, text: "hi".to_string() }]
+                    } else {
+                        Vec::new()
+                    },
+                    ..Default::default()
+                };
+                Ok((transcript, Some("CUDA".to_string())))
+            }),
+            16000,
+        );
+        let addr = server.spawn(0).unwrap();
+
+        let post = |target: &str, body: &[u8]| -> String {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            let head =
+                format!("POST {} HTTP/1.1\r\nContent-Length: {}\r\n\r\n", target, body.len());
+            stream.write_all(head.as_bytes()).unwrap();
+            stream.write_all(body).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let raw: Vec<u8> = vec![0u8; 1600 * 4];
+        let response = post("/transcribe", &raw);
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.ends_with(r#"{"device":"CUDA","text":"1600 samples"}"#), "{}", response);
+
+        let response = post("/transcribe?segments=1", &raw);
+        let segments = r#""segments":[{"end_ms":500,"start_ms":0,"text":"hi"}]"#;
+        assert!(response.contains(segments), "{}", response);
+
+        assert!(post("/transcribe", &raw[..3]).starts_with("HTTP/1.1 400"));
+        assert!(post("/other", &raw).starts_with("HTTP/1.1 404"));
+    }
+}
@@ -1,11 +1,22 @@
-use crate::backend_loader::{discover_backends, get_backends_dir, BackendManifest, ManifestModel};
-use crate::config::{detect_cuda_path, detect_cudnn_path, get_models_dir, validate_cuda_path, validate_cudnn_path, Config};
+use crate::always_listen::{
+    effective_threshold, measure_noise_floor, threshold_from_noise_floor, AlwaysListenConfig,
+    MIN_SPEECH_MS_RANGE, POST_SILENCE_MS_RANGE, VAD_THRESHOLD_RANGE,
+};
+use crate::audio::AudioCapture;
+use crate::autostart;
+use crate::backend_loader::{discover_backends, BackendManifest, ManifestModel};
+use crate::config::{detect_cuda_path, detect_cudnn_path, get_backends_dir, get_models_dir, validate_cuda_path, validate_cudnn_path, Config, TypeMode, COMMON_LANGUAGES};
 use crate::downloader::{self, DownloadProgress};
-use cpal::traits::{DeviceTrait, HostTrait};
+use crate::font::{draw_rect, draw_text};
+use crate::text_field::TextField;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use image::GenericImageView;
 use std::num::NonZeroU32;
 use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tao::dpi::LogicalSize;
 use tao::event::{ElementState, Event, MouseButton, WindowEvent};
 use tao::event_loop::{ControlFlow, EventLoopBuilder};
@@ -15,15 +26,29 @@ use tao::window::{Icon, WindowBuilder};
 const WINDOW_WIDTH: u32 = 500;
 const WINDOW_HEIGHT: u32 = 500;
 const WINDOW_ICON_PNG: &[u8] = include_bytes!("../assets/mic_gray.png");
+/// How long to record ambient noise when calibrating the mic
+const CALIBRATION_DURATION: Duration = Duration::from_secs(2);
+/// Give up on a calibration whose recording never finishes (e.g. a hung driver)
+const CALIBRATION_TIMEOUT: Duration = Duration::from_secs(6);
+/// Whisper only reads the last ~224 tokens of a prompt
+const MAX_PROMPT_CHARS: usize = 400;
+/// Prompt text lines that fit in the edit field
+const PROMPT_LINE_CHARS: usize = 52;
+const VISIBLE_PROMPT_LINES: usize = 8;
+/// Model filter box in the model page header
+const MAX_FILTER_CHARS: usize = 40;
+const FILTER_LINE_CHARS: usize = 26;
+/// Always-listen minimum speech +/- step
+const MIN_SPEECH_STEP_MS: u64 = 50;
 
 // Colors
-const BG_COLOR: u32 = 0xFF1a1a2e;
-const HEADER_BG: u32 = 0xFF16213e;
-const TEXT_COLOR: u32 = 0xFFe8e8e8;
-const DIM_TEXT: u32 = 0xFF888888;
+pub(crate) const BG_COLOR: u32 = 0xFF1a1a2e;
+pub(crate) const HEADER_BG: u32 = 0xFF16213e;
+pub(crate) const TEXT_COLOR: u32 = 0xFFe8e8e8;
+pub(crate) const DIM_TEXT: u32 = 0xFF888888;
 const ACCENT_COLOR: u32 = 0xFF4a9eff;
-const BUTTON_COLOR: u32 = 0xFF2d4a6f;
-const BUTTON_HOVER: u32 = 0xFF3d5a8f;
+pub(crate) const BUTTON_COLOR: u32 = 0xFF2d4a6f;
+pub(crate) const BUTTON_HOVER: u32 = 0xFF3d5a8f;
 const SELECTED_COLOR: u32 = 0xFF0f3460;
 const PROGRESS_BG: u32 = 0xFF2a2a4a;
 const PROGRESS_FG: u32 = 0xFF4ade80;
@@ -38,6 +63,9 @@ enum SetupPage {
     HotkeyConfig(HotkeyTarget),
     CudaConfig,
     AudioConfig,
+    Advanced,
+    AlwaysListenTuning,
+    Languages,
 }
 
 /// Unified model entry combining backend and model info
@@ -60,6 +88,31 @@ enum HotkeyCapture {
     WaitingForKey,
 }
 
+/// Editable text fields in the wizard
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FieldId {
+    InitialPrompt,
+    ModelFilter,
+}
+
+impl FieldId {
+    /// Page the field is shown on (keys only reach it there)
+    fn page(self) -> SetupPage {
+        match self {
+            FieldId::InitialPrompt => SetupPage::Advanced,
+            FieldId::ModelFilter => SetupPage::ModelSelection,
+        }
+    }
+
+    /// Button pressed when Enter is typed in the field
+    fn submit_button(self) -> Option<Button> {
+        match self {
+            FieldId::InitialPrompt => Some(Button::ConfirmPrompt),
+            FieldId::ModelFilter => None,
+        }
+    }
+}
+
 struct SetupState {
     current_page: SetupPage,
 
@@ -72,24 +125,60 @@ struct SetupState {
     // Unified model list (all models from all backends)
     all_models: Vec<UnifiedModel>,
     selected_model: Option<usize>,
+    /// Compute type for the selected ct2 model (None = manifest default)
+    compute_type: Option<String>,
+    /// Scroll position within the filtered model list
     model_scroll_offset: usize,
+    /// Search text narrowing the model list
+    model_filter: TextField,
     // Audio input devices
     input_devices: Vec<String>,
     selected_input_device: Option<String>,
     device_scroll_offset: usize,
+    // Noise calibration (result arrives from a recording thread started at the Instant)
+    vad_noise_floor: Option<f32>,
+    calibration: Option<(Receiver<anyhow::Result<f32>>, Instant)>,
+    calibration_error: Option<String>,
+    // Live level meter on the microphone page (None = not testing)
+    mic_test: Option<MicTest>,
+    // Parallel model file downloads (from the config)
+    download_concurrency: usize,
 
     // Auto-selected backend (based on model choice)
     selected_backend_id: Option<String>,
 
     // Hotkey configuration
     push_to_talk_hotkey: Option<String>,
+    push_to_talk_hold: bool,
     toggle_listening_hotkey: Option<String>,
+    /// Not editable here, but captured keys must not clash with these
+    mute_hotkey: String,
+    pause_listen_hotkey: String,
     hotkey_capture: HotkeyCapture,
     captured_key: Option<String>,
+    /// Why the last captured key was rejected
+    hotkey_error: Option<String>,
     current_modifiers: ModifiersState,
 
     // Always-listen settings
     silence_timeout_ms: u64,
+    /// Explicit VAD threshold (None = from the calibrated noise floor)
+    vad_threshold: Option<f32>,
+    vad_min_speech_ms: u64,
+
+    // Vocabulary prompt (edited on the Advanced page)
+    initial_prompt: TextField,
+    /// Inference threads (0 = library default), also on the Advanced page
+    cpu_threads: u32,
+    /// Languages auto-detect may choose from (empty = any), edited on the Languages page
+    allowed_languages: Vec<String>,
+    /// Whether the Run registry entry exists (read on open, not taken from the config)
+    autostart: bool,
+    /// Text field receiving keyboard input, if any
+    focused_field: Option<FieldId>,
+
+    // Paste transcripts through the clipboard instead of typing them
+    type_mode: TypeMode,
 
     // GPU/CUDA settings
     use_gpu: bool,
@@ -102,6 +191,12 @@ struct SetupState {
     status: String,
     download_progress: Option<Arc<DownloadProgress>>,
     model_downloaded: bool,
+    /// Model (index into `all_models`) waiting for a second Delete click
+    delete_pending: Option<usize>,
+    /// Model folder the app is configured to use
+    active_model_path: Option<std::path::PathBuf>,
+    /// Bytes used by the models folder (refreshed after downloads and deletes)
+    models_disk_bytes: u64,
     // Overlay settings (persisted from config)
     overlay_visible: bool,
     overlay_x: Option<i32>,
@@ -121,6 +216,9 @@ enum Button {
     ConfigureToggleListen,
     GpuToggle,
     ConfigureCuda,
+    ConfigureAdvanced,
+    ConfigureTuning,
+    TypeModeToggle,
     Start,
     Close,
 
@@ -128,8 +226,11 @@ enum Button {
     Model(usize),
     Download,
     OpenLink,
+    DeleteModel,
     ModelScrollUp,
     ModelScrollDown,
+    ModelFilterField,
+    ComputeTypeCycle,
     Back,
 
     // Hotkey config page
@@ -137,6 +238,9 @@ enum Button {
     ConfirmHotkey,
     ClearHotkey,
 
+    // Push-to-talk config (hold vs press-to-toggle)
+    PushToTalkModeToggle,
+
     // Toggle listen config (silence timeout)
     SilenceTimeoutDecrease,
     SilenceTimeoutIncrease,
@@ -151,6 +255,29 @@ enum Button {
     DeviceScrollUp,
     DeviceScrollDown,
     ConfirmDevice,
+    CalibrateMic,
+    TestMic,
+
+    // Advanced page
+    PromptField,
+    ConfirmPrompt,
+    ClearPrompt,
+    CpuThreadsDecrease,
+    CpuThreadsIncrease,
+    AutostartToggle,
+    ConfigureLanguages,
+
+    // Languages page (indices into COMMON_LANGUAGES)
+    Language(usize),
+    ClearLanguages,
+    ConfirmLanguages,
+
+    // Always-listen tuning page (also uses the silence timeout buttons)
+    VadThresholdDecrease,
+    VadThresholdIncrease,
+    MinSpeechDecrease,
+    MinSpeechIncrease,
+    ConfirmTuning,
 }
 
 struct ButtonRect {
@@ -165,9 +292,15 @@ const VISIBLE_MODELS: usize = 6;
 const VISIBLE_DEVICES: usize = 6;
 const DEFAULT_DEVICE_LABEL: &str = "<Default device>";
 
+/// Rows of the two language columns on the Languages page
+const LANGUAGE_ROWS: usize = 8;
+
 impl SetupState {
     fn new(from_settings: bool) -> Self {
         let existing_config = Config::load().ok();
+        if let Some(config) = &existing_config {
+            crate::config::set_dir_overrides(config);
+        }
 
         // Load audio input devices
         let mut input_devices: Vec<String> = Vec::new();
@@ -208,6 +341,8 @@ impl SetupState {
                 });
             }
         }
+        // Fastest first; models without a tier keep their manifest order at the end
+        all_models.sort_by_key(|u| u.model.tier_rank());
 
         // Resolve saved model selection from config (if any).
         let mut selected_model: Option<usize> = None;
@@ -258,7 +393,9 @@ impl SetupState {
             available_backends,
             all_models,
             selected_model,
+            compute_type: existing_config.as_ref().and_then(|c| c.compute_type.clone()),
             model_scroll_offset: 0,
+            model_filter: TextField::new("", MAX_FILTER_CHARS),
             selected_backend_id,
             input_devices,
             selected_input_device,
@@ -269,19 +406,57 @@ impl SetupState {
                     .map(|c| c.hotkey_push_to_talk.clone())
                     .unwrap_or_else(|| "Backquote".to_string()),
             ),
+            push_to_talk_hold: existing_config
+                .as_ref()
+                .map(|c| c.push_to_talk_hold)
+                .unwrap_or(true),
             toggle_listening_hotkey: Some(
                 existing_config
                     .as_ref()
                     .map(|c| c.hotkey_always_listen.clone())
                     .unwrap_or_else(|| "Control+Backquote".to_string()),
             ),
+            mute_hotkey: existing_config
+                .as_ref()
+                .map(|c| c.hotkey_mute.clone())
+                .unwrap_or_else(|| Config::default().hotkey_mute),
+            pause_listen_hotkey: existing_config
+                .as_ref()
+                .map(|c| c.hotkey_pause_listen.clone())
+                .unwrap_or_default(),
             hotkey_capture: HotkeyCapture::Idle,
             captured_key: None,
+            hotkey_error: None,
             current_modifiers: ModifiersState::default(),
             silence_timeout_ms: existing_config
                 .as_ref()
                 .map(|c| c.silence_timeout_ms)
                 .unwrap_or(2000),
+            vad_threshold: existing_config.as_ref().and_then(|c| c.vad_threshold),
+            vad_min_speech_ms: existing_config
+                .as_ref()
+                .map(|c| c.vad_min_speech_ms)
+                .unwrap_or_else(|| Config::default().vad_min_speech_ms),
+            initial_prompt: TextField::new(
+                existing_config.as_ref().map_or("", |c| c.initial_prompt.as_str()),
+                MAX_PROMPT_CHARS,
+            ),
+            cpu_threads: existing_config.as_ref().map_or(0, |c| c.cpu_threads),
+            allowed_languages: existing_config
+                .as_ref()
+                .map(|c| c.allowed_languages())
+                .unwrap_or_default(),
+            autostart: autostart::is_enabled(),
+            focused_field: None,
+            type_mode: existing_config.as_ref().map(|c| c.type_mode).unwrap_or_default(),
+            vad_noise_floor: existing_config.as_ref().and_then(|c| c.vad_noise_floor),
+            calibration: None,
+            calibration_error: None,
+            mic_test: None,
+            download_concurrency: existing_config
+                .as_ref()
+                .map(|c| c.download_concurrency)
+                .unwrap_or_else(|| Config::default().download_concurrency),
             use_gpu,
             cuda_path,
             cudnn_path,
@@ -290,6 +465,9 @@ impl SetupState {
             status,
             download_progress: None,
             model_downloaded,
+            delete_pending: None,
+            active_model_path: existing_config.as_ref().map(|c| c.model_path.clone()),
+            models_disk_bytes: models_disk_usage(),
             overlay_visible: existing_config
                 .as_ref()
                 .map(|c| c.overlay_visible)
@@ -301,6 +479,22 @@ impl SetupState {
         }
     }
 
+    /// The focused text field, if it's on the current page
+    fn focused_text_field(&mut self) -> Option<&mut TextField> {
+        match self.focused_field {
+            Some(id) if id.page() == self.current_page => match id {
+                FieldId::InitialPrompt => Some(&mut self.initial_prompt),
+                FieldId::ModelFilter => Some(&mut self.model_filter),
+            },
+            _ => None,
+        }
+    }
+
+    /// Indices into `all_models` of the models matching the filter
+    fn filtered_models(&self) -> Vec<usize> {
+        filter_models(&self.all_models, self.model_filter.text())
+    }
+
     fn selected_unified_model(&self) -> Option<&UnifiedModel> {
         self.selected_model.map(|idx| &self.all_models[idx])
     }
@@ -324,19 +518,7 @@ impl SetupState {
     }
 
     fn check_model_exists(&self) -> bool {
-        if let (Some(unified), Ok(models_dir)) =
-            (self.selected_unified_model(), get_models_dir())
-        {
-            let model_folder = models_dir.join(&unified.model.folder_name);
-            // Check if last file in the list exists
-            if let Some(last_file) = unified.model.files.last() {
-                model_folder.join(last_file).exists()
-            } else {
-                model_folder.exists()
-            }
-        } else {
-            false
-        }
+        self.selected_unified_model().is_some_and(is_unified_model_downloaded)
     }
 
     #[allow(dead_code)]
@@ -353,21 +535,70 @@ impl SetupState {
             HotkeyTarget::ToggleListening => self.toggle_listening_hotkey = key,
         }
     }
+
+    /// Error message if `key` is already bound to another action
+    fn hotkey_conflict(&self, target: HotkeyTarget, key: &str) -> Option<String> {
+        let others = [
+            (HotkeyTarget::PushToTalk, "Push-to-Talk", self.push_to_talk_hotkey.as_deref()),
+            (
+                HotkeyTarget::ToggleListening,
+                "Toggle Listen",
+                self.toggle_listening_hotkey.as_deref(),
+            ),
+        ];
+        let others = others
+            .into_iter()
+            .filter(|(other, _, _)| *other != target)
+            .map(|(_, name, hotkey)| (name, hotkey))
+            .chain([
+                ("Mute", Some(self.mute_hotkey.as_str())),
+                ("Pause Listen", Some(self.pause_listen_hotkey.as_str())),
+            ]);
+        conflicting_hotkey(key, others)
+            .map(|name| format!("Already used by {}; hotkeys must differ", name))
+    }
+}
+
+/// Live capture from one input device, feeding the mic page's level meter.
+/// Dropping it stops the stream.
+struct MicTest {
+    device: Option<String>,
+    level: Arc<AtomicU32>,
+    _stream: cpal::Stream,
+}
+
+impl MicTest {
+    fn start(device_name: Option<&str>) -> anyhow::Result<Self> {
+        let capture = AudioCapture::new_with_device(device_name)?;
+        // Only the level is used; with the receiver gone the audio is dropped
+        let (tx, _) = crossbeam_channel::bounded(1);
+        let stream = capture.create_always_listen_stream(tx, Arc::new(AtomicBool::new(true)))?;
+        stream.play()?;
+        Ok(Self {
+            device: device_name.map(str::to_string),
+            level: capture.level_meter(),
+            _stream: stream,
+        })
+    }
+
+    /// RMS of the latest input chunk
+    fn level(&self) -> f32 {
+        f32::from_bits(self.level.load(Ordering::Relaxed))
+    }
+}
+
+/// Record a short stretch of silence and measure the mic's noise floor
+fn calibrate_noise_floor(device_name: Option<&str>) -> anyhow::Result<f32> {
+    let mut capture = AudioCapture::new_with_device(device_name)?;
+    capture.start_recording()?;
+    std::thread::sleep(CALIBRATION_DURATION);
+    let samples = capture.stop_recording()?;
+    measure_noise_floor(&samples, AlwaysListenConfig::default().frame_samples)
 }
 
 /// Check if a unified model is downloaded
 fn is_unified_model_downloaded(unified: &UnifiedModel) -> bool {
-    if let Ok(models_dir) = get_models_dir() {
-        let model_folder = models_dir.join(&unified.model.folder_name);
-        // Check if last file in the list exists
-        if let Some(last_file) = unified.model.files.last() {
-            model_folder.join(last_file).exists()
-        } else {
-            model_folder.exists()
-        }
-    } else {
-        false
-    }
+    get_models_dir().is_ok_and(|dir| unified.model.is_downloaded(&dir))
 }
 
 fn load_window_icon() -> Option<Icon> {
@@ -422,31 +653,97 @@ fn run_setup_inner(from_settings: bool) -> ! {
         if let Some(ref progress) = state.download_progress {
             if progress.is_finished() {
                 if let Some(err) = progress.get_error() {
-                    state.status = format!("Download failed: {}", err);
+                    state.status = if err.starts_with("Checksum failed") {
+                        err
+                    } else {
+                        format!("Download failed: {}", err)
+                    };
                 } else {
                     state.status = "Download complete!".to_string();
                     state.model_downloaded = true;
                 }
+                state.models_disk_bytes = models_disk_usage();
                 state.download_progress = None;
                 window.request_redraw();
             } else {
                 let (downloaded, total) = progress.get_progress();
-                let (current_file, total_files) = progress.get_file_progress();
+                let (completed_files, total_files) = progress.get_file_progress();
                 if total > 0 {
-                    let percent = (downloaded as f64 / total as f64 * 100.0) as u32;
+                    let percent = (progress.fraction() * 100.0) as u32;
                     let mb_downloaded = downloaded as f64 / 1_000_000.0;
                     let mb_total = total as f64 / 1_000_000.0;
                     state.status = format!(
-                        "File {}/{}: {:.1}/{:.1} MB ({}%)",
-                        current_file, total_files, mb_downloaded, mb_total, percent
+                        "{}/{} files: {:.1}/{:.1} MB ({}%)",
+                        completed_files, total_files, mb_downloaded, mb_total, percent
                     );
                 } else {
-                    state.status = format!("Downloading file {}/{}...", current_file, total_files);
+                    state.status = format!("Downloading {} files...", total_files);
                 }
                 window.request_redraw();
             }
         }
 
+        // Stop the mic test when its page is left; follow the selected device
+        if let Some(test) = &state.mic_test {
+            if state.current_page != SetupPage::AudioConfig {
+                state.mic_test = None;
+            } else if test.device != state.selected_input_device {
+                let device = state.selected_input_device.clone();
+                state.mic_test = None;
+                state.mic_test = match MicTest::start(device.as_deref()) {
+                    Ok(test) => Some(test),
+                    Err(e) => {
+                        state.status = format!("Mic test failed: {:#}", e);
+                        None
+                    }
+                };
+            }
+        }
+
+        // Check noise calibration
+        if let Some((ref rx, started)) = state.calibration {
+            match rx.try_recv() {
+                Ok(Ok(floor)) => {
+                    let threshold = threshold_from_noise_floor(floor);
+                    state.vad_noise_floor = Some(floor);
+                    state.vad_threshold = Some(threshold);
+                    state.status = format!(
+                        "Noise floor {:.4} - speech threshold set to {:.4}.",
+                        floor, threshold
+                    );
+                    if let Ok(mut config) = Config::load() {
+                        config.vad_noise_floor = state.vad_noise_floor;
+                        config.vad_threshold = state.vad_threshold;
+                        if let Err(e) = config.save() {
+                            state.status = format!("Error saving calibration: {}", e);
+                        }
+                    }
+                    state.calibration = None;
+                    window.request_redraw();
+                }
+                Ok(Err(e)) => {
+                    state.status = format!("Calibration failed: {}", e);
+                    state.calibration_error = Some(e.to_string());
+                    state.calibration = None;
+                    window.request_redraw();
+                }
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    state.status = "Calibration failed.".to_string();
+                    state.calibration_error = Some("Calibration failed".to_string());
+                    state.calibration = None;
+                    window.request_redraw();
+                }
+                Err(mpsc::TryRecvError::Empty) if started.elapsed() > CALIBRATION_TIMEOUT => {
+                    // The recording thread is abandoned; its result is dropped if it ever arrives
+                    state.status = "Calibration timed out - check the mic.".to_string();
+                    state.calibration_error = Some("Timed out".to_string());
+                    state.calibration = None;
+                    window.request_redraw();
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+        }
+
         match event {
             Event::UserEvent(SetupEvent::Exit(_config)) => {
                 *control_flow = ControlFlow::Exit;
@@ -474,22 +771,7 @@ fn run_setup_inner(from_settings: bool) -> ! {
                 if state.hotkey_capture == HotkeyCapture::WaitingForKey {
                     if key_event.state == ElementState::Pressed {
                         use tao::keyboard::Key;
-                        
-                        // Build modifier prefix first
-                        let mut parts = Vec::new();
-                        if state.current_modifiers.control_key() {
-                            parts.push("Control".to_string());
-                        }
-                        if state.current_modifiers.alt_key() {
-                            parts.push("Alt".to_string());
-                        }
-                        if state.current_modifiers.shift_key() {
-                            parts.push("Shift".to_string());
-                        }
-                        if state.current_modifiers.super_key() {
-                            parts.push("Super".to_string());
-                        }
-                        
+
                         // Get key name based on the Key variant
                         let key_name = match &key_event.logical_key {
                             Key::Character(c) => c.to_uppercase().to_string(),
@@ -521,20 +803,61 @@ fn run_setup_inner(from_settings: bool) -> ! {
                             Key::F10 => "F10".to_string(),
                             Key::F11 => "F11".to_string(),
                             Key::F12 => "F12".to_string(),
+                            Key::F13 => "F13".to_string(),
+                            Key::F14 => "F14".to_string(),
+                            Key::F15 => "F15".to_string(),
+                            Key::F16 => "F16".to_string(),
+                            Key::F17 => "F17".to_string(),
+                            Key::F18 => "F18".to_string(),
+                            Key::F19 => "F19".to_string(),
+                            Key::F20 => "F20".to_string(),
+                            Key::F21 => "F21".to_string(),
+                            Key::F22 => "F22".to_string(),
+                            Key::F23 => "F23".to_string(),
+                            Key::F24 => "F24".to_string(),
+                            Key::MediaPlayPause => "MediaPlayPause".to_string(),
+                            Key::MediaStop => "MediaStop".to_string(),
+                            Key::MediaTrackNext => "MediaTrackNext".to_string(),
+                            Key::MediaTrackPrevious => "MediaTrackPrevious".to_string(),
+                            Key::AudioVolumeMute => "AudioVolumeMute".to_string(),
+                            Key::AudioVolumeUp => "AudioVolumeUp".to_string(),
+                            Key::AudioVolumeDown => "AudioVolumeDown".to_string(),
                             // Modifier keys - ignore them as standalone keys
                             Key::Control | Key::Shift | Key::Alt | Key::Super | Key::AltGraph => return,
-                            // Ignore other keys we don't handle
-                            _ => return,
+                            _ => {
+                                state.hotkey_error = Some("That key can't be used as a hotkey".to_string());
+                                window.request_redraw();
+                                return;
+                            }
                         };
-                        
-                        parts.push(key_name);
-                        let key_str = parts.join("+");
-                        
-                        eprintln!("DEBUG: Captured hotkey: {}", key_str);
-                        state.captured_key = Some(key_str);
-                        state.hotkey_capture = HotkeyCapture::Idle;
+
+                        capture_hotkey(&mut state, &key_name);
                         window.request_redraw();
                     }
+                } else if key_event.state == ElementState::Pressed {
+                    use tao::keyboard::Key;
+
+                    let Some(field_id) = state.focused_field else { return };
+                    match &key_event.logical_key {
+                        Key::Escape => state.focused_field = None,
+                        Key::Enter => {
+                            if let Some(button) = field_id.submit_button() {
+                                handle_click(&mut state, button);
+                            }
+                        }
+                        key => {
+                            let edited = state
+                                .focused_text_field()
+                                .is_some_and(|field| edit_text_field(field, key));
+                            if !edited {
+                                return;
+                            }
+                            if field_id == FieldId::ModelFilter {
+                                state.model_scroll_offset = 0;
+                            }
+                        }
+                    }
+                    window.request_redraw();
                 }
             }
             Event::WindowEvent {
@@ -566,7 +889,7 @@ fn run_setup_inner(from_settings: bool) -> ! {
                         tao::event::MouseScrollDelta::PixelDelta(pos) => -(pos.y / 20.0) as i32,
                         _ => 0,
                     };
-                    let model_count = state.all_models.len();
+                    let model_count = state.filtered_models().len();
                     let new_offset = (state.model_scroll_offset as i32 + scroll_amount)
                         .max(0)
                         .min((model_count.saturating_sub(VISIBLE_MODELS)) as i32);
@@ -586,6 +909,29 @@ fn run_setup_inner(from_settings: bool) -> ! {
                     window.request_redraw();
                 }
             }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::MouseInput {
+                        state: ElementState::Pressed,
+                        button,
+                        ..
+                    },
+                ..
+            } if button != MouseButton::Left
+                && state.hotkey_capture == HotkeyCapture::WaitingForKey =>
+            {
+                // Side buttons arrive as Other(1) / Other(2)
+                let key_name = match button {
+                    MouseButton::Middle => "Mouse3".to_string(),
+                    MouseButton::Other(1) => "Mouse4".to_string(),
+                    MouseButton::Other(2) => "Mouse5".to_string(),
+                    MouseButton::Right => "Mouse2".to_string(),
+                    MouseButton::Other(n) => format!("Mouse{}", n + 3),
+                    _ => return,
+                };
+                capture_hotkey(&mut state, &key_name);
+                window.request_redraw();
+            }
             Event::WindowEvent {
                 event:
                     WindowEvent::MouseInput {
@@ -624,7 +970,7 @@ fn run_setup_inner(from_settings: bool) -> ! {
                 }
             }
             Event::MainEventsCleared => {
-                if state.download_progress.is_some() {
+                if state.download_progress.is_some() || state.mic_test.is_some() {
                     std::thread::sleep(std::time::Duration::from_millis(50));
                 }
                 window.request_redraw();
@@ -641,6 +987,55 @@ enum SetupEvent {
     ExitWithoutConfig,
 }
 
+/// Finish a hotkey capture: prefix the held modifiers and keep the result if it can be bound
+fn capture_hotkey(state: &mut SetupState, key_name: &str) {
+    let mut parts = Vec::new();
+    if state.current_modifiers.control_key() {
+        parts.push("Control");
+    }
+    if state.current_modifiers.alt_key() {
+        parts.push("Alt");
+    }
+    if state.current_modifiers.shift_key() {
+        parts.push("Shift");
+    }
+    if state.current_modifiers.super_key() {
+        parts.push("Super");
+    }
+    parts.push(key_name);
+    let key_str = parts.join("+");
+
+    let target = match state.current_page {
+        SetupPage::HotkeyConfig(target) => target,
+        _ => return,
+    };
+    if let Some(conflict) = state.hotkey_conflict(target, &key_str) {
+        // Keep listening so another key can be tried
+        state.hotkey_error = Some(conflict);
+        return;
+    }
+    match crate::hotkeys::validate_hotkey(&key_str) {
+        Ok(()) => {
+            state.captured_key = Some(key_str);
+            state.hotkey_error = None;
+            state.hotkey_capture = HotkeyCapture::Idle;
+        }
+        // Keep listening so another key can be tried
+        Err(e) => state.hotkey_error = Some(e.to_string()),
+    }
+}
+
+/// Name of the first action in `others` bound to the same combination as `key`
+fn conflicting_hotkey<'a>(
+    key: &str,
+    others: impl IntoIterator<Item = (&'a str, Option<&'a str>)>,
+) -> Option<&'a str> {
+    others
+        .into_iter()
+        .find(|(_, hotkey)| hotkey.is_some_and(|hotkey| crate::hotkeys::same_hotkey(key, hotkey)))
+        .map(|(name, _)| name)
+}
+
 #[allow(dead_code)]
 fn is_modifier_key(keycode: KeyCode) -> bool {
     matches!(keycode,
@@ -762,6 +1157,9 @@ fn get_button_rects(state: &SetupState) -> Vec<ButtonRect> {
         SetupPage::HotkeyConfig(target) => get_hotkey_page_buttons(state, *target),
         SetupPage::CudaConfig => get_cuda_page_buttons(state),
         SetupPage::AudioConfig => get_audio_page_buttons(state),
+        SetupPage::Advanced => get_prompt_page_buttons(state),
+        SetupPage::AlwaysListenTuning => get_tuning_page_buttons(state),
+        SetupPage::Languages => get_languages_page_buttons(state),
     }
 }
 
@@ -854,6 +1252,34 @@ fn get_home_buttons(state: &SetupState) -> Vec<ButtonRect> {
         });
     }
 
+    // Advanced button - right end of the GPU row
+    buttons.push(ButtonRect {
+        x: 390,
+        y,
+        width: 80,
+        height: FIELD_HEIGHT,
+        button: Button::ConfigureAdvanced,
+    });
+    y += 35;
+
+    // Paste mode toggle (row below GPU)
+    buttons.push(ButtonRect {
+        x: 30,
+        y,
+        width: 250,
+        height: FIELD_HEIGHT,
+        button: Button::TypeModeToggle,
+    });
+
+    // Always-listen tuning - same row as the paste toggle
+    buttons.push(ButtonRect {
+        x: 290,
+        y,
+        width: 180,
+        height: FIELD_HEIGHT,
+        button: Button::ConfigureTuning,
+    });
+
     // Start button - fixed position at bottom (matches render at y=440)
     buttons.push(ButtonRect {
         x: 175,
@@ -947,6 +1373,24 @@ fn get_audio_page_buttons(state: &SetupState) -> Vec<ButtonRect> {
         button: Button::ConfirmDevice,
     });
 
+    // Test button (live level meter)
+    buttons.push(ButtonRect {
+        x: 30,
+        y: 62,
+        width: 80,
+        height: 30,
+        button: Button::TestMic,
+    });
+
+    // Calibrate button
+    buttons.push(ButtonRect {
+        x: 30,
+        y: 390,
+        width: 150,
+        height: 35,
+        button: Button::CalibrateMic,
+    });
+
     // Scroll buttons
     buttons.push(ButtonRect {
         x: 450,
@@ -982,6 +1426,186 @@ fn get_audio_page_buttons(state: &SetupState) -> Vec<ButtonRect> {
     buttons
 }
 
+fn get_prompt_page_buttons(_state: &SetupState) -> Vec<ButtonRect> {
+    vec![
+        // Close button at bottom left
+        ButtonRect {
+            x: 50,
+            y: 440,
+            width: 150,
+            height: 45,
+            button: Button::Close,
+        },
+        // Back button
+        ButtonRect {
+            x: 400,
+            y: 10,
+            width: 80,
+            height: 30,
+            button: Button::Back,
+        },
+        // Save button
+        ButtonRect {
+            x: 300,
+            y: 440,
+            width: 150,
+            height: 35,
+            button: Button::ConfirmPrompt,
+        },
+        // Clear button
+        ButtonRect {
+            x: 30,
+            y: 390,
+            width: 100,
+            height: 35,
+            button: Button::ClearPrompt,
+        },
+        // CPU thread count
+        ButtonRect {
+            x: 300,
+            y: 390,
+            width: 40,
+            height: 35,
+            button: Button::CpuThreadsDecrease,
+        },
+        ButtonRect {
+            x: 430,
+            y: 390,
+            width: 40,
+            height: 35,
+            button: Button::CpuThreadsIncrease,
+        },
+        // Start at sign-in
+        ButtonRect {
+            x: 30,
+            y: 340,
+            width: 250,
+            height: 30,
+            button: Button::AutostartToggle,
+        },
+        // Allowed languages
+        ButtonRect {
+            x: 300,
+            y: 335,
+            width: 170,
+            height: 28,
+            button: Button::ConfigureLanguages,
+        },
+        // Clicking the prompt text focuses it
+        ButtonRect {
+            x: 30,
+            y: 120,
+            width: 440,
+            height: 20 * VISIBLE_PROMPT_LINES as u32 + 10,
+            button: Button::PromptField,
+        },
+    ]
+}
+
+/// Position of a language toggle on the Languages page
+fn language_button_rect(idx: usize) -> ButtonRect {
+    ButtonRect {
+        x: 30 + (idx / LANGUAGE_ROWS) as u32 * 225,
+        y: 100 + (idx % LANGUAGE_ROWS) as u32 * 34,
+        width: 215,
+        height: 30,
+        button: Button::Language(idx),
+    }
+}
+
+fn get_languages_page_buttons(_state: &SetupState) -> Vec<ButtonRect> {
+    let mut buttons: Vec<ButtonRect> =
+        (0..COMMON_LANGUAGES.len()).map(language_button_rect).collect();
+    buttons.extend([
+        // Close button at bottom left
+        ButtonRect {
+            x: 50,
+            y: 440,
+            width: 150,
+            height: 45,
+            button: Button::Close,
+        },
+        // Back button
+        ButtonRect {
+            x: 400,
+            y: 10,
+            width: 80,
+            height: 30,
+            button: Button::Back,
+        },
+        // Save button
+        ButtonRect {
+            x: 300,
+            y: 440,
+            width: 150,
+            height: 35,
+            button: Button::ConfirmLanguages,
+        },
+        // Clear button
+        ButtonRect {
+            x: 30,
+            y: 390,
+            width: 100,
+            height: 35,
+            button: Button::ClearLanguages,
+        },
+    ]);
+    buttons
+}
+
+/// Rows on the always-listen tuning page: (y, decrease, increase)
+const TUNING_ROWS: [(u32, Button, Button); 3] = [
+    (100, Button::VadThresholdDecrease, Button::VadThresholdIncrease),
+    (190, Button::SilenceTimeoutDecrease, Button::SilenceTimeoutIncrease),
+    (280, Button::MinSpeechDecrease, Button::MinSpeechIncrease),
+];
+
+fn get_tuning_page_buttons(_state: &SetupState) -> Vec<ButtonRect> {
+    let mut buttons = vec![
+        // Close button at bottom left
+        ButtonRect {
+            x: 50,
+            y: 440,
+            width: 150,
+            height: 45,
+            button: Button::Close,
+        },
+        // Back button
+        ButtonRect {
+            x: 400,
+            y: 10,
+            width: 80,
+            height: 30,
+            button: Button::Back,
+        },
+        // Save button
+        ButtonRect {
+            x: 300,
+            y: 440,
+            width: 150,
+            height: 35,
+            button: Button::ConfirmTuning,
+        },
+    ];
+    for (y, decrease, increase) in TUNING_ROWS {
+        buttons.push(ButtonRect {
+            x: 150,
+            y,
+            width: 40,
+            height: 35,
+            button: decrease,
+        });
+        buttons.push(ButtonRect {
+            x: 310,
+            y,
+            width: 40,
+            height: 35,
+            button: increase,
+        });
+    }
+    buttons
+}
+
 fn get_model_page_buttons(state: &SetupState) -> Vec<ButtonRect> {
     let mut buttons = Vec::new();
 
@@ -1003,12 +1627,21 @@ fn get_model_page_buttons(state: &SetupState) -> Vec<ButtonRect> {
         button: Button::Back,
     });
 
-    // Get model count from unified list
-    let model_count = state.all_models.len();
+    // Filter box
+    buttons.push(ButtonRect {
+        x: 160,
+        y: 10,
+        width: 230,
+        height: 30,
+        button: Button::ModelFilterField,
+    });
 
-    // Model list items
+    // Model list items (indices map back into all_models)
+    let filtered = state.filtered_models();
+    let model_count = filtered.len();
     let end_idx = (state.model_scroll_offset + VISIBLE_MODELS).min(model_count);
-    for (display_idx, model_idx) in (state.model_scroll_offset..end_idx).enumerate() {
+    let visible = filtered.iter().skip(state.model_scroll_offset).take(VISIBLE_MODELS);
+    for (display_idx, &model_idx) in visible.enumerate() {
         buttons.push(ButtonRect {
             x: 30,
             y: 60 + (display_idx as u32 * 40),
@@ -1056,6 +1689,26 @@ fn get_model_page_buttons(state: &SetupState) -> Vec<ButtonRect> {
         button: Button::OpenLink,
     });
 
+    // Delete button
+    buttons.push(ButtonRect {
+        x: 290,
+        y: 310,
+        width: 120,
+        height: 35,
+        button: Button::DeleteModel,
+    });
+
+    // Compute type (only for models that offer a choice)
+    if state.selected_model_info().is_some_and(|m| !m.compute_types.is_empty()) {
+        buttons.push(ButtonRect {
+            x: 290,
+            y: 392,
+            width: 180,
+            height: 30,
+            button: Button::ComputeTypeCycle,
+        });
+    }
+
     buttons
 }
 
@@ -1107,6 +1760,17 @@ fn get_hotkey_page_buttons(state: &SetupState, target: HotkeyTarget) -> Vec<Butt
         button: Button::ClearHotkey,
     });
 
+    // Hold/toggle mode (only for Push-to-Talk)
+    if target == HotkeyTarget::PushToTalk {
+        buttons.push(ButtonRect {
+            x: 100,
+            y: 365,
+            width: 300,
+            height: 35,
+            button: Button::PushToTalkModeToggle,
+        });
+    }
+
     // Silence timeout controls (only for Toggle Listen)
     if target == HotkeyTarget::ToggleListening {
         // Decrease button (-)
@@ -1140,6 +1804,10 @@ fn is_inside(pos: (f64, f64), btn: &ButtonRect) -> bool {
 }
 
 fn handle_click(state: &mut SetupState, button: Button) -> Option<SetupEvent> {
+    // Any other click cancels a pending delete
+    if button != Button::DeleteModel {
+        state.delete_pending = None;
+    }
     match button {
         // Home page
         Button::SelectModel => {
@@ -1149,6 +1817,7 @@ fn handle_click(state: &mut SetupState, button: Button) -> Option<SetupEvent> {
             }
             state.current_page = SetupPage::ModelSelection;
             state.model_scroll_offset = 0;
+            state.focused_field = Some(FieldId::ModelFilter);
             if state.selected_model.is_none() {
                 state.status = "Select a model from the list".to_string();
             }
@@ -1178,6 +1847,22 @@ fn handle_click(state: &mut SetupState, button: Button) -> Option<SetupEvent> {
             state.current_page = SetupPage::CudaConfig;
             None
         }
+        Button::ConfigureAdvanced => {
+            state.current_page = SetupPage::Advanced;
+            state.focused_field = Some(FieldId::InitialPrompt);
+            None
+        }
+        Button::ConfigureTuning => {
+            state.current_page = SetupPage::AlwaysListenTuning;
+            None
+        }
+        Button::TypeModeToggle => {
+            state.type_mode = match state.type_mode {
+                TypeMode::Keystrokes => TypeMode::ClipboardPaste,
+                TypeMode::ClipboardPaste => TypeMode::Keystrokes,
+            };
+            None
+        }
         Button::Start => {
             if state.selected_model.is_none() {
                 state.status = "Please select a model first!".to_string();
@@ -1212,6 +1897,62 @@ fn handle_click(state: &mut SetupState, button: Button) -> Option<SetupEvent> {
                 config.overlay_visible = state.overlay_visible;
                 config.overlay_x = state.overlay_x;
                 config.overlay_y = state.overlay_y;
+                config.vad_noise_floor = state.vad_noise_floor;
+                config.vad_threshold = state.vad_threshold;
+                config.vad_min_speech_ms = state.vad_min_speech_ms;
+                config.initial_prompt = state.initial_prompt.text().trim().to_string();
+                config.type_mode = state.type_mode;
+                config.push_to_talk_hold = state.push_to_talk_hold;
+                config.compute_type = state.compute_type.clone();
+                config.cpu_threads = state.cpu_threads;
+                config.allowed_languages = state.allowed_languages.clone();
+                config.autostart = state.autostart;
+                // Keep settings that the wizard doesn't edit
+                if let Ok(existing) = Config::load() {
+                    config.profiles = existing.profiles;
+                    config.hotkey_mute = existing.hotkey_mute;
+                    config.hotkey_pause_listen = existing.hotkey_pause_listen;
+                    config.double_tap_ms = existing.double_tap_ms;
+                    config.incremental_typing = existing.incremental_typing;
+                    config.output_mode = existing.output_mode;
+                    config.clipboard_append = existing.clipboard_append;
+                    config.trailing_char = existing.trailing_char;
+                    config.restore_focus = existing.restore_focus;
+                    config.pre_roll_ms = existing.pre_roll_ms;
+                    config.chunk_seconds = existing.chunk_seconds;
+                    config.keep_warm = existing.keep_warm;
+                    config.keep_warm_minutes = existing.keep_warm_minutes;
+                    config.highpass_enabled = existing.highpass_enabled;
+                    config.normalize_enabled = existing.normalize_enabled;
+                    config.download_concurrency = existing.download_concurrency;
+                    config.overlay_always_on_top = existing.overlay_always_on_top;
+                    config.overlay_click_through = existing.overlay_click_through;
+                    config.overlay_show_text = existing.overlay_show_text;
+                    config.audio_cues = existing.audio_cues;
+                    config.audio_cue_device = existing.audio_cue_device;
+                    config.history_size = existing.history_size;
+                    config.history_persist = existing.history_persist;
+                    config.fallback_model = existing.fallback_model;
+                    config.transcript_log_path = existing.transcript_log_path;
+                    config.transcript_export_dir = existing.transcript_export_dir;
+                    config.backends_dir = existing.backends_dir;
+                    config.models_dir = existing.models_dir;
+                    config.replacements = existing.replacements;
+                    config.fix_common_errors = existing.fix_common_errors;
+                    config.dictation_commands = existing.dictation_commands;
+                    config.command_phrases = existing.command_phrases;
+                    config.decoding = existing.decoding;
+                    config.max_repeats = existing.max_repeats;
+                    config.quick_languages = existing.quick_languages;
+                    config.language = existing.language;
+                    config.hallucination_phrases = existing.hallucination_phrases;
+                    config.hallucination_max_rms = existing.hallucination_max_rms;
+                    config.no_speech_threshold = existing.no_speech_threshold;
+                    config.vad_pre_roll_ms = existing.vad_pre_roll_ms;
+                    config.noise_gate_threshold = existing.noise_gate_threshold;
+                    config.noise_gate_attack_ms = existing.noise_gate_attack_ms;
+                    config.noise_gate_release_ms = existing.noise_gate_release_ms;
+                }
                 if let Err(e) = config.save() {
                     state.status = format!("Error saving config: {}", e);
                     return None;
@@ -1258,6 +1999,11 @@ fn handle_click(state: &mut SetupState, button: Button) -> Option<SetupEvent> {
             // Auto-select backend based on chosen model
             let unified = &state.all_models[idx];
             state.selected_backend_id = Some(unified.backend_id.clone());
+            // A compute type only carries over if the new model offers it too
+            let offered = |ct: &String| unified.model.compute_types.contains(ct);
+            if !state.compute_type.as_ref().is_some_and(offered) {
+                state.compute_type = None;
+            }
             state.model_downloaded = state.check_model_exists();
             if state.model_downloaded {
                 state.status = "Model ready! Click Back then Start.".to_string();
@@ -1299,24 +2045,74 @@ fn handle_click(state: &mut SetupState, button: Button) -> Option<SetupEvent> {
                     &backend_id,
                     &model,
                     dest_folder,
+                    state.download_concurrency,
                 ));
             }
             None
         }
+        Button::ComputeTypeCycle => {
+            if let Some(model) = state.selected_model_info() {
+                let current = state.compute_type.as_deref().or(model.default_compute_type.as_deref());
+                state.compute_type = next_compute_type(&model.compute_types, current);
+            }
+            None
+        }
         Button::OpenLink => {
             if let Some(model) = state.selected_model_info() {
                 let _ = open::that(&model.download_url);
             }
             None
         }
+        Button::DeleteModel => {
+            let (Some(idx), Ok(models_dir)) = (state.selected_model, get_models_dir()) else {
+                state.status = "Select a model first!".to_string();
+                return None;
+            };
+            if state.download_progress.is_some() {
+                return None;
+            }
+            if !state.model_downloaded {
+                state.status = "This model isn't downloaded.".to_string();
+                return None;
+            }
+            let unified = &state.all_models[idx];
+            let folder = models_dir.join(&unified.model.folder_name);
+            if state.delete_pending != Some(idx) {
+                state.delete_pending = Some(idx);
+                let in_use = state.active_model_path.as_ref().is_some_and(|p| p.starts_with(&folder));
+                state.status = if in_use {
+                    "This is the model in use! Click Delete again to remove it.".to_string()
+                } else {
+                    let size = format_disk_size(dir_size(&folder));
+                    format!("Click Delete again to remove {} ({}).", unified.model.display_name, size)
+                };
+                return None;
+            }
+            state.delete_pending = None;
+            match std::fs::remove_dir_all(&folder) {
+                Ok(()) => {
+                    state.status = format!("Deleted {}.", unified.model.display_name);
+                }
+                Err(e) => {
+                    state.status = format!("Error deleting model: {}", e);
+                }
+            }
+            state.model_downloaded = state.check_model_exists();
+            state.models_disk_bytes = models_disk_usage();
+            None
+        }
         Button::ModelScrollUp => {
             if state.model_scroll_offset > 0 {
                 state.model_scroll_offset -= 1;
             }
             None
         }
+        Button::ModelFilterField => {
+            state.focused_field = Some(FieldId::ModelFilter);
+            None
+        }
         Button::ModelScrollDown => {
-            let model_count = state.all_models.len();
+            let model_count = state.filtered_models().len();
             let max_offset = model_count.saturating_sub(VISIBLE_MODELS);
             if state.model_scroll_offset < max_offset {
                 state.model_scroll_offset += 1;
@@ -1351,6 +2147,7 @@ fn handle_click(state: &mut SetupState, button: Button) -> Option<SetupEvent> {
         Button::Back => {
             state.current_page = SetupPage::Home;
             state.hotkey_capture = HotkeyCapture::Idle;
+            state.hotkey_error = None;
             // Update status for home page
             if state.selected_model.is_some() && state.model_downloaded {
                 state.status = "Ready! Click Start to begin.".to_string();
@@ -1364,6 +2161,8 @@ fn handle_click(state: &mut SetupState, button: Button) -> Option<SetupEvent> {
         Button::ConfirmDevice => {
             if let Ok(mut config) = Config::load() {
                 config.input_device_name = state.selected_input_device.clone();
+                config.vad_noise_floor = state.vad_noise_floor;
+                config.vad_threshold = state.vad_threshold;
                 if let Err(e) = config.save() {
                     state.status = format!("Error saving microphone: {}", e);
                 }
@@ -1371,6 +2170,98 @@ fn handle_click(state: &mut SetupState, button: Button) -> Option<SetupEvent> {
             state.current_page = SetupPage::Home;
             None
         }
+        Button::PromptField => {
+            state.focused_field = Some(FieldId::InitialPrompt);
+            None
+        }
+        Button::ConfirmPrompt => {
+            let prompt = state.initial_prompt.text().trim().to_string();
+            state.initial_prompt.set_text(&prompt);
+            if let Ok(mut config) = Config::load() {
+                config.initial_prompt = prompt;
+                config.cpu_threads = state.cpu_threads;
+                config.autostart = state.autostart;
+                if let Err(e) = config.save() {
+                    state.status = format!("Error saving prompt: {}", e);
+                }
+            }
+            state.focused_field = None;
+            state.current_page = SetupPage::Home;
+            None
+        }
+        Button::ClearPrompt => {
+            state.initial_prompt.clear();
+            state.focused_field = Some(FieldId::InitialPrompt);
+            None
+        }
+        Button::CpuThreadsDecrease => {
+            state.cpu_threads = state.cpu_threads.saturating_sub(1);
+            None
+        }
+        Button::CpuThreadsIncrease => {
+            state.cpu_threads = (state.cpu_threads + 1).min(max_cpu_threads());
+            None
+        }
+        Button::ConfigureLanguages => {
+            state.current_page = SetupPage::Languages;
+            state.focused_field = None;
+            None
+        }
+        Button::Language(idx) => {
+            let code = COMMON_LANGUAGES[idx].0;
+            match state.allowed_languages.iter().position(|l| l == code) {
+                Some(pos) => {
+                    state.allowed_languages.remove(pos);
+                }
+                None => state.allowed_languages.push(code.to_string()),
+            }
+            None
+        }
+        Button::ClearLanguages => {
+            state.allowed_languages.clear();
+            None
+        }
+        Button::ConfirmLanguages => {
+            if let Ok(mut config) = Config::load() {
+                config.allowed_languages = state.allowed_languages.clone();
+                if let Err(e) = config.save() {
+                    state.status = format!("Error saving languages: {}", e);
+                }
+            }
+            state.current_page = SetupPage::Home;
+            None
+        }
+        Button::AutostartToggle => {
+            if let Err(e) = autostart::set_enabled(!state.autostart) {
+                state.status = format!("Failed to change autostart: {:#}", e);
+            }
+            // Show what the registry now says, even if the write failed
+            state.autostart = autostart::is_enabled();
+            None
+        }
+        Button::TestMic => {
+            if state.mic_test.take().is_none() {
+                match MicTest::start(state.selected_input_device.as_deref()) {
+                    Ok(test) => state.mic_test = Some(test),
+                    Err(e) => state.status = format!("Mic test failed: {:#}", e),
+                }
+            }
+            None
+        }
+        Button::CalibrateMic => {
+            if state.calibration.is_some() {
+                return None;
+            }
+            let device = state.selected_input_device.clone();
+            let (tx, rx) = mpsc::channel();
+            std::thread::spawn(move || {
+                let _ = tx.send(calibrate_noise_floor(device.as_deref()));
+            });
+            state.calibration = Some((rx, Instant::now()));
+            state.calibration_error = None;
+            state.status = "Calibrating... stay quiet for 2 seconds.".to_string();
+            None
+        }
 
         // CUDA config page
         Button::DetectCuda => {
@@ -1423,10 +2314,19 @@ fn handle_click(state: &mut SetupState, button: Button) -> Option<SetupEvent> {
             state.hotkey_capture = HotkeyCapture::WaitingForKey;
             // Reset captured key when entering capture mode
             state.captured_key = None;
+            state.hotkey_error = None;
             None
         }
         Button::ConfirmHotkey => {
             if let SetupPage::HotkeyConfig(target) = state.current_page {
+                let conflict = state
+                    .captured_key
+                    .as_deref()
+                    .and_then(|key| state.hotkey_conflict(target, key));
+                if let Some(conflict) = conflict {
+                    state.hotkey_error = Some(conflict);
+                    return None;
+                }
                 state.set_hotkey(target, state.captured_key.clone());
             }
             if let Ok(mut config) = Config::load() {
@@ -1439,6 +2339,7 @@ fn handle_click(state: &mut SetupState, button: Button) -> Option<SetupEvent> {
                     .clone()
                     .unwrap_or_else(|| "Control+Backquote".to_string());
                 config.silence_timeout_ms = state.silence_timeout_ms;
+                config.push_to_talk_hold = state.push_to_talk_hold;
                 if let Err(e) = config.save() {
                     state.status = format!("Error saving hotkeys: {}", e);
                 }
@@ -1455,23 +2356,95 @@ fn handle_click(state: &mut SetupState, button: Button) -> Option<SetupEvent> {
             state.captured_key = None;
             None
         }
+        Button::PushToTalkModeToggle => {
+            state.push_to_talk_hold = !state.push_to_talk_hold;
+            None
+        }
         Button::SilenceTimeoutDecrease => {
             // Decrease by 100ms (0.1s), minimum 100ms (0.1s)
-            if state.silence_timeout_ms > 100 {
-                state.silence_timeout_ms = state.silence_timeout_ms.saturating_sub(100);
-            }
+            let value = state.silence_timeout_ms.saturating_sub(100);
+            state.silence_timeout_ms = value.max(*POST_SILENCE_MS_RANGE.start());
             None
         }
         Button::SilenceTimeoutIncrease => {
             // Increase by 100ms (0.1s), maximum 5000ms (5 seconds)
-            if state.silence_timeout_ms < 5000 {
-                state.silence_timeout_ms = state.silence_timeout_ms.saturating_add(100);
+            let value = state.silence_timeout_ms.saturating_add(100);
+            state.silence_timeout_ms = value.min(*POST_SILENCE_MS_RANGE.end());
+            None
+        }
+
+        // Always-listen tuning page
+        Button::VadThresholdDecrease | Button::VadThresholdIncrease => {
+            let current = effective_threshold(state.vad_threshold, state.vad_noise_floor);
+            let up = button == Button::VadThresholdIncrease;
+            state.vad_threshold = Some(step_threshold(current, up));
+            None
+        }
+        Button::MinSpeechDecrease => {
+            let value = state.vad_min_speech_ms.saturating_sub(MIN_SPEECH_STEP_MS);
+            state.vad_min_speech_ms = value.max(*MIN_SPEECH_MS_RANGE.start());
+            None
+        }
+        Button::MinSpeechIncrease => {
+            let value = state.vad_min_speech_ms.saturating_add(MIN_SPEECH_STEP_MS);
+            state.vad_min_speech_ms = value.min(*MIN_SPEECH_MS_RANGE.end());
+            None
+        }
+        Button::ConfirmTuning => {
+            if let Ok(mut config) = Config::load() {
+                config.vad_threshold = state.vad_threshold;
+                config.silence_timeout_ms = state.silence_timeout_ms;
+                config.vad_min_speech_ms = state.vad_min_speech_ms;
+                if let Err(e) = config.save() {
+                    state.status = format!("Error saving tuning: {}", e);
+                }
             }
+            state.current_page = SetupPage::Home;
             None
         }
     }
 }
 
+/// Next VAD threshold up or down (25% steps, rounded to 4 decimals and clamped)
+fn step_threshold(threshold: f32, up: bool) -> f32 {
+    let next = if up { threshold * 1.25 } else { threshold / 1.25 };
+    let rounded = (next * 10_000.0).round() / 10_000.0;
+    rounded.clamp(*VAD_THRESHOLD_RANGE.start(), *VAD_THRESHOLD_RANGE.end())
+}
+
+/// Most CPU threads the wizard offers (the machine's logical cores)
+fn max_cpu_threads() -> u32 {
+    std::thread::available_parallelism().map_or(1, |n| n.get() as u32)
+}
+
+/// CPU thread count for display ("Auto" = library default)
+fn format_cpu_threads(cpu_threads: u32) -> String {
+    if cpu_threads == 0 {
+        "Auto".to_string()
+    } else {
+        cpu_threads.to_string()
+    }
+}
+
+/// Allowed languages for display ("Any" = no restriction)
+fn format_allowed_languages(languages: &[String]) -> String {
+    if languages.is_empty() {
+        "Any".to_string()
+    } else {
+        languages.join(", ")
+    }
+}
+
+/// Compute type after `current` in the manifest's list, wrapping around
+/// (the first one if `current` isn't listed)
+fn next_compute_type(allowed: &[String], current: Option<&str>) -> Option<String> {
+    let next = match allowed.iter().position(|ct| Some(ct.as_str()) == current) {
+        Some(pos) => (pos + 1) % allowed.len(),
+        None => 0,
+    };
+    allowed.get(next).cloned()
+}
+
 fn render(state: &SetupState, buffer: &mut [u32], width: u32, height: u32) {
     // Clear background
     for pixel in buffer.iter_mut() {
@@ -1484,6 +2457,9 @@ fn render(state: &SetupState, buffer: &mut [u32], width: u32, height: u32) {
         SetupPage::HotkeyConfig(target) => render_hotkey_page(state, buffer, width, height, *target),
         SetupPage::CudaConfig => render_cuda_page(state, buffer, width, height),
         SetupPage::AudioConfig => render_audio_page(state, buffer, width, height),
+        SetupPage::Advanced => render_prompt_page(state, buffer, width, height),
+        SetupPage::AlwaysListenTuning => render_tuning_page(state, buffer, width, height),
+        SetupPage::Languages => render_languages_page(state, buffer, width, height),
     }
 }
 
@@ -1589,6 +2565,25 @@ fn render_home_page(state: &SetupState, buffer: &mut [u32], width: u32, _height:
         let cuda_status = if state.cuda_valid { "OK" } else { "Setup" };
         draw_text(buffer, width, 310, y + TEXT_OFFSET, cuda_status, if state.cuda_valid { PROGRESS_FG } else { TEXT_COLOR });
     }
+
+    // Advanced button (highlighted when a prompt is set)
+    let advanced_bg = if state.hovered_button == Some(Button::ConfigureAdvanced) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 390, y, 80, FIELD_HEIGHT, advanced_bg);
+    let advanced_color = if state.initial_prompt.text().trim().is_empty() { TEXT_COLOR } else { PROGRESS_FG };
+    draw_text(buffer, width, 398, y + TEXT_OFFSET, "Advanced", advanced_color);
+    y += 35;
+
+    // Paste mode toggle
+    let paste_bg = if state.hovered_button == Some(Button::TypeModeToggle) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 30, y, 250, FIELD_HEIGHT, paste_bg);
+    let paste_indicator = if state.type_mode == TypeMode::ClipboardPaste { "[x]" } else { "[ ]" };
+    let paste_text = format!("{} Paste via clipboard", paste_indicator);
+    draw_text(buffer, width, 40, y + TEXT_OFFSET, &paste_text, TEXT_COLOR);
+
+    // Always-listen tuning button
+    let tuning_bg = if state.hovered_button == Some(Button::ConfigureTuning) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 290, y, 180, FIELD_HEIGHT, tuning_bg);
+    draw_text(buffer, width, 300, y + TEXT_OFFSET, "Always-Listen Tuning", TEXT_COLOR);
     y += 35;
 
     // CUDA status (when GPU enabled)
@@ -1642,7 +2637,7 @@ fn render_cuda_page(state: &SetupState, buffer: &mut [u32], width: u32, _height:
     let cuda_text = state.cuda_path.as_ref()
         .map(|p| p.to_string_lossy().into_owned())
         .unwrap_or_else(|| "Not detected".to_string());
-    let cuda_text_short = if cuda_text.len() > 40 { format!("...{}", &cuda_text[cuda_text.len()-37..]) } else { cuda_text };
+    let cuda_text_short = ellipsize_start(&cuda_text, 40);
     draw_text(buffer, width, 40, 100, &cuda_text_short, if state.cuda_valid { TEXT_COLOR } else { DIM_TEXT });
 
     // CUDA status indicator
@@ -1660,7 +2655,7 @@ fn render_cuda_page(state: &SetupState, buffer: &mut [u32], width: u32, _height:
     let cudnn_text = state.cudnn_path.as_ref()
         .map(|p| p.to_string_lossy().into_owned())
         .unwrap_or_else(|| "Not detected".to_string());
-    let cudnn_text_short = if cudnn_text.len() > 40 { format!("...{}", &cudnn_text[cudnn_text.len()-37..]) } else { cudnn_text };
+    let cudnn_text_short = ellipsize_start(&cudnn_text, 40);
     draw_text(buffer, width, 40, 170, &cudnn_text_short, if state.cudnn_valid { TEXT_COLOR } else { DIM_TEXT });
 
     // cuDNN status indicator
@@ -1741,12 +2736,230 @@ fn render_audio_page(state: &SetupState, buffer: &mut [u32], width: u32, _height
     let confirm_bg = if state.hovered_button == Some(Button::ConfirmDevice) { BUTTON_HOVER } else { BUTTON_COLOR };
     draw_rect(buffer, width, 300, 440, 150, 35, confirm_bg);
     draw_text(buffer, width, 330, 450, "Use Selected", TEXT_COLOR);
+
+    // Test button and live level meter
+    let test_bg = if state.hovered_button == Some(Button::TestMic) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 30, 62, 80, 30, test_bg);
+    let test_label = if state.mic_test.is_some() { "Stop" } else { "Test" };
+    draw_text(buffer, width, 50, 72, test_label, TEXT_COLOR);
+    if let Some(test) = &state.mic_test {
+        draw_rect(buffer, width, 125, 72, 300, 10, PROGRESS_BG);
+        let filled = (crate::overlay::level_fraction(test.level()) * 300.0) as u32;
+        draw_rect(buffer, width, 125, 72, filled, 10, PROGRESS_FG);
+    } else {
+        draw_text(buffer, width, 125, 72, "Speak to check the selected mic", DIM_TEXT);
+    }
+
+    // Calibrate button and measured level
+    let calibrate_bg = if state.hovered_button == Some(Button::CalibrateMic) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 30, 390, 150, 35, calibrate_bg);
+    let calibrate_label = if state.calibration.is_some() { "Listening..." } else { "Calibrate Mic" };
+    draw_text(buffer, width, 45, 402, calibrate_label, TEXT_COLOR);
+    let level = if state.calibration.is_some() {
+        "Stay quiet...".to_string()
+    } else if let Some(ref err) = state.calibration_error {
+        truncate_chars(err, 37).to_string()
+    } else if let Some(floor) = state.vad_noise_floor {
+        let threshold = effective_threshold(state.vad_threshold, Some(floor));
+        format!("Noise {:.4}, threshold {:.4}", floor, threshold)
+    } else {
+        "Not calibrated".to_string()
+    };
+    draw_text(buffer, width, 195, 402, &level, DIM_TEXT);
+}
+
+fn render_prompt_page(state: &SetupState, buffer: &mut [u32], width: u32, _height: u32) {
+    // Header
+    draw_rect(buffer, width, 0, 0, width, 50, HEADER_BG);
+    draw_text(buffer, width, 20, 20, "Advanced Settings", TEXT_COLOR);
+
+    // Back button
+    let back_bg = if state.hovered_button == Some(Button::Back) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 400, 10, 80, 30, back_bg);
+    draw_text(buffer, width, 420, 20, "Back", TEXT_COLOR);
+
+    // Close button at bottom left
+    let close_bg = if state.hovered_button == Some(Button::Close) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 50, 440, 150, 45, close_bg);
+    draw_text(buffer, width, 100, 458, "Close", TEXT_COLOR);
+
+    draw_text(buffer, width, 30, 62, "Vocabulary prompt", TEXT_COLOR);
+    draw_text(buffer, width, 30, 80, "Names and terms you often dictate, e.g.", DIM_TEXT);
+    draw_text(buffer, width, 30, 95, "\"Kubernetes, kubectl, PostgreSQL\".", DIM_TEXT);
+
+    let focused = state.focused_field == Some(FieldId::InitialPrompt);
+    let field_bg = if focused { CAPTURE_BG } else { FIELD_BG };
+    draw_rect(buffer, width, 30, 120, 440, 20 * VISIBLE_PROMPT_LINES as u32 + 10, field_bg);
+    draw_text_field(buffer, width, 40, 130, &state.initial_prompt, PROMPT_LINE_CHARS, VISIBLE_PROMPT_LINES, focused);
+    let count = format!("{}/{}", state.initial_prompt.char_count(), state.initial_prompt.max_chars());
+    draw_text(buffer, width, 30, 300, &count, DIM_TEXT);
+    draw_text(buffer, width, 30, 320, "Used by whisper.cpp models.", DIM_TEXT);
+
+    // CPU threads (Auto = the inference library's default)
+    draw_text(buffer, width, 300, 370, "CPU threads:", TEXT_COLOR);
+    let dec_bg = if state.hovered_button == Some(Button::CpuThreadsDecrease) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 300, 390, 40, 35, dec_bg);
+    draw_text(buffer, width, 315, 402, "-", TEXT_COLOR);
+    let threads = format_cpu_threads(state.cpu_threads);
+    draw_rect(buffer, width, 345, 390, 80, 35, FIELD_BG);
+    draw_text(buffer, width, 385 - threads.len() as u32 * 4, 402, &threads, TEXT_COLOR);
+    let inc_bg = if state.hovered_button == Some(Button::CpuThreadsIncrease) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 430, 390, 40, 35, inc_bg);
+    draw_text(buffer, width, 442, 402, "+", TEXT_COLOR);
+
+    // Start at sign-in
+    let autostart_bg = if state.hovered_button == Some(Button::AutostartToggle) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 30, 340, 250, 30, autostart_bg);
+    let autostart_indicator = if state.autostart { "[x]" } else { "[ ]" };
+    draw_text(buffer, width, 40, 350, &format!("{} Start with Windows", autostart_indicator), TEXT_COLOR);
+
+    // Allowed languages
+    let languages_bg = if state.hovered_button == Some(Button::ConfigureLanguages) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 300, 335, 170, 28, languages_bg);
+    let languages = format!("Languages: {}", format_allowed_languages(&state.allowed_languages));
+    draw_text(buffer, width, 308, 345, truncate_chars(&languages, 20), TEXT_COLOR);
+
+    // Clear button
+    let clear_bg = if state.hovered_button == Some(Button::ClearPrompt) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 30, 390, 100, 35, clear_bg);
+    draw_text(buffer, width, 60, 402, "Clear", TEXT_COLOR);
+
+    // Save button
+    let confirm_bg = if state.hovered_button == Some(Button::ConfirmPrompt) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 300, 440, 150, 35, confirm_bg);
+    draw_text(buffer, width, 355, 450, "Save", TEXT_COLOR);
+}
+
+fn render_languages_page(state: &SetupState, buffer: &mut [u32], width: u32, _height: u32) {
+    // Header
+    draw_rect(buffer, width, 0, 0, width, 50, HEADER_BG);
+    draw_text(buffer, width, 20, 20, "Languages", TEXT_COLOR);
+
+    // Back button
+    let back_bg = if state.hovered_button == Some(Button::Back) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 400, 10, 80, 30, back_bg);
+    draw_text(buffer, width, 420, 20, "Back", TEXT_COLOR);
+
+    draw_text(buffer, width, 30, 62, "Auto-detect picks only from the checked languages", DIM_TEXT);
+    draw_text(buffer, width, 30, 78, "(none = any). A single one is always used.", DIM_TEXT);
+
+    for (idx, (code, name)) in COMMON_LANGUAGES.iter().enumerate() {
+        let rect = language_button_rect(idx);
+        let checked = state.allowed_languages.iter().any(|l| l == code);
+        let bg = if checked {
+            SELECTED_COLOR
+        } else if state.hovered_button == Some(rect.button) {
+            BUTTON_HOVER
+        } else {
+            BUTTON_COLOR
+        };
+        draw_rect(buffer, width, rect.x, rect.y, rect.width, rect.height, bg);
+        let indicator = if checked { "[x]" } else { "[ ]" };
+        draw_text(buffer, width, rect.x + 10, rect.y + 10, &format!("{} {} ({})", indicator, name, code), TEXT_COLOR);
+    }
+
+    // Codes set in the config that aren't in the list above
+    let others: Vec<&str> = state
+        .allowed_languages
+        .iter()
+        .map(String::as_str)
+        .filter(|l| !COMMON_LANGUAGES.iter().any(|(code, _)| code == l))
+        .collect();
+    if !others.is_empty() {
+        let text = format!("Also: {}", others.join(", "));
+        draw_text(buffer, width, 150, 402, truncate_chars(&text, 18), DIM_TEXT);
+    }
+
+    // Clear button
+    let clear_bg = if state.hovered_button == Some(Button::ClearLanguages) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 30, 390, 100, 35, clear_bg);
+    draw_text(buffer, width, 60, 402, "Clear", TEXT_COLOR);
+
+    // Close button at bottom left
+    let close_bg = if state.hovered_button == Some(Button::Close) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 50, 440, 150, 45, close_bg);
+    draw_text(buffer, width, 100, 458, "Close", TEXT_COLOR);
+
+    // Save button
+    let confirm_bg = if state.hovered_button == Some(Button::ConfirmLanguages) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 300, 440, 150, 35, confirm_bg);
+    draw_text(buffer, width, 355, 450, "Save", TEXT_COLOR);
+}
+
+fn render_tuning_page(state: &SetupState, buffer: &mut [u32], width: u32, _height: u32) {
+    // Header
+    draw_rect(buffer, width, 0, 0, width, 50, HEADER_BG);
+    draw_text(buffer, width, 20, 20, "Always-Listen Tuning", TEXT_COLOR);
+
+    // Back button
+    let back_bg = if state.hovered_button == Some(Button::Back) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 400, 10, 80, 30, back_bg);
+    draw_text(buffer, width, 420, 20, "Back", TEXT_COLOR);
+
+    // Close button at bottom left
+    let close_bg = if state.hovered_button == Some(Button::Close) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 50, 440, 150, 45, close_bg);
+    draw_text(buffer, width, 100, 458, "Close", TEXT_COLOR);
+
+    let threshold = effective_threshold(state.vad_threshold, state.vad_noise_floor);
+    let threshold_hint = match (state.vad_threshold, state.vad_noise_floor) {
+        (Some(_), _) => "Calibrate Mic (microphone page) re-measures",
+        (None, Some(_)) => "From the calibrated noise level",
+        (None, None) => "Default; try Calibrate Mic on the mic page",
+    };
+    let rows = [
+        ("Speech threshold:", format!("{:.4}", threshold), threshold_hint),
+        (
+            "Silence before transcribing:",
+            format!("{:.1}s", state.silence_timeout_ms as f64 / 1000.0),
+            "Pause that ends an utterance",
+        ),
+        (
+            "Minimum speech:",
+            format!("{}ms", state.vad_min_speech_ms),
+            "Shorter sounds (clicks, coughs) are ignored",
+        ),
+    ];
+    for ((y, decrease, increase), (label, value, hint)) in TUNING_ROWS.into_iter().zip(rows) {
+        draw_text(buffer, width, 100, y - 20, label, TEXT_COLOR);
+
+        let dec_bg = if state.hovered_button == Some(decrease) { BUTTON_HOVER } else { BUTTON_COLOR };
+        draw_rect(buffer, width, 150, y, 40, 35, dec_bg);
+        draw_text(buffer, width, 165, y + 10, "-", TEXT_COLOR);
+
+        draw_rect(buffer, width, 200, y, 100, 35, FIELD_BG);
+        draw_text(buffer, width, 250 - value.len() as u32 * 4, y + 10, &value, TEXT_COLOR);
+
+        let inc_bg = if state.hovered_button == Some(increase) { BUTTON_HOVER } else { BUTTON_COLOR };
+        draw_rect(buffer, width, 310, y, 40, 35, inc_bg);
+        draw_text(buffer, width, 322, y + 10, "+", TEXT_COLOR);
+
+        draw_text(buffer, width, 100, y + 45, hint, DIM_TEXT);
+    }
+
+    // Save button
+    let confirm_bg = if state.hovered_button == Some(Button::ConfirmTuning) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 300, 440, 150, 35, confirm_bg);
+    draw_text(buffer, width, 355, 450, "Save", TEXT_COLOR);
 }
 
 fn render_model_page(state: &SetupState, buffer: &mut [u32], width: u32, _height: u32) {
     // Header
     draw_rect(buffer, width, 0, 0, width, 50, HEADER_BG);
-    draw_text(buffer, width, 20, 15, "Select Model", TEXT_COLOR);
+    draw_text(buffer, width, 20, 12, "Select Model", TEXT_COLOR);
+    let filtered = state.filtered_models();
+    let count = format!("{} of {} models", filtered.len(), state.all_models.len());
+    draw_text(buffer, width, 20, 30, &count, DIM_TEXT);
+
+    // Filter box (placeholder text until something is typed)
+    let filter_focused = state.focused_field == Some(FieldId::ModelFilter);
+    let filter_bg = if filter_focused { CAPTURE_BG } else { FIELD_BG };
+    draw_rect(buffer, width, 160, 10, 230, 30, filter_bg);
+    if state.model_filter.text().is_empty() && !filter_focused {
+        draw_text(buffer, width, 170, 21, "Type to filter", DIM_TEXT);
+    } else {
+        draw_text_field(buffer, width, 170, 21, &state.model_filter, FILTER_LINE_CHARS, 1, filter_focused);
+    }
 
     // Back button
     let back_bg = if state.hovered_button == Some(Button::Back) { BUTTON_HOVER } else { BUTTON_COLOR };
@@ -1764,10 +2977,15 @@ fn render_model_page(state: &SetupState, buffer: &mut [u32], width: u32, _height
         return;
     }
 
-    // Model list (unified from all backends)
-    let model_count = state.all_models.len();
+    if filtered.is_empty() {
+        draw_text(buffer, width, 30, 100, "No models match the filter", DIM_TEXT);
+    }
+
+    // Model list (unified from all backends, narrowed by the filter)
+    let model_count = filtered.len();
     let end_idx = (state.model_scroll_offset + VISIBLE_MODELS).min(model_count);
-    for (display_idx, model_idx) in (state.model_scroll_offset..end_idx).enumerate() {
+    let visible = filtered.iter().skip(state.model_scroll_offset).take(VISIBLE_MODELS);
+    for (display_idx, &model_idx) in visible.enumerate() {
         let y = 60 + (display_idx as u32 * 40);
         let unified = &state.all_models[model_idx];
         let is_selected = state.selected_model == Some(model_idx);
@@ -1787,11 +3005,7 @@ fn render_model_page(state: &SetupState, buffer: &mut [u32], width: u32, _height
         let downloaded = is_unified_model_downloaded(unified);
         let status = if downloaded { " [OK]" } else { "" };
         // Show backend name with model (truncate if too long)
-        let backend_short = if unified.backend_name.len() > 12 {
-            &unified.backend_name[..12]
-        } else {
-            &unified.backend_name
-        };
+        let backend_short = truncate_chars(&unified.backend_name, 12);
         let label = format!("{} {} ({}MB) [{}]{}",
             indicator,
             unified.model.display_name,
@@ -1799,7 +3013,9 @@ fn render_model_page(state: &SetupState, buffer: &mut [u32], width: u32, _height
             backend_short,
             status
         );
-        draw_text(buffer, width, 40, y + 10, &label, TEXT_COLOR);
+        draw_text(buffer, width, 40, y + 6, &label, TEXT_COLOR);
+        let hint = model_hint(&unified.model);
+        draw_text(buffer, width, 40, y + 21, truncate_chars(&hint, 53), DIM_TEXT);
     }
 
     // Scroll indicators
@@ -1820,15 +3036,34 @@ fn render_model_page(state: &SetupState, buffer: &mut [u32], width: u32, _height
     draw_rect(buffer, width, 160, 310, 120, 35, link_bg);
     draw_text(buffer, width, 180, 320, "Open Link", TEXT_COLOR);
 
+    // Delete button (asks for a second click before deleting)
+    let delete_bg = if state.hovered_button == Some(Button::DeleteModel) { BUTTON_HOVER } else { BUTTON_COLOR };
+    draw_rect(buffer, width, 290, 310, 120, 35, delete_bg);
+    if state.delete_pending.is_some() {
+        draw_text(buffer, width, 318, 320, "Confirm", 0xFFFF6666);
+    } else {
+        draw_text(buffer, width, 326, 320, "Delete", TEXT_COLOR);
+    }
+
+    let disk = format!("Models on disk: {}", format_disk_size(state.models_disk_bytes));
+    draw_text(buffer, width, 30, 400, &disk, DIM_TEXT);
+
+    // Compute type selector (ct2 models list the types they support)
+    if let Some(model) = state.selected_model_info().filter(|m| !m.compute_types.is_empty()) {
+        let type_bg = if state.hovered_button == Some(Button::ComputeTypeCycle) { BUTTON_HOVER } else { BUTTON_COLOR };
+        draw_rect(buffer, width, 290, 392, 180, 30, type_bg);
+        let current = state.compute_type.as_deref().or(model.default_compute_type.as_deref()).unwrap_or("default");
+        draw_text(buffer, width, 300, 402, truncate_chars(&format!("Type: {}", current), 20), TEXT_COLOR);
+    }
+
     // Status text
     draw_text(buffer, width, 30, 360, &state.status, DIM_TEXT);
 
     // Progress bar
     if let Some(ref progress) = state.download_progress {
-        let (downloaded, total) = progress.get_progress();
         draw_rect(buffer, width, 30, 375, 440, 15, PROGRESS_BG);
-        if total > 0 {
-            let fill_width = ((downloaded as f64 / total as f64) * 440.0) as u32;
+        let fill_width = (progress.fraction() * 440.0) as u32;
+        if fill_width > 0 {
             draw_rect(buffer, width, 30, 375, fill_width, 15, PROGRESS_FG);
         }
     }
@@ -1890,8 +3125,28 @@ fn render_hotkey_page(state: &SetupState, buffer: &mut [u32], width: u32, _heigh
     draw_rect(buffer, width, 255, 260, 95, 35, clear_bg);
     draw_text(buffer, width, 280, 270, "Clear", TEXT_COLOR);
 
-    // Instructions
-    draw_text(buffer, width, 100, 310, "Click 'Set Hotkey' then press any key", DIM_TEXT);
+    // Instructions, or why the last key was rejected
+    match &state.hotkey_error {
+        Some(err) => draw_text(buffer, width, 20, 310, truncate_chars(err, 57), 0xFFFF6666),
+        None => draw_text(buffer, width, 60, 310, "Click 'Set Hotkey' then press a key or mouse button", DIM_TEXT),
+    }
+
+    // Hold/toggle mode (only for Push-to-Talk)
+    if target == HotkeyTarget::PushToTalk {
+        draw_text(buffer, width, 100, 345, "Mode:", TEXT_COLOR);
+        let mode_bg = if state.hovered_button == Some(Button::PushToTalkModeToggle) { BUTTON_HOVER } else { BUTTON_COLOR };
+        draw_rect(buffer, width, 100, 365, 300, 35, mode_bg);
+        let (hold, toggle) = if state.push_to_talk_hold { ("(o)", "( )") } else { ("( )", "(o)") };
+        let mode_text = format!("{} Hold   {} Press to toggle", hold, toggle);
+        draw_text(buffer, width, 115, 375, &mode_text, TEXT_COLOR);
+
+        let hint = if state.push_to_talk_hold {
+            "Record while the key is held down"
+        } else {
+            "Press once to start, again to stop"
+        };
+        draw_text(buffer, width, 100, 410, hint, DIM_TEXT);
+    }
 
     // Silence timeout control (only for Toggle Listening)
     if target == HotkeyTarget::ToggleListening {
@@ -1926,137 +3181,147 @@ fn format_hotkey_display(key: &str) -> String {
        .replace("Arrow", "")
 }
 
-fn draw_rect(buffer: &mut [u32], buf_width: u32, x: u32, y: u32, w: u32, h: u32, color: u32) {
-    for dy in 0..h {
-        for dx in 0..w {
-            let px = x + dx;
-            let py = y + dy;
-            if px < buf_width {
-                let idx = (py * buf_width + px) as usize;
-                if idx < buffer.len() {
-                    buffer[idx] = color;
-                }
-            }
-        }
+/// First `max_chars` characters of `text` (never splits a multi-byte character)
+pub(crate) fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((idx, _)) => &text[..idx],
+        None => text,
     }
 }
 
-fn draw_text(buffer: &mut [u32], buf_width: u32, x: u32, y: u32, text: &str, color: u32) {
-    let chars: Vec<char> = text.chars().collect();
-    for (i, ch) in chars.iter().enumerate() {
-        draw_char(buffer, buf_width, x + (i as u32 * 8), y, *ch, color);
+/// Total size of the files under `path` (0 if it doesn't exist)
+fn dir_size(path: &std::path::Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else { return 0 };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Bytes used by all downloaded models
+fn models_disk_usage() -> u64 {
+    get_models_dir().map(|dir| dir_size(&dir)).unwrap_or(0)
+}
+
+/// "850 MB" / "2.9 GB"
+fn format_disk_size(bytes: u64) -> String {
+    let mb = bytes as f64 / 1_000_000.0;
+    if mb >= 1000.0 {
+        format!("{:.1} GB", mb / 1000.0)
+    } else {
+        format!("{:.0} MB", mb)
     }
 }
 
-fn draw_char(buffer: &mut [u32], buf_width: u32, x: u32, y: u32, ch: char, color: u32) {
-    let bitmap = get_char_bitmap(ch);
-    for (row, bits) in bitmap.iter().enumerate() {
-        for col in 0..6 {
-            if (bits >> (5 - col)) & 1 == 1 {
-                let px = x + col;
-                let py = y + row as u32;
-                if px < buf_width {
-                    let idx = (py * buf_width + px) as usize;
-                    if idx < buffer.len() {
-                        buffer[idx] = color;
-                    }
-                }
-            }
-        }
+/// Second line of a model's row: its speed/accuracy tier and languages, e.g.
+/// "Fast, less accurate - English only"
+fn model_hint(model: &ManifestModel) -> String {
+    let tier = match model.quality_tier.trim().to_lowercase().as_str() {
+        "" => None,
+        "fast" => Some("Fast, less accurate".to_string()),
+        "balanced" => Some("Balanced".to_string()),
+        "accurate" => Some("Accurate, slower".to_string()),
+        _ => Some(model.quality_tier.trim().to_string()),
+    };
+    let languages = match model.languages.as_slice() {
+        [] if model.is_english_only => "English only".to_string(),
+        [] => "Multilingual".to_string(),
+        [only] if only.eq_ignore_ascii_case("en") => "English only".to_string(),
+        codes if codes.len() <= 4 => codes.join(", "),
+        codes => format!("{} languages", codes.len()),
+    };
+    tier.into_iter().chain([languages]).collect::<Vec<_>>().join(" - ")
+}
+
+/// Indices of the models whose name or backend contains `filter` (ignoring case)
+fn filter_models(models: &[UnifiedModel], filter: &str) -> Vec<usize> {
+    let filter = filter.trim().to_lowercase();
+    models
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| {
+            m.model.display_name.to_lowercase().contains(&filter)
+                || m.backend_name.to_lowercase().contains(&filter)
+        })
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Apply an editing key to a text field; returns false for keys it ignores
+fn edit_text_field(field: &mut TextField, key: &tao::keyboard::Key) -> bool {
+    use tao::keyboard::Key;
+
+    match key {
+        Key::Character(c) => field.insert(c),
+        Key::Space => field.insert(" "),
+        Key::Backspace => field.backspace(),
+        Key::Delete => field.delete(),
+        Key::ArrowLeft => field.move_left(),
+        Key::ArrowRight => field.move_right(),
+        Key::Home => field.move_home(),
+        Key::End => field.move_end(),
+        _ => return false,
     }
+    true
 }
 
-fn get_char_bitmap(ch: char) -> [u8; 7] {
-    match ch {
-        'A' => [0x1E, 0x21, 0x21, 0x3F, 0x21, 0x21, 0x21],
-        'B' => [0x3E, 0x21, 0x21, 0x3E, 0x21, 0x21, 0x3E],
-        'C' => [0x1E, 0x21, 0x20, 0x20, 0x20, 0x21, 0x1E],
-        'D' => [0x3C, 0x22, 0x21, 0x21, 0x21, 0x22, 0x3C],
-        'E' => [0x3F, 0x20, 0x20, 0x3E, 0x20, 0x20, 0x3F],
-        'F' => [0x3F, 0x20, 0x20, 0x3E, 0x20, 0x20, 0x20],
-        'G' => [0x1E, 0x21, 0x20, 0x27, 0x21, 0x21, 0x1E],
-        'H' => [0x21, 0x21, 0x21, 0x3F, 0x21, 0x21, 0x21],
-        'I' => [0x1C, 0x08, 0x08, 0x08, 0x08, 0x08, 0x1C],
-        'J' => [0x01, 0x01, 0x01, 0x01, 0x21, 0x21, 0x1E],
-        'K' => [0x21, 0x22, 0x24, 0x38, 0x24, 0x22, 0x21],
-        'L' => [0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x3F],
-        'M' => [0x21, 0x33, 0x2D, 0x21, 0x21, 0x21, 0x21],
-        'N' => [0x21, 0x31, 0x29, 0x25, 0x23, 0x21, 0x21],
-        'O' => [0x1E, 0x21, 0x21, 0x21, 0x21, 0x21, 0x1E],
-        'P' => [0x3E, 0x21, 0x21, 0x3E, 0x20, 0x20, 0x20],
-        'Q' => [0x1E, 0x21, 0x21, 0x21, 0x25, 0x22, 0x1D],
-        'R' => [0x3E, 0x21, 0x21, 0x3E, 0x24, 0x22, 0x21],
-        'S' => [0x1E, 0x21, 0x20, 0x1E, 0x01, 0x21, 0x1E],
-        'T' => [0x3F, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08],
-        'U' => [0x21, 0x21, 0x21, 0x21, 0x21, 0x21, 0x1E],
-        'V' => [0x21, 0x21, 0x21, 0x21, 0x12, 0x12, 0x0C],
-        'W' => [0x21, 0x21, 0x21, 0x21, 0x2D, 0x33, 0x21],
-        'X' => [0x21, 0x12, 0x0C, 0x0C, 0x0C, 0x12, 0x21],
-        'Y' => [0x21, 0x21, 0x12, 0x0C, 0x08, 0x08, 0x08],
-        'Z' => [0x3F, 0x02, 0x04, 0x08, 0x10, 0x20, 0x3F],
-        'a' => [0x00, 0x00, 0x1E, 0x01, 0x1F, 0x21, 0x1F],
-        'b' => [0x20, 0x20, 0x3E, 0x21, 0x21, 0x21, 0x3E],
-        'c' => [0x00, 0x00, 0x1E, 0x20, 0x20, 0x20, 0x1E],
-        'd' => [0x01, 0x01, 0x1F, 0x21, 0x21, 0x21, 0x1F],
-        'e' => [0x00, 0x00, 0x1E, 0x21, 0x3F, 0x20, 0x1E],
-        'f' => [0x06, 0x08, 0x1E, 0x08, 0x08, 0x08, 0x08],
-        'g' => [0x00, 0x1F, 0x21, 0x21, 0x1F, 0x01, 0x1E],
-        'h' => [0x20, 0x20, 0x3E, 0x21, 0x21, 0x21, 0x21],
-        'i' => [0x08, 0x00, 0x18, 0x08, 0x08, 0x08, 0x1C],
-        'j' => [0x02, 0x00, 0x06, 0x02, 0x02, 0x22, 0x1C],
-        'k' => [0x20, 0x20, 0x22, 0x24, 0x38, 0x24, 0x22],
-        'l' => [0x18, 0x08, 0x08, 0x08, 0x08, 0x08, 0x1C],
-        'm' => [0x00, 0x00, 0x36, 0x2D, 0x21, 0x21, 0x21],
-        'n' => [0x00, 0x00, 0x3E, 0x21, 0x21, 0x21, 0x21],
-        'o' => [0x00, 0x00, 0x1E, 0x21, 0x21, 0x21, 0x1E],
-        'p' => [0x00, 0x3E, 0x21, 0x21, 0x3E, 0x20, 0x20],
-        'q' => [0x00, 0x1F, 0x21, 0x21, 0x1F, 0x01, 0x01],
-        'r' => [0x00, 0x00, 0x2E, 0x30, 0x20, 0x20, 0x20],
-        's' => [0x00, 0x00, 0x1E, 0x20, 0x1E, 0x01, 0x3E],
-        't' => [0x08, 0x08, 0x1E, 0x08, 0x08, 0x08, 0x06],
-        'u' => [0x00, 0x00, 0x21, 0x21, 0x21, 0x21, 0x1F],
-        'v' => [0x00, 0x00, 0x21, 0x21, 0x12, 0x12, 0x0C],
-        'w' => [0x00, 0x00, 0x21, 0x21, 0x21, 0x2D, 0x12],
-        'x' => [0x00, 0x00, 0x21, 0x12, 0x0C, 0x12, 0x21],
-        'y' => [0x00, 0x21, 0x21, 0x1F, 0x01, 0x21, 0x1E],
-        'z' => [0x00, 0x00, 0x3F, 0x02, 0x0C, 0x10, 0x3F],
-        '0' => [0x1E, 0x21, 0x23, 0x25, 0x29, 0x31, 0x1E],
-        '1' => [0x08, 0x18, 0x08, 0x08, 0x08, 0x08, 0x1C],
-        '2' => [0x1E, 0x21, 0x01, 0x0E, 0x10, 0x20, 0x3F],
-        '3' => [0x1E, 0x21, 0x01, 0x0E, 0x01, 0x21, 0x1E],
-        '4' => [0x02, 0x06, 0x0A, 0x12, 0x3F, 0x02, 0x02],
-        '5' => [0x3F, 0x20, 0x3E, 0x01, 0x01, 0x21, 0x1E],
-        '6' => [0x0E, 0x10, 0x20, 0x3E, 0x21, 0x21, 0x1E],
-        '7' => [0x3F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
-        '8' => [0x1E, 0x21, 0x21, 0x1E, 0x21, 0x21, 0x1E],
-        '9' => [0x1E, 0x21, 0x21, 0x1F, 0x01, 0x02, 0x1C],
-        ' ' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
-        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08],
-        ',' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x10],
-        ':' => [0x00, 0x00, 0x08, 0x00, 0x00, 0x08, 0x00],
-        '!' => [0x08, 0x08, 0x08, 0x08, 0x08, 0x00, 0x08],
-        '?' => [0x1E, 0x21, 0x01, 0x0E, 0x08, 0x00, 0x08],
-        '-' => [0x00, 0x00, 0x00, 0x1E, 0x00, 0x00, 0x00],
-        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3F],
-        '(' => [0x04, 0x08, 0x10, 0x10, 0x10, 0x08, 0x04],
-        ')' => [0x10, 0x08, 0x04, 0x04, 0x04, 0x08, 0x10],
-        '[' => [0x1C, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1C],
-        ']' => [0x1C, 0x04, 0x04, 0x04, 0x04, 0x04, 0x1C],
-        '*' => [0x00, 0x08, 0x2A, 0x1C, 0x2A, 0x08, 0x00],
-        '/' => [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x00],
-        '%' => [0x31, 0x32, 0x04, 0x08, 0x13, 0x23, 0x00],
-        '`' => [0x10, 0x08, 0x04, 0x00, 0x00, 0x00, 0x00],
-        '\'' => [0x08, 0x08, 0x10, 0x00, 0x00, 0x00, 0x00],
-        '"' => [0x14, 0x14, 0x28, 0x00, 0x00, 0x00, 0x00],
-        '+' => [0x00, 0x08, 0x08, 0x3E, 0x08, 0x08, 0x00],
-        '=' => [0x00, 0x00, 0x3E, 0x00, 0x3E, 0x00, 0x00],
-        '<' => [0x02, 0x04, 0x08, 0x10, 0x08, 0x04, 0x02],
-        '>' => [0x10, 0x08, 0x04, 0x02, 0x04, 0x08, 0x10],
-        '^' => [0x08, 0x14, 0x22, 0x00, 0x00, 0x00, 0x00],
-        _ => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+/// Draw a text field wrapped at `line_chars`, scrolled so the cursor line is visible
+#[allow(clippy::too_many_arguments)]
+fn draw_text_field(
+    buffer: &mut [u32],
+    buf_width: u32,
+    x: u32,
+    y: u32,
+    field: &TextField,
+    line_chars: usize,
+    visible_lines: usize,
+    focused: bool,
+) {
+    let cursor_line = field.cursor() / line_chars;
+    let first = (cursor_line + 1).saturating_sub(visible_lines);
+    let lines = chunk_chars(field.text(), line_chars);
+    for (i, line) in lines.iter().skip(first).take(visible_lines).enumerate() {
+        draw_text(buffer, buf_width, x, y + i as u32 * 20, line, TEXT_COLOR);
+    }
+    if focused {
+        let col = (field.cursor() % line_chars) as u32;
+        let row = (cursor_line - first) as u32;
+        draw_rect(buffer, buf_width, x + col * 8, y + row * 20 - 2, 1, 12, ACCENT_COLOR);
     }
 }
 
+/// Split `text` into lines of at most `max_chars` characters
+fn chunk_chars(text: &str, max_chars: usize) -> Vec<&str> {
+    let mut lines = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let line = truncate_chars(rest, max_chars);
+        lines.push(line);
+        rest = &rest[line.len()..];
+    }
+    lines
+}
+
+/// Shorten `text` to at most `max_chars` characters, keeping the end and
+/// prefixing "..." (used for long paths)
+fn ellipsize_start(text: &str, max_chars: usize) -> String {
+    let count = text.chars().count();
+    if count <= max_chars {
+        return text.to_string();
+    }
+    let keep = max_chars.saturating_sub(3);
+    let start = text
+        .char_indices()
+        .nth(count - keep)
+        .map(|(idx, _)| idx)
+        .unwrap_or(text.len());
+    format!("...{}", &text[start..])
+}
+
 // ============================================
 // UI Tests
 // ============================================
@@ -2069,6 +3334,172 @@ mod tests {
     // Helper Functions Tests
     // ============================================
 
+    #[test]
+    fn test_truncate_chars_multibyte() {
+        assert_eq!(truncate_chars("Whisper (whisper.cpp)", 12), "Whisper (whi");
+        assert_eq!(truncate_chars("short", 12), "short");
+        // Byte index 12 falls inside a multi-byte character in these names
+        assert_eq!(truncate_chars("Modèle français", 12), "Modèle franç");
+        assert_eq!(truncate_chars("音声認識モデル大型版テスト用", 12), "音声認識モデル大型版テス");
+        assert_eq!(truncate_chars("Ελληνικό μοντέλο", 12), "Ελληνικό μον");
+    }
+
+    #[test]
+    fn test_ellipsize_start_multibyte() {
+        assert_eq!(ellipsize_start("C:\\CUDA", 40), "C:\\CUDA");
+
+        let ascii = "C:\\Program Files\\NVIDIA GPU Computing Toolkit\\CUDA\\v12.4";
+        let short = ellipsize_start(ascii, 40);
+        assert_eq!(short.chars().count(), 40);
+        assert!(short.starts_with("...") && short.ends_with("v12.4"));
+
+        let cjk = "C:\\ユーザー\\開発者\\ツール\\エヌビディア\\クーダ\\ツールキット\\バージョン十二";
+        let short = ellipsize_start(cjk, 40);
+        assert_eq!(short.chars().count(), 40);
+        assert!(short.ends_with("バージョン十二"));
+    }
+
+    #[test]
+    fn test_prompt_text_helpers() {
+        let mut prompt = TextField::new("", MAX_PROMPT_CHARS);
+        assert!(edit_text_field(&mut prompt, &tao::keyboard::Key::Character("k")));
+        assert!(edit_text_field(&mut prompt, &tao::keyboard::Key::Space));
+        assert!(edit_text_field(&mut prompt, &tao::keyboard::Key::ArrowLeft));
+        assert!(edit_text_field(&mut prompt, &tao::keyboard::Key::Backspace));
+        assert_eq!(prompt.text(), " ");
+        assert!(!edit_text_field(&mut prompt, &tao::keyboard::Key::Tab));
+
+        assert_eq!(chunk_chars("abcdefg", 3), vec!["abc", "def", "g"]);
+        assert_eq!(chunk_chars("日本語です", 2), vec!["日本", "語で", "す"]);
+        assert!(chunk_chars("", 3).is_empty());
+    }
+
+    #[test]
+    fn test_step_threshold() {
+        assert_eq!(step_threshold(0.02, true), 0.025);
+        assert_eq!(step_threshold(0.02, false), 0.016);
+        // Never leaves the allowed range
+        assert_eq!(step_threshold(*VAD_THRESHOLD_RANGE.end(), true), *VAD_THRESHOLD_RANGE.end());
+        assert_eq!(step_threshold(0.001, false), *VAD_THRESHOLD_RANGE.start());
+    }
+
+    #[test]
+    fn test_conflicting_hotkey() {
+        let others = [("Toggle Listen", Some("Control+Backquote")), ("Mute", None)];
+        assert_eq!(conflicting_hotkey("Ctrl+`", others), Some("Toggle Listen"));
+        assert_eq!(conflicting_hotkey("Backquote", others), None);
+        assert_eq!(conflicting_hotkey("F2", [("Mute", Some("F2"))]), Some("Mute"));
+    }
+
+    #[test]
+    fn test_format_cpu_threads() {
+        assert_eq!(format_cpu_threads(0), "Auto");
+        assert_eq!(format_cpu_threads(4), "4");
+        assert!(max_cpu_threads() >= 1);
+    }
+
+    #[test]
+    fn test_language_buttons() {
+        assert_eq!(format_allowed_languages(&[]), "Any");
+        assert_eq!(format_allowed_languages(&["en".into(), "de".into()]), "en, de");
+        // Both columns fit above the Clear button and inside the window
+        for idx in 0..COMMON_LANGUAGES.len() {
+            let rect = language_button_rect(idx);
+            assert!(rect.x + rect.width <= WINDOW_WIDTH - 20 && rect.y + rect.height < 390);
+        }
+    }
+
+    #[test]
+    fn test_next_compute_type() {
+        let allowed: Vec<String> = ["default", "int8", "float16"].map(String::from).to_vec();
+        assert_eq!(next_compute_type(&allowed, Some("default")).as_deref(), Some("int8"));
+        assert_eq!(next_compute_type(&allowed, Some("float16")).as_deref(), Some("default"));
+        // Unknown or unset starts at the top of the list
+        assert_eq!(next_compute_type(&allowed, Some("bfloat16")).as_deref(), Some("default"));
+        assert_eq!(next_compute_type(&allowed, None).as_deref(), Some("default"));
+        assert_eq!(next_compute_type(&[], Some("int8")), None);
+    }
+
+    #[test]
+    fn test_disk_size_helpers() {
+        let dir = std::env::temp_dir().join(format!("stt-setup-size-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("a.bin"), vec![0u8; 1000]).unwrap();
+        std::fs::write(dir.join("nested").join("b.bin"), vec![0u8; 500]).unwrap();
+        assert_eq!(dir_size(&dir), 1500);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(dir_size(&dir), 0);
+
+        assert_eq!(format_disk_size(75_000_000), "75 MB");
+        assert_eq!(format_disk_size(2_950_000_000), "3.0 GB");
+        assert_eq!(format_disk_size(0), "0 MB");
+    }
+
+    #[test]
+    fn test_filter_models() {
+        let unified = |name: &str, backend: &str| UnifiedModel {
+            backend_id: backend.to_lowercase(),
+            backend_name: backend.to_string(),
+            model: ManifestModel {
+                id: name.to_lowercase(),
+                display_name: name.to_string(),
+                folder_name: name.to_lowercase(),
+                size_mb: 75,
+                hf_repo: "test/repo".to_string(),
+                download_url: "https://example.com/model.bin".to_string(),
+                files: vec!["model.bin".to_string()],
+                is_english_only: false,
+                checksums: None,
+                required_sample_rate: None,
+                compute_types: Vec::new(),
+                default_compute_type: None,
+                primary_file: None,
+                quality_tier: String::new(),
+                languages: Vec::new(),
+            },
+        };
+        let models = vec![
+            unified("Tiny", "Whisper.cpp"),
+            unified("Large v3", "Whisper.cpp"),
+            unified("Large v3", "CTranslate2"),
+        ];
+
+        assert_eq!(filter_models(&models, ""), vec![0, 1, 2]);
+        assert_eq!(filter_models(&models, "LARGE"), vec![1, 2]);
+        assert_eq!(filter_models(&models, " ctrans "), vec![2]);
+        assert!(filter_models(&models, "medium").is_empty());
+    }
+
+    #[test]
+    fn test_model_hint() {
+        let model = |tier: &str, languages: &[&str], english_only: bool| ManifestModel {
+            id: "m".to_string(),
+            display_name: "M".to_string(),
+            folder_name: "m".to_string(),
+            size_mb: 75,
+            hf_repo: String::new(),
+            download_url: String::new(),
+            files: Vec::new(),
+            is_english_only: english_only,
+            checksums: None,
+            required_sample_rate: None,
+            compute_types: Vec::new(),
+            default_compute_type: None,
+            primary_file: None,
+            quality_tier: tier.to_string(),
+            languages: languages.iter().map(|l| l.to_string()).collect(),
+        };
+
+        assert_eq!(model_hint(&model("fast", &["en"], true)), "Fast, less accurate - English only");
+        assert_eq!(model_hint(&model("Accurate", &[], false)), "Accurate, slower - Multilingual");
+        assert_eq!(model_hint(&model("balanced", &["de", "fr"], false)), "Balanced - de, fr");
+        assert_eq!(model_hint(&model("turbo", &[], false)), "turbo - Multilingual");
+        // Manifests without the hints still describe the language
+        assert_eq!(model_hint(&model("", &[], true)), "English only");
+        let many = ["en", "de", "fr", "es", "it"];
+        assert_eq!(model_hint(&model("", &many, false)), "5 languages");
+    }
+
     #[test]
     fn test_format_hotkey_display() {
         // Test Control modifier
@@ -2148,51 +3579,6 @@ mod tests {
         assert!(!is_modifier_key(KeyCode::Space));
     }
 
-    // ============================================
-    // Drawing Functions Tests
-    // ============================================
-
-    #[test]
-    fn test_draw_rect() {
-        let mut buffer = vec![0u32; 100 * 100]; // 100x100 buffer
-        
-        // Draw a red rectangle
-        draw_rect(&mut buffer, 100, 10, 10, 20, 20, 0xFFFF0000);
-        
-        // Check corners
-        assert_eq!(buffer[10 * 100 + 10], 0xFFFF0000); // Top-left
-        assert_eq!(buffer[10 * 100 + 29], 0xFFFF0000); // Top-right
-        assert_eq!(buffer[29 * 100 + 10], 0xFFFF0000); // Bottom-left
-        assert_eq!(buffer[29 * 100 + 29], 0xFFFF0000); // Bottom-right
-        
-        // Check outside rectangle is unchanged
-        assert_eq!(buffer[9 * 100 + 9], 0);
-        assert_eq!(buffer[30 * 100 + 30], 0);
-    }
-
-    #[test]
-    fn test_draw_text() {
-        let mut buffer = vec![0u32; 200 * 50];
-        
-        // Draw "ABC" at position (10, 10)
-        draw_text(&mut buffer, 200, 10, 10, "ABC", 0xFFFFFFFF);
-        
-        // Just verify it doesn't panic - visual verification would need screenshot
-        // Check that some pixels were written
-        let has_content = buffer.iter().any(|&p| p != 0);
-        assert!(has_content, "Text rendering should write pixels");
-    }
-
-    #[test]
-    fn test_get_char_bitmap_coverage() {
-        // Test all printable ASCII characters
-        for ch in ' '..='~' {
-            let bitmap = get_char_bitmap(ch);
-            // Bitmap should be valid (we just verify no panic)
-            assert_eq!(bitmap.len(), 7);
-        }
-    }
-
     // ============================================
     // Button Geometry Tests
     // ============================================
@@ -2228,17 +3614,36 @@ mod tests {
             available_backends: vec![],
             all_models: vec![],
             selected_model: None,
+            compute_type: None,
+            cpu_threads: 0,
+            allowed_languages: vec![],
+            autostart: false,
             model_scroll_offset: 0,
+            model_filter: TextField::new("", MAX_FILTER_CHARS),
             selected_backend_id: None,
             input_devices: vec![DEFAULT_DEVICE_LABEL.to_string()],
             selected_input_device: None,
             device_scroll_offset: 0,
             push_to_talk_hotkey: Some("Backquote".to_string()),
+            push_to_talk_hold: true,
             toggle_listening_hotkey: Some("Control+Backquote".to_string()),
+            mute_hotkey: "Control+Shift+Backquote".to_string(),
+            pause_listen_hotkey: String::new(),
             hotkey_capture: HotkeyCapture::Idle,
             captured_key: None,
+            hotkey_error: None,
             current_modifiers: ModifiersState::default(),
             silence_timeout_ms: 2000,
+            vad_threshold: None,
+            vad_min_speech_ms: 300,
+            initial_prompt: TextField::new("", MAX_PROMPT_CHARS),
+            focused_field: None,
+            type_mode: TypeMode::default(),
+            vad_noise_floor: None,
+            calibration: None,
+            calibration_error: None,
+            mic_test: None,
+            download_concurrency: 3,
             use_gpu: false,
             cuda_path: None,
             cudnn_path: None,
@@ -2247,6 +3652,9 @@ mod tests {
             status: "Test".to_string(),
             download_progress: None,
             model_downloaded: false,
+            delete_pending: None,
+            active_model_path: None,
+            models_disk_bytes: 0,
             overlay_visible: true,
             overlay_x: None,
             overlay_y: None,
@@ -2306,6 +3714,8 @@ mod tests {
             SetupPage::HotkeyConfig(HotkeyTarget::ToggleListening),
             SetupPage::CudaConfig,
             SetupPage::AudioConfig,
+            SetupPage::AlwaysListenTuning,
+            SetupPage::Languages,
         ];
         
         // Verify all pages are distinct
@@ -0,0 +1,143 @@
+//! Orderly exit
+//!
+//! Background threads are told to stop through a shared flag and joined up to
+//! a deadline, and transcriptions still running get a chance to finish, so the
+//! models can be unloaded and the log flushed before the process ends.
+
+use parking_lot::{Condvar, Mutex};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// How often `Workers::join` checks whether the remaining threads have stopped
+const JOIN_POLL: Duration = Duration::from_millis(10);
+
+/// Long-running background threads, joined on exit
+#[derive(Default)]
+pub struct Workers {
+    handles: Vec<(&'static str, JoinHandle<()>)>,
+}
+
+impl Workers {
+    /// Start a named thread; it must return once the app's running flag is cleared
+    pub fn spawn<F>(&mut self, name: &'static str, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        match std::thread::Builder::new().name(name.to_string()).spawn(f) {
+            Ok(handle) => self.handles.push((name, handle)),
+            Err(e) => warn!("Failed to start {} thread: {}", name, e),
+        }
+    }
+
+    /// Join the threads that stop within `timeout`. Returns the names of any still
+    /// running; those are left detached.
+    pub fn join(self, timeout: Duration) -> Vec<&'static str> {
+        let deadline = Instant::now() + timeout;
+        let mut pending = self.handles;
+        loop {
+            let (done, rest): (Vec<_>, Vec<_>) =
+                pending.into_iter().partition(|(_, handle)| handle.is_finished());
+            for (name, handle) in done {
+                if handle.join().is_err() {
+                    warn!("{} thread panicked", name);
+                }
+            }
+            pending = rest;
+            if pending.is_empty() || Instant::now() >= deadline {
+                return pending.into_iter().map(|(name, _)| name).collect();
+            }
+            std::thread::sleep(JOIN_POLL);
+        }
+    }
+}
+
+/// Counts work in progress (e.g. transcription threads) so exit can wait for it
+#[derive(Clone, Default)]
+pub struct InFlight(Arc<(Mutex<usize>, Condvar)>);
+
+/// Marks one unit of work as running until dropped
+pub struct InFlightGuard(InFlight);
+
+impl InFlight {
+    pub fn enter(&self) -> InFlightGuard {
+        *self.0 .0.lock() += 1;
+        InFlightGuard(self.clone())
+    }
+
+    pub fn count(&self) -> usize {
+        *self.0 .0.lock()
+    }
+
+    /// Wait until nothing is running; false if `timeout` passed first
+    pub fn wait_idle(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let (count, idle) = &*self.0;
+        let mut count = count.lock();
+        while *count > 0 {
+            if idle.wait_until(&mut count, deadline).timed_out() {
+                return *count == 0;
+            }
+        }
+        true
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let (count, idle) = &*self.0 .0;
+        let mut count = count.lock();
+        *count -= 1;
+        if *count == 0 {
+            idle.notify_all();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[test]
+    fn test_workers_join() {
+        let running = Arc::new(AtomicBool::new(true));
+        let mut workers = Workers::default();
+        let flag = Arc::clone(&running);
+        workers.spawn("polite", move || {
+            while flag.load(Ordering::SeqCst) {
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        });
+        let (release, stuck) = std::sync::mpsc::channel::<()>();
+        workers.spawn("stuck", move || {
+            let _ = stuck.recv();
+        });
+
+        running.store(false, Ordering::SeqCst);
+        assert_eq!(workers.join(Duration::from_millis(200)), vec!["stuck"]);
+        drop(release);
+        assert!(Workers::default().join(Duration::ZERO).is_empty());
+    }
+
+    #[test]
+    fn test_in_flight() {
+        let in_flight = InFlight::default();
+        assert!(in_flight.wait_idle(Duration::ZERO));
+
+        let guard = in_flight.enter();
+        let second = in_flight.enter();
+        assert_eq!(in_flight.count(), 2);
+        drop(second);
+        assert!(!in_flight.wait_idle(Duration::from_millis(20)));
+
+        let worker = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            drop(guard);
+        });
+        assert!(in_flight.wait_idle(Duration::from_secs(5)));
+        assert_eq!(in_flight.count(), 0);
+        worker.join().unwrap();
+    }
+}
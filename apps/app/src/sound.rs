@@ -0,0 +1,176 @@
+//! Short beeps confirming that recording started or stopped
+//!
+//! Tones are generated in code and played on a background thread through a cpal
+//! output stream, so the event loop never waits on audio. Without an output
+//! device the cues are silently disabled.
+
+use anyhow::{Context, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{Device, FromSample, Sample, SampleFormat, SizedSample, Stream, StreamConfig};
+use crossbeam_channel::{Sender, TrySendError};
+use std::f32::consts::TAU;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Length of each blip
+const CUE_MS: u32 = 90;
+/// Fade in/out so the blip doesn't click
+const FADE_MS: u32 = 10;
+/// Peak amplitude (0.0-1.0)
+const CUE_VOLUME: f32 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cue {
+    /// Recording (or always-listen) started: high blip
+    Start,
+    /// Recording stopped and is being transcribed: lower blip
+    Stop,
+}
+
+impl Cue {
+    fn frequency(self) -> f32 {
+        match self {
+            Cue::Start => 880.0,
+            Cue::Stop => 587.0,
+        }
+    }
+}
+
+/// Plays cues on a background thread; `play` never blocks
+pub struct SoundCues {
+    enabled: bool,
+    tx: Sender<Cue>,
+}
+
+impl SoundCues {
+    /// Start the playback thread on the named output device (None or not found = default)
+    pub fn new(device_name: Option<String>, enabled: bool) -> Self {
+        // A couple of pending cues is plenty; extra ones are dropped
+        let (tx, rx) = crossbeam_channel::bounded::<Cue>(2);
+        std::thread::spawn(move || {
+            let (device, config, format) = match open_output(device_name.as_deref()) {
+                Ok(output) => output,
+                Err(e) => {
+                    info!("Audio cues disabled: {:#}", e);
+                    return;
+                }
+            };
+            for cue in rx {
+                let rate = config.sample_rate.0;
+                let samples = tone(cue.frequency(), CUE_MS, rate);
+                let duration = Duration::from_secs_f32(samples.len() as f32 / rate as f32);
+                match play(&device, &config, format, samples.into()) {
+                    // Keep the stream alive until the tone has played out
+                    Ok(_stream) => std::thread::sleep(duration + Duration::from_millis(50)),
+                    Err(e) => warn!("Failed to play audio cue: {}", e),
+                }
+            }
+        });
+        Self { enabled, tx }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn play(&self, cue: Cue) {
+        if !self.enabled {
+            return;
+        }
+        match self.tx.try_send(cue) {
+            Ok(()) | Err(TrySendError::Full(_)) => {}
+            // The thread found no output device
+            Err(TrySendError::Disconnected(_)) => debug!("No output device for audio cue"),
+        }
+    }
+}
+
+fn open_output(device_name: Option<&str>) -> Result<(Device, StreamConfig, SampleFormat)> {
+    let host = cpal::default_host();
+    let named = device_name.and_then(|name| {
+        let found = host
+            .output_devices()
+            .ok()?
+            .find(|dev| dev.name().is_ok_and(|n| n == name));
+        if found.is_none() {
+            warn!("Output device '{}' not found. Using default.", name);
+        }
+        found
+    });
+    let device = named
+        .or_else(|| host.default_output_device())
+        .context("No output device available")?;
+    let supported = device
+        .default_output_config()
+        .context("Failed to get default output config")?;
+    debug!("Audio cue device: {:?} {:?}", device.name().unwrap_or_default(), supported);
+    Ok((device, supported.config(), supported.sample_format()))
+}
+
+fn play(
+    device: &Device,
+    config: &StreamConfig,
+    format: SampleFormat,
+    samples: Arc<[f32]>,
+) -> Result<Stream> {
+    match format {
+        SampleFormat::F32 => play_as::<f32>(device, config, samples),
+        SampleFormat::I16 => play_as::<i16>(device, config, samples),
+        SampleFormat::U16 => play_as::<u16>(device, config, samples),
+        other => anyhow::bail!("Unsupported output sample format {:?}", other),
+    }
+}
+
+fn play_as<T>(device: &Device, config: &StreamConfig, samples: Arc<[f32]>) -> Result<Stream>
+where
+    T: SizedSample + FromSample<f32>,
+{
+    let channels = config.channels as usize;
+    let mut pos = 0;
+    let stream = device.build_output_stream(
+        config,
+        move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+            // Same sample on every channel, silence once the tone is done
+            for frame in data.chunks_mut(channels) {
+                let value = samples.get(pos).copied().unwrap_or(0.0);
+                frame.fill(T::from_sample(value));
+                pos += 1;
+            }
+        },
+        |err| warn!("Audio cue stream error: {}", err),
+        None,
+    )?;
+    stream.play()?;
+    Ok(stream)
+}
+
+/// Mono sine blip of `duration_ms` with short linear fades at both ends
+pub fn tone(frequency: f32, duration_ms: u32, sample_rate: u32) -> Vec<f32> {
+    let len = (sample_rate as u64 * duration_ms as u64 / 1000) as usize;
+    let fade = ((sample_rate as u64 * FADE_MS as u64 / 1000) as usize).clamp(1, len.max(1));
+    (0..len)
+        .map(|i| {
+            let envelope = (i.min(len - 1 - i) as f32 / fade as f32).min(1.0);
+            let phase = TAU * frequency * i as f32 / sample_rate as f32;
+            phase.sin() * CUE_VOLUME * envelope
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tone_shape() {
+        let samples = tone(Cue::Start.frequency(), 100, 48_000);
+        assert_eq!(samples.len(), 4800);
+        // Faded in and out, never louder than the cue volume
+        assert_eq!(samples[0], 0.0);
+        assert_eq!(samples[samples.len() - 1], 0.0);
+        let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!(peak > CUE_VOLUME * 0.9 && peak <= CUE_VOLUME);
+        assert!(tone(440.0, 0, 48_000).is_empty());
+    }
+}
@@ -0,0 +1,166 @@
+//! Single editable text value with a cursor, for the setup wizard's text inputs
+//!
+//! Only the editing logic lives here; setup.rs draws it with the bitmap font.
+
+/// Editable text with a character-based cursor
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextField {
+    text: String,
+    /// Cursor position in characters (0 = before the first character)
+    cursor: usize,
+    max_chars: usize,
+}
+
+impl TextField {
+    /// A field holding `text` (cut to `max_chars`) with the cursor at the end
+    pub fn new(text: &str, max_chars: usize) -> Self {
+        let mut field = Self { text: String::new(), cursor: 0, max_chars };
+        field.insert(text);
+        field
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn char_count(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    pub fn max_chars(&self) -> usize {
+        self.max_chars
+    }
+
+    /// Replace the text and move the cursor to the end
+    pub fn set_text(&mut self, text: &str) {
+        self.clear();
+        self.insert(text);
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    /// Insert typed text at the cursor, dropping control characters and
+    /// anything past `max_chars`
+    pub fn insert(&mut self, text: &str) {
+        let room = self.max_chars.saturating_sub(self.char_count());
+        let typed: String = text.chars().filter(|c| !c.is_control()).take(room).collect();
+        let at = self.byte_index(self.cursor);
+        self.text.insert_str(at, &typed);
+        self.cursor += typed.chars().count();
+    }
+
+    /// Delete the character before the cursor
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.delete();
+        }
+    }
+
+    /// Delete the character after the cursor
+    pub fn delete(&mut self) {
+        if self.cursor < self.char_count() {
+            let at = self.byte_index(self.cursor);
+            self.text.remove(at);
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.char_count());
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.char_count();
+    }
+
+    /// Byte offset of the character at `char_pos` (or the end of the text)
+    fn byte_index(&self, char_pos: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(char_pos)
+            .map_or(self.text.len(), |(i, _)| i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_at_cursor() {
+        let mut field = TextField::new("kubectl", 20);
+        assert_eq!(field.cursor(), 7);
+
+        field.move_home();
+        field.insert("run ");
+        assert_eq!(field.text(), "run kubectl");
+        assert_eq!(field.cursor(), 4);
+
+        // Control characters are dropped, and the length is capped
+        field.move_end();
+        field.insert("\t, psql and more text");
+        assert_eq!(field.text(), "run kubectl, psql an");
+        assert_eq!(field.char_count(), 20);
+        assert_eq!(field.cursor(), 20);
+
+        assert_eq!(TextField::new(&"x".repeat(30), 10).char_count(), 10);
+    }
+
+    #[test]
+    fn test_backspace_and_delete() {
+        let mut field = TextField::new("abc", 10);
+        field.backspace();
+        assert_eq!(field.text(), "ab");
+
+        field.move_home();
+        field.backspace(); // nothing before the cursor
+        field.delete();
+        assert_eq!(field.text(), "b");
+        assert_eq!(field.cursor(), 0);
+
+        field.move_end();
+        field.delete(); // nothing after the cursor
+        assert_eq!(field.text(), "b");
+
+        field.clear();
+        assert_eq!(field.text(), "");
+        assert_eq!(field.cursor(), 0);
+    }
+
+    #[test]
+    fn test_cursor_moves_by_character() {
+        let mut field = TextField::new("日本語", 10);
+        field.move_left();
+        field.move_left();
+        field.insert("x");
+        assert_eq!(field.text(), "日x本語");
+        field.delete();
+        assert_eq!(field.text(), "日x語");
+
+        field.move_home();
+        field.move_left();
+        assert_eq!(field.cursor(), 0);
+        for _ in 0..10 {
+            field.move_right();
+        }
+        assert_eq!(field.cursor(), 3);
+
+        field.set_text("new");
+        assert_eq!(field.cursor(), 3);
+    }
+}
@@ -0,0 +1,88 @@
+//! Spacing and capitalization fixes for transcripts
+//!
+//! Always-listen chunks in particular can come back starting in lowercase, with
+//! a space before punctuation ("hello , world") or with doubled spaces.
+
+/// Punctuation that shouldn't have a space in front of it
+const CLOSING_PUNCTUATION: &[char] = &['.', ',', '?', '!', ';', ':'];
+
+/// Capitalize the first letter, drop spaces before punctuation and collapse
+/// runs of spaces between words. Leading whitespace is kept as it is.
+pub fn normalize(text: &str) -> String {
+    capitalize_first(&collapse_spaces(&collapse_space_before_punctuation(text)))
+}
+
+/// "Hello , world ." -> "Hello, world."
+fn collapse_space_before_punctuation(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if CLOSING_PUNCTUATION.contains(&c) {
+            let trimmed = out.trim_end_matches(' ').len();
+            out.truncate(trimmed);
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// "one  two" -> "one two", leaving any indentation before the first word alone
+fn collapse_spaces(text: &str) -> String {
+    let indent = text.len() - text.trim_start_matches(' ').len();
+    let mut out = String::with_capacity(text.len());
+    out.push_str(&text[..indent]);
+    for c in text[indent..].chars() {
+        if !(c == ' ' && out.ends_with(' ') && out.len() > indent) {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Uppercase the first letter, skipping leading spaces, quotes and brackets.
+/// Text that starts with a number ("3 apples") is left alone.
+fn capitalize_first(text: &str) -> String {
+    match text.char_indices().find(|(_, c)| c.is_alphanumeric()) {
+        Some((idx, c)) if c.is_lowercase() => {
+            let mut out = String::with_capacity(text.len());
+            out.push_str(&text[..idx]);
+            out.extend(c.to_uppercase());
+            out.push_str(&text[idx + c.len_utf8()..]);
+            out
+        }
+        _ => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("hello , world ."), "Hello, world.");
+        assert_eq!(normalize("one  two   three"), "One two three");
+        assert_eq!(normalize("wait ,  what ?"), "Wait, what?");
+        assert_eq!(normalize("émile"), "Émile");
+        assert_eq!(normalize("..."), "...");
+        assert_eq!(normalize(""), "");
+    }
+
+    #[test]
+    fn test_normalize_leaves_correct_text_alone() {
+        let text = "Already Capitalized. Nothing to do!";
+        assert_eq!(normalize(text), text);
+        // Leading indentation and line breaks survive
+        assert_eq!(normalize("  indented"), "  Indented");
+        assert_eq!(normalize("first\nsecond"), "First\nsecond");
+    }
+
+    #[test]
+    fn test_normalize_numbers_and_quotes() {
+        assert_eq!(normalize("3 apples , please"), "3 apples, please");
+        assert_eq!(normalize("42"), "42");
+        assert_eq!(normalize("  \"what ?"), "  \"What?");
+        assert_eq!(normalize("\"quoted\" , he said"), "\"Quoted\", he said");
+        assert_eq!(normalize("'tis the season"), "'Tis the season");
+        assert_eq!(normalize("(aside)  text"), "(Aside) text");
+    }
+}
@@ -0,0 +1,148 @@
+//! The tray's "Save Last Transcript": one transcript written to its own .txt file
+//!
+//! Files go in `transcript_export_dir` (default: a `transcripts` folder next to
+//! the exe) and are named after when the transcript was made and its first words,
+//! e.g. `2026-10-16 14-03-22 Dear Sam, thanks for the.txt`. The time is UTC.
+
+use crate::history::HistoryEntry;
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Words of the transcript used in the file name
+const NAME_WORDS: usize = 5;
+
+/// Longest part of the file name taken from the transcript, in characters
+const MAX_NAME_CHARS: usize = 40;
+
+/// Names Windows reserves for devices, with or without an extension
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// `text` made safe as a Windows file name: reserved and control characters
+/// become spaces, runs of spaces collapse, trailing dots and spaces are dropped
+/// and device names such as `CON` get a leading underscore
+pub fn sanitize_file_name(text: &str) -> String {
+    let replaced: String = text
+        .chars()
+        .map(|c| if c.is_control() || r#"<>:"/\|?*"#.contains(c) { ' ' } else { c })
+        .collect();
+    let mut name = replaced.split_whitespace().collect::<Vec<_>>().join(" ");
+    name.truncate(name.trim_end_matches(['.', ' ']).len());
+    let stem = name.split('.').next().unwrap_or_default();
+    if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        name.insert(0, '_');
+    }
+    name
+}
+
+/// "YYYY-MM-DD HH-MM-SS" (UTC) for a Unix time in seconds
+fn format_timestamp(unix: u64) -> String {
+    let (days, secs) = (unix / 86_400, unix % 86_400);
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}-{:02}-{:02}",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+/// File name for a transcript, without the ".txt"
+pub fn file_stem(entry: &HistoryEntry) -> String {
+    let words = entry.text.split_whitespace().take(NAME_WORDS).collect::<Vec<_>>().join(" ");
+    let words: String = sanitize_file_name(&words).chars().take(MAX_NAME_CHARS).collect();
+    let words = sanitize_file_name(&words);
+    let timestamp = format_timestamp(entry.timestamp);
+    if words.is_empty() {
+        timestamp
+    } else {
+        format!("{} {}", timestamp, words)
+    }
+}
+
+/// Write a transcript to a new file in `dir` (created if needed), never replacing
+/// an existing one. Returns the file's path.
+pub fn save(dir: &Path, entry: &HistoryEntry) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    let stem = file_stem(entry);
+    for n in 1.. {
+        let name = if n == 1 { format!("{}.txt", stem) } else { format!("{} ({}).txt", stem, n) };
+        let path = dir.join(name);
+        let mut file = match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e).with_context(|| format!("creating {}", path.display())),
+        };
+        file.write_all(entry.text.as_bytes())
+            .with_context(|| format!("writing {}", path.display()))?;
+        return Ok(path);
+    }
+    unreachable!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(text: &str, timestamp: u64) -> HistoryEntry {
+        HistoryEntry { text: text.to_string(), timestamp }
+    }
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name("hello world"), "hello world");
+        assert_eq!(sanitize_file_name(r#"a<b>c:d"e/f\g|h?i*j"#), "a b c d e f g h i j");
+        assert_eq!(sanitize_file_name("line\none\ttab"), "line one tab");
+        assert_eq!(sanitize_file_name("What now?"), "What now");
+        assert_eq!(sanitize_file_name("The end... "), "The end");
+        assert_eq!(sanitize_file_name("café 日本語"), "café 日本語");
+        assert_eq!(sanitize_file_name("con"), "_con");
+        assert_eq!(sanitize_file_name("NUL.txt"), "_NUL.txt");
+        assert_eq!(sanitize_file_name("console"), "console");
+        assert_eq!(sanitize_file_name(" ?* "), "");
+    }
+
+    #[test]
+    fn test_file_stem() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00-00-00");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29 00-00-00");
+        assert_eq!(format_timestamp(1_791_000_000), "2026-10-03 04-00-00");
+
+        let ts = 1_791_000_000;
+        let stem = file_stem(&entry("Dear Sam, thanks for the notes. See you", ts));
+        assert_eq!(stem, "2026-10-03 04-00-00 Dear Sam, thanks for the");
+        assert_eq!(file_stem(&entry("???", ts)), "2026-10-03 04-00-00");
+        // Long words are cut without leaving a trailing space
+        let long = format!("{} end", "x".repeat(39));
+        let expected = format!("2026-10-03 04-00-00 {}", "x".repeat(39));
+        assert_eq!(file_stem(&entry(&long, ts)), expected);
+    }
+
+    #[test]
+    fn test_save_never_overwrites() {
+        let dir = std::env::temp_dir().join(format!("stt-export-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let first = save(&dir, &entry("Hello: world", 0)).unwrap();
+        let second = save(&dir, &entry("Hello: world", 0)).unwrap();
+        assert_eq!(first.file_name().unwrap(), "1970-01-01 00-00-00 Hello world.txt");
+        assert_eq!(second.file_name().unwrap(), "1970-01-01 00-00-00 Hello world (2).txt");
+        assert_eq!(std::fs::read_to_string(&second).unwrap(), "Hello: world");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,150 @@
+//! Machine-readable transcript log
+//!
+//! When `transcript_log_path` is set, every transcription is appended to that
+//! file as one JSON object per line. Once the file passes the size cap it is
+//! renamed to `<name>.1` (replacing any older one) and a fresh file is started.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Size at which the log is rotated
+pub const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+
+/// One line of the log
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TranscriptRecord<'a> {
+    /// Unix time in seconds
+    pub ts: u64,
+    pub text: &'a str,
+    /// Device the model ran on, if the backend reported it
+    pub device: Option<&'a str>,
+    pub audio_s: f64,
+    pub latency_ms: u64,
+    /// Detected (or requested) language
+    pub language: Option<&'a str>,
+}
+
+impl<'a> TranscriptRecord<'a> {
+    /// Record stamped with the current time
+    pub fn now(text: &'a str, audio_s: f64, latency_ms: u64) -> Self {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        Self { ts, text, device: None, audio_s, latency_ms, language: None }
+    }
+}
+
+/// Appends records to the log file; share it behind a mutex so always-listen
+/// results don't interleave
+pub struct TranscriptLog {
+    path: PathBuf,
+    max_bytes: u64,
+    /// Opened on the first append, and again after rotation
+    writer: Option<BufWriter<File>>,
+    /// Current size of the file
+    size: u64,
+}
+
+impl TranscriptLog {
+    pub fn new(path: PathBuf, max_bytes: u64) -> Self {
+        Self { path, max_bytes, writer: None, size: 0 }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write one record and flush it to disk
+    pub fn append(&mut self, record: &TranscriptRecord) -> Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        if self.writer.is_none() {
+            self.open()?;
+        }
+        if self.size > 0 && self.size + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let writer = self.writer.as_mut().context("Transcript log is not open")?;
+        writer.write_all(&line)?;
+        writer.flush()?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    fn open(&mut self) -> Result<()> {
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open {}", self.path.display()))?;
+        self.size = file.metadata()?.len();
+        self.writer = Some(BufWriter::new(file));
+        Ok(())
+    }
+
+    /// Move the full log aside and start an empty one
+    fn rotate(&mut self) -> Result<()> {
+        self.writer = None;
+        fs::rename(&self.path, rotated_path(&self.path))
+            .with_context(|| format!("Failed to rotate {}", self.path.display()))?;
+        self.open()
+    }
+}
+
+/// `transcripts.jsonl` -> `transcripts.jsonl.1`
+fn rotated_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".1");
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcript_log_appends_and_rotates() {
+        let dir = std::env::temp_dir().join("stt_test_transcript_log");
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("transcripts.jsonl");
+
+        let mut log = TranscriptLog::new(path.clone(), 200);
+        let mut record = TranscriptRecord::now("hello world", 1.5, 320);
+        record.device = Some("cuda");
+        record.language = Some("en");
+        log.append(&record).unwrap();
+        log.append(&TranscriptRecord::now("second", 0.8, 120)).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> =
+            content.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["text"], "hello world");
+        assert_eq!(lines[0]["device"], "cuda");
+        assert_eq!(lines[0]["language"], "en");
+        assert_eq!(lines[0]["latency_ms"], 320);
+        assert_eq!(lines[1]["device"], serde_json::Value::Null);
+
+        // The third line doesn't fit under the cap, so the first two move aside
+        log.append(&TranscriptRecord::now("third", 0.5, 90)).unwrap();
+        assert_eq!(fs::read_to_string(rotated_path(&path)).unwrap(), content);
+        let current = fs::read_to_string(&path).unwrap();
+        assert_eq!(current.lines().count(), 1);
+        assert!(current.contains("\"third\""));
+
+        // A reopened log picks up the existing size
+        let log = {
+            let mut log = TranscriptLog::new(path.clone(), 200);
+            log.append(&TranscriptRecord::now("fourth", 0.5, 90)).unwrap();
+            log
+        };
+        assert_eq!(log.size, fs::metadata(&path).unwrap().len());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,7 +1,8 @@
+use crate::config;
 use anyhow::Result;
 use image::GenericImageView;
 use tray_icon::{
-    menu::{Menu, MenuEvent, MenuId, MenuItem, PredefinedMenuItem},
+    menu::{CheckMenuItem, Menu, MenuEvent, MenuId, MenuItem, PredefinedMenuItem, Submenu},
     Icon, TrayIcon, TrayIconBuilder,
 };
 
@@ -18,14 +19,50 @@ pub enum AppStatus {
     Processing,
     AlwaysListening,
     AlwaysListeningRecording, // Active speech detected in always-listen mode
+    Muted,                    // Mute hotkey pressed: nothing is recorded or typed
+    Paused,                   // Always-listen paused by its hotkey; the mic stays open
+    Paused,                   // Always-listen paused by its hotkey; the mic stays open
+    LoadingModel,             // Switching models from the tray menu
 }
 
 pub struct TrayManager {
     tray: TrayIcon,
+    status_item: MenuItem,
+    always_listen_item: CheckMenuItem,
+    overlay_on_top_item: CheckMenuItem,
+    overlay_click_through_item: CheckMenuItem,
+    audio_cues_item: CheckMenuItem,
+    clipboard_only_item: CheckMenuItem,
+    save_transcript_item: MenuItem,
+    pub always_listen_id: MenuId,
     pub show_overlay_id: MenuId,
+    pub overlay_on_top_id: MenuId,
+    pub overlay_click_through_id: MenuId,
+    pub audio_cues_id: MenuId,
+    pub clipboard_only_id: MenuId,
     pub settings_id: MenuId,
+    pub last_timing_id: MenuId,
+    pub history_id: MenuId,
+    pub save_transcript_id: MenuId,
+    pub save_profile_id: MenuId,
     pub exit_id: MenuId,
+    model_menu: Submenu,
+    /// "Model" entries and the model id each one selects
+    model_items: Vec<(CheckMenuItem, String)>,
+    language_menu: Submenu,
+    /// "Language" entries and the language each one selects (None = Auto)
+    language_items: Vec<(CheckMenuItem, Option<String>)>,
+    profile_menu: Submenu,
+    no_profiles_item: MenuItem,
+    /// "Switch Profile" entries and the profile each one loads
+    profile_items: Vec<(MenuItem, String)>,
     icons: TrayIcons,
+    /// Device the last transcription ran on, shown in the tooltip
+    device: Option<String>,
+    /// Microphone problem appended to the tooltip (e.g. a low capture rate)
+    mic_warning: Option<String>,
+    /// Average real-time factor of recent transcriptions, shown in the tooltip
+    average_rtf: Option<f64>,
 }
 
 struct TrayIcons {
@@ -36,21 +73,68 @@ struct TrayIcons {
 }
 
 impl TrayManager {
-    pub fn new() -> Result<Self> {
+    pub fn new(
+        profiles: &[String],
+        overlay_on_top: bool,
+        overlay_click_through: bool,
+        audio_cues: bool,
+        clipboard_only: bool,
+    ) -> Result<Self> {
         let icons = TrayIcons::new()?;
 
+        let status_item = MenuItem::new(status_label(AppStatus::Idle), false, None);
+        let always_listen_item = CheckMenuItem::new("Always Listening", true, false, None);
         let show_overlay_item = MenuItem::new("Show/Hide Overlay", true, None);
+        let overlay_on_top_item =
+            CheckMenuItem::new("Overlay Always on Top", true, overlay_on_top, None);
+        let overlay_click_through_item =
+            CheckMenuItem::new("Overlay Click-Through", true, overlay_click_through, None);
+        let audio_cues_item = CheckMenuItem::new("Sound Cues", true, audio_cues, None);
+        let clipboard_only_item =
+            CheckMenuItem::new("Copy to Clipboard Instead of Typing", true, clipboard_only, None);
         let settings_item = MenuItem::new("Settings", true, None);
+        let last_timing_item = MenuItem::new("Show last timing", true, None);
+        let history_item = MenuItem::new("History", true, None);
+        let save_transcript_item = MenuItem::new("Save Last Transcript", false, None);
+        let model_menu = Submenu::new("Model", true);
+        let language_menu = Submenu::new("Language", true);
+        let profile_menu = Submenu::new("Switch Profile", true);
+        let no_profiles_item = MenuItem::new("No saved profiles", false, None);
+        let save_profile_item = MenuItem::new("Save Profile As...", true, None);
         let exit_item = MenuItem::new("Exit", true, None);
 
+        let always_listen_id = always_listen_item.id().clone();
         let show_overlay_id = show_overlay_item.id().clone();
+        let overlay_on_top_id = overlay_on_top_item.id().clone();
+        let overlay_click_through_id = overlay_click_through_item.id().clone();
+        let audio_cues_id = audio_cues_item.id().clone();
+        let clipboard_only_id = clipboard_only_item.id().clone();
         let settings_id = settings_item.id().clone();
+        let last_timing_id = last_timing_item.id().clone();
+        let history_id = history_item.id().clone();
+        let save_transcript_id = save_transcript_item.id().clone();
+        let save_profile_id = save_profile_item.id().clone();
         let exit_id = exit_item.id().clone();
 
         let menu = Menu::new();
+        menu.append(&status_item)?;
+        menu.append(&PredefinedMenuItem::separator())?;
+        menu.append(&always_listen_item)?;
         menu.append(&show_overlay_item)?;
+        menu.append(&overlay_on_top_item)?;
+        menu.append(&overlay_click_through_item)?;
+        menu.append(&audio_cues_item)?;
+        menu.append(&clipboard_only_item)?;
         menu.append(&PredefinedMenuItem::separator())?;
         menu.append(&settings_item)?;
+        menu.append(&last_timing_item)?;
+        menu.append(&history_item)?;
+        menu.append(&save_transcript_item)?;
+        menu.append(&PredefinedMenuItem::separator())?;
+        menu.append(&model_menu)?;
+        menu.append(&language_menu)?;
+        menu.append(&profile_menu)?;
+        menu.append(&save_profile_item)?;
         menu.append(&PredefinedMenuItem::separator())?;
         menu.append(&exit_item)?;
 
@@ -61,13 +145,134 @@ impl TrayManager {
             .build()
             .map_err(|e| anyhow::anyhow!("Failed to create tray icon: {}", e))?;
 
-        Ok(Self {
+        let mut manager = Self {
             tray,
+            status_item,
+            always_listen_item,
+            overlay_on_top_item,
+            overlay_click_through_item,
+            audio_cues_item,
+            clipboard_only_item,
+            save_transcript_item,
+            always_listen_id,
             show_overlay_id,
+            overlay_on_top_id,
+            overlay_click_through_id,
+            audio_cues_id,
+            clipboard_only_id,
             settings_id,
+            last_timing_id,
+            history_id,
+            save_transcript_id,
+            save_profile_id,
             exit_id,
+            model_menu,
+            model_items: Vec::new(),
+            language_menu,
+            language_items: Vec::new(),
+            profile_menu,
+            no_profiles_item,
+            profile_items: Vec::new(),
             icons,
-        })
+            device: None,
+            mic_warning: None,
+            average_rtf: None,
+        };
+        manager.profile_menu.append(&manager.no_profiles_item)?;
+        for name in profiles {
+            manager.add_profile(name)?;
+        }
+        Ok(manager)
+    }
+
+    /// Add a profile to the "Switch Profile" submenu (no-op if it is listed)
+    pub fn add_profile(&mut self, name: &str) -> Result<()> {
+        if self.profile_items.iter().any(|(_, n)| n == name) {
+            return Ok(());
+        }
+        if self.profile_items.is_empty() {
+            self.profile_menu.remove(&self.no_profiles_item)?;
+        }
+        let item = MenuItem::new(name, true, None);
+        self.profile_menu.append(&item)?;
+        self.profile_items.push((item, name.to_string()));
+        Ok(())
+    }
+
+    /// List the downloaded models, given as (id, display name), in the "Model" submenu
+    pub fn set_models(&mut self, models: &[(String, String)], active: &str) -> Result<()> {
+        for (item, _) in self.model_items.drain(..) {
+            self.model_menu.remove(&item)?;
+        }
+        for (id, display_name) in models {
+            let item = CheckMenuItem::new(display_name, true, id == active, None);
+            self.model_menu.append(&item)?;
+            self.model_items.push((item, id.clone()));
+        }
+        self.model_menu.set_enabled(!self.model_items.is_empty());
+        Ok(())
+    }
+
+    /// The model a "Model" menu entry selects
+    pub fn model_for(&self, id: &MenuId) -> Option<&str> {
+        self.model_items
+            .iter()
+            .find(|(item, _)| item.id() == id)
+            .map(|(_, model_id)| model_id.as_str())
+    }
+
+    /// Check only the active model (clicking an entry toggles its checkmark first)
+    pub fn set_active_model(&self, model_id: &str) {
+        for (item, id) in &self.model_items {
+            item.set_checked(id == model_id);
+        }
+    }
+
+    /// List "Auto" and the given language codes in the "Language" submenu
+    pub fn set_languages(&mut self, languages: &[String], active: Option<&str>) -> Result<()> {
+        for (item, _) in self.language_items.drain(..) {
+            self.language_menu.remove(&item)?;
+        }
+        let entries = std::iter::once(None).chain(languages.iter().map(|l| Some(l.clone())));
+        for language in entries {
+            let label = match &language {
+                Some(code) => format!("{} ({})", config::language_name(code), code),
+                None => "Auto".to_string(),
+            };
+            let item = CheckMenuItem::new(label, true, false, None);
+            self.language_menu.append(&item)?;
+            self.language_items.push((item, language));
+        }
+        self.set_active_language(active);
+        Ok(())
+    }
+
+    /// The language a "Language" menu entry selects (`Some(None)` for Auto)
+    pub fn language_for(&self, id: &MenuId) -> Option<Option<&str>> {
+        self.language_items
+            .iter()
+            .find(|(item, _)| item.id() == id)
+            .map(|(_, language)| language.as_deref())
+    }
+
+    /// Check only the active language
+    pub fn set_active_language(&self, active: Option<&str>) {
+        for (item, language) in &self.language_items {
+            let checked = match (language, active) {
+                (Some(language), Some(active)) => language.eq_ignore_ascii_case(active),
+                (None, None) => true,
+                _ => false,
+            };
+            item.set_checked(checked);
+        }
+    }
+
+    /// The profile a "Switch Profile" menu entry loads
+    pub fn profile_for(&self, id: &MenuId) -> Option<&str> {
+        self.profile_items
+            .iter()
+            .find(|(item, _)| item.id() == id)
+            .map(|(_, name)| name.as_str())
     }
 
     pub fn set_status(&mut self, status: AppStatus) {
@@ -81,10 +286,74 @@ impl TrayManager {
             AppStatus::AlwaysListeningRecording => {
                 (&self.icons.recording, "Speech to Text - Speaking...")
             }
+            AppStatus::Muted => (&self.icons.idle, "Speech to Text - Muted"),
+            AppStatus::Paused => (&self.icons.idle, "Speech to Text - Listening paused"),
+            AppStatus::Paused => (&self.icons.idle, "Speech to Text - Listening paused"),
+            AppStatus::LoadingModel => {
+                (&self.icons.processing, "Speech to Text - Loading model...")
+            }
         };
 
+        let mut details = Vec::new();
+        if let Some(device) = &self.device {
+            details.push(device.clone());
+        }
+        if let Some(rtf) = self.average_rtf {
+            details.push(format!("avg RTF {:.2}", rtf));
+        }
+        let mut tooltip = if details.is_empty() {
+            tooltip.to_string()
+        } else {
+            format!("{} ({})", tooltip, details.join(", "))
+        };
+        if let Some(warning) = &self.mic_warning {
+            tooltip = format!("{} - {}", tooltip, warning);
+        }
         let _ = self.tray.set_icon(Some(icon.clone()));
         let _ = self.tray.set_tooltip(Some(tooltip));
+        self.status_item.set_text(status_label(status));
+    }
+
+    /// Remember the device the last transcription ran on (shown from the next status change)
+    pub fn set_device(&mut self, device: Option<String>) {
+        self.device = device;
+    }
+
+    /// Remember the recent average real-time factor (shown from the next status change)
+    pub fn set_average_rtf(&mut self, rtf: Option<f64>) {
+        self.average_rtf = rtf;
+    }
+
+    /// Note a microphone problem in the tooltip (shown from the next status change)
+    pub fn set_mic_warning(&mut self, warning: Option<String>) {
+        self.mic_warning = warning;
+    }
+
+    /// Sync the "Always Listening" checkmark with the actual mode
+    /// (clicking the item toggles the checkmark before the app decides)
+    pub fn set_always_listen_checked(&self, checked: bool) {
+        self.always_listen_item.set_checked(checked);
+    }
+
+    /// Sync the overlay checkmarks with the config
+    pub fn set_overlay_checked(&self, on_top: bool, click_through: bool) {
+        self.overlay_on_top_item.set_checked(on_top);
+        self.overlay_click_through_item.set_checked(click_through);
+    }
+
+    /// Gray out "Save Last Transcript" while there is nothing to save
+    pub fn set_save_transcript_enabled(&self, enabled: bool) {
+        self.save_transcript_item.set_enabled(enabled);
+    }
+
+    /// Sync the "Sound Cues" checkmark with the config
+    pub fn set_audio_cues_checked(&self, checked: bool) {
+        self.audio_cues_item.set_checked(checked);
+    }
+
+    /// Sync the "Copy to Clipboard Instead of Typing" checkmark with the config
+    pub fn set_clipboard_only_checked(&self, checked: bool) {
+        self.clipboard_only_item.set_checked(checked);
     }
 
     pub fn menu_receiver() -> crossbeam_channel::Receiver<MenuEvent> {
@@ -92,6 +361,21 @@ impl TrayManager {
     }
 }
 
+/// Text for the disabled status header in the tray menu
+fn status_label(status: AppStatus) -> &'static str {
+    match status {
+        AppStatus::Idle => "Status: Idle",
+        AppStatus::Recording => "Status: Recording",
+        AppStatus::Processing => "Status: Processing",
+        AppStatus::AlwaysListening => "Status: Listening",
+        AppStatus::AlwaysListeningRecording => "Status: Speaking",
+        AppStatus::Muted => "Status: Muted",
+        AppStatus::Paused => "Status: Paused",
+        AppStatus::Paused => "Status: Paused",
+        AppStatus::LoadingModel => "Status: Loading model",
+    }
+}
+
 impl TrayIcons {
     fn new() -> Result<Self> {
         Ok(Self {
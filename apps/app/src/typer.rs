@@ -1,8 +1,187 @@
+use crate::config::{OutputMode, TypeMode};
 use anyhow::Result;
-use enigo::{Enigo, Keyboard, Settings};
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use std::time::Duration;
+
+/// How long the target app gets to read a pasted transcript before the
+/// previous clipboard contents are put back
+const PASTE_RESTORE_DELAY: Duration = Duration::from_millis(300);
+
+/// One simulated key press for Unicode typing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+enum KeyInput {
+    /// A UTF-16 code unit sent with `KEYEVENTF_UNICODE`
+    Unicode(u16),
+    /// Line breaks and tabs are sent as real keys; many apps ignore them as Unicode input
+    Enter,
+    Tab,
+    /// Erases a character; used to take back typed text
+    Backspace,
+}
+
+/// Split text into key presses: one per UTF-16 code unit, so characters
+/// outside the BMP (e.g. emoji) become a surrogate pair. `\r` is dropped so
+/// `\r\n` is a single Enter.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn key_inputs(text: &str) -> Vec<KeyInput> {
+    let mut inputs = Vec::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\r' => {}
+            '\n' => inputs.push(KeyInput::Enter),
+            '\t' => inputs.push(KeyInput::Tab),
+            _ => {
+                let mut units = [0u16; 2];
+                inputs.extend(ch.encode_utf16(&mut units).iter().map(|&u| KeyInput::Unicode(u)));
+            }
+        }
+    }
+    inputs
+}
+
+/// Send key presses (down + up each) to the focused window with `SendInput`
+#[cfg(target_os = "windows")]
+fn send_key_inputs(inputs: &[KeyInput]) -> Result<()> {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
+        KEYEVENTF_UNICODE, VIRTUAL_KEY, VK_BACK, VK_RETURN, VK_TAB,
+    };
+
+    let mut events = Vec::with_capacity(inputs.len() * 2);
+    for input in inputs {
+        let (vk, scan, flags) = match *input {
+            KeyInput::Unicode(unit) => (VIRTUAL_KEY(0), unit, KEYEVENTF_UNICODE),
+            KeyInput::Enter => (VK_RETURN, 0, KEYBD_EVENT_FLAGS(0)),
+            KeyInput::Tab => (VK_TAB, 0, KEYBD_EVENT_FLAGS(0)),
+            KeyInput::Backspace => (VK_BACK, 0, KEYBD_EVENT_FLAGS(0)),
+        };
+        for up in [false, true] {
+            events.push(INPUT {
+                r#type: INPUT_KEYBOARD,
+                Anonymous: INPUT_0 {
+                    ki: KEYBDINPUT {
+                        wVk: vk,
+                        wScan: scan,
+                        dwFlags: if up { flags | KEYEVENTF_KEYUP } else { flags },
+                        time: 0,
+                        dwExtraInfo: 0,
+                    },
+                },
+            });
+        }
+    }
+
+    let sent = unsafe { SendInput(&events, std::mem::size_of::<INPUT>() as i32) };
+    if sent as usize != events.len() {
+        anyhow::bail!("SendInput only sent {} of {} key events", sent, events.len());
+    }
+    Ok(())
+}
+
+/// Where a transcript ended up after `deliver_text`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    Typed,
+    Clipboard,
+}
+
+/// Destinations for transcribed text (implemented by `Typer`, mocked in tests)
+pub trait TextSink {
+    /// Type text into the active window, optionally one key press per character
+    fn type_text(&mut self, text: &str, per_key: bool) -> Result<()>;
+    /// Replace the clipboard contents with text
+    fn set_clipboard(&mut self, text: &str) -> Result<()>;
+    /// Text on the clipboard, or `None` if it holds something else or can't be read
+    fn clipboard_text(&mut self) -> Option<String>;
+    /// Erase characters before the cursor in the active window
+    fn backspace(&mut self, count: usize) -> Result<()>;
+}
+
+/// Send a transcript to the sink according to the output mode. In `ClipboardOnly`
+/// mode, `append_clipboard` adds it on a new line after the text already copied
+/// instead of replacing it. Returns `None` for empty text (nothing is typed or copied).
+pub fn deliver_text<S: TextSink + ?Sized>(
+    sink: &mut S,
+    text: &str,
+    mode: OutputMode,
+    append_clipboard: bool,
+) -> Result<Option<Delivery>> {
+    if text.is_empty() {
+        return Ok(None);
+    }
+
+    match mode {
+        OutputMode::Text => sink.type_text(text, false).map(|_| Some(Delivery::Typed)),
+        OutputMode::Keys => sink.type_text(text, true).map(|_| Some(Delivery::Typed)),
+        OutputMode::ClipboardOnly => {
+            let existing = if append_clipboard { sink.clipboard_text() } else { None };
+            let text = match existing {
+                Some(existing) if !existing.trim().is_empty() => {
+                    format!("{}\n{}", existing.trim_end(), text)
+                }
+                _ => text.to_string(),
+            };
+            sink.set_clipboard(&text).map(|_| Some(Delivery::Clipboard))
+        }
+    }
+}
+
+/// Take back `count` typed characters with backspaces. Returns false without
+/// sending any in `ClipboardOnly` mode, where nothing was typed.
+pub fn erase_typed<S: TextSink + ?Sized>(
+    sink: &mut S,
+    count: usize,
+    mode: OutputMode,
+) -> Result<bool> {
+    if count == 0 || mode == OutputMode::ClipboardOnly {
+        return Ok(false);
+    }
+    sink.backspace(count).map(|_| true)
+}
+
+/// What has been typed of an utterance whose segments are output as they are decoded.
+/// Typed text can't be taken back, so later corrections only ever append.
+#[derive(Debug, Default)]
+pub struct IncrementalOutput {
+    /// Words typed so far
+    words: usize,
+}
+
+impl IncrementalOutput {
+    /// Whether anything has been typed yet
+    pub fn is_empty(&self) -> bool {
+        self.words == 0
+    }
+
+    /// Text to type for a newly decoded segment
+    pub fn segment(&mut self, text: &str) -> String {
+        self.append(text.split_whitespace())
+    }
+
+    /// Text still to type once the full transcript is known: the words past those
+    /// already typed (a reworded earlier segment is left as typed)
+    pub fn finish(&mut self, full: &str) -> String {
+        let skip = self.words;
+        self.append(full.split_whitespace().skip(skip))
+    }
+
+    fn append<'a>(&mut self, words: impl Iterator<Item = &'a str>) -> String {
+        let mut out = String::new();
+        for word in words {
+            if self.words > 0 {
+                out.push(' ');
+            }
+            out.push_str(word);
+            self.words += 1;
+        }
+        out
+    }
+}
 
 pub struct Typer {
     enigo: Enigo,
+    type_mode: TypeMode,
 }
 
 impl Typer {
@@ -10,10 +189,23 @@ impl Typer {
         let enigo = Enigo::new(&Settings::default())
             .map_err(|e| anyhow::anyhow!("Failed to initialize Enigo: {:?}", e))?;
 
-        Ok(Self { enigo })
+        Ok(Self { enigo, type_mode: TypeMode::default() })
+    }
+
+    /// Set how `OutputMode::Text` reaches the window
+    pub fn set_type_mode(&mut self, mode: TypeMode) {
+        self.type_mode = mode;
     }
 
-    pub fn type_text(&mut self, text: &str) -> Result<()> {
+    /// Type text into the active window as keystrokes or a clipboard paste
+    pub fn type_text_with_mode(&mut self, text: &str, mode: TypeMode) -> Result<()> {
+        match mode {
+            TypeMode::Keystrokes => self.type_keystrokes(text, false),
+            TypeMode::ClipboardPaste => self.paste_text(text),
+        }
+    }
+
+    fn type_keystrokes(&mut self, text: &str, per_key: bool) -> Result<()> {
         if text.is_empty() {
             return Ok(());
         }
@@ -21,10 +213,256 @@ impl Typer {
         // Small delay to ensure the target window is ready
         std::thread::sleep(std::time::Duration::from_millis(50));
 
+        #[cfg(target_os = "windows")]
+        if per_key {
+            for ch in text.chars() {
+                send_key_inputs(&key_inputs(ch.encode_utf8(&mut [0u8; 4])))?;
+                std::thread::sleep(std::time::Duration::from_millis(5));
+            }
+        } else {
+            send_key_inputs(&key_inputs(text))?;
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        if per_key {
+            for ch in text.chars() {
+                self.enigo
+                    .key(Key::Unicode(ch), Direction::Click)
+                    .map_err(|e| anyhow::anyhow!("Failed to type key: {:?}", e))?;
+                std::thread::sleep(std::time::Duration::from_millis(5));
+            }
+        } else {
+            self.enigo
+                .text(text)
+                .map_err(|e| anyhow::anyhow!("Failed to type text: {:?}", e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Paste text with Ctrl+V, then put the previous clipboard text back.
+    /// Non-text clipboard contents (images, files) can't be saved, so they
+    /// are left replaced by the transcript.
+    fn paste_text(&mut self, text: &str) -> Result<()> {
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        let mut clipboard = arboard::Clipboard::new()
+            .map_err(|e| anyhow::anyhow!("Failed to open clipboard: {}", e))?;
+        let saved = clipboard.get_text().ok();
+        clipboard
+            .set_text(text)
+            .map_err(|e| anyhow::anyhow!("Failed to set clipboard: {}", e))?;
+
+        // Small delay to ensure the target window is ready
+        std::thread::sleep(std::time::Duration::from_millis(50));
+
         self.enigo
-            .text(text)
-            .map_err(|e| anyhow::anyhow!("Failed to type text: {:?}", e))?;
+            .key(Key::Control, Direction::Press)
+            .map_err(|e| anyhow::anyhow!("Failed to press Ctrl: {:?}", e))?;
+        let pasted = self.enigo.key(Key::Unicode('v'), Direction::Click);
+        // Always release Ctrl, even if the V press failed
+        let released = self.enigo.key(Key::Control, Direction::Release);
+        pasted.map_err(|e| anyhow::anyhow!("Failed to send Ctrl+V: {:?}", e))?;
+        released.map_err(|e| anyhow::anyhow!("Failed to release Ctrl: {:?}", e))?;
 
+        std::thread::sleep(PASTE_RESTORE_DELAY);
+        if let Some(saved) = saved {
+            clipboard
+                .set_text(saved)
+                .map_err(|e| anyhow::anyhow!("Failed to restore clipboard: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+impl TextSink for Typer {
+    fn type_text(&mut self, text: &str, per_key: bool) -> Result<()> {
+        // `Keys` output always sends individual key presses
+        if per_key {
+            self.type_keystrokes(text, true)
+        } else {
+            self.type_text_with_mode(text, self.type_mode)
+        }
+    }
+
+    fn set_clipboard(&mut self, text: &str) -> Result<()> {
+        let mut clipboard = arboard::Clipboard::new()
+            .map_err(|e| anyhow::anyhow!("Failed to open clipboard: {}", e))?;
+        clipboard
+            .set_text(text)
+            .map_err(|e| anyhow::anyhow!("Failed to set clipboard: {}", e))?;
         Ok(())
     }
+
+    fn clipboard_text(&mut self) -> Option<String> {
+        arboard::Clipboard::new().ok()?.get_text().ok()
+    }
+
+    fn backspace(&mut self, count: usize) -> Result<()> {
+        if count == 0 {
+            return Ok(());
+        }
+
+        // Small delay to ensure the target window is ready
+        std::thread::sleep(std::time::Duration::from_millis(50));
+
+        #[cfg(target_os = "windows")]
+        send_key_inputs(&vec![KeyInput::Backspace; count])?;
+
+        #[cfg(not(target_os = "windows"))]
+        for _ in 0..count {
+            self.enigo
+                .key(Key::Backspace, Direction::Click)
+                .map_err(|e| anyhow::anyhow!("Failed to press Backspace: {:?}", e))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct MockSink {
+        typed: Vec<(String, bool)>,
+        clipboard: Option<String>,
+        erased: usize,
+    }
+
+    impl TextSink for MockSink {
+        fn type_text(&mut self, text: &str, per_key: bool) -> Result<()> {
+            self.typed.push((text.to_string(), per_key));
+            Ok(())
+        }
+
+        fn set_clipboard(&mut self, text: &str) -> Result<()> {
+            self.clipboard = Some(text.to_string());
+            Ok(())
+        }
+
+        fn clipboard_text(&mut self) -> Option<String> {
+            self.clipboard.clone()
+        }
+
+        fn backspace(&mut self, count: usize) -> Result<()> {
+            self.erased += count;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_deliver_text_types() {
+        let mut sink = MockSink::default();
+        let result = deliver_text(&mut sink, "hello", OutputMode::Text, false).unwrap();
+        assert_eq!(result, Some(Delivery::Typed));
+        assert_eq!(sink.typed, vec![("hello".to_string(), false)]);
+        assert!(sink.clipboard.is_none());
+
+        let mut sink = MockSink::default();
+        deliver_text(&mut sink, "hello", OutputMode::Keys, true).unwrap();
+        assert_eq!(sink.typed, vec![("hello".to_string(), true)]);
+    }
+
+    #[test]
+    fn test_deliver_text_clipboard_only_never_types() {
+        let mut sink = MockSink::default();
+        let result = deliver_text(&mut sink, "secret", OutputMode::ClipboardOnly, false).unwrap();
+        assert_eq!(result, Some(Delivery::Clipboard));
+        assert!(sink.typed.is_empty());
+        assert_eq!(sink.clipboard.as_deref(), Some("secret"));
+        // Replaced by default
+        deliver_text(&mut sink, "again", OutputMode::ClipboardOnly, false).unwrap();
+        assert_eq!(sink.clipboard.as_deref(), Some("again"));
+    }
+
+    #[test]
+    fn test_deliver_text_appends_to_clipboard() {
+        let mut sink = MockSink::default();
+        // Nothing to append to yet
+        deliver_text(&mut sink, "first", OutputMode::ClipboardOnly, true).unwrap();
+        assert_eq!(sink.clipboard.as_deref(), Some("first"));
+        deliver_text(&mut sink, "second", OutputMode::ClipboardOnly, true).unwrap();
+        assert_eq!(sink.clipboard.as_deref(), Some("first\nsecond"));
+
+        sink.clipboard = Some("notes \n".to_string());
+        deliver_text(&mut sink, "third", OutputMode::ClipboardOnly, true).unwrap();
+        assert_eq!(sink.clipboard.as_deref(), Some("notes\nthird"));
+        sink.clipboard = Some("  ".to_string());
+        deliver_text(&mut sink, "fourth", OutputMode::ClipboardOnly, true).unwrap();
+        assert_eq!(sink.clipboard.as_deref(), Some("fourth"));
+        assert!(sink.typed.is_empty());
+    }
+
+    #[test]
+    fn test_erase_typed() {
+        let mut sink = MockSink::default();
+        assert!(erase_typed(&mut sink, 4, OutputMode::Text).unwrap());
+        assert!(erase_typed(&mut sink, 2, OutputMode::Keys).unwrap());
+        assert!(!erase_typed(&mut sink, 0, OutputMode::Text).unwrap());
+        // Nothing was typed to erase
+        assert!(!erase_typed(&mut sink, 3, OutputMode::ClipboardOnly).unwrap());
+        assert_eq!(sink.erased, 6);
+    }
+
+    #[test]
+    fn test_key_inputs_utf16() {
+        use KeyInput::*;
+
+        let units: Vec<KeyInput> = "café".encode_utf16().map(Unicode).collect();
+        assert_eq!(key_inputs("café"), units);
+        assert_eq!(key_inputs("é"), vec![Unicode(0x00E9)]);
+        assert_eq!(key_inputs("naïve")[2], Unicode(0x00EF));
+
+        // Outside the BMP: a high/low surrogate pair
+        assert_eq!(key_inputs("😀"), vec![Unicode(0xD83D), Unicode(0xDE00)]);
+
+        let inputs = key_inputs("café naïve 😀");
+        assert_eq!(inputs.len(), "café naïve 😀".encode_utf16().count());
+        let units: Vec<u16> = inputs
+            .iter()
+            .map(|i| match i {
+                Unicode(u) => *u,
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert_eq!(String::from_utf16(&units).unwrap(), "café naïve 😀");
+    }
+
+    #[test]
+    fn test_key_inputs_line_breaks() {
+        use KeyInput::*;
+        assert_eq!(
+            key_inputs("a\r\nb\tc\n"),
+            vec![Unicode(b'a' as u16), Enter, Unicode(b'b' as u16), Tab, Unicode(b'c' as u16), Enter]
+        );
+    }
+
+    #[test]
+    fn test_deliver_text_empty() {
+        let mut sink = MockSink::default();
+        for mode in [OutputMode::Text, OutputMode::Keys, OutputMode::ClipboardOnly] {
+            assert_eq!(deliver_text(&mut sink, "", mode, true).unwrap(), None);
+        }
+        assert!(sink.typed.is_empty());
+        assert!(sink.clipboard.is_none());
+    }
+
+    #[test]
+    fn test_incremental_output() {
+        let mut output = IncrementalOutput::default();
+        assert!(output.is_empty());
+        assert_eq!(output.segment(" Hello there."), "Hello there.");
+        assert_eq!(output.segment("  "), "");
+        assert_eq!(output.segment(" How are"), " How are");
+        // The final text reworded a typed word; only the new words are added
+        assert_eq!(output.finish("Hello there. Who are you?"), " you?");
+        assert_eq!(output.finish("Hello there. Who are you?"), "");
+
+        let mut nothing_streamed = IncrementalOutput::default();
+        assert_eq!(nothing_streamed.finish(" All at once "), "All at once");
+    }
 }
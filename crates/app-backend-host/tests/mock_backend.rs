@@ -0,0 +1,148 @@
+//! The full load/transcribe path against the mock backend DLL
+//!
+//! Cargo builds the mock's cdylib next to this test binary because it is a
+//! dev-dependency. Each test copies it into a temporary backends folder with the
+//! manifest, the way an installed backend is laid out.
+
+use app_backend_host::{
+    model_load_advice, transcribe_error_code, BackendRegistry, DecodeOptions, DeviceUsed,
+    TranscribeRequest, DEFAULT_SAMPLE_RATE,
+};
+use app_core::SttResult;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const MANIFEST: &str = include_str!("../../backends/mock/manifest.json");
+
+/// Held by tests that call into the mock, since `MOCK_BACKEND_FAIL` is process-wide
+static FAIL_ENV_LOCK: Mutex<()> = Mutex::new(());
+
+/// A backends folder containing only the mock, plus an empty models folder
+fn install_mock(name: &str) -> (PathBuf, PathBuf) {
+    let root = std::env::temp_dir().join(format!("app_host_mock_{}", name));
+    let _ = std::fs::remove_dir_all(&root);
+    let backend_dir = root.join("backends/mock");
+    std::fs::create_dir_all(&backend_dir).unwrap();
+
+    // The manifest names the Windows DLL; use this platform's file name
+    let dll_name = libloading::library_filename("mock_backend");
+    let dll_name = dll_name.to_str().unwrap();
+    let deps_dir = std::env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    std::fs::copy(deps_dir.join(dll_name), backend_dir.join(dll_name)).unwrap();
+    let manifest = MANIFEST.replace("mock_backend.dll", dll_name);
+    std::fs::write(backend_dir.join("manifest.json"), manifest).unwrap();
+
+    let models_dir = root.join("models");
+    std::fs::create_dir_all(&models_dir).unwrap();
+    (root.join("backends"), models_dir)
+}
+
+fn seconds(n: u32) -> Vec<f32> {
+    vec![0.0; (n * DEFAULT_SAMPLE_RATE) as usize]
+}
+
+#[test]
+fn test_mock_backend_transcribes() {
+    let _env = FAIL_ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (backends_dir, models_dir) = install_mock("transcribes");
+    let registry = BackendRegistry::new(backends_dir);
+    let backend = registry.load("mock").unwrap();
+    assert_eq!(backend.display_name, "Mock (testing only)");
+    let capabilities = backend.capabilities();
+    assert!(capabilities.cuda && capabilities.streaming && capabilities.warmup);
+
+    // The model has no files, so "downloading" it only creates its folder
+    let manifest_model = &backend.models()[0];
+    assert!(!manifest_model.is_downloaded(&models_dir));
+    let model_path = models_dir.join(&manifest_model.folder_name);
+    assert!(backend.create_model(&model_path, false).is_err());
+    std::fs::create_dir_all(&model_path).unwrap();
+    assert!(manifest_model.is_downloaded(&models_dir));
+
+    let (model, device) = backend.create_model_with_fallback(&model_path, true).unwrap();
+    assert_eq!(device, DeviceUsed::Gpu);
+    model.warmup().unwrap();
+
+    let transcript = model.transcribe(&seconds(2)).unwrap();
+    assert_eq!(transcript.text, "test test");
+    assert_eq!(transcript.detected_language.as_deref(), Some("en"));
+    assert_eq!(model.device_used().as_deref(), Some("CUDA"));
+    assert_eq!(model.last_timing().unwrap().inference_ms, 200);
+
+    let request =
+        TranscribeRequest { language: Some("fr"), timestamps: true, ..Default::default() };
+    let mut partial = Vec::new();
+    let mut last = None;
+    let transcript = model
+        .transcribe_with_segments(&seconds(3), &request, |text, is_final| {
+            if is_final {
+                last = Some(text.to_string());
+            } else {
+                partial.push(text.to_string());
+            }
+        })
+        .unwrap();
+    assert_eq!(partial, ["test", "test", "test"]);
+    assert_eq!(last.as_deref(), Some("test test test"));
+    assert_eq!(transcript.detected_language.as_deref(), Some("fr"));
+    let spans: Vec<_> = transcript.segments.iter().map(|s| (s.start_ms, s.end_ms)).collect();
+    assert_eq!(spans, [(0, 1000), (1000, 2000), (2000, 3000)]);
+    // Detection is limited to the allowed languages; a single one is just requested
+    let detected = |allowed: &[&str]| {
+        let allowed: Vec<String> = allowed.iter().map(|l| l.to_string()).collect();
+        let request = TranscribeRequest { allowed_languages: &allowed, ..Default::default() };
+        model.transcribe_with(&seconds(1), &request).unwrap().detected_language.unwrap()
+    };
+    assert_eq!(detected(&["de", "fr"]), "de");
+    assert_eq!(detected(&["fr", "en"]), "en");
+    assert_eq!(detected(&["it"]), "it");
+
+    // Decoder tweaks are passed through; backends without them carry on as normal
+    let decode = DecodeOptions {
+        suppress_blank: Some(false),
+        no_repeat_ngram_size: 3,
+        max_initial_timestamp_ms: 2000,
+    };
+    let request = TranscribeRequest { decode, ..Default::default() };
+    assert_eq!(model.transcribe_with(&seconds(1), &request).unwrap().text, "test");
+}
+
+#[test]
+fn test_mock_backend_simulated_errors() {
+    let _env = FAIL_ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (backends_dir, models_dir) = install_mock("errors");
+    let backend = BackendRegistry::new(backends_dir).load("mock").unwrap();
+    let model_path = models_dir.join("mock");
+    std::fs::create_dir_all(&model_path).unwrap();
+    let fail = |what: &str| std::env::set_var(mock_backend::FAIL_ENV, what);
+
+    fail("load");
+    let err = backend.create_model(&model_path, false).err().unwrap();
+    assert!(err.to_string().contains("Mock model load failure"), "{}", err);
+    assert!(model_load_advice(&err).contains("re-download"));
+
+    fail("gpu");
+    let (model, device) = backend.create_model_with_fallback(&model_path, true).unwrap();
+    assert_eq!(device, DeviceUsed::Cpu);
+    assert_eq!(model.transcribe(&seconds(1)).unwrap().text, "test");
+    assert_eq!(model.device_used().as_deref(), Some("CPU"));
+    assert!(backend.create_model(&model_path, true).is_err());
+
+    fail("transcribe, warmup");
+    let err = model.transcribe(&seconds(1)).err().unwrap();
+    assert_eq!(transcribe_error_code(&err), Some(SttResult::TranscriptionFailed));
+    assert!(model.warmup().is_err());
+
+    std::env::remove_var(mock_backend::FAIL_ENV);
+    assert_eq!(model.transcribe(&seconds(1)).unwrap().text, "test");
+    model.warmup().unwrap();
+}
+
+#[test]
+fn test_mock_manifest_parses() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../backends/mock/manifest.json");
+    let manifest = app_backend_host::BackendManifest::load(&path).unwrap();
+    assert_eq!(manifest.id, "mock");
+    let model = &manifest.models[0];
+    assert_eq!((model.size_mb, model.files.len()), (0, 0));
+}
@@ -0,0 +1,138 @@
+//! The public API against a stub backend linked into the test binary
+
+use app_backend_host::{
+    model_load_advice, Backend, BackendManifest, ManifestCapabilities, TranscribeRequest,
+    DEFAULT_SAMPLE_RATE,
+};
+use app_core::{
+    BackendInfo, BackendVTable, ModelConfig, ModelHandle, SttResult, TranscribeOptions,
+    TranscribeResult, API_VERSION,
+};
+use std::ffi::{c_char, CStr};
+use std::path::Path;
+use std::ptr;
+
+static STUB_ID: &[u8] = b"stub\0";
+static STUB_NAME: &[u8] = b"Stub Backend\0";
+static STUB_TEXT: &[u8] = b"hello from the stub\0";
+static STUB_DEVICE: &[u8] = b"CPU\0";
+static STUB_ERROR: &[u8] = b"stub: no such model\0";
+
+unsafe extern "C" fn stub_get_backend_info() -> BackendInfo {
+    BackendInfo {
+        api_version: API_VERSION,
+        id: STUB_ID.as_ptr() as *const c_char,
+        display_name: STUB_NAME.as_ptr() as *const c_char,
+        version: STUB_ID.as_ptr() as *const c_char,
+        supports_cuda: false,
+        required_sample_rate: DEFAULT_SAMPLE_RATE,
+        required_channels: 1,
+    }
+}
+
+/// Any path loads except "missing"
+unsafe extern "C" fn stub_create_model(config: *const ModelConfig) -> *mut ModelHandle {
+    if CStr::from_ptr((*config).model_path).to_bytes() == b"missing" {
+        return ptr::null_mut();
+    }
+    Box::into_raw(Box::new(0u8)) as *mut ModelHandle
+}
+
+unsafe extern "C" fn stub_destroy_model(handle: *mut ModelHandle) {
+    drop(Box::from_raw(handle as *mut u8));
+}
+
+/// Always the same text, tagged with the requested language
+unsafe extern "C" fn stub_transcribe(
+    _handle: *mut ModelHandle,
+    _audio: *const f32,
+    _audio_len: usize,
+    options: *const TranscribeOptions,
+) -> TranscribeResult {
+    TranscribeResult {
+        code: SttResult::Ok,
+        text: STUB_TEXT.as_ptr() as *const c_char,
+        text_len: STUB_TEXT.len() - 1,
+        device_used: STUB_DEVICE.as_ptr() as *const c_char,
+        inference_ms: 5,
+        segments: ptr::null(),
+        segment_count: 0,
+        detected_language: (*options).language,
+        avg_logprob: -0.5,
+        no_speech_prob: 0.0,
+    }
+}
+
+/// Everything in the result is static
+unsafe extern "C" fn stub_free_result(_result: *mut TranscribeResult) {}
+
+unsafe extern "C" fn stub_get_last_error() -> *const c_char {
+    STUB_ERROR.as_ptr() as *const c_char
+}
+
+unsafe extern "C" fn stub_get_last_error_code() -> SttResult {
+    SttResult::ModelNotFound
+}
+
+fn stub_backend() -> Backend {
+    let manifest = BackendManifest {
+        id: "stub".to_string(),
+        display_name: "Stub Backend".to_string(),
+        dll_name: "stub.dll".to_string(),
+        version: "0.0.0".to_string(),
+        models: vec![],
+        capabilities: ManifestCapabilities { supports_cuda: false, supports_multilingual: true },
+    };
+    let vtable = BackendVTable {
+        get_backend_info: stub_get_backend_info,
+        create_model: stub_create_model,
+        destroy_model: stub_destroy_model,
+        transcribe: stub_transcribe,
+        free_result: stub_free_result,
+        get_last_error: stub_get_last_error,
+        get_last_error_code: Some(stub_get_last_error_code),
+        warmup: None,
+        transcribe_streaming: None,
+    };
+    unsafe { Backend::from_vtable(manifest, vtable) }.unwrap()
+}
+
+#[test]
+fn test_stub_backend_transcribes() {
+    let backend = stub_backend();
+    assert_eq!(backend.id, "stub");
+    assert_eq!(backend.display_name, "Stub Backend");
+    let capabilities = backend.capabilities();
+    assert_eq!(capabilities.sample_rate, DEFAULT_SAMPLE_RATE);
+    assert!(!capabilities.streaming && !capabilities.warmup);
+
+    let model = backend.create_model(Path::new("models/stub"), false).unwrap();
+    // The model keeps working after the backend is dropped
+    drop(backend);
+
+    let samples = vec![0.0f32; DEFAULT_SAMPLE_RATE as usize];
+    let transcript = model.transcribe(&samples).unwrap();
+    assert_eq!(transcript.text, "hello from the stub");
+    assert_eq!(transcript.detected_language, None);
+    assert_eq!(transcript.avg_logprob, -0.5);
+    assert_eq!(model.device_used().as_deref(), Some("CPU"));
+    assert_eq!(model.last_timing().unwrap().inference_ms, 5);
+
+    let request = TranscribeRequest { language: Some("de"), ..Default::default() };
+    let transcript = model.transcribe_with(&samples, &request).unwrap();
+    assert_eq!(transcript.detected_language.as_deref(), Some("de"));
+
+    // Accidental taps never reach the backend
+    assert_eq!(model.transcribe(&[0.0; 10]).unwrap().text, "");
+}
+
+#[test]
+fn test_stub_backend_load_errors() {
+    let backend = stub_backend();
+    let err = backend.create_model(Path::new("missing"), false).err().unwrap();
+    assert!(err.to_string().contains("stub: no such model"), "{}", err);
+    assert!(model_load_advice(&err).contains("re-download"));
+
+    let err = Backend::open(Path::new("no/such/backend")).err().unwrap();
+    assert!(format!("{:#}", err).contains("manifest.json"), "{:#}", err);
+}
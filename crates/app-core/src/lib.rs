@@ -3,10 +3,42 @@
 //! This crate defines the C-compatible interface that all speech-to-text
 //! backend DLLs must implement.
 
-use std::ffi::c_char;
+use std::ffi::{c_char, c_void, CString};
+
+pub mod vram;
 
 /// API version for compatibility checking
-pub const API_VERSION: u32 = 1;
+/// v2: added `TranscribeResult::inference_ms`
+/// v3: added `TranscribeResult::segments` / `segment_count`
+/// v4: added `TranscribeOptions::translate`
+/// v5: added `TranscribeResult::detected_language`
+/// v6: added the optional `transcribe_streaming` export
+/// v7: added `TranscribeOptions::beam_size` / `num_hypotheses`
+/// v8: added `TranscribeOptions::initial_prompt`
+/// v9: added `BackendInfo::required_sample_rate` / `required_channels`
+/// v10: added the `ModelCorrupt`, `CudaOutOfMemory`, `InvalidAudio` and
+/// `LanguageUnsupported` result codes
+/// v11: added `ModelConfig::allow_cpu_fallback`
+/// v12: added `TranscribeResult::avg_logprob` / `no_speech_prob`
+/// v13: added `ModelConfig::compute_type`
+/// v14: added `ModelConfig::cpu_threads`
+/// v15: added the `get_abi_hash` export (required from this version on)
+/// v16: added `TranscribeOptions::allowed_languages`
+/// v17: added `TranscribeOptions::suppress_blank` / `no_repeat_ngram_size` /
+/// `max_initial_timestamp_ms`
+///
+/// Changes must be additive: new exports are loaded optionally, and new
+/// fields go at the end of a struct. A change that alters the layout of a
+/// struct passed across the boundary (as v2 did for `TranscribeResult`)
+/// must also raise `API_VERSION_MIN_SUPPORTED`. When bumping, add the
+/// previous version's layout to `ABI_HASHES` so its backends keep loading.
+pub const API_VERSION: u32 = 17;
+
+/// Oldest backend API version the host can still load
+pub const API_VERSION_MIN_SUPPORTED: u32 = 14;
+
+/// First API version whose backends must export `get_abi_hash`
+pub const ABI_HASH_SINCE_VERSION: u32 = 15;
 
 /// Result codes for backend operations
 #[repr(C)]
@@ -18,9 +50,46 @@ pub enum SttResult {
     TranscriptionFailed = 3,
     OutOfMemory = 4,
     UnsupportedDevice = 5,
+    ModelNotFound = 6,
+    UnsupportedModel = 7,
+    /// The model can't run the requested task (e.g. translate on an English-only model)
+    UnsupportedTask = 8,
+    /// The model file exists but is truncated or not a valid model
+    ModelCorrupt = 9,
+    /// The GPU ran out of memory (a smaller model or CPU mode may work)
+    CudaOutOfMemory = 10,
+    /// The audio can't be transcribed (e.g. NaN or infinite samples)
+    InvalidAudio = 11,
+    /// The model doesn't know the requested language
+    LanguageUnsupported = 12,
     UnknownError = 99,
 }
 
+/// Pick the closest `SttResult` for an error message from the inference library
+///
+/// Memory errors become `CudaOutOfMemory` when `on_gpu` is set or the message
+/// names CUDA; anything unrecognised maps to `fallback`.
+pub fn classify_error(msg: &str, on_gpu: bool, fallback: SttResult) -> SttResult {
+    let lower = msg.to_lowercase();
+    let has = |keys: &[&str]| keys.iter().any(|k| lower.contains(k));
+    let corrupt = ["corrupt", "magic", "unexpected end", "truncated", "invalid model"];
+    if has(&["out of memory", "outofmemory", "alloc"]) {
+        if on_gpu || lower.contains("cuda") {
+            SttResult::CudaOutOfMemory
+        } else {
+            SttResult::OutOfMemory
+        }
+    } else if has(&corrupt) {
+        SttResult::ModelCorrupt
+    } else if lower.contains("language") {
+        SttResult::LanguageUnsupported
+    } else if lower.contains("compute type") {
+        SttResult::InvalidParam
+    } else {
+        fallback
+    }
+}
+
 /// Configuration for creating a model
 #[repr(C)]
 pub struct ModelConfig {
@@ -30,6 +99,46 @@ pub struct ModelConfig {
     pub use_gpu: bool,
     /// Language code (e.g., "en") or null for auto-detect
     pub language: *const c_char,
+    /// Load on the CPU if GPU creation fails instead of returning null.
+    /// After a fallback the GPU error is still reported by `get_last_error`
+    /// / `get_last_error_code`. Backends without a fallback ignore this.
+    pub allow_cpu_fallback: bool,
+    /// Weight/compute precision, e.g. "int8" or "float16" (null = backend default).
+    /// Backends without a choice of precision ignore this.
+    pub compute_type: *const c_char,
+    /// CPU threads for inference (0 = the inference library's default)
+    pub cpu_threads: u32,
+}
+
+/// An on/off option that can be left to the backend's default
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OptionalBool {
+    #[default]
+    Default = 0,
+    Off = 1,
+    On = 2,
+}
+
+impl OptionalBool {
+    /// The requested value, or `None` for the backend default
+    pub fn get(self) -> Option<bool> {
+        match self {
+            Self::Default => None,
+            Self::Off => Some(false),
+            Self::On => Some(true),
+        }
+    }
+}
+
+impl From<Option<bool>> for OptionalBool {
+    fn from(value: Option<bool>) -> Self {
+        match value {
+            None => Self::Default,
+            Some(false) => Self::Off,
+            Some(true) => Self::On,
+        }
+    }
 }
 
 /// Options for transcription
@@ -37,8 +146,28 @@ pub struct ModelConfig {
 pub struct TranscribeOptions {
     /// Language code (e.g., "en") or null for auto-detect
     pub language: *const c_char,
-    /// Whether to include timestamps
+    /// Whether to fill in `TranscribeResult::segments`
     pub timestamps: bool,
+    /// Translate speech to English instead of transcribing it
+    pub translate: bool,
+    /// Beam search width (0 or 1 = greedy decoding)
+    pub beam_size: u32,
+    /// Hypotheses kept by beam search (0 = backend default)
+    pub num_hypotheses: u32,
+    /// Text that conditions the decoder, e.g. expected jargon (null-terminated UTF-8, or null)
+    pub initial_prompt: *const c_char,
+    /// Comma-separated language codes auto-detect may choose from (e.g. "en,de"), or
+    /// null for any. Ignored when `language` is set.
+    pub allowed_languages: *const c_char,
+    /// Keep the decoder from starting with a blank (CTranslate2's default is on).
+    /// Turning it off can bring back a first word that gets dropped.
+    pub suppress_blank: OptionalBool,
+    /// Never repeat a run of this many tokens (0 = backend default, no limit).
+    /// Curbs repetition loops, but also blocks genuine repeats of that length.
+    pub no_repeat_ngram_size: u32,
+    /// Latest the first timestamp may fall, in ms (0 = backend default, 1000). Only
+    /// used when decoding with timestamps; raise it for clips with leading silence.
+    pub max_initial_timestamp_ms: u32,
 }
 
 impl Default for TranscribeOptions {
@@ -46,6 +175,14 @@ impl Default for TranscribeOptions {
         Self {
             language: std::ptr::null(),
             timestamps: false,
+            translate: false,
+            beam_size: 0,
+            num_hypotheses: 0,
+            initial_prompt: std::ptr::null(),
+            allowed_languages: std::ptr::null(),
+            suppress_blank: OptionalBool::Default,
+            no_repeat_ngram_size: 0,
+            max_initial_timestamp_ms: 0,
         }
     }
 }
@@ -61,6 +198,31 @@ pub struct TranscribeResult {
     pub text_len: usize,
     /// Device used for transcription ("CPU", "CUDA", etc.)
     pub device_used: *const c_char,
+    /// Wall-clock inference time in milliseconds (0 if inference was skipped)
+    pub inference_ms: u64,
+    /// Timed segments (owned by backend), null unless `TranscribeOptions::timestamps` was set
+    pub segments: *const Segment,
+    /// Number of entries in `segments`
+    pub segment_count: usize,
+    /// Language of the audio, e.g. "de" (null-terminated UTF-8, owned by backend).
+    /// The requested language, or the detected one when auto-detect was used.
+    /// Null if the backend couldn't tell.
+    pub detected_language: *const c_char,
+    /// Mean log probability of the decoded tokens (0.0 if the backend can't report it)
+    pub avg_logprob: f32,
+    /// Probability that the audio contains no speech (0.0 if the backend can't report it)
+    pub no_speech_prob: f32,
+}
+
+/// A timed span of transcribed text
+#[repr(C)]
+pub struct Segment {
+    /// Start time in milliseconds from the beginning of the audio
+    pub start_ms: i64,
+    /// End time in milliseconds from the beginning of the audio
+    pub end_ms: i64,
+    /// Segment text (null-terminated UTF-8, owned by backend)
+    pub text: *const c_char,
 }
 
 /// Information about a backend
@@ -76,6 +238,10 @@ pub struct BackendInfo {
     pub version: *const c_char,
     /// Whether this backend supports CUDA
     pub supports_cuda: bool,
+    /// Sample rate (Hz) the backend expects its input audio at
+    pub required_sample_rate: u32,
+    /// Channel count the backend expects (interleaved if > 1)
+    pub required_channels: u32,
 }
 
 /// Opaque handle to a loaded model
@@ -84,6 +250,109 @@ pub struct ModelHandle {
     _opaque: [u8; 0],
 }
 
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Fold `value` into an FNV-1a hash, one byte at a time
+const fn fnv1a(mut hash: u64, value: usize) -> u64 {
+    let bytes = (value as u64).to_le_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+    hash
+}
+
+/// Fold a struct's size, alignment and field offsets into `hash`
+macro_rules! hash_layout {
+    ($hash:expr, $ty:ty { $($field:ident),* $(,)? }) => {{
+        let mut hash = fnv1a($hash, std::mem::size_of::<$ty>());
+        hash = fnv1a(hash, std::mem::align_of::<$ty>());
+        $(hash = fnv1a(hash, std::mem::offset_of!($ty, $field));)*
+        hash
+    }};
+}
+
+/// Hash of every FFI struct layout, with `TranscribeOptions` as `$options`
+macro_rules! abi_hash {
+    ($options:ty { $($field:ident),* $(,)? }) => {{
+        let mut hash = fnv1a(FNV_OFFSET, std::mem::size_of::<SttResult>());
+        hash = hash_layout!(hash, ModelConfig {
+            model_path, use_gpu, language, allow_cpu_fallback, compute_type, cpu_threads,
+        });
+        hash = hash_layout!(hash, $options { $($field),* });
+        hash = hash_layout!(hash, TranscribeResult {
+            code, text, text_len, device_used, inference_ms, segments, segment_count,
+            detected_language, avg_logprob, no_speech_prob,
+        });
+        hash = hash_layout!(hash, Segment { start_ms, end_ms, text });
+        hash_layout!(hash, BackendInfo {
+            api_version, id, display_name, version, supports_cuda, required_sample_rate,
+            required_channels,
+        })
+    }};
+}
+
+/// Hash of the layout of every struct passed across the FFI boundary
+///
+/// Backends export it through `get_abi_hash` and the host refuses a backend whose
+/// hash differs from the one its API version should have (see `abi_hash_for`):
+/// that DLL was built against other struct definitions and would read or write
+/// the wrong memory.
+pub const ABI_HASH: u64 = abi_hash!(TranscribeOptions {
+    language, timestamps, translate, beam_size, num_hypotheses, initial_prompt,
+    allowed_languages, suppress_blank, no_repeat_ngram_size, max_initial_timestamp_ms,
+});
+
+/// Struct layouts of older API versions, kept only to compute their ABI hashes
+#[allow(dead_code)]
+mod legacy {
+    use std::ffi::c_char;
+
+    /// `TranscribeOptions` in v15
+    #[repr(C)]
+    pub struct TranscribeOptionsV15 {
+        pub language: *const c_char,
+        pub timestamps: bool,
+        pub translate: bool,
+        pub beam_size: u32,
+        pub num_hypotheses: u32,
+        pub initial_prompt: *const c_char,
+    }
+
+    /// `TranscribeOptions` in v16
+    #[repr(C)]
+    pub struct TranscribeOptionsV16 {
+        pub language: *const c_char,
+        pub timestamps: bool,
+        pub translate: bool,
+        pub beam_size: u32,
+        pub num_hypotheses: u32,
+        pub initial_prompt: *const c_char,
+        pub allowed_languages: *const c_char,
+    }
+}
+
+/// ABI hashes of the API versions since `ABI_HASH_SINCE_VERSION`. Older versions'
+/// structs are prefixes of today's, so their backends still load.
+const ABI_HASHES: &[(u32, u64)] = &[
+    (15, abi_hash!(legacy::TranscribeOptionsV15 {
+        language, timestamps, translate, beam_size, num_hypotheses, initial_prompt,
+    })),
+    (16, abi_hash!(legacy::TranscribeOptionsV16 {
+        language, timestamps, translate, beam_size, num_hypotheses, initial_prompt,
+        allowed_languages,
+    })),
+    (API_VERSION, ABI_HASH),
+];
+
+/// The ABI hash a backend built for `api_version` exports, if that version has one
+pub fn abi_hash_for(api_version: u32) -> Option<u64> {
+    ABI_HASHES.iter().find(|(version, _)| *version == api_version).map(|&(_, hash)| hash)
+}
+
 // Function pointer types for backend exports
 
 /// Get information about this backend
@@ -97,7 +366,7 @@ pub type CreateModelFn = unsafe extern "C" fn(config: *const ModelConfig) -> *mu
 pub type DestroyModelFn = unsafe extern "C" fn(handle: *mut ModelHandle);
 
 /// Transcribe audio samples
-/// Audio must be f32 samples at 16kHz mono
+/// Audio must be f32 samples in the format reported by `BackendInfo`
 pub type TranscribeFn = unsafe extern "C" fn(
     handle: *mut ModelHandle,
     audio: *const f32,
@@ -105,6 +374,23 @@ pub type TranscribeFn = unsafe extern "C" fn(
     options: *const TranscribeOptions,
 ) -> TranscribeResult;
 
+/// Receives transcribed text as it is produced (null-terminated UTF-8,
+/// only valid for the duration of the call). Called with `is_final = false`
+/// for each new segment, then once with the full text and `is_final = true`.
+pub type SegmentCallback =
+    unsafe extern "C" fn(text: *const c_char, is_final: bool, user_data: *mut c_void);
+
+/// Transcribe audio samples, reporting segments through `callback` as they are decoded
+/// Optional export: the host falls back to `transcribe` without it
+pub type TranscribeStreamingFn = unsafe extern "C" fn(
+    handle: *mut ModelHandle,
+    audio: *const f32,
+    audio_len: usize,
+    options: *const TranscribeOptions,
+    callback: SegmentCallback,
+    user_data: *mut c_void,
+) -> TranscribeResult;
+
 /// Free a transcription result
 pub type FreeResultFn = unsafe extern "C" fn(result: *mut TranscribeResult);
 
@@ -112,6 +398,18 @@ pub type FreeResultFn = unsafe extern "C" fn(result: *mut TranscribeResult);
 /// Returns null if no error
 pub type GetLastErrorFn = unsafe extern "C" fn() -> *const c_char;
 
+/// Get the result code of the last failed call (e.g. why create_model returned null)
+/// Optional export: returns `Ok` if no error is recorded
+pub type GetLastErrorCodeFn = unsafe extern "C" fn() -> SttResult;
+
+/// Get the `ABI_HASH` the backend was built with
+/// Required from `ABI_HASH_SINCE_VERSION`; older backends don't export it
+pub type GetAbiHashFn = unsafe extern "C" fn() -> u64;
+
+/// Run a tiny inference to force lazy allocations before the first real call
+/// Optional export: backends without it are simply not warmed up
+pub type WarmupFn = unsafe extern "C" fn(handle: *mut ModelHandle) -> SttResult;
+
 /// VTable containing all backend function pointers
 #[derive(Clone)]
 pub struct BackendVTable {
@@ -121,6 +419,9 @@ pub struct BackendVTable {
     pub transcribe: TranscribeFn,
     pub free_result: FreeResultFn,
     pub get_last_error: GetLastErrorFn,
+    pub get_last_error_code: Option<GetLastErrorCodeFn>,
+    pub warmup: Option<WarmupFn>,
+    pub transcribe_streaming: Option<TranscribeStreamingFn>,
 }
 
 // Helper functions for backends to create FFI strings
@@ -133,8 +434,140 @@ macro_rules! cstr {
     };
 }
 
+/// Move `(start_ms, end_ms, text)` segments into a heap array for
+/// `TranscribeResult::segments`. Release it with [`free_segments`].
+pub fn segments_into_raw(segments: Vec<(i64, i64, String)>) -> (*const Segment, usize) {
+    if segments.is_empty() {
+        return (std::ptr::null(), 0);
+    }
+    let array: Box<[Segment]> = segments
+        .into_iter()
+        .map(|(start_ms, end_ms, text)| Segment {
+            start_ms,
+            end_ms,
+            text: CString::new(text.replace('\0', ""))
+                .unwrap_or_default()
+                .into_raw(),
+        })
+        .collect();
+    let count = array.len();
+    (Box::into_raw(array) as *const Segment, count)
+}
+
+/// Free an array created by [`segments_into_raw`]
+///
+/// # Safety
+/// `segments` and `count` must come from one `segments_into_raw` call and
+/// must not be freed twice.
+pub unsafe fn free_segments(segments: *const Segment, count: usize) {
+    if segments.is_null() {
+        return;
+    }
+    let array = Box::from_raw(std::ptr::slice_from_raw_parts_mut(segments as *mut Segment, count));
+    for segment in array.iter() {
+        if !segment.text.is_null() {
+            drop(CString::from_raw(segment.text as *mut c_char));
+        }
+    }
+}
+
 /// Helper trait for setting thread-local error messages
 pub trait SetLastError {
     fn set_last_error(msg: &str);
     fn clear_last_error();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    #[test]
+    fn test_segments_round_trip() {
+        let (ptr, count) = segments_into_raw(vec![
+            (0, 1200, "Hello".to_string()),
+            (1200, 2500, "world".to_string()),
+        ]);
+        assert_eq!(count, 2);
+
+        let segments = unsafe { std::slice::from_raw_parts(ptr, count) };
+        assert_eq!(segments[1].start_ms, 1200);
+        assert_eq!(segments[1].end_ms, 2500);
+        let text = unsafe { CStr::from_ptr(segments[1].text) };
+        assert_eq!(text.to_str().unwrap(), "world");
+
+        unsafe { free_segments(ptr, count) };
+    }
+
+    #[test]
+    fn test_abi_hash_for_every_supported_version() {
+        for version in ABI_HASH_SINCE_VERSION.max(API_VERSION_MIN_SUPPORTED)..=API_VERSION {
+            assert!(abi_hash_for(version).is_some(), "no ABI hash for v{}", version);
+        }
+        assert_eq!(abi_hash_for(API_VERSION), Some(ABI_HASH));
+        // Each version's layout differs from the others'
+        assert!(ABI_HASHES.iter().all(|&(v, h)| ABI_HASHES.iter().all(|&(w, g)| v == w || h != g)));
+    }
+
+    #[test]
+    fn test_optional_bool() {
+        for value in [None, Some(false), Some(true)] {
+            assert_eq!(OptionalBool::from(value).get(), value);
+        }
+        assert_eq!(TranscribeOptions::default().suppress_blank, OptionalBool::Default);
+    }
+
+    #[test]
+    fn test_classify_error() {
+        let fallback = SttResult::TranscriptionFailed;
+        let cases = [
+            ("CUDA failed with error out of memory", false, SttResult::CudaOutOfMemory),
+            ("failed to allocate buffer", true, SttResult::CudaOutOfMemory),
+            ("failed to allocate buffer", false, SttResult::OutOfMemory),
+            ("invalid model file (bad magic)", false, SttResult::ModelCorrupt),
+            ("Unsupported language 'xx'", false, SttResult::LanguageUnsupported),
+            (
+                "Requested float16 compute type, but the target device does not support it",
+                false,
+                SttResult::InvalidParam,
+            ),
+            ("FailedToDecode", false, fallback),
+        ];
+        for (msg, on_gpu, expected) in cases {
+            assert_eq!(classify_error(msg, on_gpu, fallback), expected, "{}", msg);
+        }
+    }
+
+    #[test]
+    fn test_abi_hash_tracks_layout() {
+        #[repr(C)]
+        struct Before {
+            a: u32,
+            b: *const c_char,
+        }
+        #[repr(C)]
+        struct Reordered {
+            b: *const c_char,
+            a: u32,
+        }
+        #[repr(C)]
+        struct Appended {
+            a: u32,
+            b: *const c_char,
+            c: bool,
+        }
+        let before = hash_layout!(FNV_OFFSET, Before { a, b });
+        assert_eq!(before, hash_layout!(FNV_OFFSET, Before { a, b }));
+        assert_ne!(before, hash_layout!(FNV_OFFSET, Reordered { a, b }));
+        assert_ne!(before, hash_layout!(FNV_OFFSET, Appended { a, b }));
+        assert_ne!(ABI_HASH, FNV_OFFSET);
+    }
+
+    #[test]
+    fn test_segments_empty() {
+        let (ptr, count) = segments_into_raw(Vec::new());
+        assert!(ptr.is_null());
+        assert_eq!(count, 0);
+        unsafe { free_segments(ptr, count) };
+    }
+}
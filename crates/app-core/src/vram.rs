@@ -0,0 +1,206 @@
+//! Free GPU memory, checked before loading a model onto CUDA
+//!
+//! Loading a model that doesn't fit fails deep inside the inference library with
+//! an unhelpful message. Backends call `check_vram` first and report
+//! `CudaOutOfMemory` instead, so the host falls back to CPU with a clear reason.
+//!
+//! Free memory comes from NVML, which ships with the NVIDIA driver and needs no
+//! CUDA context. The check is best-effort: if NVML can't be loaded or queried it
+//! passes and the load is attempted as before.
+
+use std::ffi::{c_char, c_int, c_uint, c_void, CStr};
+use std::path::Path;
+
+/// VRAM a model needs beyond the size of its files (decoder state, compute buffers)
+pub const VRAM_HEADROOM_MB: u64 = 512;
+
+/// `nvmlMemory_t`; only `free` is read
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+struct NvmlMemory {
+    total: u64,
+    free: u64,
+    used: u64,
+}
+
+type NvmlInit = unsafe extern "C" fn() -> c_int;
+type NvmlShutdown = unsafe extern "C" fn() -> c_int;
+type NvmlDeviceGetCount = unsafe extern "C" fn(*mut c_uint) -> c_int;
+type NvmlDeviceGetHandleByIndex = unsafe extern "C" fn(c_uint, *mut *mut c_void) -> c_int;
+type NvmlDeviceGetMemoryInfo = unsafe extern "C" fn(*mut c_void, *mut NvmlMemory) -> c_int;
+
+const NVML_SUCCESS: c_int = 0;
+
+#[cfg(target_os = "windows")]
+mod sys {
+    use std::ffi::{c_char, c_void};
+
+    /// System32 on current drivers, NVSMI on older ones
+    pub const NVML_LIBRARIES: &[&[u8]] = &[
+        b"nvml.dll\0",
+        b"C:\\Program Files\\NVIDIA Corporation\\NVSMI\\nvml.dll\0",
+    ];
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn LoadLibraryA(name: *const c_char) -> *mut c_void;
+        fn GetProcAddress(module: *mut c_void, name: *const c_char) -> *mut c_void;
+        fn FreeLibrary(module: *mut c_void) -> i32;
+    }
+
+    pub unsafe fn open(name: *const c_char) -> *mut c_void {
+        LoadLibraryA(name)
+    }
+
+    pub unsafe fn symbol(library: *mut c_void, name: *const c_char) -> *mut c_void {
+        GetProcAddress(library, name)
+    }
+
+    pub unsafe fn close(library: *mut c_void) {
+        FreeLibrary(library);
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod sys {
+    use std::ffi::{c_char, c_int, c_void};
+
+    pub const NVML_LIBRARIES: &[&[u8]] = &[b"libnvidia-ml.so.1\0"];
+
+    const RTLD_NOW: c_int = 2;
+
+    extern "C" {
+        fn dlopen(name: *const c_char, flags: c_int) -> *mut c_void;
+        fn dlsym(library: *mut c_void, name: *const c_char) -> *mut c_void;
+        fn dlclose(library: *mut c_void) -> c_int;
+    }
+
+    pub unsafe fn open(name: *const c_char) -> *mut c_void {
+        dlopen(name, RTLD_NOW)
+    }
+
+    pub unsafe fn symbol(library: *mut c_void, name: *const c_char) -> *mut c_void {
+        dlsym(library, name)
+    }
+
+    pub unsafe fn close(library: *mut c_void) {
+        dlclose(library);
+    }
+}
+
+/// Look up an NVML export as the function pointer type `F`
+unsafe fn nvml_fn<F: Copy>(library: *mut c_void, name: &CStr) -> Option<F> {
+    let symbol = sys::symbol(library, name.as_ptr() as *const c_char);
+    // Function pointers and data pointers have the same size on every target we build
+    (!symbol.is_null()).then(|| std::mem::transmute_copy(&symbol))
+}
+
+/// Largest free memory in MB across NVIDIA GPUs, or `None` if it can't be queried.
+/// The largest, because NVML and CUDA may number the devices differently.
+pub fn free_vram_mb() -> Option<u64> {
+    unsafe {
+        let library = sys::NVML_LIBRARIES
+            .iter()
+            .map(|name| sys::open(name.as_ptr() as *const c_char))
+            .find(|library| !library.is_null())?;
+        let free = query_free_mb(library);
+        sys::close(library);
+        free
+    }
+}
+
+unsafe fn query_free_mb(library: *mut c_void) -> Option<u64> {
+    let init: NvmlInit = nvml_fn(library, c"nvmlInit_v2")?;
+    let shutdown: NvmlShutdown = nvml_fn(library, c"nvmlShutdown")?;
+    let get_count: NvmlDeviceGetCount = nvml_fn(library, c"nvmlDeviceGetCount_v2")?;
+    let get_handle: NvmlDeviceGetHandleByIndex =
+        nvml_fn(library, c"nvmlDeviceGetHandleByIndex_v2")?;
+    let get_memory: NvmlDeviceGetMemoryInfo = nvml_fn(library, c"nvmlDeviceGetMemoryInfo")?;
+
+    if init() != NVML_SUCCESS {
+        return None;
+    }
+    let mut count: c_uint = 0;
+    let mut free = None;
+    if get_count(&mut count) == NVML_SUCCESS {
+        for index in 0..count {
+            let mut device = std::ptr::null_mut();
+            let mut memory = NvmlMemory::default();
+            if get_handle(index, &mut device) == NVML_SUCCESS
+                && get_memory(device, &mut memory) == NVML_SUCCESS
+            {
+                free = free.max(Some(memory.free / (1024 * 1024)));
+            }
+        }
+    }
+    shutdown();
+    free
+}
+
+/// Size in MB of a model file, or of the files directly inside a model folder
+pub fn model_size_mb(path: &Path) -> u64 {
+    let bytes = if path.is_dir() {
+        std::fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|e| e.metadata().ok())
+                    .filter(|m| m.is_file())
+                    .map(|m| m.len())
+                    .sum()
+            })
+            .unwrap_or(0)
+    } else {
+        std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+    };
+    bytes / (1024 * 1024)
+}
+
+/// Why a model of `model_mb` can't be loaded with `free_mb` of VRAM, if it can't
+fn shortfall(model_mb: u64, free_mb: u64) -> Option<String> {
+    let needed = model_mb + VRAM_HEADROOM_MB;
+    (free_mb < needed).then(|| {
+        format!(
+            "Not enough free GPU memory for this model: it needs about {} MB, {} MB is free",
+            needed, free_mb
+        )
+    })
+}
+
+/// Check that a model of `model_mb` fits in free VRAM. Returns the reason it
+/// doesn't; passes if free memory can't be queried.
+pub fn check_vram(model_mb: u64) -> Result<(), String> {
+    match free_vram_mb().and_then(|free| shortfall(model_mb, free)) {
+        Some(msg) => Err(msg),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortfall() {
+        assert_eq!(shortfall(1500, 8192), None);
+        assert_eq!(shortfall(1500, 1500 + VRAM_HEADROOM_MB), None);
+        let msg = shortfall(3000, 2048).unwrap();
+        assert!(msg.contains("3512 MB") && msg.contains("2048 MB"), "{}", msg);
+    }
+
+    #[test]
+    fn test_model_size_mb() {
+        let dir = std::env::temp_dir().join("app_core_model_size");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("model.bin"), vec![0u8; 3 * 1024 * 1024]).unwrap();
+        std::fs::write(dir.join("config.json"), "{}").unwrap();
+        std::fs::write(dir.join("nested/ignored.bin"), vec![0u8; 1024 * 1024]).unwrap();
+
+        assert_eq!(model_size_mb(&dir), 3);
+        assert_eq!(model_size_mb(&dir.join("model.bin")), 3);
+        assert_eq!(model_size_mb(&dir.join("missing.bin")), 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,309 @@
+//! Mock backend for testing the host without real models
+//!
+//! Implements every export with no inference behind it. The "model" is any file
+//! or folder that exists (the manifest's model has no files, so its download just
+//! creates the folder), and the transcript is the word "test" once per second of
+//! audio. Failures are simulated on demand through `MOCK_BACKEND_FAIL`, a
+//! comma-separated list of:
+//!
+//! - `load`: `create_model` fails as if the model were corrupt
+//! - `gpu`: GPU loads run out of VRAM (falling back to CPU when allowed)
+//! - `transcribe`: `transcribe` fails
+//! - `warmup`: `warmup` fails
+//!
+//! The variable is read on every call, so tests can change it between calls.
+
+// The exports take raw pointers from the host, which upholds the app-core contract
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use app_core::*;
+use std::cell::{Cell, RefCell};
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::ptr;
+
+/// Comma-separated failures to simulate (see the module docs)
+pub const FAIL_ENV: &str = "MOCK_BACKEND_FAIL";
+
+/// Word returned for each second of audio
+pub const MOCK_WORD: &str = "test";
+
+// Thread-local storage for error messages
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+    static LAST_ERROR_CODE: Cell<SttResult> = const { Cell::new(SttResult::Ok) };
+}
+
+fn set_error_code(code: SttResult, msg: &str) {
+    LAST_ERROR.with(|e| {
+        *e.borrow_mut() = CString::new(msg).ok();
+    });
+    LAST_ERROR_CODE.with(|c| c.set(code));
+}
+
+fn clear_error() {
+    LAST_ERROR.with(|e| {
+        *e.borrow_mut() = None;
+    });
+    LAST_ERROR_CODE.with(|c| c.set(SttResult::Ok));
+}
+
+/// Whether `MOCK_BACKEND_FAIL` asks for this failure
+fn should_fail(what: &str) -> bool {
+    std::env::var(FAIL_ENV).is_ok_and(|v| v.split(',').any(|s| s.trim() == what))
+}
+
+/// Internal model state
+struct MockModel {
+    device_name: CString,
+}
+
+// Static strings for backend info
+const BACKEND_ID: &[u8] = b"mock\0";
+const BACKEND_NAME: &[u8] = b"Mock (testing only)\0";
+const BACKEND_VERSION: &[u8] = b"0.1.0\0";
+
+const SAMPLE_RATE: u32 = 16000;
+
+/// Reported inference time per second of audio (an RTF of 0.1)
+const INFERENCE_MS_PER_SECOND: u64 = 100;
+
+/// One `MOCK_WORD` per started second of audio, each as its own segment
+fn mock_segments(audio_len: usize) -> Vec<(i64, i64, String)> {
+    let seconds = audio_len.div_ceil(SAMPLE_RATE as usize).max(1) as i64;
+    (0..seconds)
+        .map(|i| (i * 1000, (i + 1) * 1000, MOCK_WORD.to_string()))
+        .collect()
+}
+
+/// Get information about this backend
+#[no_mangle]
+pub extern "C" fn get_backend_info() -> BackendInfo {
+    BackendInfo {
+        api_version: API_VERSION,
+        id: BACKEND_ID.as_ptr() as *const c_char,
+        display_name: BACKEND_NAME.as_ptr() as *const c_char,
+        version: BACKEND_VERSION.as_ptr() as *const c_char,
+        supports_cuda: true,
+        required_sample_rate: SAMPLE_RATE,
+        required_channels: 1,
+    }
+}
+
+/// Layout hash of the FFI structs this backend was built against
+#[no_mangle]
+pub extern "C" fn get_abi_hash() -> u64 {
+    ABI_HASH
+}
+
+/// Create a new model instance
+#[no_mangle]
+pub extern "C" fn create_model(config: *const ModelConfig) -> *mut ModelHandle {
+    clear_error();
+
+    if config.is_null() {
+        set_error_code(SttResult::InvalidParam, "Config is null");
+        return ptr::null_mut();
+    }
+    let config = unsafe { &*config };
+    if config.model_path.is_null() {
+        set_error_code(SttResult::InvalidParam, "Model path is null");
+        return ptr::null_mut();
+    }
+    let model_path = unsafe { CStr::from_ptr(config.model_path) }.to_string_lossy();
+    if !std::path::Path::new(model_path.as_ref()).exists() {
+        set_error_code(SttResult::ModelNotFound, &format!("Model not found: {}", model_path));
+        return ptr::null_mut();
+    }
+    if should_fail("load") {
+        set_error_code(SttResult::ModelCorrupt, "Mock model load failure");
+        return ptr::null_mut();
+    }
+
+    let mut device_name = if config.use_gpu { "CUDA" } else { "CPU" };
+    if config.use_gpu && should_fail("gpu") {
+        set_error_code(SttResult::CudaOutOfMemory, "Mock CUDA out of memory");
+        // Like the ct2 backend: the GPU error stays set after a CPU fallback
+        if !config.allow_cpu_fallback {
+            return ptr::null_mut();
+        }
+        device_name = "CPU";
+    }
+
+    let model = Box::new(MockModel { device_name: CString::new(device_name).unwrap() });
+    Box::into_raw(model) as *mut ModelHandle
+}
+
+/// Destroy a model instance
+#[no_mangle]
+pub extern "C" fn destroy_model(handle: *mut ModelHandle) {
+    if !handle.is_null() {
+        unsafe {
+            drop(Box::from_raw(handle as *mut MockModel));
+        }
+    }
+}
+
+/// Transcribe audio samples
+#[no_mangle]
+pub extern "C" fn transcribe(
+    handle: *mut ModelHandle,
+    audio: *const f32,
+    audio_len: usize,
+    options: *const TranscribeOptions,
+) -> TranscribeResult {
+    run_transcribe(handle, audio, audio_len, options, None)
+}
+
+/// Transcribe audio samples, passing each word to `callback` as a segment
+#[no_mangle]
+pub extern "C" fn transcribe_streaming(
+    handle: *mut ModelHandle,
+    audio: *const f32,
+    audio_len: usize,
+    options: *const TranscribeOptions,
+    callback: SegmentCallback,
+    user_data: *mut c_void,
+) -> TranscribeResult {
+    let result = run_transcribe(handle, audio, audio_len, options, Some((callback, user_data)));
+    if result.code == SttResult::Ok {
+        let text = if result.text.is_null() { cstr!("") } else { result.text };
+        unsafe { callback(text, true, user_data) };
+    }
+    result
+}
+
+fn error_result(code: SttResult, msg: &str) -> TranscribeResult {
+    set_error_code(code, msg);
+    TranscribeResult {
+        code,
+        text: ptr::null(),
+        text_len: 0,
+        device_used: ptr::null(),
+        inference_ms: 0,
+        segments: ptr::null(),
+        segment_count: 0,
+        detected_language: ptr::null(),
+        avg_logprob: 0.0,
+        no_speech_prob: 0.0,
+    }
+}
+
+fn run_transcribe(
+    handle: *mut ModelHandle,
+    audio: *const f32,
+    audio_len: usize,
+    options: *const TranscribeOptions,
+    callback: Option<(SegmentCallback, *mut c_void)>,
+) -> TranscribeResult {
+    clear_error();
+
+    if handle.is_null() {
+        return error_result(SttResult::ModelNotLoaded, "Model handle is null");
+    }
+    if audio.is_null() {
+        return error_result(SttResult::InvalidParam, "Audio is null");
+    }
+    if should_fail("transcribe") {
+        return error_result(SttResult::TranscriptionFailed, "Mock transcription failure");
+    }
+    let model = unsafe { &*(handle as *const MockModel) };
+    let options = unsafe { options.as_ref() };
+
+    let words = mock_segments(audio_len);
+    if let Some((callback, user_data)) = callback {
+        for (_, _, word) in &words {
+            let word = CString::new(word.as_str()).unwrap();
+            unsafe { callback(word.as_ptr(), false, user_data) };
+        }
+    }
+    let text = words.iter().map(|(_, _, w)| w.as_str()).collect::<Vec<_>>().join(" ");
+    let text_len = text.len();
+    let (segments, segment_count) = if options.is_some_and(|o| o.timestamps) {
+        segments_into_raw(words)
+    } else {
+        (ptr::null(), 0)
+    };
+    // The requested language, or English as if it had been detected (the first
+    // allowed language if English isn't allowed)
+    let c_str = |p: *const c_char| (!p.is_null()).then(|| unsafe { CStr::from_ptr(p) });
+    let language = match options.and_then(|o| c_str(o.language)) {
+        Some(language) => language.to_owned(),
+        None => {
+            let allowed = options.and_then(|o| c_str(o.allowed_languages));
+            let allowed = allowed.map(|a| a.to_string_lossy().into_owned()).unwrap_or_default();
+            let mut codes = allowed.split(',').map(str::trim).filter(|c| !c.is_empty());
+            let detected = match codes.clone().next() {
+                Some(first) if !codes.any(|c| c == "en") => first,
+                _ => "en",
+            };
+            CString::new(detected).unwrap()
+        }
+    };
+
+    TranscribeResult {
+        code: SttResult::Ok,
+        text: CString::new(text).unwrap().into_raw(),
+        text_len,
+        device_used: model.device_name.as_ptr(),
+        inference_ms: audio_len as u64 * INFERENCE_MS_PER_SECOND / SAMPLE_RATE as u64,
+        segments,
+        segment_count,
+        detected_language: language.into_raw(),
+        avg_logprob: -0.1,
+        no_speech_prob: 0.0,
+    }
+}
+
+/// Pretend to warm up
+#[no_mangle]
+pub extern "C" fn warmup(handle: *mut ModelHandle) -> SttResult {
+    clear_error();
+
+    if handle.is_null() {
+        set_error_code(SttResult::ModelNotLoaded, "Model handle is null");
+        return SttResult::ModelNotLoaded;
+    }
+    if should_fail("warmup") {
+        set_error_code(SttResult::TranscriptionFailed, "Mock warmup failure");
+        return SttResult::TranscriptionFailed;
+    }
+    SttResult::Ok
+}
+
+/// Free a transcription result
+#[no_mangle]
+pub extern "C" fn free_result(result: *mut TranscribeResult) {
+    if !result.is_null() {
+        let result = unsafe { &mut *result };
+        if !result.text.is_null() {
+            unsafe {
+                drop(CString::from_raw(result.text as *mut c_char));
+            }
+            result.text = ptr::null();
+        }
+        unsafe { free_segments(result.segments, result.segment_count) };
+        result.segments = ptr::null();
+        result.segment_count = 0;
+        if !result.detected_language.is_null() {
+            unsafe {
+                drop(CString::from_raw(result.detected_language as *mut c_char));
+            }
+            result.detected_language = ptr::null();
+        }
+    }
+}
+
+/// Get the last error message
+#[no_mangle]
+pub extern "C" fn get_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| match e.borrow().as_ref() {
+        Some(s) => s.as_ptr(),
+        None => ptr::null(),
+    })
+}
+
+/// Get the result code of the last error
+#[no_mangle]
+pub extern "C" fn get_last_error_code() -> SttResult {
+    LAST_ERROR_CODE.with(|c| c.get())
+}
@@ -4,32 +4,54 @@
 //! Whisper inference. Supports GGML model format.
 
 use app_core::*;
-use std::cell::RefCell;
-use std::ffi::{c_char, CStr, CString};
+use std::cell::{Cell, RefCell};
+use std::ffi::{c_char, c_void, CStr, CString};
 use std::ptr;
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+use std::sync::Mutex;
+use std::time::Instant;
+use whisper_rs::{
+    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperError,
+    WhisperState,
+};
 
 // Thread-local storage for error messages
 thread_local! {
     static LAST_ERROR: RefCell<Option<CString>> = RefCell::new(None);
+    static LAST_ERROR_CODE: Cell<SttResult> = Cell::new(SttResult::Ok);
 }
 
 fn set_error(msg: &str) {
+    set_error_code(SttResult::UnknownError, msg);
+}
+
+fn set_error_code(code: SttResult, msg: &str) {
     LAST_ERROR.with(|e| {
         *e.borrow_mut() = CString::new(msg).ok();
     });
+    LAST_ERROR_CODE.with(|c| c.set(code));
 }
 
 fn clear_error() {
     LAST_ERROR.with(|e| {
         *e.borrow_mut() = None;
     });
+    LAST_ERROR_CODE.with(|c| c.set(SttResult::Ok));
 }
 
 /// Internal model state
 struct WhisperModel {
     ctx: WhisperContext,
+    /// Decoder state reused across calls; the lock also serializes concurrent calls
+    state: Mutex<WhisperState>,
     device_name: CString,
+    /// Threads for `FullParams::set_n_threads` (None = whisper-rs default)
+    n_threads: Option<i32>,
+}
+
+impl WhisperModel {
+    fn on_gpu(&self) -> bool {
+        self.device_name.as_bytes() == b"CUDA"
+    }
 }
 
 // Static strings for backend info
@@ -37,6 +59,44 @@ const BACKEND_ID: &[u8] = b"whisper-cpp\0";
 const BACKEND_NAME: &[u8] = b"Whisper (whisper.cpp)\0";
 const BACKEND_VERSION: &[u8] = b"0.1.0\0";
 
+// Whisper models take 16kHz mono audio
+const SAMPLE_RATE: u32 = 16000;
+
+// One second of silence for warmup
+const WARMUP_SAMPLES: usize = SAMPLE_RATE as usize;
+
+// Language detection looks at the first 30 second window only
+const DETECT_SAMPLES: usize = 30 * SAMPLE_RATE as usize;
+
+/// Extensions of a whisper.cpp model file
+const MODEL_EXTENSIONS: &[&str] = &["bin", "ggml"];
+
+/// The GGML file to load: `path` itself, or the single .bin/.ggml file in a model folder
+fn resolve_model_file(path: &std::path::Path) -> Result<std::path::PathBuf, String> {
+    if !path.is_dir() {
+        return Ok(path.to_path_buf());
+    }
+    let entries = std::fs::read_dir(path)
+        .map_err(|e| format!("Can't read model folder {}: {}", path.display(), e))?;
+    let is_model_file = |p: &std::path::Path| {
+        let ext = p.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+        p.is_file() && MODEL_EXTENSIONS.iter().any(|m| ext.eq_ignore_ascii_case(m))
+    };
+    let mut files: Vec<std::path::PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| is_model_file(p))
+        .collect();
+    match files.len() {
+        1 => Ok(files.remove(0)),
+        0 => Err(format!("Model folder {} has no .bin or .ggml model file", path.display())),
+        n => Err(format!(
+            "Model folder {} has {} model files; expected exactly one .bin or .ggml file",
+            path.display(),
+            n
+        )),
+    }
+}
+
 /// Get information about this backend
 #[no_mangle]
 pub extern "C" fn get_backend_info() -> BackendInfo {
@@ -49,16 +109,24 @@ pub extern "C" fn get_backend_info() -> BackendInfo {
         supports_cuda: true,
         #[cfg(not(feature = "cuda"))]
         supports_cuda: false,
+        required_sample_rate: SAMPLE_RATE,
+        required_channels: 1,
     }
 }
 
+/// Layout hash of the FFI structs this backend was built against
+#[no_mangle]
+pub extern "C" fn get_abi_hash() -> u64 {
+    ABI_HASH
+}
+
 /// Create a new model instance
 #[no_mangle]
 pub extern "C" fn create_model(config: *const ModelConfig) -> *mut ModelHandle {
     clear_error();
 
     if config.is_null() {
-        set_error("Config is null");
+        set_error_code(SttResult::InvalidParam, "Config is null");
         return ptr::null_mut();
     }
 
@@ -66,18 +134,43 @@ pub extern "C" fn create_model(config: *const ModelConfig) -> *mut ModelHandle {
 
     // Get model path
     let model_path = if config.model_path.is_null() {
-        set_error("Model path is null");
+        set_error_code(SttResult::InvalidParam, "Model path is null");
         return ptr::null_mut();
     } else {
         match unsafe { CStr::from_ptr(config.model_path) }.to_str() {
             Ok(s) => s,
             Err(_) => {
-                set_error("Invalid UTF-8 in model path");
+                set_error_code(SttResult::InvalidParam, "Invalid UTF-8 in model path");
                 return ptr::null_mut();
             }
         }
     };
 
+    if !std::path::Path::new(model_path).exists() {
+        set_error_code(
+            SttResult::ModelNotFound,
+            &format!("Model not found: {}", model_path),
+        );
+        return ptr::null_mut();
+    }
+    let model_file = match resolve_model_file(std::path::Path::new(model_path)) {
+        Ok(file) => file,
+        Err(msg) => {
+            set_error_code(SttResult::ModelNotFound, &msg);
+            return ptr::null_mut();
+        }
+    };
+    // Fail fast with a clear reason if the model can't fit, rather than deep in
+    // whisper.cpp; the host then falls back to CPU
+    #[cfg(feature = "cuda")]
+    if config.use_gpu {
+        if let Err(msg) = vram::check_vram(vram::model_size_mb(&model_file)) {
+            set_error_code(SttResult::CudaOutOfMemory, &msg);
+            return ptr::null_mut();
+        }
+    }
+    let model_path = model_file.to_string_lossy();
+
     // Create context parameters
     #[allow(unused_mut)]
     let mut ctx_params = WhisperContextParameters::default();
@@ -103,19 +196,38 @@ pub extern "C" fn create_model(config: *const ModelConfig) -> *mut ModelHandle {
     };
 
     // Create whisper context
-    match WhisperContext::new_with_params(model_path, ctx_params) {
-        Ok(ctx) => {
-            let model = Box::new(WhisperModel {
-                ctx,
-                device_name: CString::new(device_name).unwrap(),
-            });
-            Box::into_raw(model) as *mut ModelHandle
+    let ctx = match WhisperContext::new_with_params(&model_path, ctx_params) {
+        Ok(ctx) => ctx,
+        Err(e) => {
+            // The file exists, so a failed init means it isn't a readable GGML model
+            let msg = format!("Failed to load model: {:?}", e);
+            let on_gpu = device_name == "CUDA";
+            set_error_code(classify_error(&msg, on_gpu, SttResult::ModelCorrupt), &msg);
+            return ptr::null_mut();
         }
+    };
+
+    // Allocate the decoder state once; whisper_full resets it on every call
+    let state = match ctx.create_state() {
+        Ok(state) => state,
         Err(e) => {
-            set_error(&format!("Failed to load model: {:?}", e));
-            ptr::null_mut()
+            let code = if device_name == "CUDA" {
+                SttResult::CudaOutOfMemory
+            } else {
+                SttResult::OutOfMemory
+            };
+            set_error_code(code, &format!("Failed to create state: {:?}", e));
+            return ptr::null_mut();
         }
-    }
+    };
+
+    let model = Box::new(WhisperModel {
+        ctx,
+        state: Mutex::new(state),
+        device_name: CString::new(device_name).unwrap(),
+        n_threads: (config.cpu_threads > 0).then_some(config.cpu_threads as i32),
+    });
+    Box::into_raw(model) as *mut ModelHandle
 }
 
 /// Destroy a model instance
@@ -135,6 +247,58 @@ pub extern "C" fn transcribe(
     audio: *const f32,
     audio_len: usize,
     options: *const TranscribeOptions,
+) -> TranscribeResult {
+    run_transcribe(handle, audio, audio_len, options, None)
+}
+
+/// Transcribe audio samples, passing each segment to `callback` as whisper.cpp decodes it
+#[no_mangle]
+pub extern "C" fn transcribe_streaming(
+    handle: *mut ModelHandle,
+    audio: *const f32,
+    audio_len: usize,
+    options: *const TranscribeOptions,
+    callback: SegmentCallback,
+    user_data: *mut c_void,
+) -> TranscribeResult {
+    let result = run_transcribe(handle, audio, audio_len, options, Some((callback, user_data)));
+    if result.code == SttResult::Ok {
+        let text = if result.text.is_null() { cstr!("") } else { result.text };
+        unsafe { callback(text, true, user_data) };
+    }
+    result
+}
+
+/// The most likely of `allowed` according to whisper.cpp's language detection
+/// (None if none of the codes is a language whisper.cpp knows)
+fn detect_allowed(
+    state: &mut WhisperState,
+    audio: &[f32],
+    allowed: &[&str],
+    n_threads: Option<i32>,
+) -> Result<Option<&'static str>, WhisperError> {
+    let ids: Vec<i32> = allowed.iter().filter_map(|lang| whisper_rs::get_lang_id(lang)).collect();
+    if ids.is_empty() {
+        return Ok(None);
+    }
+    // whisper.cpp's own default when no thread count is set
+    let threads = n_threads.map_or_else(
+        || std::thread::available_parallelism().map_or(1, |n| n.get()).min(4),
+        |n| n as usize,
+    );
+    state.pcm_to_mel(&audio[..audio.len().min(DETECT_SAMPLES)], threads)?;
+    let (_, probs) = state.lang_detect(0, threads)?;
+    let prob = |id: i32| probs.get(id as usize).copied().unwrap_or(0.0);
+    let best = ids.into_iter().max_by(|&a, &b| prob(a).total_cmp(&prob(b)));
+    Ok(best.and_then(whisper_rs::get_lang_str))
+}
+
+fn run_transcribe(
+    handle: *mut ModelHandle,
+    audio: *const f32,
+    audio_len: usize,
+    options: *const TranscribeOptions,
+    on_segment: Option<(SegmentCallback, *mut c_void)>,
 ) -> TranscribeResult {
     clear_error();
 
@@ -145,6 +309,12 @@ pub extern "C" fn transcribe(
             text: ptr::null(),
             text_len: 0,
             device_used: ptr::null(),
+            inference_ms: 0,
+            segments: ptr::null(),
+            segment_count: 0,
+            detected_language: ptr::null(),
+            avg_logprob: 0.0,
+            no_speech_prob: 0.0,
         };
     }
 
@@ -160,75 +330,212 @@ pub extern "C" fn transcribe(
             text: text_ptr,
             text_len: 0,
             device_used: model.device_name.as_ptr(),
+            inference_ms: 0,
+            segments: ptr::null(),
+            segment_count: 0,
+            detected_language: ptr::null(),
+            avg_logprob: 0.0,
+            no_speech_prob: 0.0,
         };
     }
 
-    let model = unsafe { &mut *(handle as *mut WhisperModel) };
+    let model = unsafe { &*(handle as *const WhisperModel) };
     let audio_slice = unsafe { std::slice::from_raw_parts(audio, audio_len) };
 
-    // Get language from options
-    let language = if !options.is_null() {
-        let opts = unsafe { &*options };
-        if !opts.language.is_null() {
-            unsafe { CStr::from_ptr(opts.language) }
-                .to_str()
-                .ok()
-                .map(|s| s.to_string())
-        } else {
-            Some("en".to_string())
-        }
+    let timestamps = !options.is_null() && unsafe { (*options).timestamps };
+    let translate = !options.is_null() && unsafe { (*options).translate };
+    // whisper.cpp keeps only the best beam, so num_hypotheses has no effect here.
+    // The decoder tweaks (`suppress_blank` etc.) are CTranslate2's; whisper.cpp
+    // keeps its own settings.
+    let beam_size = if options.is_null() { 0 } else { unsafe { (*options).beam_size } };
+    let initial_prompt = if options.is_null() || unsafe { (*options).initial_prompt.is_null() } {
+        None
     } else {
-        Some("en".to_string())
+        unsafe { CStr::from_ptr((*options).initial_prompt) }.to_str().ok()
     };
 
-    // Create state and params
-    let mut state = match model.ctx.create_state() {
-        Ok(s) => s,
-        Err(e) => {
-            set_error(&format!("Failed to create state: {:?}", e));
-            return TranscribeResult {
-                code: SttResult::TranscriptionFailed,
-                text: ptr::null(),
-                text_len: 0,
-                device_used: model.device_name.as_ptr(),
-            };
+    if translate && !model.ctx.is_multilingual() {
+        set_error_code(
+            SttResult::UnsupportedTask,
+            "Translation needs a multilingual model (this model is English-only)",
+        );
+        return TranscribeResult {
+            code: SttResult::UnsupportedTask,
+            text: ptr::null(),
+            text_len: 0,
+            device_used: model.device_name.as_ptr(),
+            inference_ms: 0,
+            segments: ptr::null(),
+            segment_count: 0,
+            detected_language: ptr::null(),
+            avg_logprob: 0.0,
+            no_speech_prob: 0.0,
+        };
+    }
+
+    // Get language from options (null = auto-detect; English-only models are always "en")
+    let multilingual = model.ctx.is_multilingual();
+    let requested = if options.is_null() || unsafe { (*options).language.is_null() } {
+        None
+    } else {
+        unsafe { CStr::from_ptr((*options).language) }
+            .to_str()
+            .ok()
+            .map(|s| s.to_string())
+    };
+    let language = match requested {
+        Some(lang) => Some(lang),
+        None if multilingual => None,
+        None => Some("en".to_string()),
+    };
+    // Languages auto-detect may pick from (empty = any)
+    let allowed_languages = unsafe { options.as_ref() }
+        .map(|o| o.allowed_languages)
+        .filter(|list| !list.is_null())
+        .map(|list| unsafe { CStr::from_ptr(list) }.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let allowed: Vec<&str> = allowed_languages
+        .split(',')
+        .map(str::trim)
+        .filter(|lang| !lang.is_empty())
+        .collect();
+
+    // Reject input whisper.cpp would otherwise turn into garbage or a generic failure
+    let rejected = if audio_slice.iter().any(|s| !s.is_finite()) {
+        Some((SttResult::InvalidAudio, "Audio contains NaN or infinite samples".to_string()))
+    } else {
+        language.as_deref().and_then(|lang| {
+            let known = whisper_rs::get_lang_id(lang).is_some();
+            if !known || (!multilingual && lang != "en") {
+                let msg = format!("Language '{}' is not supported by this model", lang);
+                Some((SttResult::LanguageUnsupported, msg))
+            } else {
+                None
+            }
+        })
+    };
+    if let Some((code, msg)) = rejected {
+        set_error_code(code, &msg);
+        return TranscribeResult {
+            code,
+            text: ptr::null(),
+            text_len: 0,
+            device_used: model.device_name.as_ptr(),
+            inference_ms: 0,
+            segments: ptr::null(),
+            segment_count: 0,
+            detected_language: ptr::null(),
+            avg_logprob: 0.0,
+            no_speech_prob: 0.0,
+        };
+    }
+
+    // Reuse the cached state (waits for any other call on this model)
+    let mut state = model.state.lock().unwrap_or_else(|e| e.into_inner());
+
+    // whisper.cpp can't restrict its own detection, so detect up front and pick the
+    // most likely allowed language (if detection fails, any language goes)
+    let language = match language {
+        None if !allowed.is_empty() => {
+            let detected = detect_allowed(&mut state, audio_slice, &allowed, model.n_threads);
+            detected.ok().flatten().map(str::to_string)
         }
+        language => language,
     };
 
-    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-    if let Some(lang) = language.as_deref() {
-        params.set_language(Some(lang));
+    let mut params = FullParams::new(sampling_strategy(beam_size));
+    params.set_language(language.as_deref());
+    params.set_translate(translate);
+    if let Some(prompt) = initial_prompt.filter(|p| !p.is_empty()) {
+        params.set_initial_prompt(prompt);
     }
     params.set_print_special(false);
     params.set_print_progress(false);
     params.set_print_realtime(false);
     params.set_print_timestamps(false);
+    if let Some(n_threads) = model.n_threads {
+        params.set_n_threads(n_threads);
+    }
+    if let Some((callback, user_data)) = on_segment {
+        params.set_segment_callback_safe(move |data: whisper_rs::SegmentCallbackData| {
+            if let Ok(text) = CString::new(data.text.trim()) {
+                unsafe { callback(text.as_ptr(), false, user_data) };
+            }
+        });
+    }
 
     // Perform transcription
-    if let Err(e) = state.full(params, audio_slice) {
-        set_error(&format!("Transcription failed: {:?}", e));
+    let start = Instant::now();
+    let full_result = state.full(params, audio_slice);
+    let inference_ms = start.elapsed().as_millis() as u64;
+    if let Err(e) = full_result {
+        let msg = format!("Transcription failed: {:?}", e);
+        let code = match e {
+            WhisperError::UnableToCalculateSpectrogram => SttResult::InvalidAudio,
+            _ => classify_error(&msg, model.on_gpu(), SttResult::TranscriptionFailed),
+        };
+        set_error_code(code, &msg);
         return TranscribeResult {
-            code: SttResult::TranscriptionFailed,
+            code,
             text: ptr::null(),
             text_len: 0,
             device_used: model.device_name.as_ptr(),
+            inference_ms,
+            segments: ptr::null(),
+            segment_count: 0,
+            detected_language: ptr::null(),
+            avg_logprob: 0.0,
+            no_speech_prob: 0.0,
         };
     }
 
     // Collect results
     let num_segments = state.full_n_segments();
     let mut result_text = String::new();
+    let mut timed_segments = Vec::new();
+    let mut logprob_sum = 0.0f32;
+    let mut token_count = 0usize;
+    let mut no_speech_prob = 1.0f32;
 
     for i in 0..num_segments {
         if let Some(segment) = state.get_segment(i) {
+            // The audio counts as speech if any segment is confidently speech
+            no_speech_prob = no_speech_prob.min(segment.no_speech_probability());
+            for t in 0..segment.n_tokens() {
+                if let Some(token) = segment.get_token(t) {
+                    logprob_sum += token.token_data().plog;
+                    token_count += 1;
+                }
+            }
             if let Ok(text) = segment.to_str() {
                 if !result_text.is_empty() {
                     result_text.push(' ');
                 }
                 result_text.push_str(text);
+                if timestamps {
+                    // whisper.cpp timestamps are in centiseconds
+                    timed_segments.push((
+                        segment.start_timestamp() * 10,
+                        segment.end_timestamp() * 10,
+                        text.trim().to_string(),
+                    ));
+                }
             }
         }
     }
+    let (segments, segment_count) = segments_into_raw(timed_segments);
+    let avg_logprob = if token_count > 0 { logprob_sum / token_count as f32 } else { 0.0 };
+    if num_segments == 0 {
+        no_speech_prob = 0.0;
+    }
+
+    let detected = match language.as_deref() {
+        Some(lang) => Some(lang),
+        None => whisper_rs::get_lang_str(state.full_lang_id_from_state()),
+    };
+    let detected_language = detected
+        .and_then(|lang| CString::new(lang).ok())
+        .map_or(ptr::null(), |lang| lang.into_raw() as *const c_char);
 
     let text = result_text.trim().to_string();
     let text_len = text.len();
@@ -241,6 +548,59 @@ pub extern "C" fn transcribe(
         text: text_ptr,
         text_len,
         device_used: model.device_name.as_ptr(),
+        inference_ms,
+        segments,
+        segment_count,
+        detected_language,
+        avg_logprob,
+        no_speech_prob,
+    }
+}
+
+/// Beam search for `beam_size > 1`, otherwise greedy decoding
+fn sampling_strategy(beam_size: u32) -> SamplingStrategy {
+    if beam_size > 1 {
+        SamplingStrategy::BeamSearch {
+            beam_size: beam_size as i32,
+            // whisper.cpp's default (patience is not implemented upstream)
+            patience: -1.0,
+        }
+    } else {
+        SamplingStrategy::Greedy { best_of: 1 }
+    }
+}
+
+/// Run a short silent inference so the first real transcription is fast
+#[no_mangle]
+pub extern "C" fn warmup(handle: *mut ModelHandle) -> SttResult {
+    clear_error();
+
+    if handle.is_null() {
+        set_error("Model handle is null");
+        return SttResult::ModelNotLoaded;
+    }
+
+    let model = unsafe { &*(handle as *const WhisperModel) };
+    let silence = vec![0.0f32; WARMUP_SAMPLES];
+
+    let mut state = model.state.lock().unwrap_or_else(|e| e.into_inner());
+
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_language(Some("en"));
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    if let Some(n_threads) = model.n_threads {
+        params.set_n_threads(n_threads);
+    }
+
+    match state.full(params, &silence) {
+        Ok(_) => SttResult::Ok,
+        Err(e) => {
+            set_error(&format!("Warmup failed: {:?}", e));
+            SttResult::TranscriptionFailed
+        }
     }
 }
 
@@ -255,6 +615,15 @@ pub extern "C" fn free_result(result: *mut TranscribeResult) {
             }
             result.text = ptr::null();
         }
+        unsafe { free_segments(result.segments, result.segment_count) };
+        result.segments = ptr::null();
+        result.segment_count = 0;
+        if !result.detected_language.is_null() {
+            unsafe {
+                drop(CString::from_raw(result.detected_language as *mut c_char));
+            }
+            result.detected_language = ptr::null();
+        }
     }
 }
 
@@ -266,3 +635,9 @@ pub extern "C" fn get_last_error() -> *const c_char {
         None => ptr::null(),
     })
 }
+
+/// Get the result code of the last error
+#[no_mangle]
+pub extern "C" fn get_last_error_code() -> SttResult {
+    LAST_ERROR_CODE.with(|c| c.get())
+}
@@ -3,27 +3,35 @@
 //! This backend uses the ct2rs crate (CTranslate2 Rust bindings) for
 //! fast Whisper inference. Supports models from Systran/faster-whisper.
 
-use ct2rs::{Config, Device, Whisper, WhisperOptions};
+use ct2rs::{ComputeType, Config, Device, Whisper, WhisperOptions};
 use app_core::*;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::ffi::{c_char, CStr, CString};
 use std::ptr;
+use std::time::Instant;
 
 // Thread-local storage for error messages
 thread_local! {
     static LAST_ERROR: RefCell<Option<CString>> = RefCell::new(None);
+    static LAST_ERROR_CODE: Cell<SttResult> = Cell::new(SttResult::Ok);
 }
 
 fn set_error(msg: &str) {
+    set_error_code(SttResult::UnknownError, msg);
+}
+
+fn set_error_code(code: SttResult, msg: &str) {
     LAST_ERROR.with(|e| {
         *e.borrow_mut() = CString::new(msg).ok();
     });
+    LAST_ERROR_CODE.with(|c| c.set(code));
 }
 
 fn clear_error() {
     LAST_ERROR.with(|e| {
         *e.borrow_mut() = None;
     });
+    LAST_ERROR_CODE.with(|c| c.set(SttResult::Ok));
 }
 
 /// Internal model state
@@ -37,6 +45,70 @@ const BACKEND_ID: &[u8] = b"whisper-ct2\0";
 const BACKEND_NAME: &[u8] = b"Whisper (CTranslate2)\0";
 const BACKEND_VERSION: &[u8] = b"0.1.0\0";
 
+// Whisper models take 16kHz mono audio
+const SAMPLE_RATE: u32 = 16000;
+
+// One second of silence for warmup
+const WARMUP_SAMPLES: usize = SAMPLE_RATE as usize;
+
+// Whisper processes audio in 30 second windows; each generated string is one window
+const CHUNK_MS: i64 = 30_000;
+
+/// `ModelConfig::compute_type` names (CTranslate2's own) and their ct2rs values
+const COMPUTE_TYPES: &[(&str, ComputeType)] = &[
+    ("default", ComputeType::DEFAULT),
+    ("auto", ComputeType::AUTO),
+    ("float32", ComputeType::FLOAT32),
+    ("int8", ComputeType::INT8),
+    ("int8_float32", ComputeType::INT8_FLOAT32),
+    ("int8_float16", ComputeType::INT8_FLOAT16),
+    ("int8_bfloat16", ComputeType::INT8_BFLOAT16),
+    ("int16", ComputeType::INT16),
+    ("float16", ComputeType::FLOAT16),
+    ("bfloat16", ComputeType::BFLOAT16),
+];
+
+/// Half-precision types CTranslate2 only runs on a CUDA GPU
+const GPU_ONLY_COMPUTE_TYPES: &[&str] = &["float16", "bfloat16", "int8_float16", "int8_bfloat16"];
+
+/// Look up a compute type name (case-insensitive)
+fn parse_compute_type(name: &str) -> Result<ComputeType, String> {
+    COMPUTE_TYPES
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|&(_, compute_type)| compute_type)
+        .ok_or_else(|| {
+            let names: Vec<&str> = COMPUTE_TYPES.iter().map(|(n, _)| *n).collect();
+            format!("Unknown compute type '{}' (expected one of: {})", name, names.join(", "))
+        })
+}
+
+/// Reject compute types the CPU can't run, before CTranslate2 gives a vaguer error
+fn check_cpu_compute_type(name: &str) -> Result<(), String> {
+    if GPU_ONLY_COMPUTE_TYPES.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+        return Err(format!(
+            "Compute type '{}' needs a CUDA GPU; use int8 or float32 on the CPU",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Files a CTranslate2 Whisper model folder needs (every manifest entry lists these)
+const REQUIRED_FILES: &[&str] = &["model.bin", "config.json", "tokenizer.json", "vocabulary.txt"];
+
+/// Name the required files missing from `dir`, which ct2rs would otherwise
+/// report as an unrelated-looking load error
+fn check_model_dir(dir: &std::path::Path) -> Result<(), String> {
+    let missing: Vec<&str> =
+        REQUIRED_FILES.iter().copied().filter(|f| !dir.join(f).is_file()).collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Model folder {} is missing {}", dir.display(), missing.join(", ")))
+    }
+}
+
 /// Get information about this backend
 #[no_mangle]
 pub extern "C" fn get_backend_info() -> BackendInfo {
@@ -49,16 +121,24 @@ pub extern "C" fn get_backend_info() -> BackendInfo {
         supports_cuda: true,
         #[cfg(not(feature = "cuda"))]
         supports_cuda: false,
+        required_sample_rate: SAMPLE_RATE,
+        required_channels: 1,
     }
 }
 
+/// Layout hash of the FFI structs this backend was built against
+#[no_mangle]
+pub extern "C" fn get_abi_hash() -> u64 {
+    ABI_HASH
+}
+
 /// Create a new model instance
 #[no_mangle]
 pub extern "C" fn create_model(config: *const ModelConfig) -> *mut ModelHandle {
     clear_error();
 
     if config.is_null() {
-        set_error("Config is null");
+        set_error_code(SttResult::InvalidParam, "Config is null");
         return ptr::null_mut();
     }
 
@@ -66,63 +146,128 @@ pub extern "C" fn create_model(config: *const ModelConfig) -> *mut ModelHandle {
 
     // Get model path
     let model_path = if config.model_path.is_null() {
-        set_error("Model path is null");
+        set_error_code(SttResult::InvalidParam, "Model path is null");
         return ptr::null_mut();
     } else {
         match unsafe { CStr::from_ptr(config.model_path) }.to_str() {
             Ok(s) => s,
             Err(_) => {
-                set_error("Invalid UTF-8 in model path");
+                set_error_code(SttResult::InvalidParam, "Invalid UTF-8 in model path");
                 return ptr::null_mut();
             }
         }
     };
 
+    if !std::path::Path::new(model_path).exists() {
+        set_error_code(
+            SttResult::ModelNotFound,
+            &format!("Model not found: {}", model_path),
+        );
+        return ptr::null_mut();
+    }
+    if let Err(msg) = check_model_dir(std::path::Path::new(model_path)) {
+        set_error_code(SttResult::ModelNotFound, &msg);
+        return ptr::null_mut();
+    }
+
+    let compute_type_name = if config.compute_type.is_null() {
+        None
+    } else {
+        match unsafe { CStr::from_ptr(config.compute_type) }.to_str() {
+            Ok(s) if !s.is_empty() => Some(s),
+            Ok(_) => None,
+            Err(_) => {
+                set_error_code(SttResult::InvalidParam, "Invalid UTF-8 in compute type");
+                return ptr::null_mut();
+            }
+        }
+    };
+    let compute_type = match compute_type_name.map(parse_compute_type).transpose() {
+        Ok(compute_type) => compute_type.unwrap_or(ComputeType::DEFAULT),
+        Err(msg) => {
+            set_error_code(SttResult::InvalidParam, &msg);
+            return ptr::null_mut();
+        }
+    };
+
     // Determine device and create model
     if config.use_gpu {
         #[cfg(feature = "cuda")]
         {
-            // GPU requested - try CUDA only, no fallback
-            match try_create_whisper(model_path, Device::CUDA) {
-                Ok(whisper) => {
-                    let model = Box::new(WhisperModel {
-                        whisper,
-                        device_name: CString::new("CUDA").unwrap(),
-                    });
-                    return Box::into_raw(model) as *mut ModelHandle;
-                }
-                Err(e) => {
-                    set_error(&format!("CUDA initialization failed: {}. Check CUDA/cuDNN paths in config.", e));
-                    return ptr::null_mut();
+            // Fail fast with a clear reason if the model can't fit, rather than
+            // deep in CTranslate2
+            let model_mb = vram::model_size_mb(std::path::Path::new(model_path));
+            if let Err(msg) = vram::check_vram(model_mb) {
+                set_error_code(SttResult::CudaOutOfMemory, &msg);
+            } else {
+                let device = Device::CUDA;
+                match try_create_whisper(model_path, device, compute_type, config.cpu_threads) {
+                    Ok(whisper) => return into_handle(whisper, "CUDA"),
+                    Err(e) => {
+                        let msg = format!(
+                            "CUDA initialization failed: {}. Check CUDA/cuDNN paths in config.",
+                            e
+                        );
+                        let code = classify_error(&msg, true, SttResult::UnsupportedDevice);
+                        set_error_code(code, &msg);
+                    }
                 }
             }
         }
         #[cfg(not(feature = "cuda"))]
         {
-            set_error("GPU requested but CUDA support not compiled in this build");
+            set_error_code(
+                SttResult::UnsupportedDevice,
+                "GPU requested but CUDA support not compiled in this build",
+            );
+        }
+        // Callers that want to detect GPU problems get the error instead of a CPU model
+        if !config.allow_cpu_fallback {
             return ptr::null_mut();
         }
+        eprintln!("GPU model load failed, falling back to CPU");
     }
 
-    // CPU mode
-    match try_create_whisper(model_path, Device::CPU) {
-        Ok(whisper) => {
-            let model = Box::new(WhisperModel {
-                whisper,
-                device_name: CString::new("CPU").unwrap(),
-            });
-            Box::into_raw(model) as *mut ModelHandle
+    // CPU mode. After a GPU fallback the GPU error is left in place for the host,
+    // and a GPU-only compute type gives way to the model's own
+    let compute_type = match compute_type_name.map(check_cpu_compute_type) {
+        Some(Err(msg)) if !config.use_gpu => {
+            set_error_code(SttResult::InvalidParam, &msg);
+            return ptr::null_mut();
         }
+        Some(Err(_)) => ComputeType::DEFAULT,
+        _ => compute_type,
+    };
+    match try_create_whisper(model_path, Device::CPU, compute_type, config.cpu_threads) {
+        Ok(whisper) => into_handle(whisper, "CPU"),
         Err(e) => {
-            set_error(&format!("Failed to load model: {}", e));
+            let msg = format!("Failed to load model: {}", e);
+            set_error_code(classify_error(&msg, false, SttResult::ModelCorrupt), &msg);
             ptr::null_mut()
         }
     }
 }
 
-fn try_create_whisper(model_path: &str, device: Device) -> Result<Whisper, String> {
+/// Box a loaded model; `device_name` is reported as `device_used` in results
+fn into_handle(whisper: Whisper, device_name: &str) -> *mut ModelHandle {
+    let model = Box::new(WhisperModel {
+        whisper,
+        device_name: CString::new(device_name).unwrap(),
+    });
+    Box::into_raw(model) as *mut ModelHandle
+}
+
+fn try_create_whisper(
+    model_path: &str,
+    device: Device,
+    compute_type: ComputeType,
+    cpu_threads: u32,
+) -> Result<Whisper, String> {
     let config = Config {
         device,
+        compute_type,
+        // 0 lets CTranslate2 pick
+        num_threads_per_replica: cpu_threads as usize,
         ..Default::default()
     };
     Whisper::new(model_path, config).map_err(|e| format!("{:?}: {}", device, e))
@@ -155,6 +300,12 @@ pub extern "C" fn transcribe(
             text: ptr::null(),
             text_len: 0,
             device_used: ptr::null(),
+            inference_ms: 0,
+            segments: ptr::null(),
+            segment_count: 0,
+            detected_language: ptr::null(),
+            avg_logprob: 0.0,
+            no_speech_prob: 0.0,
         };
     }
 
@@ -170,57 +321,273 @@ pub extern "C" fn transcribe(
             text: text_ptr,
             text_len: 0,
             device_used: model.device_name.as_ptr(),
+            inference_ms: 0,
+            segments: ptr::null(),
+            segment_count: 0,
+            detected_language: ptr::null(),
+            avg_logprob: 0.0,
+            no_speech_prob: 0.0,
         };
     }
 
     let model = unsafe { &*(handle as *const WhisperModel) };
     let audio_slice = unsafe { std::slice::from_raw_parts(audio, audio_len) };
 
-    // Get language from options
-    let language = if !options.is_null() {
-        let opts = unsafe { &*options };
-        if !opts.language.is_null() {
-            unsafe { CStr::from_ptr(opts.language) }.to_str().ok()
+    let timestamps = !options.is_null() && unsafe { (*options).timestamps };
+    let whisper_options = if options.is_null() {
+        WhisperOptions::default()
+    } else {
+        whisper_options(unsafe { &*options })
+    };
+    // ct2rs builds the decoder prompt itself, always with the transcribe task and
+    // without previous-text tokens, so neither translation nor a prompt can be requested
+    let prompted = !options.is_null()
+        && !unsafe { (*options).initial_prompt }.is_null()
+        && !unsafe { CStr::from_ptr((*options).initial_prompt) }.to_bytes().is_empty();
+    let unsupported = if !options.is_null() && unsafe { (*options).translate } {
+        Some(if model.whisper.is_multilingual() {
+            "Translation is not supported by the CTranslate2 backend yet; use whisper.cpp"
         } else {
-            Some("en") // Default to English
-        }
+            "Translation needs a multilingual model (this model is English-only)"
+        })
+    } else if prompted {
+        Some("initial_prompt is not supported by the CTranslate2 backend yet; use whisper.cpp")
+    } else {
+        None
+    };
+    if let Some(msg) = unsupported {
+        set_error_code(SttResult::UnsupportedTask, msg);
+        return TranscribeResult {
+            code: SttResult::UnsupportedTask,
+            text: ptr::null(),
+            text_len: 0,
+            device_used: model.device_name.as_ptr(),
+            inference_ms: 0,
+            segments: ptr::null(),
+            segment_count: 0,
+            detected_language: ptr::null(),
+            avg_logprob: 0.0,
+            no_speech_prob: 0.0,
+        };
+    }
+
+    // Get language from options (null = auto-detect; English-only models are always "en")
+    let requested = if options.is_null() || unsafe { (*options).language.is_null() } {
+        None
     } else {
-        Some("en")
+        unsafe { CStr::from_ptr((*options).language) }.to_str().ok()
+    };
+    // `allowed_languages` is ignored: ct2rs detects the language inside generate()
+    // without exposing the probabilities, so detection can't be narrowed. (The host
+    // already turns a single allowed language into `language`.)
+    let language = match requested {
+        Some(lang) => Some(lang),
+        None if model.whisper.is_multilingual() => None,
+        None => Some("en"),
     };
 
+    // Reject input CTranslate2 would otherwise turn into garbage or a generic failure
+    let rejected = if audio_slice.iter().any(|s| !s.is_finite()) {
+        Some((SttResult::InvalidAudio, "Audio contains NaN or infinite samples".to_string()))
+    } else {
+        language
+            .filter(|lang| !model.whisper.is_multilingual() && *lang != "en")
+            .map(|lang| {
+                let msg = format!("Language '{}' needs a multilingual model", lang);
+                (SttResult::LanguageUnsupported, msg)
+            })
+    };
+    if let Some((code, msg)) = rejected {
+        set_error_code(code, &msg);
+        return TranscribeResult {
+            code,
+            text: ptr::null(),
+            text_len: 0,
+            device_used: model.device_name.as_ptr(),
+            inference_ms: 0,
+            segments: ptr::null(),
+            segment_count: 0,
+            detected_language: ptr::null(),
+            avg_logprob: 0.0,
+            no_speech_prob: 0.0,
+        };
+    }
+
     // Perform transcription
-    match model.whisper.generate(
+    let start = Instant::now();
+    let generated = model.whisper.generate(
         audio_slice,
         language,
-        false, // timestamps
-        &WhisperOptions::default(),
-    ) {
+        timestamps,
+        &whisper_options,
+    );
+    let inference_ms = start.elapsed().as_millis() as u64;
+
+    match generated {
         Ok(results) => {
-            let text = results.join(" ").trim().to_string();
+            let (text, segments, segment_count) = if timestamps {
+                let timed: Vec<(i64, i64, String)> = results
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, chunk)| parse_timestamped(chunk, i as i64 * CHUNK_MS))
+                    .collect();
+                let text = timed
+                    .iter()
+                    .map(|(_, _, text)| text.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let (segments, segment_count) = segments_into_raw(timed);
+                (text, segments, segment_count)
+            } else {
+                (results.join(" ").trim().to_string(), ptr::null(), 0)
+            };
             let text_len = text.len();
             let text_cstring = CString::new(text).unwrap();
             let text_ptr = text_cstring.as_ptr();
             std::mem::forget(text_cstring); // Caller must free via free_result
 
+            // ct2rs detects the language inside generate() but doesn't return it,
+            // so only a requested (or English-only) language can be reported
+            let detected_language = language
+                .and_then(|lang| CString::new(lang).ok())
+                .map_or(ptr::null(), |lang| lang.into_raw() as *const c_char);
+
             TranscribeResult {
                 code: SttResult::Ok,
                 text: text_ptr,
                 text_len,
                 device_used: model.device_name.as_ptr(),
+                inference_ms,
+                segments,
+                segment_count,
+                detected_language,
+                // ct2rs's generate() returns only the text, dropping the scores
+                // CTranslate2 computes, so these stay at their neutral values
+                avg_logprob: 0.0,
+                no_speech_prob: 0.0,
             }
         }
         Err(e) => {
-            set_error(&format!("Transcription failed: {}", e));
+            let msg = format!("Transcription failed: {}", e);
+            let on_gpu = model.device_name.as_bytes() == b"CUDA";
+            let code = classify_error(&msg, on_gpu, SttResult::TranscriptionFailed);
+            set_error_code(code, &msg);
             TranscribeResult {
-                code: SttResult::TranscriptionFailed,
+                code,
                 text: ptr::null(),
                 text_len: 0,
                 device_used: model.device_name.as_ptr(),
+                inference_ms,
+                segments: ptr::null(),
+                segment_count: 0,
+                detected_language: ptr::null(),
+                avg_logprob: 0.0,
+                no_speech_prob: 0.0,
             }
         }
     }
 }
 
+/// CTranslate2 options for the requested beam search. A `beam_size` of 0 decodes
+/// greedily like whisper.cpp does, rather than with CTranslate2's default of 5 beams.
+fn beam_options(beam_size: u32, num_hypotheses: u32) -> WhisperOptions {
+    let mut options = WhisperOptions {
+        beam_size: beam_size.max(1) as usize,
+        ..Default::default()
+    };
+    if num_hypotheses > 0 {
+        // CTranslate2 rejects more hypotheses than beams
+        options.num_hypotheses = (num_hypotheses as usize).min(options.beam_size);
+    }
+    options
+}
+
+/// CTranslate2 options for a request: beam search plus the decoder tweaks, each
+/// left at the library default when unset
+fn whisper_options(options: &TranscribeOptions) -> WhisperOptions {
+    let mut whisper = beam_options(options.beam_size, options.num_hypotheses);
+    if let Some(suppress_blank) = options.suppress_blank.get() {
+        whisper.suppress_blank = suppress_blank;
+    }
+    if options.no_repeat_ngram_size > 0 {
+        whisper.no_repeat_ngram_size = options.no_repeat_ngram_size as usize;
+    }
+    if options.max_initial_timestamp_ms > 0 {
+        // Timestamp tokens are 20ms apart
+        whisper.max_initial_timestamp_index = (options.max_initial_timestamp_ms / 20) as usize;
+    }
+    whisper
+}
+
+/// Split generated text with `<|1.23|>` timestamp tokens into
+/// `(start_ms, end_ms, text)` segments, offset by `offset_ms`
+fn parse_timestamped(text: &str, offset_ms: i64) -> Vec<(i64, i64, String)> {
+    let mut segments = Vec::new();
+    let mut start: Option<i64> = None;
+    let mut pending = String::new();
+    let mut rest = text;
+
+    while let Some(open) = rest.find("<|") {
+        pending.push_str(&rest[..open]);
+        let Some(len) = rest[open..].find("|>") else {
+            break;
+        };
+        let token = &rest[open + 2..open + len];
+        rest = &rest[open + len + 2..];
+
+        // Skip other special tokens like <|en|>
+        let Ok(seconds) = token.parse::<f64>() else {
+            continue;
+        };
+        let ms = offset_ms + (seconds * 1000.0).round() as i64;
+        match start.take() {
+            None => start = Some(ms),
+            Some(start_ms) => {
+                let segment_text = pending.trim();
+                if !segment_text.is_empty() {
+                    segments.push((start_ms, ms, segment_text.to_string()));
+                }
+            }
+        }
+        pending.clear();
+    }
+
+    // Text after the last timestamp (no closing token)
+    pending.push_str(rest);
+    let trailing = pending.trim();
+    if !trailing.is_empty() {
+        let start_ms = start.unwrap_or(offset_ms);
+        segments.push((start_ms, start_ms, trailing.to_string()));
+    }
+
+    segments
+}
+
+/// Run a short silent inference so the first real transcription is fast
+#[no_mangle]
+pub extern "C" fn warmup(handle: *mut ModelHandle) -> SttResult {
+    clear_error();
+
+    if handle.is_null() {
+        set_error("Model handle is null");
+        return SttResult::ModelNotLoaded;
+    }
+
+    let model = unsafe { &*(handle as *const WhisperModel) };
+    let silence = vec![0.0f32; WARMUP_SAMPLES];
+
+    match model
+        .whisper
+        .generate(&silence, Some("en"), false, &WhisperOptions::default())
+    {
+        Ok(_) => SttResult::Ok,
+        Err(e) => {
+            set_error(&format!("Warmup failed: {}", e));
+            SttResult::TranscriptionFailed
+        }
+    }
+}
+
 /// Free a transcription result
 #[no_mangle]
 pub extern "C" fn free_result(result: *mut TranscribeResult) {
@@ -232,6 +599,15 @@ pub extern "C" fn free_result(result: *mut TranscribeResult) {
             }
             result.text = ptr::null();
         }
+        unsafe { free_segments(result.segments, result.segment_count) };
+        result.segments = ptr::null();
+        result.segment_count = 0;
+        if !result.detected_language.is_null() {
+            unsafe {
+                drop(CString::from_raw(result.detected_language as *mut c_char));
+            }
+            result.detected_language = ptr::null();
+        }
     }
 }
 
@@ -245,3 +621,116 @@ pub extern "C" fn get_last_error() -> *const c_char {
         }
     })
 }
+
+/// Get the result code of the last error
+#[no_mangle]
+pub extern "C" fn get_last_error_code() -> SttResult {
+    LAST_ERROR_CODE.with(|c| c.get())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_type_names() {
+        assert!(parse_compute_type("int8").is_ok());
+        assert!(parse_compute_type("INT8_FLOAT16").is_ok());
+        let err = parse_compute_type("int4").unwrap_err();
+        assert!(err.contains("'int4'") && err.contains("float16"), "{}", err);
+
+        assert!(check_cpu_compute_type("int8").is_ok());
+        assert!(check_cpu_compute_type("float32").is_ok());
+        assert!(check_cpu_compute_type("float16").unwrap_err().contains("CUDA"));
+    }
+
+    #[test]
+    fn test_check_model_dir() {
+        let dir = std::env::temp_dir().join(format!("ct2-model-dir-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for file in ["model.bin", "config.json", "vocabulary.txt"] {
+            std::fs::write(dir.join(file), b"").unwrap();
+        }
+        let err = check_model_dir(&dir).unwrap_err();
+        assert!(err.ends_with("is missing tokenizer.json"), "{}", err);
+
+        std::fs::write(dir.join("tokenizer.json"), b"").unwrap();
+        assert!(check_model_dir(&dir).is_ok());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_manifest_lists_required_files() {
+        // The app checks downloads against the manifest, so it must agree with create_model
+        let manifest = include_str!("../manifest.json");
+        let file_lists: Vec<&str> = manifest.lines().filter(|l| l.contains("\"files\"")).collect();
+        assert!(!file_lists.is_empty());
+        for line in file_lists {
+            for file in REQUIRED_FILES {
+                assert!(line.contains(&format!("\"{}\"", file)), "{} not in {}", file, line);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_timestamped() {
+        let text = "<|0.00|> Hello there.<|1.50|><|1.50|> General Kenobi.<|3.25|>";
+        assert_eq!(
+            parse_timestamped(text, 0),
+            vec![
+                (0, 1500, "Hello there.".to_string()),
+                (1500, 3250, "General Kenobi.".to_string()),
+            ]
+        );
+
+        // Second 30s window is offset; unclosed text still becomes a segment
+        assert_eq!(
+            parse_timestamped("<|0.40|> tail", CHUNK_MS),
+            vec![(30_400, 30_400, "tail".to_string())]
+        );
+
+        // No timestamp tokens at all
+        assert_eq!(parse_timestamped(" plain ", 0), vec![(0, 0, "plain".to_string())]);
+        assert!(parse_timestamped("", 0).is_empty());
+    }
+
+    #[test]
+    fn test_beam_options() {
+        let default = WhisperOptions::default();
+        let options = beam_options(0, 0);
+        assert_eq!(options.beam_size, 1);
+        assert_eq!(options.num_hypotheses, default.num_hypotheses);
+
+        let options = beam_options(3, 2);
+        assert_eq!(options.beam_size, 3);
+        assert_eq!(options.num_hypotheses, 2);
+
+        // Hypotheses are capped at the beam width
+        assert_eq!(beam_options(2, 8).num_hypotheses, 2);
+    }
+
+    #[test]
+    fn test_whisper_options() {
+        let default = WhisperOptions::default();
+        let options = whisper_options(&TranscribeOptions::default());
+        assert_eq!(options.suppress_blank, default.suppress_blank);
+        assert_eq!(options.no_repeat_ngram_size, default.no_repeat_ngram_size);
+        assert_eq!(options.max_initial_timestamp_index, default.max_initial_timestamp_index);
+
+        let request = TranscribeOptions {
+            beam_size: 3,
+            suppress_blank: OptionalBool::Off,
+            no_repeat_ngram_size: 4,
+            max_initial_timestamp_ms: 2000,
+            ..Default::default()
+        };
+        let options = whisper_options(&request);
+        assert_eq!(options.beam_size, 3);
+        assert!(!options.suppress_blank);
+        assert_eq!(options.no_repeat_ngram_size, 4);
+        assert_eq!(options.max_initial_timestamp_index, 100);
+
+        let request = TranscribeOptions { suppress_blank: OptionalBool::On, ..Default::default() };
+        assert!(whisper_options(&request).suppress_blank);
+    }
+}